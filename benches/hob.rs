@@ -0,0 +1,14 @@
+//! Timing of the *hob* variants on random inputs, run with `cargo bench --bench hob`
+
+use understanding_bitwise::compare::{compare_methods, CompareOptions};
+use understanding_bitwise::prng::Xorshift32;
+use understanding_bitwise::HOB_METHODS;
+
+fn main() {
+    let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+    let inputs: Vec<u32> = (0..10_000).map(|_| rng.next_u32()).collect();
+    let timings = compare_methods(&HOB_METHODS, |index| inputs[index], CompareOptions { inputs: inputs.len(), rounds: 20 });
+    for timing in timings {
+        println!("{:<20}{:>10.2?} per call, median round {:?}", timing.name, timing.per_call(), timing.median);
+    }
+}
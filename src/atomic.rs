@@ -0,0 +1,289 @@
+//! Lock-free bit set
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// Atomic integer that can be used as a word of the *AtomicBitSet*. Values are passed as *u64* regardless of the word width
+pub trait AtomicWord: Send + Sync {
+    /// Bits count of the word
+    const BITS: u32;
+
+    /// Returns the word with all bits set to 0
+    fn zero() -> Self;
+
+    /// Loads the value of the word
+    fn load_bits(&self, ordering: Ordering) -> u64;
+
+    /// Performs *bitwise or* with the mask and returns the previous value
+    fn fetch_or_bits(&self, mask: u64, ordering: Ordering) -> u64;
+
+    /// Performs *bitwise and* with the mask and returns the previous value
+    fn fetch_and_bits(&self, mask: u64, ordering: Ordering) -> u64;
+
+    /// Stores the new value if the word still has the current value. Returns the previous value as *Ok* on success and as *Err* on failure
+    fn compare_exchange_bits(&self, current: u64, new: u64, success: Ordering, failure: Ordering) -> Result<u64, u64>;
+}
+
+macro_rules! impl_atomic_word {
+    ($atomic:ty, $int:ty) => {
+        impl AtomicWord for $atomic {
+            const BITS: u32 = <$int>::BITS;
+
+            fn zero() -> Self {
+                <$atomic>::new(0)
+            }
+
+            fn load_bits(&self, ordering: Ordering) -> u64 {
+                self.load(ordering) as u64
+            }
+
+            fn fetch_or_bits(&self, mask: u64, ordering: Ordering) -> u64 {
+                self.fetch_or(mask as $int, ordering) as u64
+            }
+
+            fn fetch_and_bits(&self, mask: u64, ordering: Ordering) -> u64 {
+                self.fetch_and(mask as $int, ordering) as u64
+            }
+
+            fn compare_exchange_bits(&self, current: u64, new: u64, success: Ordering, failure: Ordering) -> Result<u64, u64> {
+                self.compare_exchange_weak(current as $int, new as $int, success, failure)
+                    .map(|value| value as u64)
+                    .map_err(|value| value as u64)
+            }
+        }
+    };
+}
+
+impl_atomic_word!(AtomicU32, u32);
+impl_atomic_word!(AtomicU64, u64);
+
+/// Memory ordering used by the *AtomicBitSet* operations
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MemoryOrdering {
+    /// Only the atomicity of every single bit operation is guaranteed
+    Relaxed,
+    /// Setting a bit *acquires* and clearing a bit *releases*, so the bits can guard the data they allocate
+    #[default]
+    AcquireRelease,
+}
+
+impl MemoryOrdering {
+    /// Returns the ordering for the loads
+    fn load(self) -> Ordering {
+        match self {
+            MemoryOrdering::Relaxed => Ordering::Relaxed,
+            MemoryOrdering::AcquireRelease => Ordering::Acquire,
+        }
+    }
+
+    /// Returns the ordering for the read-modify-write operations
+    fn read_modify_write(self) -> Ordering {
+        match self {
+            MemoryOrdering::Relaxed => Ordering::Relaxed,
+            MemoryOrdering::AcquireRelease => Ordering::AcqRel,
+        }
+    }
+}
+
+/// Fixed size bit set which can be shared between threads without locking. Suits slot allocation
+/// # Examples
+/// ```
+/// # use understanding_bitwise::atomic::AtomicBitSet;
+/// let slots = AtomicBitSet::<std::sync::atomic::AtomicU64>::new(3);
+/// assert_eq!(slots.find_and_set_first_zero(), Some(0));
+/// assert_eq!(slots.find_and_set_first_zero(), Some(1));
+/// slots.clear(0);
+/// assert_eq!(slots.find_and_set_first_zero(), Some(0));
+/// assert_eq!(slots.find_and_set_first_zero(), Some(2));
+/// assert_eq!(slots.find_and_set_first_zero(), None);
+/// ```
+pub struct AtomicBitSet<W: AtomicWord = AtomicU32> {
+    words: Box<[W]>,
+    len: u32,
+    ordering: MemoryOrdering,
+}
+
+impl<W: AtomicWord> AtomicBitSet<W> {
+    /// Returns the set of the given bits count with all bits set to 0. Uses *MemoryOrdering::AcquireRelease*
+    /// # Arguments
+    /// * `len` - bits count
+    pub fn new(len: u32) -> Self {
+        let words_count = len.div_ceil(W::BITS);
+        Self {
+            words: (0..words_count).map(|_| W::zero()).collect(),
+            len,
+            ordering: MemoryOrdering::default(),
+        }
+    }
+
+    /// Returns the set that uses the given memory ordering
+    /// # Arguments
+    /// * `ordering` - memory ordering of the operations
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::atomic::{AtomicBitSet, MemoryOrdering};
+    /// let set = AtomicBitSet::<std::sync::atomic::AtomicU32>::new(8).with_ordering(MemoryOrdering::Relaxed);
+    /// assert_eq!(set.ordering(), MemoryOrdering::Relaxed);
+    /// ```
+    pub fn with_ordering(mut self, ordering: MemoryOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Returns the memory ordering of the operations
+    pub fn ordering(&self) -> MemoryOrdering {
+        self.ordering
+    }
+
+    /// Returns the bits count
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Returns true if the set has no bits
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Helper function. Returns the word and the mask of the bit or None if the index is out of range
+    /// # Arguments
+    /// * `index` - index of the bit
+    fn locate(&self, index: u32) -> Option<(&W, u64)> {
+        if index >= self.len {
+            return None;
+        }
+        Some((&self.words[(index / W::BITS) as usize], 1 << index % W::BITS))
+    }
+
+    /// Returns the value of the bit or None if the index is out of range
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn get(&self, index: u32) -> Option<bool> {
+        let (word, mask) = self.locate(index)?;
+        Some(word.load_bits(self.ordering.load()) & mask != 0)
+    }
+
+    /// Sets the bit to 1. Returns None if the index is out of range
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn set(&self, index: u32) -> Option<()> {
+        self.test_and_set(index).map(|_| ())
+    }
+
+    /// Sets the bit to 0. Returns None if the index is out of range
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn clear(&self, index: u32) -> Option<()> {
+        let (word, mask) = self.locate(index)?;
+        word.fetch_and_bits(! mask, self.ordering.read_modify_write());
+        Some(())
+    }
+
+    /// Sets the bit to 1 and returns its previous value or None if the index is out of range
+    /// # Arguments
+    /// * `index` - index of the bit
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::atomic::AtomicBitSet;
+    /// let set = AtomicBitSet::<std::sync::atomic::AtomicU32>::new(40);
+    /// assert_eq!(set.test_and_set(35), Some(false));
+    /// assert_eq!(set.test_and_set(35), Some(true));
+    /// assert_eq!(set.test_and_set(40), None);
+    /// ```
+    pub fn test_and_set(&self, index: u32) -> Option<bool> {
+        let (word, mask) = self.locate(index)?;
+        Some(word.fetch_or_bits(mask, self.ordering.read_modify_write()) & mask != 0)
+    }
+
+    /// Sets the lowest bit that is 0 to 1 and returns its index or None if all bits are 1.
+    /// Every word is retried with *compare and swap* until some bit of it is claimed or it gets full
+    pub fn find_and_set_first_zero(&self) -> Option<u32> {
+        for (word_index, word) in (0u32..).zip(self.words.iter()) {
+            let first_index = word_index * W::BITS;
+            // Bits beyond the length of the set are treated as already set
+            let valid_bits = (self.len - first_index).min(W::BITS);
+            let valid_mask = if valid_bits == u64::BITS { u64::MAX } else { (1u64 << valid_bits) - 1 };
+            let mut current = word.load_bits(Ordering::Relaxed);
+            loop {
+                let free = ! current & valid_mask;
+                if free == 0 {
+                    break;
+                }
+                // Isolates the lowest bit that is 0
+                let lowest = free & free.wrapping_neg();
+                match word.compare_exchange_bits(current, current | lowest, self.ordering.read_modify_write(), Ordering::Relaxed) {
+                    Ok(_) => return Some(first_index + lowest.trailing_zeros()),
+                    Err(actual) => current = actual,
+                }
+            }
+        }
+        None
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn general_test_set_clear<W: AtomicWord>() {
+        let set = AtomicBitSet::<W>::new(100);
+        assert_eq!(set.len(), 100);
+        for index in (0..100).step_by(3) {
+            assert_eq!(set.set(index), Some(()));
+        }
+        for index in 0..100 {
+            assert_eq!(set.get(index), Some(index % 3 == 0));
+        }
+        assert_eq!(set.clear(3), Some(()));
+        assert_eq!(set.get(3), Some(false));
+        assert_eq!(set.get(100), None);
+        assert_eq!(set.set(100), None);
+        assert_eq!(set.clear(u32::MAX), None);
+    }
+
+    #[test]
+    fn test_set_clear() {
+        general_test_set_clear::<AtomicU32>();
+        general_test_set_clear::<AtomicU64>();
+    }
+
+    fn general_test_find_and_set_first_zero<W: AtomicWord>() {
+        let set = AtomicBitSet::<W>::new(70).with_ordering(MemoryOrdering::Relaxed);
+        for index in 0..70 {
+            assert_eq!(set.find_and_set_first_zero(), Some(index));
+        }
+        assert_eq!(set.find_and_set_first_zero(), None);
+        set.clear(65);
+        set.clear(33);
+        assert_eq!(set.find_and_set_first_zero(), Some(33));
+        assert_eq!(set.find_and_set_first_zero(), Some(65));
+        assert_eq!(AtomicBitSet::<W>::new(0).find_and_set_first_zero(), None);
+    }
+
+    #[test]
+    fn test_find_and_set_first_zero() {
+        general_test_find_and_set_first_zero::<AtomicU32>();
+        general_test_find_and_set_first_zero::<AtomicU64>();
+    }
+
+    #[test]
+    fn test_concurrent_allocation() {
+        const SLOTS: u32 = 1000;
+        let set = Arc::new(AtomicBitSet::<AtomicU64>::new(SLOTS));
+        let handles: Vec<_> = (0..8).map(|_| {
+            let set = Arc::clone(&set);
+            thread::spawn(move || {
+                let mut claimed = Vec::new();
+                while let Some(index) = set.find_and_set_first_zero() {
+                    claimed.push(index);
+                }
+                claimed
+            })
+        }).collect();
+        let mut claimed: Vec<u32> = handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect();
+        claimed.sort_unstable();
+        assert_eq!(claimed, (0..SLOTS).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,283 @@
+//! Command line companion of the crate: bit operations on numbers given in binary, hexadecimal or decimal,
+//! printed as annotated binary. Built with the `cli` feature
+
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+use understanding_bitwise::diagram::{write_bit_diagram, write_bit_diagram_with_fields, write_bit_diff};
+use understanding_bitwise::evaluator::Evaluator;
+use understanding_bitwise::explained::Tracer;
+use understanding_bitwise::{binary_ones_count, hob, parse_number, set_bit, set_bits, swap_bits, unset_bit};
+
+const USAGE: &str = "\
+usage: bitwise [-q | --quiet] <command> <arguments>
+
+commands:
+  repr NUMBER                 bits of the number
+  set NUMBER INDEX            number with the bit set to 1
+  unset NUMBER INDEX          number with the bit set to 0
+  swap NUMBER INDEX1 INDEX2   number with the bits swapped
+  hob NUMBER                  index of the highest one
+  count NUMBER                count of ones
+  diff A B                    bits that differ
+  mask OFFSET WIDTH           mask of the bit field
+  repl                        playground evaluating expressions, :help lists its commands
+
+Numbers are binary (0b), hexadecimal (0x) or decimal. A NUMBER of - runs the command
+for every line of the standard input. --quiet prints only the result: the number in binary
+for repr, the Hamming distance for diff and a decimal number otherwise";
+
+const REPL_HELP: &str = "\
+Enter an expression like 'set_bit(x, 3) & ~0b1' or an assignment like 'x = 0xF0 >> 2'.
+Operators: ! ~ + - << >> & ^ | and parentheses, with the precedence of Rust.
+Functions: set_bit(x, i) unset_bit(x, i) invert_bit(x, i) swap_bits(x, i, j) remove_bit(x, i) hob(x) count(x).
+
+  :trace   turn printing of every step on or off
+  :vars    list the variables
+  :help    show this help
+  :quit    leave";
+
+/// Helper function. Returns the parsed number or the error naming the argument
+/// # Arguments
+/// * `text` - argument
+fn number(text: &str) -> Result<u32, String> {
+    parse_number(text).ok_or_else(|| format!("'{text}' isn't a 32-bit number"))
+}
+
+/// Helper function. Returns the parsed bit index or the error if it isn't a number below 32
+/// # Arguments
+/// * `text` - argument
+fn index(text: &str) -> Result<u32, String> {
+    number(text).ok().filter(|&index| index < u32::BITS).ok_or_else(|| format!("'{text}' isn't a bit index from 0 to 31"))
+}
+
+/// Helper function. Writes the change of the number made by a single bit operation, or just the result if quiet
+/// # Arguments
+/// * `before` - number before the operation
+/// * `after` - number after the operation
+/// * `quiet` - whether only the result is written
+/// * `out` - *Write* object that gets the output
+fn write_change<W: Write>(before: u32, after: u32, quiet: bool, out: &mut W) -> io::Result<()> {
+    if !quiet {
+        write_bit_diff(before, after, out)?;
+    }
+    writeln!(out, "{after}")
+}
+
+/// Helper function. Runs the command on the number
+/// # Arguments
+/// * `command` - name of the command
+/// * `value` - number argument
+/// * `rest` - arguments after the number
+/// * `quiet` - whether only the result is written
+/// * `out` - *Write* object that gets the output
+fn run_on<W: Write>(command: &str, value: u32, rest: &[&str], quiet: bool, out: &mut W) -> Result<(), String> {
+    let io_error = |error: io::Error| error.to_string();
+    match (command, rest) {
+        ("repr", []) => {
+            if quiet {
+                return writeln!(out, "{value:b}").map_err(io_error);
+            }
+            writeln!(out, "{value} = {value:#x}").map_err(io_error)?;
+            write_bit_diagram(value, out).map_err(io_error)
+        }
+        ("set", [bit]) => write_change(value, set_bit(value, index(bit)?).unwrap(), quiet, out).map_err(io_error),
+        ("unset", [bit]) => write_change(value, unset_bit(value, index(bit)?).unwrap(), quiet, out).map_err(io_error),
+        ("swap", [bit1, bit2]) => {
+            write_change(value, swap_bits(value, index(bit1)?, index(bit2)?).unwrap(), quiet, out).map_err(io_error)
+        }
+        ("hob", []) => {
+            let hob = hob(value).ok_or("0 has no hob")?;
+            if !quiet {
+                write_bit_diagram_with_fields(value, &[hob], out).map_err(io_error)?;
+            }
+            writeln!(out, "{hob}").map_err(io_error)
+        }
+        ("count", []) => {
+            if !quiet {
+                write_bit_diagram(value, out).map_err(io_error)?;
+            }
+            writeln!(out, "{}", binary_ones_count(value)).map_err(io_error)
+        }
+        ("diff", [other]) => {
+            let other = number(other)?;
+            if quiet {
+                return writeln!(out, "{}", (value ^ other).count_ones()).map_err(io_error);
+            }
+            let diff = write_bit_diff(value, other, out).map_err(io_error)?;
+            writeln!(out, "diff mask {:#x}, distance {}", diff.diff_mask, diff.distance).map_err(io_error)
+        }
+        ("mask", [width]) => {
+            let width = number(width)?;
+            let ones = u32::MAX.checked_shr(u32::BITS.saturating_sub(width)).unwrap_or(0);
+            let mask = set_bits(0, value, width, ones).ok_or(format!("the field of {width} bits at {value} doesn't fit"))?;
+            if !quiet {
+                write_bit_diagram_with_fields(mask, &[value, value + width], out).map_err(io_error)?;
+            }
+            writeln!(out, "{mask}").map_err(io_error)
+        }
+        ("repr" | "set" | "unset" | "swap" | "hob" | "count" | "diff" | "mask", _) => {
+            Err(format!("wrong count of arguments for {command}"))
+        }
+        _ => Err(format!("unknown command '{command}'")),
+    }
+}
+
+/// Helper function. Runs the read-evaluate-print loop over the lines of the input
+/// # Arguments
+/// * `input` - lines of expressions and commands
+/// * `quiet` - whether the prompt is omitted and the results are written in decimal only
+/// * `out` - *Write* object that gets the output
+fn repl<R: BufRead, W: Write>(input: R, quiet: bool, out: &mut W) -> io::Result<()> {
+    let prompt = |out: &mut W| if quiet { Ok(()) } else { write!(out, "> ").and_then(|()| out.flush()) };
+    let mut evaluator = Evaluator::new();
+    let mut trace = false;
+    prompt(out)?;
+    for line in input.lines() {
+        match line?.trim() {
+            "" => {}
+            ":quit" => break,
+            ":help" => writeln!(out, "{REPL_HELP}")?,
+            ":trace" => {
+                trace = !trace;
+                writeln!(out, "trace {}", if trace { "on" } else { "off" })?;
+            }
+            ":vars" => {
+                for (name, value) in evaluator.variables() {
+                    writeln!(out, "{name} = {value} = {value:#x}")?;
+                }
+            }
+            line => match evaluator.run(line) {
+                Ok(steps) => {
+                    let value = steps.last().expect("an expression has a step").value;
+                    if trace {
+                        Tracer::new(&mut *out).run(Some(steps))?;
+                    }
+                    if quiet {
+                        writeln!(out, "{value}")?;
+                    } else {
+                        writeln!(out, "{value} = {value:#x} = {value:#b}")?;
+                    }
+                }
+                Err(error) => writeln!(out, "error {error}")?,
+            },
+        }
+        prompt(out)?;
+    }
+    Ok(())
+}
+
+/// Helper function. Runs the command line, reading the numbers from the input if the number argument is -
+/// # Arguments
+/// * `args` - arguments without the program name
+/// * `input` - standard input
+/// * `out` - *Write* object that gets the output
+fn run<R: BufRead, W: Write>(args: &[String], input: R, out: &mut W) -> Result<(), String> {
+    let mut args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let quiet = matches!(args.first(), Some(&"-q" | &"--quiet"));
+    if quiet {
+        args.remove(0);
+    }
+    if args.as_slice() == ["repl"] {
+        return repl(input, quiet, out).map_err(|error| error.to_string());
+    }
+    let [command, value, rest @ ..] = args.as_slice() else {
+        return Err("missing command or number".to_owned());
+    };
+    if *value != "-" {
+        return run_on(command, number(value)?, rest, quiet, out);
+    }
+    for line in input.lines() {
+        let line = line.map_err(|error| error.to_string())?;
+        if !line.trim().is_empty() {
+            run_on(command, number(line.trim())?, rest, quiet, out)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if matches!(args.first().map(String::as_str), Some("-h" | "--help")) {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match run(&args, io::stdin().lock(), &mut io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("bitwise: {error}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(args: &str, input: &str) -> Result<String, String> {
+        let args: Vec<String> = args.split_whitespace().map(str::to_owned).collect();
+        let mut out = Vec::new();
+        run(&args, input.as_bytes(), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_quiet() {
+        assert_eq!(output("-q repr 0xA", ""), Ok("1010\n".to_owned()));
+        assert_eq!(output("-q set 0b1000 0", ""), Ok("9\n".to_owned()));
+        assert_eq!(output("--quiet unset 15 3", ""), Ok("7\n".to_owned()));
+        assert_eq!(output("-q swap 0b01 0 1", ""), Ok("2\n".to_owned()));
+        assert_eq!(output("-q hob 0x80", ""), Ok("7\n".to_owned()));
+        assert_eq!(output("-q count 0xFF", ""), Ok("8\n".to_owned()));
+        assert_eq!(output("-q diff 0b1100 0b1010", ""), Ok("2\n".to_owned()));
+        assert_eq!(output("-q mask 4 8", ""), Ok("4080\n".to_owned()));
+        assert_eq!(output("-q mask 0 32", ""), Ok(format!("{}\n", u32::MAX)));
+        // Every line of the input
+        assert_eq!(output("-q hob -", "1\n\n0x10\n0b100\n"), Ok("0\n4\n2\n".to_owned()));
+    }
+
+    #[test]
+    fn test_annotated() {
+        let text = output("set 0b1000 0", "").unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines, [format!("{:032b}", 8).as_str(), &format!("{:032b}", 9), &format!("{:>32}", "^"), "9"]);
+        let text = output("repr 255", "").unwrap();
+        assert!(text.starts_with("255 = 0xff\n31 30"));
+        let text = output("diff 1 3", "").unwrap();
+        assert!(text.ends_with("diff mask 0x2, distance 1\n"));
+        let text = output("mask 4 4", "").unwrap();
+        assert_eq!(text.lines().next().unwrap().matches('|').count(), 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(output("", "").is_err());
+        assert!(output("-q", "").is_err());
+        assert!(output("frobnicate 1", "").unwrap_err().contains("unknown command"));
+        assert!(output("set 1", "").unwrap_err().contains("wrong count"));
+        assert!(output("set 1 32", "").unwrap_err().contains("bit index"));
+        assert!(output("repr 0x1_0000_0000", "").unwrap_err().contains("32-bit number"));
+        assert!(output("hob 0", "").is_err());
+        assert!(output("mask 30 4", "").unwrap_err().contains("doesn't fit"));
+        assert!(output("count -", "7\nseven\n").is_err());
+        assert!(output("repl 1", "").is_err());
+    }
+
+    #[test]
+    fn test_repl() {
+        let text = output("-q repl", "x = 0b1010\n\nset_bit(x, 0) ^ 1\ny = x << 1\nx +\n:vars\n:quit\n1\n").unwrap();
+        assert_eq!(text, "10\n10\n20\nerror at 3: expected a number, a variable or '('\nx = 10 = 0xa\ny = 20 = 0x14\n");
+        let text = output("repl", "1 << 4\n").unwrap();
+        assert_eq!(text, "> 16 = 0x10 = 0b10000\n> ");
+        // Every step of the expression
+        let text = output("-q repl", ":trace\n~0 >> 28\n:trace\n2\n").unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "trace on");
+        assert_eq!(lines[1], format!("{:032b}  0", 0));
+        assert_eq!(lines[3], format!("{:032b}  ~0", u32::MAX));
+        assert_eq!(lines[7], format!("{:032b}  ~0 >> 28", 15));
+        assert_eq!(lines[8..], ["", "15", "trace off", "2"]);
+        assert!(output("-q repl", ":help\n").unwrap().contains(":trace"));
+    }
+}
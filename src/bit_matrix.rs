@@ -0,0 +1,410 @@
+//! Matrices over [*GF(2)*](https://en.wikipedia.org/wiki/GF(2)), the field of the bits with XOR as the addition
+//! and AND as the multiplication. Rows are u64 words, the column *j* is the bit *j*, so up to 64 columns.
+//! A vector is a u64 too: a matrix times a vector takes the parity of the AND of every row with the vector.
+//! Shifts, rotations, XORs of them, LFSR and CRC steps are linear maps over GF(2), so their compositions and powers
+//! are matrix products and powers. XOR row reduction (Gaussian elimination) gives the rank, solutions of linear
+//! bit-equation systems and the nullspace
+
+use std::fmt;
+
+/// Count of rows or columns of the largest matrix
+pub const MAX_SIZE: u32 = u64::BITS;
+
+/// Matrix over GF(2)
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bit_matrix::BitMatrix;
+/// // Rotation of 4 bits by one to the left
+/// let rotate = BitMatrix::from_linear_map(4, |vector| (vector << 1 | vector >> 3) & 0xF).unwrap();
+/// assert_eq!(rotate.apply(0b1001), 0b0011);
+/// assert_eq!(rotate.pow(4).unwrap(), BitMatrix::identity(4).unwrap());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BitMatrix {
+    rows: Vec<u64>,
+    columns: u32,
+}
+
+impl BitMatrix {
+    /// Returns the matrix or None if the rows or the columns count is above *MAX_SIZE* or a row has ones beyond the columns
+    /// # Arguments
+    /// * `rows` - rows of the matrix, the column *j* is the bit *j*
+    /// * `columns` - count of columns
+    pub fn new(rows: Vec<u64>, columns: u32) -> Option<Self> {
+        if columns > MAX_SIZE || rows.len() > MAX_SIZE as usize || rows.iter().any(|&row| row & ! column_mask(columns) != 0) {
+            return None;
+        }
+        Some(BitMatrix { rows, columns })
+    }
+
+    /// Returns the matrix of zeros or None if the rows or the columns count is above *MAX_SIZE*
+    /// # Arguments
+    /// * `rows` - count of rows
+    /// * `columns` - count of columns
+    pub fn zero(rows: usize, columns: u32) -> Option<Self> {
+        Self::new(vec![0; rows], columns)
+    }
+
+    /// Returns the identity matrix or None if the size is above *MAX_SIZE*
+    /// # Arguments
+    /// * `size` - count of rows and columns
+    pub fn identity(size: u32) -> Option<Self> {
+        Self::new((0..size.min(MAX_SIZE)).map(|index| 1 << index).collect(), size)
+    }
+
+    /// Returns the matrix of the linear map or None if the size is above *MAX_SIZE*.
+    /// The column *j* is the image of the unit vector *1 << j*; only the lowest *size* bits of the images are used
+    /// # Arguments
+    /// * `size` - count of rows and columns
+    /// * `map` - linear map of the vectors of *size* bits
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::bit_matrix::BitMatrix;
+    /// let shift = BitMatrix::from_linear_map(8, |vector| vector << 1).unwrap();
+    /// assert_eq!(shift.apply(0b1000_0001), 0b0000_0010);
+    /// ```
+    pub fn from_linear_map(size: u32, map: impl Fn(u64) -> u64) -> Option<Self> {
+        if size > MAX_SIZE {
+            return None;
+        }
+        let images: Vec<u64> = (0..size).map(|column| map(1 << column) & column_mask(size)).collect();
+        let rows = (0..size).map(|row| {
+            images.iter().enumerate().fold(0, |result, (column, &image)| result | (image >> row & 1) << column)
+        }).collect();
+        Some(BitMatrix { rows, columns: size })
+    }
+
+    /// Returns the rows
+    pub fn rows(&self) -> &[u64] {
+        &self.rows
+    }
+
+    /// Returns the count of rows
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the count of columns
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Returns the entry or None if the position is out of the matrix
+    /// # Arguments
+    /// * `row` - index of the row
+    /// * `column` - index of the column
+    pub fn get(&self, row: usize, column: u32) -> Option<bool> {
+        (column < self.columns).then(|| self.rows.get(row).map(|&bits| bits >> column & 1 == 1))?
+    }
+
+    /// Returns the transposed matrix
+    pub fn transpose(&self) -> Self {
+        let rows = (0..self.columns).map(|column| {
+            self.rows.iter().enumerate().fold(0, |result, (index, &row)| result | (row >> column & 1) << index)
+        }).collect();
+        BitMatrix { rows, columns: self.rows.len() as u32 }
+    }
+
+    /// Returns the product of the vector (a column) by the matrix: the bit *i* is the parity of the AND
+    /// of the row *i* with the vector. Bits of the vector beyond the columns are ignored
+    /// # Arguments
+    /// * `vector` - vector to work with
+    pub fn apply(&self, vector: u64) -> u64 {
+        self.rows.iter().enumerate().fold(0, |result, (index, &row)| result | (((row & vector).count_ones() & 1) as u64) << index)
+    }
+
+    /// Returns the product of the matrices or None if the columns count of this one differs from the rows count of the other.
+    /// Every entry is the parity of the AND of a row of this matrix with a column of the other one
+    /// # Arguments
+    /// * `other` - right factor
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::bit_matrix::BitMatrix;
+    /// let a = BitMatrix::new(vec![0b11, 0b01], 2).unwrap();
+    /// let b = BitMatrix::new(vec![0b10, 0b11], 2).unwrap();
+    /// assert_eq!(a.multiply(&b).unwrap().rows(), [0b01, 0b10]);
+    /// ```
+    pub fn multiply(&self, other: &BitMatrix) -> Option<BitMatrix> {
+        if self.columns as usize != other.rows.len() {
+            return None;
+        }
+        let other_columns = other.transpose();
+        let rows = self.rows.iter().map(|&row| other_columns.apply(row)).collect();
+        Some(BitMatrix { rows, columns: other.columns })
+    }
+
+    /// Returns the matrix raised to the power by repeated squaring or None if the matrix isn't square
+    /// # Arguments
+    /// * `exponent` - power
+    pub fn pow(&self, exponent: u64) -> Option<BitMatrix> {
+        if self.columns as usize != self.rows.len() {
+            return None;
+        }
+        let mut result = BitMatrix::identity(self.columns)?;
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent != 0 {
+            if exponent & 1 == 1 {
+                result = result.multiply(&base)?;
+            }
+            base = base.multiply(&base)?;
+            exponent >>= 1;
+        }
+        Some(result)
+    }
+
+    /// Helper function. Returns the rows in reduced row echelon form, the target bits permuted and XORed along
+    /// with the rows, and the pivot column of every nonzero row
+    /// # Arguments
+    /// * `target` - right-hand side, one bit per row
+    fn row_reduce(&self, target: u64) -> (Vec<u64>, u64, Vec<u32>) {
+        let mut rows = self.rows.clone();
+        let mut target = target;
+        let mut pivots = Vec::new();
+        for column in 0..self.columns {
+            let pivot_row = pivots.len();
+            let Some(found) = (pivot_row..rows.len()).find(|&index| rows[index] >> column & 1 == 1) else {
+                continue;
+            };
+            rows.swap(pivot_row, found);
+            // Swap of the target bits of both rows, see *swap_bits_xor*
+            let difference = (target >> pivot_row ^ target >> found) & 1;
+            target ^= difference << pivot_row | difference << found;
+            let pivot = rows[pivot_row];
+            let pivot_target = target >> pivot_row & 1;
+            for (index, row) in rows.iter_mut().enumerate() {
+                if index != pivot_row && *row >> column & 1 == 1 {
+                    *row ^= pivot;
+                    target ^= pivot_target << index;
+                }
+            }
+            pivots.push(column);
+        }
+        (rows, target, pivots)
+    }
+
+    /// Returns the count of linearly independent rows (equal to the count of independent columns)
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::bit_matrix::BitMatrix;
+    /// // The third row is the XOR of the first two
+    /// assert_eq!(BitMatrix::new(vec![0b011, 0b110, 0b101], 3).unwrap().rank(), 2);
+    /// ```
+    pub fn rank(&self) -> u32 {
+        self.row_reduce(0).2.len() as u32
+    }
+
+    /// Returns a vector *x* such that the matrix applied to it gives the target, or None if there is no such vector.
+    /// Free variables are 0; add nullspace vectors to get the other solutions
+    /// # Arguments
+    /// * `target` - right-hand side, one bit per row
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::bit_matrix::BitMatrix;
+    /// // x0 ^ x1 = 1, x1 ^ x2 = 0, x0 ^ x2 = 1
+    /// let matrix = BitMatrix::new(vec![0b011, 0b110, 0b101], 3).unwrap();
+    /// let solution = matrix.solve(0b101).unwrap();
+    /// assert_eq!(matrix.apply(solution), 0b101);
+    /// assert_eq!(matrix.solve(0b111), None);
+    /// ```
+    pub fn solve(&self, target: u64) -> Option<u64> {
+        let (_, target, pivots) = self.row_reduce(target);
+        // Zero rows below the pivots must have zero targets
+        let unused = target & column_mask(self.rows.len() as u32);
+        if unused.checked_shr(pivots.len() as u32).unwrap_or(0) != 0 {
+            return None;
+        }
+        Some(pivots.iter().enumerate().fold(0, |solution, (index, &column)| solution | (target >> index & 1) << column))
+    }
+
+    /// Returns the basis of the nullspace: vectors mapped to 0, one per free column.
+    /// Their XOR combinations are all vectors mapped to 0
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::bit_matrix::BitMatrix;
+    /// let matrix = BitMatrix::new(vec![0b011, 0b110, 0b101], 3).unwrap();
+    /// assert_eq!(matrix.nullspace(), vec![0b111]);
+    /// ```
+    pub fn nullspace(&self) -> Vec<u64> {
+        let (rows, _, pivots) = self.row_reduce(0);
+        let pivot_mask = pivots.iter().fold(0u64, |mask, &column| mask | 1 << column);
+        (0..self.columns).filter(|&column| pivot_mask >> column & 1 == 0).map(|free| {
+            // Every pivot variable cancels the free variable in its row
+            pivots.iter().enumerate().fold(1 << free, |vector, (index, &column)| vector | (rows[index] >> free & 1) << column)
+        }).collect()
+    }
+}
+
+impl fmt::Display for BitMatrix {
+    /// Writes every row on its own line, the column 0 first
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, &row) in self.rows.iter().enumerate() {
+            if index != 0 {
+                writeln!(f)?;
+            }
+            for column in 0..self.columns {
+                write!(f, "{}", row >> column & 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Helper function. Returns the mask of the lowest *columns* bits
+/// # Arguments
+/// * `columns` - count of columns up to 64
+fn column_mask(columns: u32) -> u64 {
+    u64::MAX.checked_shr(u64::BITS - columns).unwrap_or(0)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lfsr::{Lfsr, LfsrConfig};
+    use crate::prng::{xorshift32_step, Xorshift32, XORSHIFT32_SHIFTS};
+    use crate::xor_basis::XorBasis;
+
+    fn random_matrix(rng: &mut Xorshift32, rows: usize, columns: u32) -> BitMatrix {
+        let rows = (0..rows).map(|_| ((rng.next_u32() as u64) << 32 | rng.next_u32() as u64) & column_mask(columns)).collect();
+        BitMatrix::new(rows, columns).unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(BitMatrix::new(vec![0b100], 2).is_none());
+        assert!(BitMatrix::new(vec![], 65).is_none());
+        assert!(BitMatrix::identity(65).is_none());
+        assert!(BitMatrix::zero(65, 1).is_none());
+        assert!(BitMatrix::new(vec![1; 65], 1).is_none());
+        let matrix = BitMatrix::new(vec![u64::MAX, 1], 64).unwrap();
+        assert_eq!((matrix.row_count(), matrix.columns()), (2, 64));
+        assert_eq!(matrix.get(1, 0), Some(true));
+        assert_eq!(matrix.get(1, 1), Some(false));
+        assert_eq!(matrix.get(2, 0), None);
+        assert_eq!(matrix.get(0, 64), None);
+        assert_eq!(BitMatrix::zero(3, 2).unwrap().rows(), [0, 0, 0]);
+        assert_eq!(BitMatrix::new(vec![0b01, 0b11], 2).unwrap().to_string(), "10\n11");
+    }
+
+    #[test]
+    fn test_multiply() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for (rows, inner, columns) in [(3, 5, 7), (64, 64, 64), (1, 64, 1), (10, 1, 10)] {
+            let a = random_matrix(&mut rng, rows, inner);
+            let b = random_matrix(&mut rng, inner as usize, columns);
+            let product = a.multiply(&b).unwrap();
+            assert_eq!((product.row_count(), product.columns()), (rows, columns));
+            for vector in [0, 1, 0xDEAD_BEEF_1234_5678 & column_mask(columns)] {
+                assert_eq!(product.apply(vector), a.apply(b.apply(vector)));
+            }
+            // (AB)ᵀ = BᵀAᵀ
+            let transposed = b.transpose().multiply(&a.transpose()).unwrap();
+            assert_eq!(product.transpose(), transposed);
+            assert!(b.multiply(&random_matrix(&mut rng, columns as usize - 1, 3)).is_none());
+        }
+        let square = random_matrix(&mut rng, 20, 20);
+        assert_eq!(square.multiply(&BitMatrix::identity(20).unwrap()).unwrap(), square);
+    }
+
+    #[test]
+    fn test_pow() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        let matrix = random_matrix(&mut rng, 16, 16);
+        let mut power = BitMatrix::identity(16).unwrap();
+        for exponent in 0..20 {
+            assert_eq!(matrix.pow(exponent).unwrap(), power);
+            power = power.multiply(&matrix).unwrap();
+        }
+        assert!(BitMatrix::zero(2, 3).unwrap().pow(2).is_none());
+    }
+
+    #[test]
+    fn test_linear_maps() {
+        let step = |vector| xorshift32_step(vector as u32, XORSHIFT32_SHIFTS) as u64;
+        let xorshift = BitMatrix::from_linear_map(32, step).unwrap();
+        for state in [1, 0xDEAD_BEEF, u32::MAX as u64] {
+            assert_eq!(xorshift.apply(state), step(state));
+            assert_eq!(xorshift.pow(3).unwrap().apply(state), step(step(step(state))));
+        }
+        // Full period 2³² - 1 of xorshift32: Tᵖᵉʳⁱᵒᵈ = I, and no proper divisor of it works
+        let identity = BitMatrix::identity(32).unwrap();
+        assert_eq!(xorshift.pow(u32::MAX as u64).unwrap(), identity);
+        for prime in [3, 5, 17, 257, 65_537] {
+            assert_ne!(xorshift.pow(u32::MAX as u64 / prime).unwrap(), identity);
+        }
+        // Jump ahead of an LFSR
+        let lfsr = Lfsr::new(16, 0xB400, 1, LfsrConfig::Galois).unwrap();
+        let galois = BitMatrix::from_linear_map(16, |vector| vector >> 1 ^ 0xB400 * (vector & 1)).unwrap();
+        let mut stepped = lfsr;
+        (0..1000).for_each(|_| { stepped.next_bit(); });
+        assert_eq!(galois.pow(1000).unwrap().apply(lfsr.state() as u64), stepped.state() as u64);
+        assert_eq!(galois.pow(0xFFFF).unwrap(), BitMatrix::identity(16).unwrap());
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for (rows, columns) in [(5, 5), (32, 32), (10, 32), (32, 10), (64, 64)] {
+            let matrix = random_matrix(&mut rng, rows, columns);
+            let mut basis = XorBasis::new();
+            for &row in matrix.rows() {
+                basis.insert(row as u32);
+            }
+            if columns <= 32 {
+                assert_eq!(matrix.rank(), basis.rank());
+            }
+            assert_eq!(matrix.rank(), matrix.transpose().rank());
+        }
+        assert_eq!(BitMatrix::identity(64).unwrap().rank(), 64);
+        assert_eq!(BitMatrix::zero(3, 3).unwrap().rank(), 0);
+    }
+
+    #[test]
+    fn test_solve_and_nullspace() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for (rows, columns) in [(5, 5), (8, 3), (3, 8), (20, 20), (64, 64), (64, 40)] {
+            for _ in 0..10 {
+                let matrix = random_matrix(&mut rng, rows, columns);
+                let nullspace = matrix.nullspace();
+                assert_eq!(nullspace.len() as u32, columns - matrix.rank());
+                for &vector in &nullspace {
+                    assert_eq!(matrix.apply(vector), 0);
+                }
+                let combined = nullspace.iter().fold(0, |combined, &vector| combined ^ vector);
+                assert_eq!(matrix.apply(combined), 0);
+                // Images of vectors are always solvable
+                let vector = (rng.next_u32() as u64) << 32 | rng.next_u32() as u64;
+                let target = matrix.apply(vector);
+                let solution = matrix.solve(target).unwrap();
+                assert_eq!(matrix.apply(solution), target);
+                // Rows beyond the rank constrain the target
+                if let Some(target) = (0..64).map(|bit| target ^ 1 << bit).find(|&target| matrix.solve(target).is_none()) {
+                    assert!(matrix.rank() < rows as u32 && target & ! column_mask(rows as u32) == 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lights_out() {
+        // Pressing a light toggles it and its neighbours
+        for (size, rank) in [(3, 9), (4, 12), (5, 23)] {
+            let toggles = |presses: u64| (0..size * size).filter(|&cell| presses >> cell & 1 == 1).fold(0u64, |lights, cell| {
+                let (row, column) = (cell / size, cell % size);
+                let mut toggled = lights ^ 1 << cell;
+                if row > 0 { toggled ^= 1 << (cell - size); }
+                if row + 1 < size { toggled ^= 1 << (cell + size); }
+                if column > 0 { toggled ^= 1 << (cell - 1); }
+                if column + 1 < size { toggled ^= 1 << (cell + 1); }
+                toggled
+            });
+            let matrix = BitMatrix::from_linear_map(size * size, toggles).unwrap();
+            assert_eq!(matrix.rank(), rank);
+            assert_eq!(matrix.nullspace().len() as u32, size * size - rank);
+            let all_on = column_mask(size * size);
+            let presses = matrix.solve(all_on).unwrap();
+            assert_eq!(toggles(presses), all_on);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Fixed width bit strings with operators, so expressions read like the math they represent
+
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+/// Bit string of the explicit width from 1 to 64. Bits above the width are always 0.
+/// Binary operators panic if the widths differ, shifts drop the bits moved out of the width
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bit_string::BitString;
+/// let a = BitString::new(0b1100, 4).unwrap();
+/// let b = BitString::new(0b1010, 4).unwrap();
+/// assert_eq!((a & b).to_string(), "1000");
+/// assert_eq!((a ^ b).to_string(), "0110");
+/// assert_eq!((! a).to_string(), "0011");
+/// assert_eq!((a << 1).to_string(), "1000");
+/// assert_eq!(a.checked_shl(1), None);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct BitString {
+    bits: u64,
+    width: u32,
+}
+
+impl BitString {
+    /// Returns the bit string or None if the width isn't in 1..=64 or the bits don't fit into the width
+    /// # Arguments
+    /// * `bits` - value of the bit string
+    /// * `width` - bits count
+    pub fn new(bits: u64, width: u32) -> Option<Self> {
+        let string = Self::zeros(width)?;
+        (bits & ! string.mask() == 0).then_some(BitString { bits, width })
+    }
+
+    /// Returns the bit string of zeros or None if the width isn't in 1..=64
+    /// # Arguments
+    /// * `width` - bits count
+    pub fn zeros(width: u32) -> Option<Self> {
+        (1..=u64::BITS).contains(&width).then_some(BitString { bits: 0, width })
+    }
+
+    /// Returns the bit string of ones or None if the width isn't in 1..=64
+    /// # Arguments
+    /// * `width` - bits count
+    pub fn ones(width: u32) -> Option<Self> {
+        Self::zeros(width).map(|string| BitString { bits: string.mask(), width })
+    }
+
+    /// Helper function. Returns the mask of the bits inside the width
+    fn mask(&self) -> u64 {
+        u64::MAX >> (u64::BITS - self.width)
+    }
+
+    /// Helper function. Returns the bit string of the same width holding the bits inside the width
+    /// # Arguments
+    /// * `bits` - bits to be truncated
+    fn truncated(&self, bits: u64) -> Self {
+        BitString { bits: bits & self.mask(), width: self.width }
+    }
+
+    /// Returns the value of the bit string
+    pub fn bits(&self) -> u64 {
+        self.bits
+    }
+
+    /// Returns the bits count
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the value of the specific bit or None if the index is out of the width
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn get(&self, index: u32) -> Option<bool> {
+        (index < self.width).then(|| self.bits >> index & 1 == 1)
+    }
+
+    /// Returns the bit string shifted left or None if some ones would be shifted out of the width
+    /// # Arguments
+    /// * `count` - number of positions to be shifted by
+    pub fn checked_shl(self, count: u32) -> Option<Self> {
+        let shifted = self << count;
+        (shifted >> count == self).then_some(shifted)
+    }
+
+    /// Returns the bit string shifted right or None if some ones would be shifted out of the width
+    /// # Arguments
+    /// * `count` - number of positions to be shifted by
+    pub fn checked_shr(self, count: u32) -> Option<Self> {
+        let shifted = self >> count;
+        (shifted << count == self).then_some(shifted)
+    }
+
+    /// Helper function. Panics if the widths of the operands differ
+    /// # Arguments
+    /// * `other` - other operand
+    /// * `operation` - name of the operation
+    fn assert_same_width(&self, other: &Self, operation: &str) {
+        assert_eq!(self.width, other.width, "{operation} of bit strings of different widths");
+    }
+}
+
+impl BitAnd for BitString {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.assert_same_width(&rhs, "AND");
+        BitString { bits: self.bits & rhs.bits, width: self.width }
+    }
+}
+
+impl BitOr for BitString {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.assert_same_width(&rhs, "OR");
+        BitString { bits: self.bits | rhs.bits, width: self.width }
+    }
+}
+
+impl BitXor for BitString {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        self.assert_same_width(&rhs, "XOR");
+        BitString { bits: self.bits ^ rhs.bits, width: self.width }
+    }
+}
+
+impl Not for BitString {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.truncated(! self.bits)
+    }
+}
+
+impl Shl<u32> for BitString {
+    type Output = Self;
+
+    /// Shifts left, the bits moved out of the width are dropped. Shifting by the width or more gives zeros
+    fn shl(self, count: u32) -> Self {
+        self.truncated(self.bits.checked_shl(count).unwrap_or(0))
+    }
+}
+
+impl Shr<u32> for BitString {
+    type Output = Self;
+
+    /// Shifts right, the bits moved out of the width are dropped. Shifting by the width or more gives zeros
+    fn shr(self, count: u32) -> Self {
+        BitString { bits: self.bits.checked_shr(count).unwrap_or(0), width: self.width }
+    }
+}
+
+impl fmt::Display for BitString {
+    /// Writes all *width* digits, the highest bit first
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:0width$b}", self.bits, width = self.width as usize)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(BitString::new(0b111, 3).map(|string| string.bits()), Some(0b111));
+        assert_eq!(BitString::new(0b1000, 3), None);
+        assert_eq!(BitString::new(0, 0), None);
+        assert_eq!(BitString::new(0, 65), None);
+        assert_eq!(BitString::ones(64).unwrap().bits(), u64::MAX);
+        assert_eq!(BitString::ones(5).unwrap().to_string(), "11111");
+        assert_eq!(BitString::zeros(3).unwrap().to_string(), "000");
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = BitString::new(0b0110, 4).unwrap();
+        let b = BitString::new(0b0011, 4).unwrap();
+        assert_eq!((a | b).bits(), 0b0111);
+        assert_eq!((a & b).bits(), 0b0010);
+        assert_eq!((a ^ b).bits(), 0b0101);
+        assert_eq!((! a).bits(), 0b1001);
+        assert_eq!(! BitString::ones(64).unwrap(), BitString::zeros(64).unwrap());
+        assert_eq!((a << 2).bits(), 0b1000);
+        assert_eq!((a >> 2).bits(), 0b0001);
+        assert_eq!((a << 4).bits(), 0);
+        assert_eq!((a >> 100).bits(), 0);
+        assert_eq!(a.get(1), Some(true));
+        assert_eq!(a.get(4), None);
+    }
+
+    #[test]
+    fn test_checked_shifts() {
+        let a = BitString::new(0b0110, 4).unwrap();
+        assert_eq!(a.checked_shl(1).map(|string| string.bits()), Some(0b1100));
+        assert_eq!(a.checked_shl(2), None);
+        assert_eq!(a.checked_shr(1).map(|string| string.bits()), Some(0b0011));
+        assert_eq!(a.checked_shr(2), None);
+        assert_eq!(BitString::zeros(8).unwrap().checked_shl(100), BitString::zeros(8));
+    }
+
+    #[test]
+    #[should_panic(expected = "XOR of bit strings of different widths")]
+    fn test_width_mismatch() {
+        let _ = BitString::zeros(3).unwrap() ^ BitString::zeros(4).unwrap();
+    }
+}
@@ -0,0 +1,233 @@
+//! [*Bitboards*](https://www.chessprogramming.org/Bitboards): a set of chess squares in u64, one bit per square.
+//! Square *a1* is the bit 0, *h1* the bit 7 and *h8* the bit 63 (little-endian rank-file mapping),
+//! so moving one file east is a shift by 1 and one rank north a shift by 8.
+//! A shift along a rank wraps from the *h* file to the *a* file of the next rank; masking out the files
+//! the pieces can't come from before the shift prevents it
+
+use std::fmt::Write;
+
+/// Squares of the *a* file
+pub const FILE_A: u64 = 0x0101_0101_0101_0101;
+
+/// Squares of the *h* file
+pub const FILE_H: u64 = FILE_A << 7;
+
+/// Squares of the first rank
+pub const RANK_1: u64 = 0xFF;
+
+/// Squares of the eighth rank
+pub const RANK_8: u64 = RANK_1 << 56;
+
+/// Squares out of the *a* file
+const NOT_FILE_A: u64 = ! FILE_A;
+
+/// Squares out of the *a* and *b* files
+const NOT_FILE_AB: u64 = ! (FILE_A | FILE_A << 1);
+
+/// Squares out of the *h* file
+const NOT_FILE_H: u64 = ! FILE_H;
+
+/// Squares out of the *g* and *h* files
+const NOT_FILE_GH: u64 = ! (FILE_H | FILE_H >> 1);
+
+/// Returns the index of the square or None if the file or the rank isn't in 0..8
+/// # Arguments
+/// * `file` - file from 0 (*a*) to 7 (*h*)
+/// * `rank` - rank from 0 (first) to 7 (eighth)
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitboard::square;
+/// assert_eq!(square(4, 3), Some(28)); // e4
+/// assert_eq!(square(8, 0), None);
+/// ```
+pub fn square(file: u32, rank: u32) -> Option<u32> {
+    (file < 8 && rank < 8).then_some(rank * 8 + file)
+}
+
+/// Returns the bitboard with the single square or None if the square isn't in 0..64
+/// # Arguments
+/// * `square` - index of the square
+pub fn square_mask(square: u32) -> Option<u64> {
+    1u64.checked_shl(square)
+}
+
+/// Returns the squares of the file or None if the file isn't in 0..8
+/// # Arguments
+/// * `file` - file from 0 (*a*) to 7 (*h*)
+pub fn file_mask(file: u32) -> Option<u64> {
+    (file < 8).then(|| FILE_A << file)
+}
+
+/// Returns the squares of the rank or None if the rank isn't in 0..8
+/// # Arguments
+/// * `rank` - rank from 0 (first) to 7 (eighth)
+pub fn rank_mask(rank: u32) -> Option<u64> {
+    (rank < 8).then(|| RANK_1 << (rank * 8))
+}
+
+/// Returns the squares attacked by the knights. All 8 jumps are shifts of the whole set at once,
+/// each with the knights that would wrap to the other edge masked out
+/// # Arguments
+/// * `knights` - squares of the knights
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitboard::knight_attacks;
+/// // Knight on b1 attacks a3, c3 and d2
+/// assert_eq!(knight_attacks(1 << 1), 1 << 16 | 1 << 18 | 1 << 11);
+/// ```
+pub fn knight_attacks(knights: u64) -> u64 {
+    let east_one = knights & NOT_FILE_H;
+    let east_two = knights & NOT_FILE_GH;
+    let west_one = knights & NOT_FILE_A;
+    let west_two = knights & NOT_FILE_AB;
+    east_one << 17 | east_one >> 15 | west_one << 15 | west_one >> 17
+        | east_two << 10 | east_two >> 6 | west_two << 6 | west_two >> 10
+}
+
+/// Returns the squares attacked by the kings: the set is smeared one file east and west, then one rank north and south
+/// # Arguments
+/// * `kings` - squares of the kings
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitboard::king_attacks;
+/// // King on a1 attacks a2, b1 and b2
+/// assert_eq!(king_attacks(1), 0b11_0000_0010);
+/// ```
+pub fn king_attacks(kings: u64) -> u64 {
+    let sideways = (kings & NOT_FILE_H) << 1 | (kings & NOT_FILE_A) >> 1;
+    let row = kings | sideways;
+    sideways | row << 8 | row >> 8
+}
+
+/// Removes the lowest square from the bitboard and returns its index, or None if the bitboard is empty.
+/// *board & (board - 1)* clears the lowest one
+/// # Arguments
+/// * `board` - bitboard to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitboard::pop_lsb;
+/// let mut board = 0b1010_0000;
+/// assert_eq!(pop_lsb(&mut board), Some(5));
+/// assert_eq!(board, 0b1000_0000);
+/// ```
+pub fn pop_lsb(board: &mut u64) -> Option<u32> {
+    if *board == 0 {
+        return None;
+    }
+    let square = board.trailing_zeros();
+    *board &= *board - 1;
+    Some(square)
+}
+
+/// Returns the iterator over the indexes of the squares from the lowest one
+/// # Arguments
+/// * `board` - bitboard to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitboard::squares;
+/// assert_eq!(squares(0x8000_0000_0000_0081).collect::<Vec<_>>(), vec![0, 7, 63]);
+/// ```
+pub fn squares(board: u64) -> impl Iterator<Item = u32> {
+    let mut board = board;
+    std::iter::from_fn(move || pop_lsb(&mut board))
+}
+
+/// Returns the board drawn as 8 lines from the eighth rank down, *x* for a square of the set and *.* otherwise,
+/// followed by the line of the file letters
+/// # Arguments
+/// * `board` - bitboard to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitboard::{board_to_string, RANK_1};
+/// let text = board_to_string(RANK_1 | 1 << 63);
+/// assert!(text.starts_with("8 . . . . . . . x\n"));
+/// assert!(text.ends_with("1 x x x x x x x x\n  a b c d e f g h\n"));
+/// ```
+pub fn board_to_string(board: u64) -> String {
+    let mut text = String::with_capacity(18 * 9);
+    for rank in (0..8).rev() {
+        write!(text, "{}", rank + 1).unwrap();
+        for file in 0..8 {
+            text.push_str(if board >> (rank * 8 + file) & 1 == 1 { " x" } else { " ." });
+        }
+        text.push('\n');
+    }
+    text.push_str("  a b c d e f g h\n");
+    text
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Attacks of a piece on the square by its jumps, checking the bounds square by square
+    fn attacks_naive(square: u32, jumps: &[(i32, i32)]) -> u64 {
+        let (file, rank) = ((square % 8) as i32, (square / 8) as i32);
+        jumps.iter()
+            .map(|(file_jump, rank_jump)| (file + file_jump, rank + rank_jump))
+            .filter(|(file, rank)| (0..8).contains(file) && (0..8).contains(rank))
+            .fold(0, |board, (file, rank)| board | 1 << (rank * 8 + file))
+    }
+
+    #[test]
+    fn test_masks() {
+        assert_eq!(square(0, 0), Some(0));
+        assert_eq!(square(7, 7), Some(63));
+        assert_eq!(square(0, 8), None);
+        assert_eq!(square_mask(63), Some(1 << 63));
+        assert_eq!(square_mask(64), None);
+        assert_eq!(file_mask(7), Some(FILE_H));
+        assert_eq!(rank_mask(7), Some(RANK_8));
+        assert_eq!(file_mask(8), None);
+        assert_eq!(rank_mask(8), None);
+        let all_files = (0..8).fold(0, |board, file| board | file_mask(file).unwrap());
+        let all_ranks = (0..8).fold(0, |board, rank| board | rank_mask(rank).unwrap());
+        assert_eq!((all_files, all_ranks), (u64::MAX, u64::MAX));
+        for file in 0..8 {
+            for rank in 0..8 {
+                let mask = square_mask(square(file, rank).unwrap()).unwrap();
+                assert_eq!(file_mask(file).unwrap() & rank_mask(rank).unwrap(), mask);
+            }
+        }
+    }
+
+    #[test]
+    fn test_attacks() {
+        const KNIGHT: [(i32, i32); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+        const KING: [(i32, i32); 8] = [(1, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1), (0, 1)];
+        for square in 0..64 {
+            assert_eq!(knight_attacks(1 << square), attacks_naive(square, &KNIGHT), "square {square}");
+            assert_eq!(king_attacks(1 << square), attacks_naive(square, &KING), "square {square}");
+        }
+        assert_eq!(knight_attacks(0), 0);
+        // Attacks of a set are the union of the attacks of its pieces
+        let knights = 1 << 1 | 1 << 6 | 1 << 57 | 1 << 62;
+        assert_eq!(knight_attacks(knights), squares(knights).fold(0, |board, square| board | knight_attacks(1 << square)));
+        // Adjacent kings attack each other
+        assert_eq!(king_attacks(0b11), 0b111_0000_0111);
+        assert_eq!(knight_attacks(1 << 27).count_ones(), 8);
+        assert_eq!(king_attacks(1 << 27).count_ones(), 8);
+    }
+
+    #[test]
+    fn test_pop_lsb() {
+        let mut board = 0xDEAD_BEEF_0000_0001u64;
+        let mut popped = 0u64;
+        while let Some(square) = pop_lsb(&mut board) {
+            assert_eq!(popped >> square, 0);
+            popped |= 1 << square;
+        }
+        assert_eq!((board, popped), (0, 0xDEAD_BEEF_0000_0001));
+        assert_eq!(squares(0).count(), 0);
+        assert_eq!(squares(u64::MAX).collect::<Vec<_>>(), (0..64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_board_to_string() {
+        let text = board_to_string(knight_attacks(1 << 1));
+        let expected = "8 . . . . . . . .\n7 . . . . . . . .\n6 . . . . . . . .\n5 . . . . . . . .\n\
+                        4 . . . . . . . .\n3 x . x . . . . .\n2 . . . x . . . .\n1 . . . . . . . .\n  a b c d e f g h\n";
+        assert_eq!(text, expected);
+    }
+}
@@ -0,0 +1,161 @@
+//! Packed bit field structs
+
+/// Helper function used by *bitfield!*. Returns the width or panics (at compile time) if the width is 0 or doesn't fit into the field type
+/// # Arguments
+/// * `width` - bits count of the field
+/// * `type_bits` - bits count of the field type
+#[doc(hidden)]
+pub const fn checked_width(width: u32, type_bits: u32) -> u32 {
+    assert!(width > 0, "bit field width must be positive");
+    assert!(width <= type_bits, "bit field width exceeds the field type");
+    width
+}
+
+/// Declares a struct with named fields packed into a *u32* or *u64* word. Fields are placed one after another starting at bit 0.
+/// Every field is declared as `getter, setter: type[width]` where the type is an unsigned integer.
+/// Getters and setters are built on *get_bits*/*set_bits*. Setters return None if the value doesn't fit into the field.
+/// Fields can't overlap by construction, and the fields exceeding the word are rejected at compile time
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitfield;
+/// bitfield! {
+///     /// First byte of the IPv4 header
+///     pub struct VersionIhl: u32 {
+///         ihl, set_ihl: u8[4],
+///         version, set_version: u8[4],
+///     }
+/// }
+///
+/// let mut byte = VersionIhl::from_raw(0x45);
+/// assert_eq!(byte.version(), 4);
+/// assert_eq!(byte.ihl(), 5);
+/// assert_eq!(byte.set_ihl(6), Some(()));
+/// assert_eq!(byte.set_ihl(16), None);
+/// assert_eq!(byte.raw(), 0x46);
+/// ```
+///
+/// ```compile_fail
+/// # use understanding_bitwise::bitfield;
+/// bitfield! {
+///     struct TooWide: u32 {
+///         low, set_low: u32[30],
+///         high, set_high: u8[4],
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! bitfield {
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $name:ident: u32 { $($fields:tt)* }
+    ) => {
+        $crate::bitfield!(@struct $(#[$outer])* $vis $name, u32, $crate::get_bits, $crate::set_bits; $($fields)*);
+    };
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $name:ident: u64 { $($fields:tt)* }
+    ) => {
+        $crate::bitfield!(@struct $(#[$outer])* $vis $name, u64, $crate::get_bits_u64, $crate::set_bits_u64; $($fields)*);
+    };
+    (@struct $(#[$outer:meta])* $vis:vis $name:ident, $storage:ty, $get:path, $set:path; $($fields:tt)*) => {
+        $(#[$outer])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+        $vis struct $name($storage);
+
+        #[allow(dead_code)]
+        impl $name {
+            /// Returns the value with all fields set to 0
+            pub const fn new() -> Self {
+                $name(0)
+            }
+
+            /// Returns the value of the raw word
+            pub const fn from_raw(raw: $storage) -> Self {
+                $name(raw)
+            }
+
+            /// Returns the raw word
+            pub const fn raw(&self) -> $storage {
+                self.0
+            }
+
+            $crate::bitfield!(@accessors $storage, $get, $set, 0; $($fields)*);
+        }
+
+        const _: () = assert!($name::BITS_USED <= <$storage>::BITS, "bit fields exceed the word");
+    };
+    (@accessors $storage:ty, $get:path, $set:path, $offset:expr;) => {
+        /// Bits count occupied by the fields
+        pub const BITS_USED: u32 = $offset;
+    };
+    (@accessors $storage:ty, $get:path, $set:path, $offset:expr;
+        $(#[$meta:meta])* $field:ident, $setter:ident: $ty:ty[$width:expr] $(, $($rest:tt)*)?) => {
+        $(#[$meta])*
+        pub fn $field(&self) -> $ty {
+            $get(self.0, $offset, $width).unwrap() as $ty
+        }
+
+        /// Sets the field. Returns None if the value doesn't fit into the field
+        pub fn $setter(&mut self, value: $ty) -> Option<()> {
+            self.0 = $set(self.0, $offset, $width, value as $storage)?;
+            Some(())
+        }
+
+        $crate::bitfield!(@accessors $storage, $get, $set,
+            $offset + $crate::bitfield::checked_width($width, <$ty>::BITS); $($($rest)*)?);
+    };
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    bitfield! {
+        struct Packed: u32 {
+            flag, set_flag: u8[1],
+            kind, set_kind: u8[3],
+            id, set_id: u16[12],
+            payload, set_payload: u16[16],
+        }
+    }
+
+    bitfield! {
+        struct Wide: u64 {
+            low, set_low: u32[32],
+            middle, set_middle: u16[16],
+            high, set_high: u16[16]
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(Packed::BITS_USED, 32);
+        assert_eq!(Wide::BITS_USED, 64);
+        let packed = Packed::from_raw(0xBEEF_ABC5);
+        assert_eq!(packed.flag(), 1);
+        assert_eq!(packed.kind(), 0b010);
+        assert_eq!(packed.id(), 0xABC);
+        assert_eq!(packed.payload(), 0xBEEF);
+    }
+
+    #[test]
+    fn test_setters() {
+        let mut packed = Packed::new();
+        assert_eq!(packed.set_kind(0b111), Some(()));
+        assert_eq!(packed.set_payload(0xFFFF), Some(()));
+        assert_eq!(packed.set_id(0x1000), None);
+        assert_eq!(packed.set_flag(2), None);
+        assert_eq!(packed.raw(), 0xFFFF_000E);
+        assert_eq!(packed.set_kind(0), Some(()));
+        assert_eq!(packed.raw(), 0xFFFF_0000);
+    }
+
+    #[test]
+    fn test_u64_storage() {
+        let mut wide = Wide::new();
+        wide.set_high(0xABCD).unwrap();
+        wide.set_low(u32::MAX).unwrap();
+        assert_eq!(wide.raw(), 0xABCD_0000_FFFF_FFFF);
+        assert_eq!(wide.middle(), 0);
+        assert_eq!(wide.high(), 0xABCD);
+    }
+}
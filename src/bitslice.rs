@@ -0,0 +1,124 @@
+//! [*Bit-slicing*](https://en.wikipedia.org/wiki/Bit_slicing): up to 32 words are transposed into 32 bit planes,
+//! the plane *b* holding the bit *b* of every word, the word *i* in the bit *i*. A boolean circuit written with
+//! AND, OR, XOR and NOT on the planes then runs on all 32 inputs at once, without table lookups or branches
+
+use crate::transpose::transpose32;
+
+/// Returns the bit planes of the words, or None if there are more than 32 words. Missing words are 0
+/// # Arguments
+/// * `words` - words to be sliced
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitslice::to_bit_planes;
+/// let planes = to_bit_planes(&[0b01, 0b11, 0b10]).unwrap();
+/// assert_eq!(planes[0], 0b011);
+/// assert_eq!(planes[1], 0b110);
+/// assert_eq!(planes[2], 0);
+/// ```
+pub fn to_bit_planes(words: &[u32]) -> Option<[u32; 32]> {
+    if words.len() > 32 {
+        return None;
+    }
+    let mut planes = [0; 32];
+    planes[..words.len()].copy_from_slice(words);
+    transpose32(&mut planes);
+    Some(planes)
+}
+
+/// Returns the first *count* words of the bit planes, the inverse of *to_bit_planes*, or None if the count is above 32
+/// # Arguments
+/// * `planes` - bit planes
+/// * `count` - count of words
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitslice::{from_bit_planes, to_bit_planes};
+/// let planes = to_bit_planes(&[7, 8, 9]).unwrap();
+/// assert_eq!(from_bit_planes(&planes, 3), Some(vec![7, 8, 9]));
+/// ```
+pub fn from_bit_planes(planes: &[u32; 32], count: usize) -> Option<Vec<u32>> {
+    if count > 32 {
+        return None;
+    }
+    let mut words = *planes;
+    transpose32(&mut words);
+    Some(words[..count].to_vec())
+}
+
+/// Returns the bit planes of 32 nibbles passed through the 4-bit S-box. Every output bit is the OR of the minterms
+/// (ANDs of the input planes or their complements) of the inputs mapped to 1, so any S-box runs in constant time
+/// # Arguments
+/// * `planes` - planes of the bits 0 to 3 of the nibbles
+/// * `sbox` - substitution table of nibbles
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitslice::{apply_sbox4_bitsliced, from_bit_planes, to_bit_planes};
+/// const PRESENT: [u8; 16] = [0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2];
+/// let planes = to_bit_planes(&[0, 1, 0xF]).unwrap();
+/// let output = apply_sbox4_bitsliced([planes[0], planes[1], planes[2], planes[3]], &PRESENT);
+/// let mut planes = [0; 32];
+/// planes[..4].copy_from_slice(&output);
+/// assert_eq!(from_bit_planes(&planes, 3), Some(vec![0xC, 0x5, 0x2]));
+/// ```
+pub fn apply_sbox4_bitsliced(planes: [u32; 4], sbox: &[u8; 16]) -> [u32; 4] {
+    let mut output = [0; 4];
+    for (input, &substituted) in sbox.iter().enumerate() {
+        // Lanes where the nibble equals the input
+        let minterm = planes.iter().enumerate().fold(u32::MAX, |minterm, (bit, &plane)| {
+            minterm & if input >> bit & 1 == 1 { plane } else { ! plane }
+        });
+        for (bit, plane) in output.iter_mut().enumerate() {
+            // Mask instead of a branch on the table entry
+            *plane |= minterm & ((substituted >> bit & 1) as u32).wrapping_neg();
+        }
+    }
+    output
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+    use crate::cipher::apply_sbox4;
+
+    const PRESENT: [u8; 16] = [0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2];
+
+    fn words() -> Vec<u32> {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        (0..32).map(|_| rng.next_u32()).collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let words = words();
+        for count in [0, 1, 5, 32] {
+            let planes = to_bit_planes(&words[..count]).unwrap();
+            for (bit, &plane) in planes.iter().enumerate() {
+                for (index, &word) in words[..count].iter().enumerate() {
+                    assert_eq!(plane >> index & 1, word >> bit & 1);
+                }
+                assert_eq!(plane.checked_shr(count as u32).unwrap_or(0), 0);
+            }
+            assert_eq!(from_bit_planes(&planes, count).unwrap(), words[..count]);
+        }
+        assert_eq!(to_bit_planes(&[0; 33]), None);
+        assert_eq!(from_bit_planes(&[0; 32], 33), None);
+    }
+
+    #[test]
+    fn test_sbox_bitsliced() {
+        // Every lane takes the nibbles of one word in turn
+        let words = words();
+        let mut planes = to_bit_planes(&words).unwrap();
+        for nibble in 0..8 {
+            let input: [u32; 4] = std::array::from_fn(|bit| planes[nibble * 4 + bit]);
+            planes[nibble * 4..nibble * 4 + 4].copy_from_slice(&apply_sbox4_bitsliced(input, &PRESENT));
+        }
+        let expected: Vec<u32> = words.iter().map(|&word| apply_sbox4(word, &PRESENT)).collect();
+        assert_eq!(from_bit_planes(&planes, 32).unwrap(), expected);
+        // Identity S-box keeps the planes
+        let identity: [u8; 16] = std::array::from_fn(|index| index as u8);
+        let input = [0xDEAD_BEEF, 0x1234_5678, 0, u32::MAX];
+        assert_eq!(apply_sbox4_bitsliced(input, &identity), input);
+    }
+}
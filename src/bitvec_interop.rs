@@ -0,0 +1,169 @@
+//! Conversions between the crate's representations and the [`bitvec`](https://docs.rs/bitvec) types.
+//! Words use *Lsb0* order everywhere: bit *i* of the word *w* is the bit *w × 32 + i*, like in the *slice* module
+
+use std::error::Error;
+use std::fmt;
+use bitvec::array::BitArray;
+use bitvec::order::Lsb0;
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+use bitvec::view::BitView;
+use crate::rle::RleBitSet;
+
+/// Returns the number as a bit array
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitvec_interop::to_bit_array;
+/// let bits = to_bit_array(0b101);
+/// assert!(bits[0] && ! bits[1] && bits[2]);
+/// ```
+pub fn to_bit_array(number: u32) -> BitArray<u32, Lsb0> {
+    BitArray::new(number)
+}
+
+/// Returns the number held by the bit array
+/// # Arguments
+/// * `bits` - bit array to work with
+pub fn from_bit_array(bits: BitArray<u32, Lsb0>) -> u32 {
+    bits.into_inner()
+}
+
+/// Returns the view of the words as a bit slice, without copying
+/// # Arguments
+/// * `words` - words to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitvec_interop::as_bit_slice;
+/// assert_eq!(as_bit_slice(&[0, 0b10]).first_one(), Some(33));
+/// ```
+pub fn as_bit_slice(words: &[u32]) -> &BitSlice<u32, Lsb0> {
+    words.view_bits()
+}
+
+/// Returns the mutable view of the words as a bit slice, without copying
+/// # Arguments
+/// * `words` - words to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitvec_interop::as_bit_slice_mut;
+/// let mut words = [0u32; 2];
+/// as_bit_slice_mut(&mut words).set(33, true);
+/// assert_eq!(words, [0, 0b10]);
+/// ```
+pub fn as_bit_slice_mut(words: &mut [u32]) -> &mut BitSlice<u32, Lsb0> {
+    words.view_bits_mut()
+}
+
+impl From<&RleBitSet> for BitVec<u32, Lsb0> {
+    /// Returns the bit vector holding the set. Its length is a whole count of words, see *RleBitSet::to_words*
+    fn from(set: &RleBitSet) -> Self {
+        BitVec::from_vec(set.to_words())
+    }
+}
+
+/// Error of converting a bit slice holding a one at an index above the valid indexes of *RleBitSet*
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bitvec_interop::{as_bit_slice, IndexOverflow};
+/// # use understanding_bitwise::rle::RleBitSet;
+/// let set = RleBitSet::try_from(as_bit_slice(&[0b1110])).unwrap();
+/// assert_eq!(set.runs().collect::<Vec<_>>(), [(1, 3)]);
+/// let error = IndexOverflow { index: u32::MAX as usize };
+/// assert_eq!(error.to_string(), "bit index 4294967295 is out of the valid range 0..4294967295 of RleBitSet");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IndexOverflow {
+    /// Lowest index of a one out of the range
+    pub index: usize,
+}
+
+impl fmt::Display for IndexOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bit index {} is out of the valid range 0..{} of RleBitSet", self.index, u32::MAX)
+    }
+}
+
+impl Error for IndexOverflow {}
+
+impl TryFrom<&BitSlice<u32, Lsb0>> for RleBitSet {
+    type Error = IndexOverflow;
+
+    /// Returns the set of the indexes of ones or an error if a one is at *u32::MAX* or above.
+    /// Every run of ones is found with a single search and added at once
+    fn try_from(bits: &BitSlice<u32, Lsb0>) -> Result<Self, Self::Error> {
+        let mut set = RleBitSet::new();
+        let mut offset = 0;
+        let mut rest = bits;
+        while let Some(start) = rest.first_one() {
+            let length = rest[start..].first_zero().unwrap_or(rest.len() - start);
+            let (run_start, run_end) = (offset + start, offset + start + length);
+            // The highest valid index is u32::MAX - 1, so a valid run ends at u32::MAX at most
+            if run_end > u32::MAX as usize {
+                return Err(IndexOverflow { index: run_start.max(u32::MAX as usize) });
+            }
+            set.insert_range(run_start as u32, length as u32).expect("the run is in the valid range");
+            offset = run_end;
+            rest = &rest[start + length..];
+        }
+        Ok(set)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slice::{ones_count_slice, set_bit_in_slice};
+
+    #[test]
+    fn test_bit_array() {
+        for number in [0, 1, 0xDEAD_BEEF, u32::MAX] {
+            let bits = to_bit_array(number);
+            assert_eq!(bits.count_ones() as u32, number.count_ones());
+            assert_eq!(from_bit_array(bits), number);
+        }
+    }
+
+    #[test]
+    fn test_bit_slice_view() {
+        let mut words = [0u32; 3];
+        for index in [0, 31, 32, 95] {
+            set_bit_in_slice(&mut words, index).unwrap();
+        }
+        let view = as_bit_slice(&words);
+        assert_eq!(view.iter_ones().collect::<Vec<_>>(), [0, 31, 32, 95]);
+        assert_eq!(view.count_ones() as u64, ones_count_slice(&words));
+        as_bit_slice_mut(&mut words)[40..50].fill(true);
+        assert_eq!(words[1], 0b11_1111_1111 << 8 | 1);
+    }
+
+    #[test]
+    fn test_rle_bit_set() {
+        let mut set = RleBitSet::new();
+        set.insert_range(3, 40).unwrap();
+        set.insert(70).unwrap();
+        let bits = BitVec::<u32, Lsb0>::from(&set);
+        assert_eq!(bits.len(), 96);
+        assert_eq!(bits.count_ones(), 41);
+        assert_eq!(RleBitSet::try_from(bits.as_bitslice()), Ok(set));
+        assert_eq!(RleBitSet::try_from(as_bit_slice(&[])), Ok(RleBitSet::new()));
+        // Slices not starting at a word boundary
+        let words = [0b1110_0111, u32::MAX];
+        let set = RleBitSet::try_from(&as_bit_slice(&words)[1..40]).unwrap();
+        assert_eq!(set.runs().collect::<Vec<_>>(), [(0, 2), (4, 3), (31, 8)]);
+    }
+
+    #[test]
+    fn test_rle_bit_set_overflow() {
+        let mut words = vec![0u32; 1 << 27];
+        let last = words.len() - 1;
+        words[last] = 1 << 30;
+        let set = RleBitSet::try_from(as_bit_slice(&words)).unwrap();
+        assert_eq!(set.runs().collect::<Vec<_>>(), [(u32::MAX - 1, 1)]);
+        // A run crossing the end of the valid range reports the first index out of it
+        words[last] = 0b11 << 30;
+        assert_eq!(RleBitSet::try_from(as_bit_slice(&words)), Err(IndexOverflow { index: u32::MAX as usize }));
+    }
+}
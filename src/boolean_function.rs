@@ -0,0 +1,228 @@
+//! [*Boolean functions*](https://en.wikipedia.org/wiki/Boolean_function) of up to 5 variables stored as truth tables:
+//! the bit *x* of the table is *f(x)*, the variable *i* is the bit *i* of *x*.
+//! Cryptographic quality measures of S-boxes and masks come from two transforms of the table: the Walsh–Hadamard
+//! spectrum (correlation with every linear function) and the algebraic normal form (XOR of ANDs of variables)
+
+use crate::parity::parity_of;
+
+/// Count of variables of the largest function: its table fills u32
+pub const MAX_VARIABLES: u32 = 5;
+
+/// Input bits with the variable *i* equal to 0, for every *i*
+const VARIABLE_ZERO_MASKS: [u32; MAX_VARIABLES as usize] = [0x5555_5555, 0x3333_3333, 0x0F0F_0F0F, 0x00FF_00FF, 0x0000_FFFF];
+
+/// Boolean function given by its truth table
+/// # Examples
+/// ```
+/// # use understanding_bitwise::boolean_function::BooleanFunction;
+/// // Majority of 3 variables
+/// let majority = BooleanFunction::from_fn(3, |x| x.count_ones() >= 2).unwrap();
+/// assert_eq!(majority.table(), 0b1110_1000);
+/// assert!(majority.is_balanced());
+/// assert_eq!(majority.algebraic_degree(), 2);
+/// assert_eq!(majority.nonlinearity(), 2);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BooleanFunction {
+    variables: u32,
+    table: u32,
+}
+
+impl BooleanFunction {
+    /// Returns the function or None if the variables count is above *MAX_VARIABLES*
+    /// or the table has ones beyond its 2ᵛᵃʳⁱᵃᵇˡᵉˢ bits
+    /// # Arguments
+    /// * `variables` - count of variables
+    /// * `table` - truth table
+    pub fn new(variables: u32, table: u32) -> Option<Self> {
+        if variables > MAX_VARIABLES || table & ! table_mask(variables) != 0 {
+            return None;
+        }
+        Some(BooleanFunction { variables, table })
+    }
+
+    /// Returns the function with the values of the closure or None if the variables count is above *MAX_VARIABLES*
+    /// # Arguments
+    /// * `variables` - count of variables
+    /// * `f` - values of the function
+    pub fn from_fn(variables: u32, f: impl Fn(u32) -> bool) -> Option<Self> {
+        if variables > MAX_VARIABLES {
+            return None;
+        }
+        let table = (0..1 << variables).fold(0, |table, input| table | (f(input) as u32) << input);
+        Some(BooleanFunction { variables, table })
+    }
+
+    /// Returns the component of the 4-bit S-box selected by the output mask: the parity of the masked output
+    /// # Arguments
+    /// * `sbox` - substitution table of nibbles
+    /// * `mask` - bits of the output XORed together
+    pub fn sbox_component(sbox: &[u8; 16], mask: u8) -> Self {
+        let table = sbox.iter().enumerate().fold(0, |table, (input, &output)| table | parity_of((output & mask) as u32) << input);
+        BooleanFunction { variables: 4, table }
+    }
+
+    /// Returns the count of variables
+    pub fn variables(&self) -> u32 {
+        self.variables
+    }
+
+    /// Returns the truth table
+    pub fn table(&self) -> u32 {
+        self.table
+    }
+
+    /// Returns the value of the function or None if the input has more bits than the variables
+    /// # Arguments
+    /// * `input` - values of the variables
+    pub fn evaluate(&self, input: u32) -> Option<bool> {
+        (input < 1 << self.variables).then(|| self.table >> input & 1 == 1)
+    }
+
+    /// Returns true if the function takes 1 for exactly half of the inputs
+    pub fn is_balanced(&self) -> bool {
+        self.table.count_ones() << 1 == 1 << self.variables
+    }
+
+    /// Returns the Walsh–Hadamard spectrum: the element *a* is the sum of (-1)^(*f(x)* ^ *a·x*) over all inputs,
+    /// so it's the count of agreements minus disagreements with the linear function *a·x*.
+    /// Computed by the fast transform: log₂ rounds of sum/difference butterflies
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::boolean_function::BooleanFunction;
+    /// // x0 ^ x1 agrees everywhere with the linear function 0b11 only
+    /// let xor = BooleanFunction::from_fn(2, |x| x.count_ones() == 1).unwrap();
+    /// assert_eq!(xor.walsh_hadamard(), vec![0, 0, 0, 4]);
+    /// ```
+    pub fn walsh_hadamard(&self) -> Vec<i32> {
+        let mut spectrum: Vec<i32> = (0..1 << self.variables).map(|input| 1 - 2 * (self.table >> input & 1) as i32).collect();
+        let mut half = 1;
+        while half < spectrum.len() {
+            for start in (0..spectrum.len()).step_by(2 * half) {
+                for index in start..start + half {
+                    let (a, b) = (spectrum[index], spectrum[index + half]);
+                    spectrum[index] = a + b;
+                    spectrum[index + half] = a - b;
+                }
+            }
+            half <<= 1;
+        }
+        spectrum
+    }
+
+    /// Returns the distance to the nearest affine function: 2ᵛᵃʳⁱᵃᵇˡᵉˢ⁻¹ - max |W(a)| / 2.
+    /// High nonlinearity resists linear cryptanalysis
+    pub fn nonlinearity(&self) -> u32 {
+        let max = self.walsh_hadamard().iter().map(|value| value.unsigned_abs()).max().unwrap_or(0);
+        ((1 << self.variables) - max) / 2
+    }
+
+    /// Returns the algebraic normal form as a table of monomials: the bit *m* is the coefficient of the AND of
+    /// the variables of *m*. The Möbius transform XORs the half with a variable 0 into the half with it 1, per variable
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::boolean_function::BooleanFunction;
+    /// // x0 | x1 = x0 ^ x1 ^ x0x1
+    /// let or = BooleanFunction::from_fn(2, |x| x != 0).unwrap();
+    /// assert_eq!(or.algebraic_normal_form(), 0b1110);
+    /// ```
+    pub fn algebraic_normal_form(&self) -> u32 {
+        let mut anf = self.table;
+        for (variable, mask) in VARIABLE_ZERO_MASKS.iter().enumerate().take(self.variables as usize) {
+            anf ^= (anf & mask) << (1 << variable);
+        }
+        anf & table_mask(self.variables)
+    }
+
+    /// Returns the count of variables of the largest monomial of the algebraic normal form, 0 for constant functions
+    pub fn algebraic_degree(&self) -> u32 {
+        let anf = self.algebraic_normal_form();
+        (0..32).filter(|&monomial| anf >> monomial & 1 == 1).map(u32::count_ones).max().unwrap_or(0)
+    }
+}
+
+/// Helper function. Returns the mask of the 2ᵛᵃʳⁱᵃᵇˡᵉˢ bits of a table
+/// # Arguments
+/// * `variables` - count of variables up to 5
+fn table_mask(variables: u32) -> u32 {
+    u32::MAX >> (u32::BITS - (1 << variables))
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    const PRESENT: [u8; 16] = [0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2];
+
+    fn walsh_hadamard_naive(function: &BooleanFunction) -> Vec<i32> {
+        (0..1 << function.variables()).map(|a: u32| {
+            (0..1 << function.variables()).map(|x: u32| {
+                let value = function.evaluate(x).unwrap() as u32 ^ (a & x).count_ones() & 1;
+                1 - 2 * value as i32
+            }).sum()
+        }).collect()
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(BooleanFunction::new(6, 0).is_none());
+        assert!(BooleanFunction::new(2, 0b1_0000).is_none());
+        assert!(BooleanFunction::from_fn(6, |_| true).is_none());
+        let function = BooleanFunction::new(5, u32::MAX).unwrap();
+        assert_eq!((function.variables(), function.evaluate(31), function.evaluate(32)), (5, Some(true), None));
+        let constant = BooleanFunction::new(0, 1).unwrap();
+        assert_eq!((constant.algebraic_degree(), constant.nonlinearity(), constant.is_balanced()), (0, 0, false));
+    }
+
+    #[test]
+    fn test_walsh_hadamard() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for variables in 0..=MAX_VARIABLES {
+            for _ in 0..20 {
+                let state = rng.next_u32();
+                let function = BooleanFunction::new(variables, state & table_mask(variables)).unwrap();
+                let spectrum = function.walsh_hadamard();
+                assert_eq!(spectrum, walsh_hadamard_naive(&function));
+                // Parseval: the squares sum to 2²ⁿ
+                assert_eq!(spectrum.iter().map(|value| value * value).sum::<i32>(), 1 << (2 * variables));
+                assert_eq!(function.is_balanced(), spectrum[0] == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_algebraic_normal_form() {
+        // The Möbius transform is an involution
+        for table in [0u32, 0xDEAD_BEEF, 0x1234_5678, u32::MAX] {
+            let function = BooleanFunction::new(5, table).unwrap();
+            let anf = BooleanFunction::new(5, function.algebraic_normal_form()).unwrap();
+            assert_eq!(anf.algebraic_normal_form(), table);
+        }
+        // Affine functions have degree 1 and nonlinearity 0
+        for mask in 1..32 {
+            let linear = BooleanFunction::from_fn(5, |x| (x & mask).count_ones() & 1 == 1).unwrap();
+            assert_eq!((linear.algebraic_degree(), linear.nonlinearity()), (1, 0));
+            assert!(linear.is_balanced());
+        }
+        let and = BooleanFunction::from_fn(5, |x| x == 31).unwrap();
+        assert_eq!((and.algebraic_normal_form(), and.algebraic_degree()), (1 << 31, 5));
+    }
+
+    #[test]
+    fn test_sbox_components() {
+        // Bent function: the largest nonlinearity of 4 variables
+        let bent = BooleanFunction::from_fn(4, |x| (x & x >> 1 & 1 ^ x >> 2 & x >> 3 & 1) == 1).unwrap();
+        assert_eq!(bent.nonlinearity(), 6);
+        assert!(bent.walsh_hadamard().iter().all(|value| value.abs() == 4));
+        // PRESENT was designed so every component is balanced, nonlinearity 4 and degree at least 2
+        for mask in 1..16 {
+            let component = BooleanFunction::sbox_component(&PRESENT, mask);
+            assert!(component.is_balanced());
+            assert_eq!(component.nonlinearity(), 4);
+            assert!(component.algebraic_degree() >= 2);
+        }
+        assert_eq!(BooleanFunction::sbox_component(&PRESENT, 0).table(), 0);
+    }
+}
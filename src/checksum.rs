@@ -0,0 +1,328 @@
+//! Checksums built from running sums: [*Fletcher*](https://en.wikipedia.org/wiki/Fletcher%27s_checksum),
+//! [*Adler-32*](https://en.wikipedia.org/wiki/Adler-32) and the internet checksum
+
+/// Running Fletcher-16 checksum: two sums of bytes modulo 255
+/// # Examples
+/// ```
+/// # use understanding_bitwise::checksum::Fletcher16;
+/// let mut checksum = Fletcher16::new();
+/// checksum.update(b"abc");
+/// checksum.update(b"de");
+/// assert_eq!(checksum.finish(), 0xC8F0);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Fletcher16 {
+    sum1: u16,
+    sum2: u16,
+}
+
+impl Fletcher16 {
+    /// Returns the checksum of no data
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the data
+    /// # Arguments
+    /// * `data` - data to work with
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            // The second sum adds the first one after every byte, so it depends on the byte positions
+            self.sum1 = (self.sum1 + byte as u16) % 255;
+            self.sum2 = (self.sum2 + self.sum1) % 255;
+        }
+    }
+
+    /// Returns the checksum of all added data. The second sum is in the high byte
+    pub fn finish(&self) -> u16 {
+        self.sum2 << 8 | self.sum1
+    }
+}
+
+/// Running Fletcher-32 checksum: two sums of little-endian 16-bit words modulo 65535. Odd data is padded with a zero byte
+/// # Examples
+/// ```
+/// # use understanding_bitwise::checksum::Fletcher32;
+/// let mut checksum = Fletcher32::new();
+/// checksum.update(b"abc");
+/// checksum.update(b"de");
+/// assert_eq!(checksum.finish(), 0xF04F_C729);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Fletcher32 {
+    sum1: u32,
+    sum2: u32,
+    /// Low byte of the word waiting for its high byte
+    pending: Option<u8>,
+}
+
+impl Fletcher32 {
+    /// Returns the checksum of no data
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Helper function. Adds the word to the sums
+    /// # Arguments
+    /// * `word` - word to be added
+    fn add_word(&mut self, word: u16) {
+        self.sum1 = (self.sum1 + word as u32) % 65535;
+        self.sum2 = (self.sum2 + self.sum1) % 65535;
+    }
+
+    /// Adds the data. Chunks may have odd lengths, the words are formed across them
+    /// # Arguments
+    /// * `data` - data to work with
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        if let (Some(low), Some((&high, rest))) = (self.pending, data.split_first()) {
+            self.add_word(u16::from_le_bytes([low, high]));
+            self.pending = None;
+            data = rest;
+        }
+        let words = data.chunks_exact(2);
+        let remainder = words.remainder();
+        for word in words {
+            self.add_word(u16::from_le_bytes([word[0], word[1]]));
+        }
+        if let Some(&low) = remainder.first() {
+            self.pending = Some(low);
+        }
+    }
+
+    /// Returns the checksum of all added data. The second sum is in the high half
+    pub fn finish(&self) -> u32 {
+        let mut finished = *self;
+        if let Some(low) = finished.pending.take() {
+            finished.add_word(low as u16);
+        }
+        finished.sum2 << 16 | finished.sum1
+    }
+}
+
+/// Modulus of Adler-32, the largest prime below 2^16
+const ADLER_MODULUS: u32 = 65521;
+
+/// Count of bytes that can be summed before the second sum may overflow u32, so the modulo is taken once per block
+const ADLER_BLOCK: usize = 5552;
+
+/// Running Adler-32 checksum (used by zlib): Fletcher-like sums of bytes modulo 65521, the first sum starts from 1
+/// # Examples
+/// ```
+/// # use understanding_bitwise::checksum::Adler32;
+/// let mut checksum = Adler32::new();
+/// checksum.update(b"Wiki");
+/// checksum.update(b"pedia");
+/// assert_eq!(checksum.finish(), 0x11E6_0398);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Adler32 {
+    sum1: u32,
+    sum2: u32,
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Adler32 { sum1: 1, sum2: 0 }
+    }
+}
+
+impl Adler32 {
+    /// Returns the checksum of no data
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the data
+    /// # Arguments
+    /// * `data` - data to work with
+    pub fn update(&mut self, data: &[u8]) {
+        for block in data.chunks(ADLER_BLOCK) {
+            for &byte in block {
+                self.sum1 += byte as u32;
+                self.sum2 += self.sum1;
+            }
+            self.sum1 %= ADLER_MODULUS;
+            self.sum2 %= ADLER_MODULUS;
+        }
+    }
+
+    /// Returns the checksum of all added data. The second sum is in the high half
+    pub fn finish(&self) -> u32 {
+        self.sum2 << 16 | self.sum1
+    }
+}
+
+/// Returns the Fletcher-16 checksum of the data
+/// # Arguments
+/// * `data` - data to work with
+pub fn fletcher16(data: &[u8]) -> u16 {
+    let mut checksum = Fletcher16::new();
+    checksum.update(data);
+    checksum.finish()
+}
+
+/// Returns the Fletcher-32 checksum of the data
+/// # Arguments
+/// * `data` - data to work with
+pub fn fletcher32(data: &[u8]) -> u32 {
+    let mut checksum = Fletcher32::new();
+    checksum.update(data);
+    checksum.finish()
+}
+
+/// Returns the Adler-32 checksum of the data
+/// # Arguments
+/// * `data` - data to work with
+pub fn adler32(data: &[u8]) -> u32 {
+    let mut checksum = Adler32::new();
+    checksum.update(data);
+    checksum.finish()
+}
+
+/// Helper function. Returns the sum folded into 16 bits: the carries out of the low half are added back (end-around carry)
+/// # Arguments
+/// * `sum` - sum of 16-bit words
+fn fold_carries(sum: u64) -> u16 {
+    let mut sum = sum;
+    while sum >> 16 != 0 {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    sum as u16
+}
+
+/// Returns the 16-bit ones' complement sum of the big endian words of the data. Odd data is padded with a zero byte.
+/// The sum doesn't depend on the byte order and on the order of the words, so partial sums (e.g. of a pseudo header) can be added
+/// # Arguments
+/// * `data` - data to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::checksum::ones_complement_sum;
+/// assert_eq!(ones_complement_sum(&[0xFF, 0xFF, 0x00, 0x02]), 0x0002);
+/// ```
+pub fn ones_complement_sum(data: &[u8]) -> u16 {
+    let words = data.chunks(2).map(|word| u16::from_be_bytes([word[0], word.get(1).copied().unwrap_or(0)]) as u64);
+    fold_carries(words.sum())
+}
+
+/// Returns the [*internet checksum*](https://www.rfc-editor.org/rfc/rfc1071) (IPv4, ICMP, UDP, TCP):
+/// the ones' complement of the ones' complement sum of the data. Data including its valid checksum gives 0
+/// # Arguments
+/// * `data` - data to work with, with the checksum field set to 0
+/// # Examples
+/// ```
+/// # use understanding_bitwise::checksum::internet_checksum;
+/// let mut header = [
+///     0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11,
+///     0x00, 0x00, 0xc0, 0xa8, 0x00, 0x01, 0xc0, 0xa8, 0x00, 0xc7,
+/// ];
+/// assert_eq!(internet_checksum(&header), 0xb861);
+/// header[10..12].copy_from_slice(&0xb861u16.to_be_bytes());
+/// assert_eq!(internet_checksum(&header), 0);
+/// ```
+pub fn internet_checksum(data: &[u8]) -> u16 {
+    ! ones_complement_sum(data)
+}
+
+/// Returns the internet checksum after a 16-bit field of the data changed, without summing the data again
+/// ([*RFC 1624*](https://www.rfc-editor.org/rfc/rfc1624): HC' = ~(~HC + ~m + m')). Routers use it to decrement TTL
+/// # Arguments
+/// * `checksum` - checksum of the data before the change
+/// * `old_field` - old value of the field
+/// * `new_field` - new value of the field
+/// # Examples
+/// ```
+/// # use understanding_bitwise::checksum::{internet_checksum, internet_checksum_update};
+/// let mut header = [
+///     0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11,
+///     0x00, 0x00, 0xc0, 0xa8, 0x00, 0x01, 0xc0, 0xa8, 0x00, 0xc7,
+/// ];
+/// // TTL and protocol share the word 4
+/// header[8] -= 1;
+/// assert_eq!(internet_checksum_update(0xb861, 0x4011, 0x3f11), internet_checksum(&header));
+/// ```
+pub fn internet_checksum_update(checksum: u16, old_field: u16, new_field: u16) -> u16 {
+    ! fold_carries(! checksum as u64 + ! old_field as u64 + new_field as u64)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_known_values() {
+        assert_eq!(fletcher16(b"abcde"), 0xC8F0);
+        assert_eq!(fletcher16(b"abcdef"), 0x2057);
+        assert_eq!(fletcher16(b"abcdefgh"), 0x0627);
+        assert_eq!(fletcher32(b"abcde"), 0xF04F_C729);
+        assert_eq!(fletcher32(b"abcdef"), 0x5650_2D2A);
+        assert_eq!(fletcher32(b"abcdefgh"), 0xEBE1_9591);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(adler32(&[]), 1);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let data: Vec<u8> = (0..20_000u32).map(|index| (index * 31 + 7) as u8).collect();
+        for split in [0, 1, 2, 3, 5551, 5552, 5553, 19_999] {
+            let mut f16 = Fletcher16::new();
+            let mut f32 = Fletcher32::new();
+            let mut adler = Adler32::new();
+            let next = (split + 1).min(data.len());
+            for chunk in [&data[..split], &data[split..next], &data[next..]] {
+                f16.update(chunk);
+                f32.update(chunk);
+                adler.update(chunk);
+            }
+            assert_eq!(f16.finish(), fletcher16(&data));
+            assert_eq!(f32.finish(), fletcher32(&data));
+            assert_eq!(adler.finish(), adler32(&data));
+        }
+    }
+
+    #[test]
+    fn test_adler32_large_input() {
+        // All bytes 0xFF stress the deferred modulo
+        let data = vec![0xFF; 100_000];
+        let (mut sum1, mut sum2) = (1u64, 0u64);
+        for &byte in &data {
+            sum1 = (sum1 + byte as u64) % ADLER_MODULUS as u64;
+            sum2 = (sum2 + sum1) % ADLER_MODULUS as u64;
+        }
+        assert_eq!(adler32(&data), (sum2 << 16 | sum1) as u32);
+    }
+
+    #[test]
+    fn test_internet_checksum() {
+        // Header of a UDP datagram with the checksum field zeroed
+        let mut header = [
+            0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11,
+            0x00, 0x00, 0xc0, 0xa8, 0x00, 0x01, 0xc0, 0xa8, 0x00, 0xc7,
+        ];
+        assert_eq!(internet_checksum(&header), 0xb861);
+        header[10..12].copy_from_slice(&[0xb8, 0x61]);
+        assert_eq!(internet_checksum(&header), 0);
+        assert_eq!(internet_checksum(&header[..19]), internet_checksum(&[&header[..19], &[0][..]].concat()));
+        // Odd data is padded, the sum doesn't depend on the word order
+        assert_eq!(ones_complement_sum(&[0x12, 0x34, 0x56]), 0x6834);
+        assert_eq!(ones_complement_sum(&[0x56, 0x00, 0x12, 0x34]), 0x6834);
+        assert_eq!(internet_checksum(&[]), 0xFFFF);
+    }
+
+    #[test]
+    fn test_internet_checksum_update() {
+        let mut data = [0u8; 40];
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for step in 0..1000 {
+            let state = rng.next_u32();
+            let checksum = internet_checksum(&data);
+            let offset = step % 20 * 2;
+            let old_field = u16::from_be_bytes([data[offset], data[offset + 1]]);
+            let new_field = state as u16;
+            data[offset..offset + 2].copy_from_slice(&new_field.to_be_bytes());
+            assert_eq!(internet_checksum_update(checksum, old_field, new_field), internet_checksum(&data));
+        }
+    }
+}
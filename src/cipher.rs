@@ -0,0 +1,197 @@
+//! XOR stream cipher: every data byte is XORed with a keystream byte, and since *x ^ k ^ k = x* the same call decrypts.
+//! **Educational only, not secure**: the generators here are linear or easily predicted, a known plaintext reveals
+//! the keystream (*ciphertext ^ plaintext*), and reusing a keystream leaks *plaintext1 ^ plaintext2*.
+//! S-boxes (substitution tables) add the nonlinearity that XOR alone lacks
+
+use crate::lfsr::Lfsr;
+use crate::prng::Xorshift32;
+
+/// Returns the data XORed with the keystream. Panics if the keystream is shorter than the data
+/// # Arguments
+/// * `data` - plaintext or ciphertext
+/// * `keystream` - keystream bytes
+/// # Examples
+/// ```
+/// # use understanding_bitwise::cipher::xor_cipher;
+/// let ciphertext = xor_cipher(b"attack", b"key".iter().copied().cycle());
+/// assert_eq!(xor_cipher(&ciphertext, b"key".iter().copied().cycle()), b"attack");
+/// ```
+pub fn xor_cipher(data: &[u8], keystream: impl Iterator<Item = u8>) -> Vec<u8> {
+    let mut result = data.to_vec();
+    xor_cipher_in_place(&mut result, keystream);
+    result
+}
+
+/// XORs the data with the keystream in place. Panics if the keystream is shorter than the data
+/// # Arguments
+/// * `data` - plaintext or ciphertext
+/// * `keystream` - keystream bytes
+pub fn xor_cipher_in_place(data: &mut [u8], keystream: impl Iterator<Item = u8>) {
+    let mut keystream = keystream;
+    for byte in data {
+        *byte ^= keystream.next().expect("keystream must not be shorter than the data");
+    }
+}
+
+/// Returns the endless keystream of the register output, 8 bits per byte, the first bit in the bit 0
+/// # Arguments
+/// * `lfsr` - register to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::cipher::{lfsr_keystream, xor_cipher};
+/// # use understanding_bitwise::lfsr::{Lfsr, LfsrConfig};
+/// let lfsr = Lfsr::new(16, 0xB400, 0xACE1, LfsrConfig::Galois).unwrap();
+/// let ciphertext = xor_cipher(b"hello", lfsr_keystream(lfsr));
+/// assert_eq!(xor_cipher(&ciphertext, lfsr_keystream(lfsr)), b"hello");
+/// ```
+pub fn lfsr_keystream(lfsr: Lfsr) -> impl Iterator<Item = u8> {
+    let mut lfsr = lfsr;
+    std::iter::repeat_with(move || (0..u8::BITS).fold(0, |byte, index| byte | (lfsr.next_bit() as u8) << index))
+}
+
+/// Returns the endless keystream of the generator outputs, 4 bytes per output, little endian
+/// # Arguments
+/// * `generator` - generator to work with
+pub fn xorshift_keystream(generator: Xorshift32) -> impl Iterator<Item = u8> {
+    let mut generator = generator;
+    std::iter::repeat_with(move || generator.next_u32().to_le_bytes()).flatten()
+}
+
+/// Returns the word with every nibble replaced by its entry of the S-box. Only the low nibbles of the entries are used
+/// # Arguments
+/// * `word` - word to work with
+/// * `sbox` - substitution table of nibbles
+/// # Examples
+/// ```
+/// # use understanding_bitwise::cipher::{apply_sbox4, invert_sbox};
+/// // S-box of the PRESENT block cipher
+/// const PRESENT: [u8; 16] = [0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2];
+/// assert_eq!(apply_sbox4(0x0000_0F01, &PRESENT), 0xCCCC_C2C5);
+/// let inverse = invert_sbox(&PRESENT).unwrap();
+/// assert_eq!(apply_sbox4(0xCCCC_C2C5, &inverse), 0x0000_0F01);
+/// ```
+pub fn apply_sbox4(word: u32, sbox: &[u8; 16]) -> u32 {
+    (0..u32::BITS).step_by(4).fold(0, |result, shift| {
+        let nibble = (word >> shift & 0xF) as usize;
+        result | ((sbox[nibble] & 0xF) as u32) << shift
+    })
+}
+
+/// Replaces every byte with its entry of the S-box in place
+/// # Arguments
+/// * `bytes` - bytes to work with
+/// * `sbox` - substitution table of bytes
+pub fn apply_sbox8(bytes: &mut [u8], sbox: &[u8; 256]) {
+    for byte in bytes {
+        *byte = sbox[*byte as usize];
+    }
+}
+
+/// Returns the inverse S-box or None if the S-box isn't a permutation of 0..N
+/// # Arguments
+/// * `sbox` - substitution table
+/// # Examples
+/// ```
+/// # use understanding_bitwise::cipher::invert_sbox;
+/// assert_eq!(invert_sbox(&[2, 0, 3, 1]), Some([1, 3, 0, 2]));
+/// assert_eq!(invert_sbox(&[2, 0, 2, 1]), None);
+/// ```
+pub fn invert_sbox<const N: usize>(sbox: &[u8; N]) -> Option<[u8; N]> {
+    let mut inverse = [0u8; N];
+    let mut seen = [false; N];
+    for (input, &output) in sbox.iter().enumerate() {
+        let output = output as usize;
+        if output >= N || seen[output] {
+            return None;
+        }
+        seen[output] = true;
+        inverse[output] = input as u8;
+    }
+    Some(inverse)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lfsr::LfsrConfig;
+
+    const PLAINTEXT: &[u8] = b"Bitwise tricks are fun, but this cipher is not secure";
+
+    #[test]
+    fn test_round_trip() {
+        let lfsr = Lfsr::new(16, 0xB400, 0xACE1, LfsrConfig::Fibonacci).unwrap();
+        let generator = Xorshift32::new(0xDEAD_BEEF).unwrap();
+        let ciphertext = xor_cipher(PLAINTEXT, xorshift_keystream(generator));
+        assert_ne!(ciphertext, PLAINTEXT);
+        assert_eq!(xor_cipher(&ciphertext, xorshift_keystream(generator)), PLAINTEXT);
+        let mut data = PLAINTEXT.to_vec();
+        xor_cipher_in_place(&mut data, lfsr_keystream(lfsr));
+        assert_eq!(data, xor_cipher(PLAINTEXT, lfsr_keystream(lfsr)));
+        xor_cipher_in_place(&mut data, lfsr_keystream(lfsr));
+        assert_eq!(data, PLAINTEXT);
+        assert_eq!(xor_cipher(&[], std::iter::empty()), []);
+    }
+
+    #[test]
+    fn test_keystreams() {
+        let mut generator = Xorshift32::new(1).unwrap();
+        let bytes: Vec<u8> = xorshift_keystream(generator).take(8).collect();
+        assert_eq!(bytes[..4], generator.next_u32().to_le_bytes());
+        assert_eq!(bytes[4..], generator.next_u32().to_le_bytes());
+        let mut lfsr = Lfsr::new(16, 0xB400, 0xACE1, LfsrConfig::Galois).unwrap();
+        let bytes: Vec<u8> = lfsr_keystream(lfsr).take(4).collect();
+        assert_eq!(u32::from_le_bytes(bytes.try_into().unwrap()), lfsr.next_word());
+    }
+
+    #[test]
+    fn test_insecurity() {
+        let generator = Xorshift32::new(42).unwrap();
+        let other = b"Meet me at the usual place at ten, bring the documents";
+        let ciphertext1 = xor_cipher(PLAINTEXT, xorshift_keystream(generator));
+        let ciphertext2 = xor_cipher(other, xorshift_keystream(generator));
+        // Known plaintext reveals the keystream
+        let keystream = xor_cipher(&ciphertext1, PLAINTEXT.iter().copied());
+        assert_eq!(xor_cipher(&ciphertext2[..keystream.len()], keystream.into_iter()), other[..PLAINTEXT.len()]);
+        // Reused keystream cancels out
+        let leaked = xor_cipher(&ciphertext1, ciphertext2.iter().copied());
+        assert_eq!(leaked, xor_cipher(PLAINTEXT, other.iter().copied()));
+        // 4 keystream bytes are the state of xorshift32, so the rest is predicted
+        let state = u32::from_le_bytes(xor_cipher(&ciphertext1[..4], PLAINTEXT.iter().copied()).try_into().unwrap());
+        let predicted: Vec<u8> = xorshift_keystream(Xorshift32::new(state).unwrap()).take(8).collect();
+        assert_eq!(predicted, xor_cipher(&ciphertext1[4..12], PLAINTEXT[4..12].iter().copied()));
+    }
+
+    #[test]
+    #[should_panic(expected = "keystream must not be shorter than the data")]
+    fn test_short_keystream() {
+        xor_cipher(PLAINTEXT, [1, 2, 3].into_iter());
+    }
+
+    #[test]
+    fn test_sbox4() {
+        const PRESENT: [u8; 16] = [0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2];
+        let inverse = invert_sbox(&PRESENT).unwrap();
+        assert_eq!(apply_sbox4(0x0123_4567, &PRESENT), 0xC56B_90AD);
+        assert_eq!(apply_sbox4(0x89AB_CDEF, &PRESENT), 0x3EF8_4712);
+        for word in [0, u32::MAX, 0xDEAD_BEEF, 0x1357_9BDF] {
+            assert_eq!(apply_sbox4(apply_sbox4(word, &PRESENT), &inverse), word);
+        }
+        let identity: [u8; 16] = std::array::from_fn(|index| index as u8);
+        assert_eq!(apply_sbox4(0xDEAD_BEEF, &identity), 0xDEAD_BEEF);
+        assert_eq!(invert_sbox(&[0u8; 16]), None);
+        assert_eq!(invert_sbox(&[16u8, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]), None);
+    }
+
+    #[test]
+    fn test_sbox8() {
+        // Multiplication by an odd number modulo 256 is a bijection
+        let sbox: [u8; 256] = std::array::from_fn(|index| (index as u8).wrapping_mul(167).wrapping_add(13));
+        let inverse = invert_sbox(&sbox).unwrap();
+        let mut data = PLAINTEXT.to_vec();
+        apply_sbox8(&mut data, &sbox);
+        assert_eq!(data[0], sbox[PLAINTEXT[0] as usize]);
+        apply_sbox8(&mut data, &inverse);
+        assert_eq!(data, PLAINTEXT);
+    }
+}
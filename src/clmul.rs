@@ -0,0 +1,100 @@
+//! [*Carry-less multiplication*](https://en.wikipedia.org/wiki/Carry-less_product): long multiplication with XOR
+//! instead of addition, so no carries propagate between the columns. It's the product of polynomials over GF(2),
+//! the bit *i* being the coefficient of xⁱ, and the primitive behind CRC folding, GHASH and GF(2ⁿ) arithmetic.
+//! The functions here are portable, *intrinsics::clmul* uses the PCLMULQDQ instruction when the CPU has it
+
+/// Returns the carry-less product of the numbers: *a* shifted by every one of *b*, XORed together
+/// # Arguments
+/// * `a` - first factor
+/// * `b` - second factor
+/// # Examples
+/// ```
+/// # use understanding_bitwise::clmul::clmul;
+/// // (x + 1)(x + 1) = x² + 1, the middle terms cancel
+/// assert_eq!(clmul(0b11, 0b11), 0b101);
+/// assert_eq!(clmul(u32::MAX, 1 << 31), (u32::MAX as u64) << 31);
+/// ```
+pub fn clmul(a: u32, b: u32) -> u64 {
+    clmul64(a as u64, b as u64) as u64
+}
+
+/// Returns the carry-less product of the 64-bit numbers, see *clmul*
+/// # Arguments
+/// * `a` - first factor
+/// * `b` - second factor
+/// # Examples
+/// ```
+/// # use understanding_bitwise::clmul::clmul64;
+/// assert_eq!(clmul64(1 << 63, 1 << 63), 1 << 126);
+/// ```
+pub fn clmul64(a: u64, b: u64) -> u128 {
+    let (a, mut b) = (a as u128, b);
+    let mut product = 0;
+    while b != 0 {
+        product ^= a << b.trailing_zeros();
+        // Clears the lowest one
+        b &= b - 1;
+    }
+    product
+}
+
+/// Returns the remainder of the polynomial division of the number by the polynomial, or None if the polynomial is 0.
+/// Every one at or above the degree of the polynomial is cancelled by XOR with the shifted polynomial
+/// # Arguments
+/// * `number` - dividend
+/// * `polynomial` - divisor
+/// # Examples
+/// ```
+/// # use understanding_bitwise::clmul::{clmul, clmul_reduce};
+/// // Multiplication in GF(2⁸) of AES: the product reduced by x⁸ + x⁴ + x³ + x + 1
+/// assert_eq!(clmul_reduce(clmul(0x57, 0x83), 0x11B), Some(0xC1));
+/// ```
+pub fn clmul_reduce(number: u64, polynomial: u64) -> Option<u64> {
+    let degree = (u64::BITS - 1).checked_sub(polynomial.leading_zeros())?;
+    let mut remainder = number;
+    while remainder >> degree != 0 {
+        let shift = u64::BITS - 1 - remainder.leading_zeros() - degree;
+        remainder ^= polynomial << shift;
+    }
+    Some(remainder)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+    use crate::gf256::gf256_mul;
+
+    fn clmul_naive(a: u32, b: u32) -> u64 {
+        (0..u32::BITS).filter(|&index| b >> index & 1 == 1).fold(0, |product, index| product ^ (a as u64) << index)
+    }
+
+    #[test]
+    fn test_clmul() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let (a, b, c) = (rng.next_u32(), rng.next_u32(), rng.next_u32());
+            assert_eq!(clmul(a, b), clmul_naive(a, b));
+            assert_eq!(clmul(a, b), clmul(b, a));
+            // Distributive over XOR; without carries every pair of ones of the factors gives at most one one
+            assert_eq!(clmul(a, b ^ c), clmul(a, b) ^ clmul(a, c));
+            assert!(clmul(a, b).count_ones() <= a.count_ones() * b.count_ones());
+        }
+        assert_eq!(clmul(0, u32::MAX), 0);
+        assert_eq!(clmul(1, 0xDEAD_BEEF), 0xDEAD_BEEF);
+        assert_eq!(clmul64(u64::MAX, u64::MAX), 0x5555_5555_5555_5555_5555_5555_5555_5555);
+    }
+
+    #[test]
+    fn test_clmul_reduce() {
+        for a in 0..=255u8 {
+            for b in [0, 1, 2, 0x53, 0x83, 0xCA, 0xFF] {
+                assert_eq!(clmul_reduce(clmul(a as u32, b as u32), 0x11B), Some(gf256_mul(a, b) as u64));
+            }
+        }
+        assert_eq!(clmul_reduce(0b1010, 0), None);
+        assert_eq!(clmul_reduce(0b1010, 1), Some(0));
+        assert_eq!(clmul_reduce(0b0110, 0b1000), Some(0b0110));
+    }
+}
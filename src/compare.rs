@@ -0,0 +1,168 @@
+//! Timing comparison of methods answering the same question (e.g. *hob* vs *hob_thr*).
+//! Results are returned as data, so callers decide how to present them
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Settings of the comparison
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CompareOptions {
+    /// Count of generated inputs. Every method is called once per input in every round
+    pub inputs: usize,
+    /// Count of timed rounds
+    pub rounds: usize,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        CompareOptions { inputs: 1_000, rounds: 10 }
+    }
+}
+
+/// Timing statistics of a single method. Durations are measured per round (all inputs processed once)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MethodTiming {
+    /// Name of the method
+    pub name: String,
+    /// Count of calls in a round
+    pub inputs: usize,
+    /// Count of timed rounds
+    pub rounds: usize,
+    /// Duration of the fastest round
+    pub min: Duration,
+    /// Duration of the slowest round
+    pub max: Duration,
+    /// Mean duration of a round
+    pub mean: Duration,
+    /// Median duration of a round, the upper one of the middle two for an even count of rounds
+    pub median: Duration,
+}
+
+impl MethodTiming {
+    /// Returns the count of calls of the method over all rounds, u64::MAX if it doesn't fit
+    pub fn calls(&self) -> u64 {
+        (self.inputs as u64).saturating_mul(self.rounds as u64)
+    }
+
+    /// Returns the mean duration of a single call
+    pub fn per_call(&self) -> Duration {
+        divide(self.mean, self.inputs)
+    }
+}
+
+/// Helper function. Returns the duration divided by the count, 0 for the count 0. *Duration::checked_div* takes u32,
+/// so the division is done in nanoseconds to keep larger counts exact
+/// # Arguments
+/// * `duration` - duration to divide
+/// * `count` - divisor
+fn divide(duration: Duration, count: usize) -> Duration {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+    match count {
+        0 => Duration::ZERO,
+        _ => {
+            // Not above the duration, so the seconds fit into u64
+            let nanos = duration.as_nanos() / count as u128;
+            Duration::new((nanos / NANOS_PER_SEC) as u64, (nanos % NANOS_PER_SEC) as u32)
+        }
+    }
+}
+
+/// Times every method over the same generated inputs. Methods are interleaved round by round, so slow drifts
+/// of the machine state affect all of them equally. Returns the statistics in the order of the methods
+/// # Arguments
+/// * `methods` - pairs of method name and method
+/// * `generator` - function returning the input by its index
+/// * `options` - settings of the comparison
+/// # Examples
+/// ```
+/// # use understanding_bitwise::{hob, hob_thr};
+/// # use understanding_bitwise::compare::{compare_methods, CompareOptions};
+/// let methods: [(&str, &dyn Fn(u32) -> Option<u32>); 2] = [("hob", &hob), ("hob_thr", &hob_thr)];
+/// let timings = compare_methods(&methods, |index| index as u32, CompareOptions { inputs: 100, rounds: 3 });
+/// assert_eq!(timings[1].name, "hob_thr");
+/// assert_eq!(timings[1].calls(), 300);
+/// assert!(timings[1].min <= timings[1].max);
+/// ```
+pub fn compare_methods<I, O, G>(methods: &[(&str, &dyn Fn(I) -> O)], generator: G, options: CompareOptions) -> Vec<MethodTiming>
+where
+    I: Clone,
+    G: FnMut(usize) -> I {
+    let inputs: Vec<I> = (0..options.inputs).map(generator).collect();
+    let mut rounds = vec![Vec::with_capacity(options.rounds); methods.len()];
+    for _ in 0..options.rounds {
+        for ((_, method), durations) in methods.iter().zip(rounds.iter_mut()) {
+            let start = Instant::now();
+            for input in &inputs {
+                black_box(method(black_box(input.clone())));
+            }
+            durations.push(start.elapsed());
+        }
+    }
+    methods.iter().zip(rounds).map(|(&(name, _), mut durations)| {
+        durations.sort();
+        let total: Duration = durations.iter().sum();
+        MethodTiming {
+            name: name.to_owned(),
+            inputs: options.inputs,
+            rounds: options.rounds,
+            min: durations.first().copied().unwrap_or_default(),
+            max: durations.last().copied().unwrap_or_default(),
+            mean: divide(total, options.rounds),
+            median: durations.get(durations.len() / 2).copied().unwrap_or_default(),
+        }
+    }).collect()
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn test_compare_methods() {
+        let fast = |number: u32| number;
+        let slow = |number: u32| {
+            sleep(Duration::from_micros(100));
+            number
+        };
+        let methods: [(&str, &dyn Fn(u32) -> u32); 2] = [("fast", &fast), ("slow", &slow)];
+        let timings = compare_methods(&methods, |index| index as u32, CompareOptions { inputs: 10, rounds: 5 });
+        assert_eq!(timings.iter().map(|timing| timing.name.as_str()).collect::<Vec<_>>(), ["fast", "slow"]);
+        for timing in &timings {
+            assert_eq!(timing.calls(), 50);
+            assert!(timing.min <= timing.median && timing.median <= timing.max);
+            assert!(timing.min <= timing.mean && timing.mean <= timing.max);
+        }
+        assert!(timings[1].min >= Duration::from_millis(1));
+        assert!(timings[1].per_call() >= Duration::from_micros(100));
+        assert!(timings[0].median < timings[1].median);
+    }
+
+    #[test]
+    fn test_compare_methods_empty() {
+        let methods: [(&str, &dyn Fn(u32) -> u32); 1] = [("identity", &|number| number)];
+        let timings = compare_methods(&methods, |index| index as u32, CompareOptions { inputs: 0, rounds: 0 });
+        assert_eq!(timings[0].mean, Duration::ZERO);
+        assert_eq!(timings[0].per_call(), Duration::ZERO);
+        assert!(compare_methods::<u32, u32, _>(&[], |index| index as u32, CompareOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_large_counts() {
+        let timing = MethodTiming {
+            name: "large".to_owned(),
+            inputs: u32::MAX as usize + 1,
+            rounds: usize::MAX,
+            min: Duration::ZERO,
+            max: Duration::MAX,
+            mean: Duration::from_secs(3 << 32),
+            median: Duration::ZERO,
+        };
+        assert_eq!(timing.calls(), u64::MAX);
+        // 2³² would have been truncated to 0 as u32
+        assert_eq!(timing.per_call(), Duration::from_secs(3));
+        assert_eq!(divide(Duration::MAX, 1), Duration::MAX);
+        assert_eq!(divide(Duration::from_nanos(7), 2), Duration::from_nanos(3));
+    }
+}
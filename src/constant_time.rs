@@ -0,0 +1,108 @@
+//! Comparison and selection without data-dependent branches or early exits.
+//! A secret-dependent branch or loop exit makes the running time depend on the secret, so an attacker timing
+//! e.g. a MAC check learns how many leading bytes of a guess are right. These functions instead accumulate
+//! differences with OR and turn conditions into all-ones/all-zeros masks, so the same instructions run for any input.
+//! The compiler is free to reintroduce branches; *black_box* discourages it, but this is best effort, not a guarantee
+//!
+//! A choice is a u32 that is 1 (true) or 0 (false)
+
+use std::hint::black_box;
+
+/// Returns 1 if the number is 0, otherwise 0. For any nonzero number either it or its negation has the highest bit set
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::constant_time::ct_is_zero;
+/// assert_eq!(ct_is_zero(0), 1);
+/// assert_eq!(ct_is_zero(0x8000_0000), 0);
+/// ```
+pub fn ct_is_zero(number: u32) -> u32 {
+    let number = black_box(number);
+    ! (number | number.wrapping_neg()) >> 31
+}
+
+/// Returns 1 if the numbers are equal, otherwise 0
+/// # Arguments
+/// * `a` - first number
+/// * `b` - second number
+pub fn ct_eq_u32(a: u32, b: u32) -> u32 {
+    ct_is_zero(a ^ b)
+}
+
+/// Returns *a* if the choice is 1 and *b* if it's 0. The choice becomes a mask of all ones or all zeros.
+/// Other choice values give a mix of the bits
+/// # Arguments
+/// * `choice` - 1 or 0
+/// * `a` - value selected by 1
+/// * `b` - value selected by 0
+/// # Examples
+/// ```
+/// # use understanding_bitwise::constant_time::ct_select_u32;
+/// assert_eq!(ct_select_u32(1, 10, 20), 10);
+/// assert_eq!(ct_select_u32(0, 10, 20), 20);
+/// ```
+pub fn ct_select_u32(choice: u32, a: u32, b: u32) -> u32 {
+    let mask = black_box(choice).wrapping_neg();
+    b ^ (a ^ b) & mask
+}
+
+/// Returns true if the slices are equal. Every byte is inspected even after a difference is found;
+/// only the lengths, which are usually public, are compared with an early exit
+/// # Arguments
+/// * `a` - first slice
+/// * `b` - second slice
+/// # Examples
+/// ```
+/// # use understanding_bitwise::constant_time::ct_eq;
+/// assert!(ct_eq(b"secret tag", b"secret tag"));
+/// assert!(!ct_eq(b"secret tag", b"secret tab"));
+/// assert!(!ct_eq(b"secret", b"secret tag"));
+/// ```
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let difference = a.iter().zip(b).fold(0u8, |difference, (&a, &b)| black_box(difference | a ^ b));
+    ct_is_zero(difference as u32) == 1
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ct_is_zero() {
+        assert_eq!(ct_is_zero(0), 1);
+        for number in [1, 2, 0x7FFF_FFFF, 0x8000_0000, u32::MAX, 0xDEAD_BEEF] {
+            assert_eq!(ct_is_zero(number), 0);
+        }
+        assert_eq!(ct_eq_u32(5, 5), 1);
+        assert_eq!(ct_eq_u32(5, 4), 0);
+    }
+
+    #[test]
+    fn test_ct_select_u32() {
+        for (a, b) in [(0, u32::MAX), (0xDEAD_BEEF, 0x1234_5678), (7, 7)] {
+            assert_eq!(ct_select_u32(1, a, b), a);
+            assert_eq!(ct_select_u32(0, a, b), b);
+            assert_eq!(ct_select_u32(ct_eq_u32(a, b), a, b), if a == b { a } else { b });
+        }
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let data: Vec<u8> = (0..=255).collect();
+        assert!(ct_eq(&data, &data.clone()));
+        assert!(ct_eq(&[], &[]));
+        for index in 0..data.len() {
+            for bit in 0..8 {
+                let mut other = data.clone();
+                other[index] ^= 1 << bit;
+                assert!(!ct_eq(&data, &other));
+            }
+        }
+        assert!(!ct_eq(&data[1..], &data));
+    }
+}
@@ -0,0 +1,355 @@
+//! [*Cyclic redundancy checks*](https://en.wikipedia.org/wiki/Cyclic_redundancy_check) of widths 1 to 32 bits
+//! described by the Rocksoft model parameters
+
+use std::io::{self, Write};
+
+/// Parameters of a CRC. The register runs MSB first, reflections are applied to the input bytes and to the result
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CrcParams {
+    /// Bits count of the register, from 1 to 32
+    pub width: u32,
+    /// Generator polynomial without the highest term, MSB first
+    pub poly: u32,
+    /// Initial register value
+    pub init: u32,
+    /// Whether every input byte is processed LSB first
+    pub reflect_in: bool,
+    /// Whether the register is reflected before the final XOR
+    pub reflect_out: bool,
+    /// Value XORed with the result
+    pub xor_out: u32,
+}
+
+/// CRC-32/ISO-HDLC, used by Ethernet, zip and PNG
+pub const CRC_32: CrcParams = CrcParams {
+    width: 32,
+    poly: 0x04C1_1DB7,
+    init: 0xFFFF_FFFF,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0xFFFF_FFFF,
+};
+
+/// CRC-32/BZIP2, the non-reflected variant of *CRC_32*
+pub const CRC_32_BZIP2: CrcParams = CrcParams { reflect_in: false, reflect_out: false, ..CRC_32 };
+
+/// CRC-8/MAXIM-DOW, used by 1-Wire sensors (e.g. DS18B20)
+pub const CRC_8_MAXIM: CrcParams = CrcParams { width: 8, poly: 0x31, init: 0, reflect_in: true, reflect_out: true, xor_out: 0 };
+
+/// CRC-8/SMBUS, used by SMBus packet error checking
+pub const CRC_8_SMBUS: CrcParams = CrcParams { width: 8, poly: 0x07, init: 0, reflect_in: false, reflect_out: false, xor_out: 0 };
+
+/// CRC-16/IBM-3740, widely known as CRC-16/CCITT-FALSE
+pub const CRC_16_CCITT_FALSE: CrcParams = CrcParams { width: 16, poly: 0x1021, init: 0xFFFF, reflect_in: false, reflect_out: false, xor_out: 0 };
+
+/// CRC-16/KERMIT, the reflected CRC-16/CCITT
+pub const CRC_16_KERMIT: CrcParams = CrcParams { width: 16, poly: 0x1021, init: 0, reflect_in: true, reflect_out: true, xor_out: 0 };
+
+/// CRC-16/XMODEM
+pub const CRC_16_XMODEM: CrcParams = CrcParams { width: 16, poly: 0x1021, init: 0, reflect_in: false, reflect_out: false, xor_out: 0 };
+
+/// CRC-16/MODBUS. The result is sent low byte first
+pub const CRC_16_MODBUS: CrcParams = CrcParams { width: 16, poly: 0x8005, init: 0xFFFF, reflect_in: true, reflect_out: true, xor_out: 0 };
+
+impl CrcParams {
+    /// Helper function. Returns the mask of the register bits
+    const fn mask(&self) -> u32 {
+        u32::MAX >> (u32::BITS - self.width)
+    }
+
+    /// Helper function. Returns the final value of the register
+    /// # Arguments
+    /// * `register` - register value, not aligned
+    const fn finish(&self, register: u32) -> u32 {
+        let register = if self.reflect_out { register.reverse_bits() >> (u32::BITS - self.width) } else { register };
+        (register ^ self.xor_out) & self.mask()
+    }
+}
+
+/// Returns the CRC of the data. Bit-serial reference implementation: every message bit enters the register,
+/// and the bit shifted out of the register top decides whether the polynomial is XORed in (feedback)
+/// # Arguments
+/// * `params` - parameters of the CRC
+/// * `data` - data to work with
+/// # Panics
+/// Panics if the width of the parameters is not in 1..=32
+/// # Examples
+/// ```
+/// # use understanding_bitwise::crc::{crc_bitwise, CRC_32};
+/// assert_eq!(crc_bitwise(&CRC_32, b"123456789"), 0xCBF4_3926);
+/// ```
+pub fn crc_bitwise(params: &CrcParams, data: &[u8]) -> u32 {
+    assert!((1..=u32::BITS).contains(&params.width), "CRC width must be in 1..=32");
+    let top = params.width - 1;
+    let mut register = params.init & params.mask();
+    for &byte in data {
+        let byte = if params.reflect_in { byte.reverse_bits() } else { byte };
+        for index in (0..u8::BITS).rev() {
+            let feedback = (register >> top ^ (byte >> index) as u32) & 1;
+            register = register << 1 & params.mask();
+            if feedback == 1 {
+                register ^= params.poly;
+            }
+        }
+    }
+    params.finish(register & params.mask())
+}
+
+/// Writes every step of the CRC as polynomial long division and returns the CRC, equal to *crc_bitwise*.
+/// The dividend is the message bits (reflected if the input is) followed by *width* zero bits, with the init value
+/// XORed into its top *width* bits. The divisor, the polynomial with its highest term, is XORed under every leading one;
+/// what remains in the last *width* bits is the register before the output reflection and XOR.
+/// *explained::crc_bitwise_explained* gives the register steps as *BitStep*s for replaying
+/// # Arguments
+/// * `params` - parameters of the CRC
+/// * `data` - data to work with
+/// * `out` - *Write* object receiving the steps, one line per row of the division
+/// # Panics
+/// Panics if the width of the parameters is not in 1..=32
+/// # Examples
+/// ```
+/// # use understanding_bitwise::crc::{crc_bitwise, crc_trace, CRC_8_SMBUS};
+/// let mut trace = Vec::new();
+/// let crc = crc_trace(&CRC_8_SMBUS, b"A", &mut trace).unwrap();
+/// assert_eq!(crc, crc_bitwise(&CRC_8_SMBUS, b"A"));
+/// let trace = String::from_utf8(trace).unwrap();
+/// assert!(trace.starts_with("message   01000001\ndivisor   100000111\ndividend  0100000100000000\n"));
+/// assert!(trace.contains("\nxor        100000111\n"));
+/// ```
+pub fn crc_trace<W: Write>(params: &CrcParams, data: &[u8], out: &mut W) -> io::Result<u32> {
+    assert!((1..=u32::BITS).contains(&params.width), "CRC width must be in 1..=32");
+    let width = params.width as usize;
+    let message: Vec<bool> = data.iter().flat_map(|&byte| {
+        let byte = if params.reflect_in { byte.reverse_bits() } else { byte };
+        (0..u8::BITS).rev().map(move |index| byte >> index & 1 == 1)
+    }).collect();
+    let divisor: Vec<bool> = std::iter::once(true).chain((0..width).rev().map(|index| params.poly >> index & 1 == 1)).collect();
+    let mut bits = message.clone();
+    bits.resize(message.len() + width, false);
+    // The register starting at init adds init·xˡᵉⁿ to the dividend
+    for (index, bit) in bits.iter_mut().take(width).enumerate() {
+        *bit ^= params.init >> (width - 1 - index) & 1 == 1;
+    }
+    let text = |bits: &[bool]| bits.iter().map(|&bit| if bit { '1' } else { '0' }).collect::<String>();
+    writeln!(out, "message   {}", text(&message))?;
+    writeln!(out, "divisor   {}", text(&divisor))?;
+    writeln!(out, "dividend  {}", text(&bits))?;
+    for position in 0..message.len() {
+        if !bits[position] {
+            continue;
+        }
+        for (bit, &divisor_bit) in bits[position..].iter_mut().zip(&divisor) {
+            *bit ^= divisor_bit;
+        }
+        writeln!(out, "xor       {}{}", " ".repeat(position), text(&divisor))?;
+        writeln!(out, "          {}", text(&bits))?;
+    }
+    let remainder = &bits[message.len()..];
+    writeln!(out, "remainder {}{}", " ".repeat(message.len()), text(remainder))?;
+    let crc = params.finish(remainder.iter().fold(0, |register, &bit| register << 1 | bit as u32));
+    writeln!(out, "crc       {:#0digits$x}", crc, digits = width.div_ceil(4) + 2)?;
+    Ok(crc)
+}
+
+/// Table-driven CRC. The register is kept aligned to the top of a u32, so one table works for all widths.
+/// The table is generated by a const fn, so `const` engines are built at compile time
+/// # Examples
+/// ```
+/// # use understanding_bitwise::crc::{Crc, CRC_32};
+/// const ENGINE: Crc = Crc::new(CRC_32);
+/// assert_eq!(ENGINE.checksum(b"123456789"), 0xCBF4_3926);
+///
+/// let mut digest = ENGINE.digest();
+/// digest.update(b"1234");
+/// digest.update(b"56789");
+/// assert_eq!(digest.finish(), 0xCBF4_3926);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Crc {
+    params: CrcParams,
+    table: [u32; 256],
+}
+
+impl Crc {
+    /// Returns the engine with the generated table. Panics if the width isn't in 1..=32
+    /// # Arguments
+    /// * `params` - parameters of the CRC
+    pub const fn new(params: CrcParams) -> Self {
+        assert!(params.width >= 1 && params.width <= u32::BITS, "CRC width must be in 1..=32");
+        let poly = params.poly << (u32::BITS - params.width);
+        let mut table = [0u32; 256];
+        let mut byte = 0;
+        while byte < 256 {
+            // Register after shifting in the byte starting from zero
+            let mut register = (byte as u32) << 24;
+            let mut bit = 0;
+            while bit < u8::BITS {
+                register = if register >> 31 == 1 { register << 1 ^ poly } else { register << 1 };
+                bit += 1;
+            }
+            table[byte] = register;
+            byte += 1;
+        }
+        Crc { params, table }
+    }
+
+    /// Returns the parameters of the CRC
+    pub fn params(&self) -> &CrcParams {
+        &self.params
+    }
+
+    /// Returns the CRC of the data
+    /// # Arguments
+    /// * `data` - data to work with
+    pub fn checksum(&self, data: &[u8]) -> u32 {
+        let mut digest = self.digest();
+        digest.update(data);
+        digest.finish()
+    }
+
+    /// Returns the digest for data arriving in chunks
+    pub fn digest(&self) -> Digest<'_> {
+        Digest { crc: self, register: (self.params.init & self.params.mask()) << (u32::BITS - self.params.width) }
+    }
+}
+
+/// Running CRC computation
+#[derive(Clone, Debug)]
+pub struct Digest<'a> {
+    crc: &'a Crc,
+    /// Register aligned to the top of the word
+    register: u32,
+}
+
+impl Digest<'_> {
+    /// Adds the data
+    /// # Arguments
+    /// * `data` - data to work with
+    pub fn update(&mut self, data: &[u8]) {
+        let reflect_in = self.crc.params.reflect_in;
+        for &byte in data {
+            let byte = if reflect_in { byte.reverse_bits() } else { byte };
+            self.register = self.register << 8 ^ self.crc.table[(self.register >> 24 ^ byte as u32) as usize];
+        }
+    }
+
+    /// Returns the CRC of all added data
+    pub fn finish(&self) -> u32 {
+        let params = &self.crc.params;
+        params.finish(self.register >> (u32::BITS - params.width))
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECK: &[u8] = b"123456789";
+
+    #[test]
+    fn test_check_values() {
+        assert_eq!(crc_bitwise(&CRC_32, CHECK), 0xCBF4_3926);
+        assert_eq!(Crc::new(CRC_32).checksum(CHECK), 0xCBF4_3926);
+        assert_eq!(crc_bitwise(&CRC_32_BZIP2, CHECK), 0xFC89_1918);
+        assert_eq!(Crc::new(CRC_32_BZIP2).checksum(CHECK), 0xFC89_1918);
+        assert_eq!(Crc::new(CRC_32).checksum(&[]), 0);
+    }
+
+    #[test]
+    fn test_presets() {
+        // Check values of the CRC catalogue: CRC of the ASCII string "123456789"
+        for (params, check) in [
+            (CRC_8_MAXIM, 0xA1),
+            (CRC_8_SMBUS, 0xF4),
+            (CRC_16_CCITT_FALSE, 0x29B1),
+            (CRC_16_KERMIT, 0x2189),
+            (CRC_16_XMODEM, 0x31C3),
+            (CRC_16_MODBUS, 0x4B37),
+        ] {
+            assert_eq!(crc_bitwise(&params, CHECK), check, "{params:?}");
+            assert_eq!(Crc::new(params).checksum(CHECK), check, "{params:?}");
+        }
+    }
+
+    #[test]
+    fn test_modbus_frame() {
+        // Read holding registers request: slave 1, function 3, address 0, count 10; CRC 0xCDC5 sent as C5 CD
+        const MODBUS: Crc = Crc::new(CRC_16_MODBUS);
+        let frame = [0x01, 0x03, 0x00, 0x00, 0x00, 0x0A];
+        assert_eq!((MODBUS.checksum(&frame) as u16).to_le_bytes(), [0xC5, 0xCD]);
+        // CRC of the frame followed by its CRC (low byte first) is 0
+        assert_eq!(MODBUS.checksum(&[0x01, 0x03, 0x00, 0x00, 0x00, 0x0A, 0xC5, 0xCD]), 0);
+    }
+
+    #[test]
+    fn test_table_matches_bitwise() {
+        let data: Vec<u8> = (0..=255).chain((0..100).map(|byte: u8| byte.wrapping_mul(37))).collect();
+        for width in 1..=32 {
+            for (reflect_in, reflect_out) in [(false, false), (true, true), (true, false)] {
+                let params = CrcParams {
+                    width,
+                    poly: 0x8D95_B3A7 & u32::MAX >> (32 - width) | 1,
+                    init: 0x1234_5678,
+                    reflect_in,
+                    reflect_out,
+                    xor_out: 0x0F0F_0F0F,
+                };
+                assert_eq!(Crc::new(params).checksum(&data), crc_bitwise(&params, &data), "{params:?}");
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "CRC width must be in 1..=32")]
+    fn test_invalid_width_bitwise() {
+        crc_bitwise(&CrcParams { width: 0, ..CRC_32 }, b"123456789");
+    }
+
+    #[test]
+    fn test_digest() {
+        let crc = Crc::new(CRC_32);
+        let data: Vec<u8> = (0..1000u32).map(|index| (index * 7) as u8).collect();
+        for split in [0, 1, 500, 999, 1000] {
+            let mut digest = crc.digest();
+            digest.update(&data[..split]);
+            digest.update(&data[split..]);
+            assert_eq!(digest.finish(), crc.checksum(&data));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "CRC width must be in 1..=32")]
+    fn test_invalid_width() {
+        Crc::new(CrcParams { width: 33, ..CRC_32 });
+    }
+
+    #[test]
+    fn test_crc_trace() {
+        let presets = [CRC_32, CRC_32_BZIP2, CRC_8_MAXIM, CRC_8_SMBUS, CRC_16_CCITT_FALSE, CRC_16_KERMIT, CRC_16_XMODEM, CRC_16_MODBUS];
+        for params in presets {
+            for data in [CHECK, b"", b"A", b"\xFF\x00"] {
+                let mut trace = Vec::new();
+                assert_eq!(crc_trace(&params, data, &mut trace).unwrap(), crc_bitwise(&params, data));
+                let trace = String::from_utf8(trace).unwrap();
+                let lines: Vec<&str> = trace.lines().collect();
+                // Every XOR clears the leading one it's aligned to
+                for pair in lines[3..lines.len() - 2].chunks(2) {
+                    let position = pair[0].find('1').unwrap();
+                    assert_eq!(&pair[1][position..=position], "0");
+                }
+                assert!(lines[lines.len() - 2].starts_with("remainder"));
+            }
+        }
+        let mut trace = Vec::new();
+        crc_trace(&CRC_8_SMBUS, b"A", &mut trace).unwrap();
+        let expected = "message   01000001\n\
+                        divisor   100000111\n\
+                        dividend  0100000100000000\n\
+                        xor        100000111\n\
+                        \x20         0000000011000000\n\
+                        remainder         11000000\n\
+                        crc       0xc0\n";
+        assert_eq!(String::from_utf8(trace).unwrap(), expected);
+    }
+}
@@ -0,0 +1,215 @@
+//! Dates and times packed into bit fields of *u32*, like the FAT file system timestamps: every component gets just
+//! enough bits for its range, the year is stored as an offset from a base year and the seconds may be stored in
+//! coarser steps to save a bit
+
+use crate::{get_bits, set_bits};
+
+/// Calendar date and time of day
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DateTime {
+    /// Year of the Gregorian calendar
+    pub year: u32,
+    /// Month from 1 to 12
+    pub month: u32,
+    /// Day of the month from 1
+    pub day: u32,
+    /// Hour from 0 to 23
+    pub hour: u32,
+    /// Minute from 0 to 59
+    pub minute: u32,
+    /// Second from 0 to 59
+    pub second: u32,
+}
+
+/// Helper function. Returns the count of days of the month of the Gregorian calendar, 0 for a month out of 1..=12
+/// # Arguments
+/// * `year` - year of the month
+/// * `month` - month from 1 to 12
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        // Multiples of 4 have the two lowest bits clear
+        2 if year & 0b11 == 0 && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+impl DateTime {
+    /// Returns the date and time or None if some component is out of its range or the day isn't in the month
+    /// # Arguments
+    /// * `year` - year
+    /// * `month` - month from 1 to 12
+    /// * `day` - day of the month from 1
+    /// * `hour` - hour from 0 to 23
+    /// * `minute` - minute from 0 to 59
+    /// * `second` - second from 0 to 59
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::date::DateTime;
+    /// assert!(DateTime::new(2024, 2, 29, 23, 59, 59).is_some());
+    /// assert!(DateTime::new(2023, 2, 29, 0, 0, 0).is_none());
+    /// assert!(DateTime::new(2024, 1, 1, 24, 0, 0).is_none());
+    /// ```
+    pub fn new(year: u32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Option<Self> {
+        let date_time = DateTime { year, month, day, hour, minute, second };
+        date_time.is_valid().then_some(date_time)
+    }
+
+    /// Returns true if every component is in its range and the day is in the month
+    pub fn is_valid(&self) -> bool {
+        (1..=days_in_month(self.year, self.month)).contains(&self.day) && self.hour < 24 && self.minute < 60 && self.second < 60
+    }
+}
+
+/// Bit fields of the date and time components: offset and width of the year, month, day, hour, minute and second
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct DateTimeLayout {
+    fields: [(u32, u32); 6],
+    year_base: u32,
+    second_step: u32,
+}
+
+impl DateTimeLayout {
+    /// MS-DOS/FAT timestamp: the date in the high half (7-bit year from 1980, 4-bit month, 5-bit day), the time in
+    /// the low half (5-bit hour, 6-bit minute, 5-bit second in steps of 2)
+    pub const FAT: DateTimeLayout = DateTimeLayout {
+        fields: [(25, 7), (21, 4), (16, 5), (11, 5), (5, 6), (0, 5)],
+        year_base: 1980,
+        second_step: 2,
+    };
+
+    /// Returns the layout or None if a field doesn't fit into 32 bits, two fields overlap or the step is 0
+    /// # Arguments
+    /// * `fields` - offset and width of the year, month, day, hour, minute and second
+    /// * `year_base` - year stored as 0
+    /// * `second_step` - seconds per stored unit
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::date::{DateTime, DateTimeLayout};
+    /// // Seconds since midnight would take 17 bits, separate fields take the same here
+    /// let layout = DateTimeLayout::new([(26, 6), (22, 4), (17, 5), (12, 5), (6, 6), (0, 6)], 2000, 1).unwrap();
+    /// let date_time = DateTime::new(2031, 12, 31, 23, 59, 59).unwrap();
+    /// assert_eq!(layout.unpack(layout.pack(date_time).unwrap()), Some(date_time));
+    /// assert!(DateTimeLayout::new([(26, 6), (22, 5), (17, 5), (12, 5), (6, 6), (0, 6)], 2000, 1).is_none());
+    /// ```
+    pub fn new(fields: [(u32, u32); 6], year_base: u32, second_step: u32) -> Option<Self> {
+        let mut used = 0;
+        for (offset, width) in fields {
+            // set_bits checks the field fits, the ones of the field show the overlap
+            let field = set_bits(0, offset, width, u32::MAX.checked_shr(u32::BITS - width).unwrap_or(0))?;
+            if used & field != 0 {
+                return None;
+            }
+            used |= field;
+        }
+        (second_step != 0).then_some(DateTimeLayout { fields, year_base, second_step })
+    }
+
+    /// Returns the date and time packed into the fields or None if it isn't valid, the year is before the base or
+    /// some component doesn't fit into its field. Seconds are rounded down to the step
+    /// # Arguments
+    /// * `date_time` - date and time to pack
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::date::{DateTime, DateTimeLayout};
+    /// let date_time = DateTime::new(2024, 6, 15, 13, 45, 31).unwrap();
+    /// let packed = DateTimeLayout::FAT.pack(date_time).unwrap();
+    /// // Date 0101100_0110_01111, time 01101_101101_01111
+    /// assert_eq!(packed, 0x58CF_6DAF);
+    /// assert_eq!(DateTimeLayout::FAT.pack(DateTime::new(1979, 1, 1, 0, 0, 0).unwrap()), None);
+    /// ```
+    pub fn pack(&self, date_time: DateTime) -> Option<u32> {
+        if !date_time.is_valid() {
+            return None;
+        }
+        let values = [
+            date_time.year.checked_sub(self.year_base)?,
+            date_time.month,
+            date_time.day,
+            date_time.hour,
+            date_time.minute,
+            date_time.second / self.second_step,
+        ];
+        self.fields.iter().zip(values).try_fold(0, |packed, (&(offset, width), value)| set_bits(packed, offset, width, value))
+    }
+
+    /// Returns the date and time of the fields or None if it isn't valid: month 0, day 31 of April, hour 25 and so on
+    /// # Arguments
+    /// * `packed` - packed date and time
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::date::{DateTime, DateTimeLayout};
+    /// assert_eq!(DateTimeLayout::FAT.unpack(0x58CF_6DAF), DateTime::new(2024, 6, 15, 13, 45, 30));
+    /// // Month 13
+    /// assert_eq!(DateTimeLayout::FAT.unpack(0x01A1_0000), None);
+    /// ```
+    pub fn unpack(&self, packed: u32) -> Option<DateTime> {
+        let [year, month, day, hour, minute, second] = self.fields.map(|(offset, width)| {
+            get_bits(packed, offset, width).expect("fields are checked to fit")
+        });
+        DateTime::new(year.checked_add(self.year_base)?, month, day, hour, minute, second.checked_mul(self.second_step)?)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_days_in_month() {
+        assert_eq!((1..=12).map(|month| days_in_month(2023, month)).sum::<u32>(), 365);
+        assert_eq!((1..=12).map(|month| days_in_month(2024, month)).sum::<u32>(), 366);
+        assert_eq!((days_in_month(1900, 2), days_in_month(2000, 2)), (28, 29));
+        assert_eq!((days_in_month(2024, 0), days_in_month(2024, 13)), (0, 0));
+        assert!(DateTime::new(2024, 4, 31, 0, 0, 0).is_none());
+        assert!(DateTime::new(2024, 12, 0, 0, 0, 0).is_none());
+        assert!(DateTime::new(2024, 12, 31, 0, 60, 0).is_none());
+    }
+
+    #[test]
+    fn test_fat() {
+        let layout = DateTimeLayout::FAT;
+        // The earliest and the latest FAT timestamps
+        assert_eq!(layout.pack(DateTime::new(1980, 1, 1, 0, 0, 0).unwrap()), Some(0x0021_0000));
+        assert_eq!(layout.pack(DateTime::new(2107, 12, 31, 23, 59, 58).unwrap()), Some(0xFF9F_BF7D));
+        assert_eq!(layout.pack(DateTime::new(2108, 1, 1, 0, 0, 0).unwrap()), None);
+        // The halves are the DOS date and time words
+        let packed = layout.pack(DateTime::new(2000, 2, 29, 12, 30, 10).unwrap()).unwrap();
+        assert_eq!(packed >> 16, 20 << 9 | 2 << 5 | 29);
+        assert_eq!(packed & 0xFFFF, 12 << 11 | 30 << 5 | 5);
+        // Odd seconds lose their last bit
+        let odd = DateTime::new(2000, 2, 29, 12, 30, 11).unwrap();
+        assert_eq!(layout.pack(odd), Some(packed));
+        assert_eq!(layout.unpack(packed).map(|date_time| date_time.second), Some(10));
+        // Zero is not a date: month and day start at 1
+        assert_eq!(layout.unpack(0), None);
+        // Second field 30 is 60 seconds
+        assert_eq!(layout.unpack(0x0021_001E), None);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let layout = DateTimeLayout::new([(26, 6), (22, 4), (17, 5), (12, 5), (6, 6), (0, 6)], 2000, 1).unwrap();
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        let mut valid = 0;
+        for _ in 0..10000 {
+            let state = rng.next_u32();
+            for layout in [DateTimeLayout::FAT, layout] {
+                if let Some(date_time) = layout.unpack(state) {
+                    assert_eq!(layout.pack(date_time), Some(state));
+                    valid += 1;
+                }
+            }
+        }
+        assert!(valid > 0);
+        let invalid = DateTime { year: 2001, month: 2, day: 29, hour: 0, minute: 0, second: 0 };
+        assert_eq!(layout.pack(invalid), None);
+        // Fields must fit, not overlap, and the step can't be 0
+        assert!(DateTimeLayout::new([(27, 6), (22, 4), (17, 5), (12, 5), (6, 6), (0, 6)], 2000, 1).is_none());
+        assert!(DateTimeLayout::new([(26, 6), (22, 4), (17, 5), (12, 5), (6, 6), (0, 6)], 2000, 0).is_none());
+    }
+}
@@ -0,0 +1,145 @@
+//! Text diagrams of the bits of a number for terminals and logs: a row of bit indices from 31 down to 0 over
+//! a row of the bit values, optionally split into fields, and the difference of two numbers
+
+use std::io::{self, Write};
+
+/// Helper function. Writes a row of 32 cells of width 2, the cell of the bit *i* returned by the closure,
+/// separated by *|* in front of every field boundary and by a space elsewhere
+/// # Arguments
+/// * `boundaries` - indexes of the lowest bits of the fields
+/// * `cell` - text of the cell of the bit
+/// * `out` - *Write* object that gets the row
+fn write_row<W: Write>(boundaries: &[u32], cell: impl Fn(u32) -> String, out: &mut W) -> io::Result<()> {
+    let mut row = String::new();
+    for index in (0..u32::BITS).rev() {
+        if index != u32::BITS - 1 {
+            row.push(if boundaries.contains(&(index + 1)) { '|' } else { ' ' });
+        }
+        row.push_str(&format!("{:>2}", cell(index)));
+    }
+    writeln!(out, "{row}")
+}
+
+/// Writes the diagram of the number: bit indices from 31 down to 0 on the first line, bit values under them
+/// # Arguments
+/// * `number` - number to work with
+/// * `out` - *Write* object that gets the diagram
+/// # Examples
+/// ```
+/// # use understanding_bitwise::diagram::write_bit_diagram;
+/// let mut vec = Vec::new();
+/// write_bit_diagram(0b101, &mut vec).unwrap();
+/// let text = String::from_utf8(vec).unwrap();
+/// let lines: Vec<&str> = text.lines().collect();
+/// assert!(lines[0].starts_with("31 30 29") && lines[0].ends_with(" 2  1  0"));
+/// assert!(lines[1].starts_with(" 0  0  0") && lines[1].ends_with(" 1  0  1"));
+/// ```
+pub fn write_bit_diagram<W: Write>(number: u32, out: &mut W) -> io::Result<()> {
+    write_bit_diagram_with_fields(number, &[], out)
+}
+
+/// Writes the diagram of the number with a *|* between the fields, see *write_bit_diagram*.
+/// A boundary is the index of the lowest bit of a field; 0 and indexes above 31 mark nothing
+/// # Arguments
+/// * `number` - number to work with
+/// * `boundaries` - indexes of the lowest bits of the fields
+/// * `out` - *Write* object that gets the diagram
+/// # Examples
+/// ```
+/// # use understanding_bitwise::diagram::write_bit_diagram_with_fields;
+/// // Fields 31..8, 7..4 and 3..0
+/// let mut vec = Vec::new();
+/// write_bit_diagram_with_fields(0xA5, &[4, 8], &mut vec).unwrap();
+/// let text = String::from_utf8(vec).unwrap();
+/// let lines: Vec<&str> = text.lines().collect();
+/// assert!(lines[0].ends_with(" 8| 7  6  5  4| 3  2  1  0"));
+/// assert!(lines[1].ends_with(" 0| 1  0  1  0| 0  1  0  1"));
+/// ```
+pub fn write_bit_diagram_with_fields<W: Write>(number: u32, boundaries: &[u32], out: &mut W) -> io::Result<()> {
+    write_row(boundaries, |index| index.to_string(), out)?;
+    write_row(boundaries, |index| (number >> index & 1).to_string(), out)
+}
+
+/// Difference of two numbers
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BitDiff {
+    /// Ones at the positions where the numbers differ
+    pub diff_mask: u32,
+    /// Count of differing positions, the Hamming distance
+    pub distance: u32,
+}
+
+/// Writes both numbers in binary one under the other, then a row with *^* under every differing bit
+/// (trailing spaces trimmed, empty for equal numbers), and returns the difference
+/// # Arguments
+/// * `a` - first number
+/// * `b` - second number
+/// * `out` - *Write* object that gets the diff
+/// # Examples
+/// ```
+/// # use understanding_bitwise::diagram::{write_bit_diff, BitDiff};
+/// let mut vec = Vec::new();
+/// let diff = write_bit_diff(0b1100, 0b1010, &mut vec).unwrap();
+/// assert_eq!(diff, BitDiff { diff_mask: 0b0110, distance: 2 });
+/// let text = String::from_utf8(vec).unwrap();
+/// let lines: Vec<&str> = text.lines().collect();
+/// assert_eq!(lines[0], "00000000000000000000000000001100");
+/// assert_eq!(lines[1], "00000000000000000000000000001010");
+/// assert_eq!(lines[2], "                             ^^");
+/// ```
+pub fn write_bit_diff<W: Write>(a: u32, b: u32, out: &mut W) -> io::Result<BitDiff> {
+    let diff_mask = a ^ b;
+    let marks: String = (0..u32::BITS).rev().map(|index| if diff_mask >> index & 1 == 1 { '^' } else { ' ' }).collect();
+    writeln!(out, "{a:032b}")?;
+    writeln!(out, "{b:032b}")?;
+    writeln!(out, "{}", marks.trim_end())?;
+    Ok(BitDiff { diff_mask, distance: diff_mask.count_ones() })
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagram(number: u32, boundaries: &[u32]) -> Vec<String> {
+        let mut vec = Vec::new();
+        write_bit_diagram_with_fields(number, boundaries, &mut vec).unwrap();
+        String::from_utf8(vec).unwrap().lines().map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn test_bit_diagram() {
+        let lines = diagram(0x8000_0001, &[]);
+        assert_eq!(lines.len(), 2);
+        // 32 cells of 2 characters and 31 separators
+        assert!(lines.iter().all(|line| line.len() == 95));
+        assert_eq!(lines[1], format!(" 1{}  1", "  0".repeat(30)));
+        let mut vec = Vec::new();
+        write_bit_diagram(0x8000_0001, &mut vec).unwrap();
+        assert_eq!(String::from_utf8(vec).unwrap(), lines.join("\n") + "\n");
+    }
+
+    #[test]
+    fn test_fields() {
+        let lines = diagram(u32::MAX, &[0, 16, 31, 32, 40]);
+        assert_eq!(lines[0].matches('|').count(), 2);
+        assert_eq!(&lines[0][..6], "31|30 ");
+        assert_eq!(&lines[1][45..54], " 1| 1  1 ");
+        assert_eq!(lines[1].len(), 95);
+    }
+
+    #[test]
+    fn test_bit_diff() {
+        let mut vec = Vec::new();
+        assert_eq!(write_bit_diff(7, 7, &mut vec).unwrap(), BitDiff { diff_mask: 0, distance: 0 });
+        assert_eq!(write_bit_diff(0, u32::MAX, &mut vec).unwrap(), BitDiff { diff_mask: u32::MAX, distance: 32 });
+        let text = String::from_utf8(vec).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[2], "");
+        assert_eq!(lines[5], "^".repeat(32));
+        let diff = write_bit_diff(0xDEAD_BEEF, 0x1234_5678, &mut Vec::new()).unwrap();
+        assert_eq!(diff.diff_mask, 0xDEAD_BEEF ^ 0x1234_5678);
+        assert_eq!(diff.distance, diff.diff_mask.count_ones());
+    }
+}
@@ -0,0 +1,157 @@
+//! Transactional multi-step editing of a number
+
+use std::ops::Range;
+use crate::{try_invert_bit, try_set_bit, try_swap_bits, try_unset_bit, BitError};
+
+/// Recorded edit step
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Step {
+    Set(u32),
+    Unset(u32),
+    Invert(u32),
+    Swap(u32, u32),
+    InvertRange(Range<u32>),
+}
+
+impl Step {
+    /// Returns the number with the step applied or the error if some index is out of range
+    /// # Arguments
+    /// * `number` - number to work with
+    fn apply(&self, number: u32) -> Result<u32, BitError> {
+        match *self {
+            Step::Set(index) => try_set_bit(number, index),
+            Step::Unset(index) => try_unset_bit(number, index),
+            Step::Invert(index) => try_invert_bit(number, index),
+            Step::Swap(index1, index2) => try_swap_bits(number, index1, index2),
+            Step::InvertRange(Range { start, end }) => {
+                if end > u32::BITS {
+                    return Err(BitError::new("invert_range", end, start..u32::BITS + 1));
+                }
+                if start > end {
+                    return Err(BitError::new("invert_range", start, 0..end + 1));
+                }
+                let width = end - start;
+                let mask = u32::MAX.checked_shr(u32::BITS - width).unwrap_or(0) << start % u32::BITS;
+                Ok(number ^ mask)
+            }
+        }
+    }
+}
+
+/// Builder of a multi-step edit. Steps are only recorded, *commit* applies all of them or none
+/// # Examples
+/// ```
+/// # use understanding_bitwise::editor::BitEditor;
+/// let edited = BitEditor::new(0).set(3).unset(9).swap(3, 4).invert_range(8..12).commit();
+/// assert_eq!(edited, Ok(0b1111_0001_0000));
+///
+/// let error = BitEditor::new(0).set(3).set(40).commit().unwrap_err();
+/// assert_eq!(error.step(), Some(1));
+/// assert_eq!(error.to_string(), "step 1: set_bit: index 40 is out of range 0..32");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BitEditor {
+    number: u32,
+    steps: Vec<Step>,
+}
+
+impl BitEditor {
+    /// Returns the editor without steps
+    /// # Arguments
+    /// * `number` - number to be edited
+    pub fn new(number: u32) -> Self {
+        BitEditor { number, steps: Vec::new() }
+    }
+
+    /// Helper function. Returns the editor with the step recorded
+    /// # Arguments
+    /// * `step` - step to be recorded
+    fn push(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Records setting the bit to 1
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn set(self, index: u32) -> Self {
+        self.push(Step::Set(index))
+    }
+
+    /// Records setting the bit to 0
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn unset(self, index: u32) -> Self {
+        self.push(Step::Unset(index))
+    }
+
+    /// Records inverting the bit
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn invert(self, index: u32) -> Self {
+        self.push(Step::Invert(index))
+    }
+
+    /// Records swapping the bits
+    /// # Arguments
+    /// * `index1` - index of the bit to be swapped
+    /// * `index2` - index of the bit to be swapped
+    pub fn swap(self, index1: u32, index2: u32) -> Self {
+        self.push(Step::Swap(index1, index2))
+    }
+
+    /// Records inverting the range of bits
+    /// # Arguments
+    /// * `range` - range of bit indexes, the end must not exceed 32
+    pub fn invert_range(self, range: Range<u32>) -> Self {
+        self.push(Step::InvertRange(range))
+    }
+
+    /// Returns the count of recorded steps
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns true if no steps are recorded
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Returns the number with all steps applied in order, or the error of the first failing step marked with its index
+    pub fn commit(self) -> Result<u32, BitError> {
+        self.steps.iter().enumerate()
+            .try_fold(self.number, |number, (index, step)| step.apply(number).map_err(|error| error.with_step(index)))
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit() {
+        assert_eq!(BitEditor::new(0b101).commit(), Ok(0b101));
+        assert_eq!(BitEditor::new(0).invert(31).set(0).unset(0).commit(), Ok(1 << 31));
+        assert_eq!(BitEditor::new(0).invert_range(0..32).commit(), Ok(u32::MAX));
+        assert_eq!(BitEditor::new(0).invert_range(32..32).invert_range(5..5).commit(), Ok(0));
+        assert_eq!(BitEditor::new(u32::MAX).invert_range(28..32).commit(), Ok(0x0FFF_FFFF));
+        let editor = BitEditor::new(0).set(1).swap(1, 30);
+        assert_eq!(editor.len(), 2);
+        assert_eq!(editor.commit(), Ok(1 << 30));
+    }
+
+    #[test]
+    fn test_commit_errors() {
+        let error = BitEditor::new(0).set(1).swap(2, 32).unset(50).commit().unwrap_err();
+        assert_eq!((error.step(), error.operation(), error.index()), (Some(1), "swap_bits", 32));
+
+        let error = BitEditor::new(0).invert_range(30..33).commit().unwrap_err();
+        assert_eq!((error.step(), error.index(), error.valid_range()), (Some(0), 33, 30..33));
+        assert_eq!(error.to_string(), "step 0: invert_range: index 33 is out of range 30..33");
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let error = BitEditor::new(0).invert_range(8..4).commit().unwrap_err();
+        assert_eq!((error.operation(), error.index(), error.valid_range()), ("invert_range", 8, 0..5));
+    }
+}
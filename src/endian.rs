@@ -0,0 +1,284 @@
+//! Endian-aware field access over byte buffers at arbitrary bit offsets.
+//! Big endian access numbers the bits from the highest bit of byte 0 and reads the highest bit of a value first (network order).
+//! Little endian access numbers the bits from the lowest bit of byte 0 and reads the lowest bit of a value first.
+//! For byte-aligned offsets both match *from_be_bytes*/*from_le_bytes*
+
+/// Bit order of a field inside a byte buffer
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Endianness {
+    /// Bit offset 0 is the highest bit of byte 0, and the first bit of the field is its highest bit (network order)
+    Big,
+    /// Bit offset 0 is the lowest bit of byte 0, and the first bit of the field is its lowest bit
+    Little,
+}
+
+/// Helper function. Returns the index of the byte and the index of the bit inside it
+/// # Arguments
+/// * `position` - bit offset in the buffer
+/// * `endianness` - bit order
+fn locate(position: usize, endianness: Endianness) -> (usize, u32) {
+    let bit = (position % 8) as u32;
+    (position / 8, match endianness {
+        Endianness::Big => 7 - bit,
+        Endianness::Little => bit,
+    })
+}
+
+/// Helper function. Returns true if the field of the width up to 64 bits fits into the buffer
+/// # Arguments
+/// * `len` - length of the buffer in bytes
+/// * `offset` - bit offset of the field
+/// * `width` - bits count of the field
+fn fits(len: usize, offset: usize, width: u32) -> bool {
+    width <= u64::BITS && offset.checked_add(width as usize).is_some_and(|end| end <= len * 8)
+}
+
+/// Returns the value of the field or None if the field doesn't fit into the buffer or is wider than 64 bits
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the field
+/// * `width` - bits count of the field
+/// * `endianness` - bit order
+/// # Examples
+/// ```
+/// # use understanding_bitwise::endian::{read_bits, Endianness};
+/// assert_eq!(read_bits(&[0b1010_0001], 0, 4, Endianness::Big), Some(0b1010));
+/// assert_eq!(read_bits(&[0b1010_0001], 0, 4, Endianness::Little), Some(0b0001));
+/// ```
+pub fn read_bits(bytes: &[u8], offset: usize, width: u32, endianness: Endianness) -> Option<u64> {
+    if ! fits(bytes.len(), offset, width) {
+        return None;
+    }
+
+    Some((0..width).fold(0, |value, i| {
+        let (byte, bit) = locate(offset + i as usize, endianness);
+        let bit_value = (bytes[byte] >> bit & 1) as u64;
+        match endianness {
+            Endianness::Big => value << 1 | bit_value,
+            Endianness::Little => value | bit_value << i,
+        }
+    }))
+}
+
+/// Writes the value of the field, other bits are left untouched.
+/// Returns None if the field doesn't fit into the buffer, is wider than 64 bits or the value doesn't fit into the field
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the field
+/// * `width` - bits count of the field
+/// * `endianness` - bit order
+/// * `value` - new value of the field
+/// # Examples
+/// ```
+/// # use understanding_bitwise::endian::{write_bits, Endianness};
+/// let mut bytes = [0u8; 2];
+/// write_bits(&mut bytes, 6, 4, Endianness::Big, 0b1011).unwrap();
+/// assert_eq!(bytes, [0b0000_0010, 0b1100_0000]);
+/// ```
+pub fn write_bits(bytes: &mut [u8], offset: usize, width: u32, endianness: Endianness, value: u64) -> Option<()> {
+    if ! fits(bytes.len(), offset, width) || value.checked_shr(width).unwrap_or(0) != 0 {
+        return None;
+    }
+
+    for i in 0..width {
+        let (byte, bit) = locate(offset + i as usize, endianness);
+        let bit_value = match endianness {
+            Endianness::Big => value >> width - 1 - i & 1,
+            Endianness::Little => value >> i & 1,
+        } as u8;
+        bytes[byte] = bytes[byte] & ! (1 << bit) | bit_value << bit;
+    }
+    Some(())
+}
+
+/// Returns the big endian field value or None if the field doesn't fit. See *read_bits*
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the field
+/// * `width` - bits count of the field
+pub fn read_bits_be(bytes: &[u8], offset: usize, width: u32) -> Option<u64> {
+    read_bits(bytes, offset, width, Endianness::Big)
+}
+
+/// Returns the little endian field value or None if the field doesn't fit. See *read_bits*
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the field
+/// * `width` - bits count of the field
+pub fn read_bits_le(bytes: &[u8], offset: usize, width: u32) -> Option<u64> {
+    read_bits(bytes, offset, width, Endianness::Little)
+}
+
+/// Writes the big endian field value. Returns None if the field or the value doesn't fit. See *write_bits*
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the field
+/// * `width` - bits count of the field
+/// * `value` - new value of the field
+pub fn write_bits_be(bytes: &mut [u8], offset: usize, width: u32, value: u64) -> Option<()> {
+    write_bits(bytes, offset, width, Endianness::Big, value)
+}
+
+/// Writes the little endian field value. Returns None if the field or the value doesn't fit. See *write_bits*
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the field
+/// * `width` - bits count of the field
+/// * `value` - new value of the field
+pub fn write_bits_le(bytes: &mut [u8], offset: usize, width: u32, value: u64) -> Option<()> {
+    write_bits(bytes, offset, width, Endianness::Little, value)
+}
+
+/// Returns the big endian *u16* starting at the bit offset or None if it doesn't fit into the buffer
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the value
+/// # Examples
+/// ```
+/// # use understanding_bitwise::endian::read_u16_be;
+/// assert_eq!(read_u16_be(&[0x12, 0x34], 0), Some(0x1234));
+/// assert_eq!(read_u16_be(&[0x01, 0x23, 0x40], 4), Some(0x1234));
+/// ```
+pub fn read_u16_be(bytes: &[u8], offset: usize) -> Option<u16> {
+    read_bits_be(bytes, offset, u16::BITS).map(|value| value as u16)
+}
+
+/// Returns the little endian *u16* starting at the bit offset or None if it doesn't fit into the buffer
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the value
+/// # Examples
+/// ```
+/// # use understanding_bitwise::endian::read_u16_le;
+/// assert_eq!(read_u16_le(&[0x34, 0x12], 0), Some(0x1234));
+/// assert_eq!(read_u16_le(&[0x40, 0x23, 0x01], 4), Some(0x1234));
+/// ```
+pub fn read_u16_le(bytes: &[u8], offset: usize) -> Option<u16> {
+    read_bits_le(bytes, offset, u16::BITS).map(|value| value as u16)
+}
+
+/// Returns the big endian *u32* starting at the bit offset or None if it doesn't fit into the buffer
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the value
+pub fn read_u32_be(bytes: &[u8], offset: usize) -> Option<u32> {
+    read_bits_be(bytes, offset, u32::BITS).map(|value| value as u32)
+}
+
+/// Returns the little endian *u32* starting at the bit offset or None if it doesn't fit into the buffer
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the value
+pub fn read_u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    read_bits_le(bytes, offset, u32::BITS).map(|value| value as u32)
+}
+
+/// Writes the big endian *u16* starting at the bit offset. Returns None if it doesn't fit into the buffer
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the value
+/// * `value` - value to be written
+pub fn write_u16_be(bytes: &mut [u8], offset: usize, value: u16) -> Option<()> {
+    write_bits_be(bytes, offset, u16::BITS, value as u64)
+}
+
+/// Writes the little endian *u16* starting at the bit offset. Returns None if it doesn't fit into the buffer
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the value
+/// * `value` - value to be written
+pub fn write_u16_le(bytes: &mut [u8], offset: usize, value: u16) -> Option<()> {
+    write_bits_le(bytes, offset, u16::BITS, value as u64)
+}
+
+/// Writes the big endian *u32* starting at the bit offset. Returns None if it doesn't fit into the buffer
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the value
+/// * `value` - value to be written
+pub fn write_u32_be(bytes: &mut [u8], offset: usize, value: u32) -> Option<()> {
+    write_bits_be(bytes, offset, u32::BITS, value as u64)
+}
+
+/// Writes the little endian *u32* starting at the bit offset. Returns None if it doesn't fit into the buffer
+/// # Arguments
+/// * `bytes` - buffer to work with
+/// * `offset` - bit offset of the value
+/// * `value` - value to be written
+pub fn write_u32_le(bytes: &mut [u8], offset: usize, value: u32) -> Option<()> {
+    write_bits_le(bytes, offset, u32::BITS, value as u64)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_bits() {
+        let bytes = [0b1010_0001, 0b1100_0011];
+        assert_eq!(read_bits_be(&bytes, 0, 4), Some(0b1010));
+        assert_eq!(read_bits_le(&bytes, 0, 4), Some(0b0001));
+        assert_eq!(read_bits_be(&bytes, 6, 4), Some(0b0111));
+        assert_eq!(read_bits_le(&bytes, 6, 4), Some(0b1110));
+        assert_eq!(read_bits_be(&bytes, 0, 16), Some(0xA1C3));
+        assert_eq!(read_bits_le(&bytes, 0, 16), Some(0xC3A1));
+        assert_eq!(read_bits_be(&bytes, 10, 7), None);
+        assert_eq!(read_bits_be(&bytes, usize::MAX, 1), None);
+        assert_eq!(read_bits_be(&[0xFF; 9], 0, 65), None);
+        assert_eq!(read_bits_le(&[0xFF; 9], 3, 64), Some(u64::MAX));
+        assert_eq!(read_bits_le(&[], 0, 0), Some(0));
+    }
+
+    #[test]
+    fn test_write_read_bits() {
+        for endianness in [Endianness::Big, Endianness::Little] {
+            for offset in 0..16 {
+                for width in 1..=20 {
+                    const ORIGINAL: [u8; 5] = [0x5A; 5];
+                    let mut bytes = ORIGINAL;
+                    let value = 0xABCDE & ((1 << width) - 1);
+                    write_bits(&mut bytes, offset, width, endianness, value).unwrap();
+                    assert_eq!(read_bits(&bytes, offset, width, endianness), Some(value));
+                    // Bits around the field are left untouched
+                    for position in (0..offset).chain(offset + width as usize..ORIGINAL.len() * 8) {
+                        let (byte, bit) = locate(position, endianness);
+                        assert_eq!(bytes[byte] >> bit & 1, ORIGINAL[byte] >> bit & 1);
+                    }
+                }
+            }
+            assert_eq!(write_bits(&mut [0; 2], 0, 4, endianness, 0x10), None);
+            assert_eq!(write_bits(&mut [0; 2], 12, 8, endianness, 0), None);
+        }
+    }
+
+    #[test]
+    fn test_aligned_matches_std() {
+        let bytes = [0xDE, 0xAD, 0xBE, 0xEF, 0x01];
+        assert_eq!(read_u16_be(&bytes, 8), Some(u16::from_be_bytes([0xAD, 0xBE])));
+        assert_eq!(read_u16_le(&bytes, 8), Some(u16::from_le_bytes([0xAD, 0xBE])));
+        assert_eq!(read_u32_be(&bytes, 0), Some(0xDEADBEEF));
+        assert_eq!(read_u32_le(&bytes, 8), Some(0x01EFBEAD));
+        assert_eq!(read_u32_le(&bytes, 9), None);
+    }
+
+    #[test]
+    fn test_unaligned_round_trip() {
+        for offset in 0..8 {
+            // Bit offsets of big and little endian fields number the bits of a byte differently, so they aren't mixed in one buffer
+            let mut bytes = [0u8; 7];
+            write_u32_be(&mut bytes, offset, 0x89ABCDEF).unwrap();
+            write_u16_be(&mut bytes, offset + 32, 0xFACE).unwrap();
+            assert_eq!(read_u32_be(&bytes, offset), Some(0x89ABCDEF));
+            assert_eq!(read_u16_be(&bytes, offset + 32), Some(0xFACE));
+
+            let mut bytes = [0u8; 7];
+            write_u32_le(&mut bytes, offset, 0x13579BDF).unwrap();
+            write_u16_le(&mut bytes, offset + 32, 0x1234).unwrap();
+            assert_eq!(read_u32_le(&bytes, offset), Some(0x13579BDF));
+            assert_eq!(read_u16_le(&bytes, offset + 32), Some(0x1234));
+        }
+        assert_eq!(write_u32_be(&mut [0; 4], 1, 0), None);
+        assert_eq!(write_u16_le(&mut [0; 1], 0, 0), None);
+    }
+}
@@ -0,0 +1,448 @@
+//! Evaluator of bitwise expressions over named 32-bit variables, the engine of playgrounds like the REPL of the
+//! `bitwise` tool. Expressions have numbers (binary, hexadecimal or decimal), variables, parentheses, the operators
+//! `! ~ + - << >> & ^ |` with the precedence of Rust, and the calls `set_bit(x, i)`, `unset_bit(x, i)`,
+//! `invert_bit(x, i)`, `swap_bits(x, i, j)`, `remove_bit(x, i)`, `hob(x)` and `count(x)`.
+//! Every expression is evaluated into *explained::Step*s, so it can be traced or replayed
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use crate::explained::{invert_bit_explained, remove_bit_explained, set_bit_explained, swap_bits_explained,
+                       unset_bit_explained, BitStep, ShiftDirection, Step};
+use crate::{binary_ones_count, hob, parse_number};
+
+/// Error of an expression: what is wrong and the byte offset in the expression where it was found
+/// # Examples
+/// ```
+/// # use understanding_bitwise::evaluator::Evaluator;
+/// let error = Evaluator::new().evaluate("1 + y").unwrap_err();
+/// assert_eq!(error.position, 4);
+/// assert_eq!(error.to_string(), "at 4: unknown variable 'y'");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EvalError {
+    /// Byte offset in the expression
+    pub position: usize,
+    /// What is wrong
+    pub message: String,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: {}", self.position, self.message)
+    }
+}
+
+impl Error for EvalError {}
+
+/// Evaluator keeping the variables between the evaluations
+/// # Examples
+/// ```
+/// # use understanding_bitwise::evaluator::Evaluator;
+/// let mut evaluator = Evaluator::new();
+/// evaluator.run("x = 0b1010").unwrap();
+/// assert_eq!(evaluator.value("set_bit(x, 0) & ~0b10").unwrap(), 0b1001);
+/// let steps = evaluator.run("y = x >> 1 | 1 << 31").unwrap();
+/// assert_eq!(steps.last().unwrap().value, 1 << 31 | 0b101);
+/// assert_eq!(evaluator.variable("y"), Some(1 << 31 | 0b101));
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct Evaluator {
+    variables: BTreeMap<String, u32>,
+}
+
+impl Evaluator {
+    /// Returns the evaluator without variables
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value of the variable or None if it isn't defined
+    /// # Arguments
+    /// * `name` - name of the variable
+    pub fn variable(&self, name: &str) -> Option<u32> {
+        self.variables.get(name).copied()
+    }
+
+    /// Returns the variables with their values in the order of the names
+    pub fn variables(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.variables.iter().map(|(name, &value)| (name.as_str(), value))
+    }
+
+    /// Defines the variable or replaces its value. Returns None if the name isn't an identifier
+    /// or is the name of a function
+    /// # Arguments
+    /// * `name` - name of the variable
+    /// * `value` - value of the variable
+    pub fn set_variable(&mut self, name: &str, value: u32) -> Option<()> {
+        if !is_identifier(name) || FUNCTIONS.contains(&name) {
+            return None;
+        }
+        self.variables.insert(name.to_owned(), value);
+        Some(())
+    }
+
+    /// Returns the steps of the expression, the value of the last one is the result, or the error
+    /// # Arguments
+    /// * `expression` - expression to be evaluated
+    pub fn evaluate(&self, expression: &str) -> Result<Vec<Step>, EvalError> {
+        let mut parser = Parser { text: expression, position: 0, steps: Vec::new(), variables: &self.variables, depth: 0 };
+        parser.expression()?;
+        parser.skip_whitespace();
+        if parser.position != expression.len() {
+            return Err(parser.error("unexpected text after the expression"));
+        }
+        Ok(parser.steps)
+    }
+
+    /// Returns the value of the expression or the error
+    /// # Arguments
+    /// * `expression` - expression to be evaluated
+    pub fn value(&self, expression: &str) -> Result<u32, EvalError> {
+        Ok(self.evaluate(expression)?.last().expect("an expression has a step").value)
+    }
+
+    /// Evaluates the line, either an expression or an assignment *name = expression* that defines the variable,
+    /// and returns the steps of the expression or the error
+    /// # Arguments
+    /// * `line` - expression or assignment
+    pub fn run(&mut self, line: &str) -> Result<Vec<Step>, EvalError> {
+        let Some((name, expression)) = line.split_once('=') else {
+            return self.evaluate(line);
+        };
+        let offset = name.len() + 1;
+        let name = name.trim();
+        if !is_identifier(name) || FUNCTIONS.contains(&name) {
+            return Err(EvalError { position: 0, message: format!("'{name}' can't be assigned") });
+        }
+        let steps = self.evaluate(expression).map_err(|error| EvalError { position: error.position + offset, ..error })?;
+        self.variables.insert(name.to_owned(), steps.last().expect("an expression has a step").value);
+        Ok(steps)
+    }
+}
+
+/// Deepest nesting of parentheses, function calls and unary operators. Every level takes several stack frames of
+/// the parser, so much deeper expressions would overflow the stack
+const MAX_DEPTH: usize = 256;
+
+/// Names of the functions, which can't be variables
+const FUNCTIONS: [&str; 7] = ["set_bit", "unset_bit", "invert_bit", "swap_bits", "remove_bit", "hob", "count"];
+
+/// Helper function. Returns true if the text is a name: a letter or *_* followed by letters, digits and *_*
+/// # Arguments
+/// * `text` - text to be checked
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|char| char.is_ascii_alphanumeric() || char == '_')
+}
+
+/// Binary operator of a precedence level: its token, its step from the operands and the description of the step
+type Operator = (&'static str, fn(usize, usize) -> BitStep, &'static str);
+
+/// Recursive descent parser evaluating the expression while it's parsed. Every rule returns the index of its step
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    steps: Vec<Step>,
+    variables: &'a BTreeMap<String, u32>,
+    /// Current nesting level
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// Returns the error at the current position
+    /// # Arguments
+    /// * `message` - what is wrong
+    fn error(&self, message: impl Into<String>) -> EvalError {
+        EvalError { position: self.position, message: message.into() }
+    }
+
+    /// Parses the nested rule one level deeper, or returns an error past *MAX_DEPTH*
+    /// # Arguments
+    /// * `rule` - rule of the nested part
+    fn nested(&mut self, rule: fn(&mut Self) -> Result<usize, EvalError>) -> Result<usize, EvalError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("expression nested too deeply"));
+        }
+        self.depth += 1;
+        let result = rule(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes the token if it's next and returns true
+    /// # Arguments
+    /// * `token` - expected token
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let found = self.text[self.position..].starts_with(token);
+        if found {
+            self.position += token.len();
+        }
+        found
+    }
+
+    /// Adds the step with the value computed from the earlier steps and returns its index
+    /// # Arguments
+    /// * `kind` - operation on the earlier steps
+    /// * `start` - offset of the source text of the step
+    /// * `description` - what the step does
+    fn push(&mut self, kind: BitStep, start: usize, description: impl Into<String>) -> usize {
+        let values: Vec<u32> = self.steps.iter().map(|step| step.value).collect();
+        let value = kind.evaluate(&values).expect("operands are earlier steps");
+        let operation = self.text[start..self.position].trim().to_owned();
+        self.steps.push(Step::new(value, kind, operation, description));
+        self.steps.len() - 1
+    }
+
+    /// Parses the binary operators of one precedence level: *next (operator next)**
+    /// # Arguments
+    /// * `operators` - operators of the level with their steps and descriptions
+    /// * `next` - rule of the operands
+    fn binary(&mut self, operators: &[Operator],
+              next: fn(&mut Self) -> Result<usize, EvalError>) -> Result<usize, EvalError> {
+        self.skip_whitespace();
+        let start = self.position;
+        let mut left = next(self)?;
+        'operands: loop {
+            for &(token, kind, description) in operators {
+                if self.eat(token) {
+                    let right = next(self)?;
+                    left = self.push(kind(left, right), start, description);
+                    continue 'operands;
+                }
+            }
+            return Ok(left);
+        }
+    }
+
+    fn expression(&mut self) -> Result<usize, EvalError> {
+        self.nested(|parser| parser.binary(&[("|", |left, right| BitStep::Or { left, right }, "OR")], Self::xor))
+    }
+
+    fn xor(&mut self) -> Result<usize, EvalError> {
+        self.binary(&[("^", |left, right| BitStep::Xor { left, right }, "XOR")], Self::and)
+    }
+
+    fn and(&mut self) -> Result<usize, EvalError> {
+        self.binary(&[("&", |source, mask| BitStep::Mask { source, mask }, "AND")], Self::shift)
+    }
+
+    /// Shifts need the amount as a number, so they can't go through *binary*
+    fn shift(&mut self) -> Result<usize, EvalError> {
+        self.skip_whitespace();
+        let start = self.position;
+        let mut source = self.additive()?;
+        loop {
+            let direction = if self.eat("<<") {
+                ShiftDirection::Left
+            } else if self.eat(">>") {
+                ShiftDirection::Right
+            } else {
+                return Ok(source);
+            };
+            let amount = self.additive()?;
+            let amount = self.steps[amount].value;
+            let description = match direction {
+                ShiftDirection::Left => "shift left, shifts by 32 or more give 0",
+                ShiftDirection::Right => "shift right, shifts by 32 or more give 0",
+            };
+            source = self.push(BitStep::Shift { source, direction, amount }, start, description);
+        }
+    }
+
+    fn additive(&mut self) -> Result<usize, EvalError> {
+        self.binary(&[
+            ("+", |left, right| BitStep::Add { left, right }, "wrapping addition"),
+            ("-", |left, right| BitStep::Subtract { left, right }, "wrapping subtraction"),
+        ], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<usize, EvalError> {
+        self.skip_whitespace();
+        let start = self.position;
+        if self.eat("!") || self.eat("~") {
+            let source = self.nested(Self::unary)?;
+            return Ok(self.push(BitStep::Not { source }, start, "NOT"));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<usize, EvalError> {
+        self.skip_whitespace();
+        let start = self.position;
+        if self.eat("(") {
+            let inner = self.expression()?;
+            if !self.eat(")") {
+                return Err(self.error("expected ')'"));
+            }
+            return Ok(inner);
+        }
+        let rest = &self.text[start..];
+        let length = rest.find(|char: char| !char.is_ascii_alphanumeric() && char != '_').unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("expected a number, a variable or '('"));
+        }
+        let token = &rest[..length];
+        if token.starts_with(|char: char| char.is_ascii_digit()) {
+            let value = parse_number(token).ok_or_else(|| self.error(format!("'{token}' isn't a 32-bit number")))?;
+            self.position += length;
+            return Ok(self.push(BitStep::Constant { value }, start, "number"));
+        }
+        if FUNCTIONS.contains(&token) {
+            self.position += length;
+            return self.call(token, start);
+        }
+        let value = self.variables.get(token).copied().ok_or_else(|| self.error(format!("unknown variable '{token}'")))?;
+        self.position += length;
+        Ok(self.push(BitStep::Input { value }, start, "variable"))
+    }
+
+    /// Parses the arguments of the function and adds its steps
+    /// # Arguments
+    /// * `name` - name of the function
+    /// * `start` - offset of the name
+    fn call(&mut self, name: &str, start: usize) -> Result<usize, EvalError> {
+        if !self.eat("(") {
+            return Err(self.error(format!("expected '(' after {name}")));
+        }
+        let mut arguments = vec![self.expression()?];
+        while self.eat(",") {
+            arguments.push(self.expression()?);
+        }
+        if !self.eat(")") {
+            return Err(self.error("expected ',' or ')'"));
+        }
+        let expected = match name {
+            "hob" | "count" => 1,
+            "swap_bits" => 3,
+            _ => 2,
+        };
+        if arguments.len() != expected {
+            return Err(EvalError { position: start, message: format!("{name} takes {expected} arguments") });
+        }
+        let values: Vec<u32> = arguments.iter().map(|&argument| self.steps[argument].value).collect();
+        let out_of_range = || EvalError { position: start, message: format!("{name}: bit index out of range 0..32") };
+        let explained = match name {
+            "hob" => {
+                let hob = hob(values[0]).ok_or(EvalError { position: start, message: "0 has no hob".to_owned() })?;
+                return Ok(self.push(BitStep::Constant { value: hob }, start, "index of the highest one"));
+            }
+            "count" => {
+                let count = binary_ones_count(values[0]);
+                return Ok(self.push(BitStep::Constant { value: count }, start, "count of ones"));
+            }
+            "set_bit" => set_bit_explained(values[0], values[1]),
+            "unset_bit" => unset_bit_explained(values[0], values[1]),
+            "invert_bit" => invert_bit_explained(values[0], values[1]),
+            "swap_bits" => swap_bits_explained(values[0], values[1], values[2]),
+            _ => remove_bit_explained(values[0], values[1]),
+        }.ok_or_else(out_of_range)?;
+        // The input of the explained steps is the first argument, the others follow the steps so far
+        let base = self.steps.len() - 1;
+        let number = arguments[0];
+        for step in explained.into_iter().skip(1) {
+            let kind = step.kind.map_operands(|index| if index == 0 { number } else { base + index });
+            self.steps.push(Step::new(step.value, kind, format!("{name}: {}", step.operation), step.description));
+        }
+        Ok(self.steps.len() - 1)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explained::replay;
+    use crate::{remove_bit, swap_bits};
+
+    fn check(evaluator: &Evaluator, expression: &str, expected: u32) {
+        let steps = evaluator.evaluate(expression).unwrap();
+        assert_eq!(steps.last().unwrap().value, expected, "{expression}");
+        // The structured steps recompute every value
+        let kinds: Vec<BitStep> = steps.iter().map(|step| step.kind).collect();
+        for (index, step) in steps.iter().enumerate() {
+            assert_eq!(replay(&kinds[..=index]), Some(step.value), "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_operators() {
+        let mut evaluator = Evaluator::new();
+        evaluator.set_variable("x", 0xDEAD_BEEF).unwrap();
+        let x = 0xDEAD_BEEFu32;
+        check(&evaluator, "x", x);
+        check(&evaluator, "x & 0xFF | 0b1 << 31", x & 0xFF | 1 << 31);
+        check(&evaluator, "x ^ x >> 16 & 0xFFFF", x ^ x >> 16 & 0xFFFF);
+        check(&evaluator, "(x | 1) - 1 + 2", (x | 1) - 1 + 2);
+        check(&evaluator, "!x & ~0 ^ !!x", !x ^ x);
+        check(&evaluator, "x & x - 1", x & x - 1);
+        check(&evaluator, "1 << 32 | x >> 40", 0);
+        check(&evaluator, "0 - 1", u32::MAX);
+        check(&evaluator, "1 << 2 + 3", 1 << 5);
+        check(&evaluator, "  1_000  ", 1000);
+    }
+
+    #[test]
+    fn test_functions() {
+        let mut evaluator = Evaluator::new();
+        evaluator.set_variable("x", 0b1000_1101).unwrap();
+        check(&evaluator, "set_bit(x, 1)", 0b1000_1111);
+        check(&evaluator, "unset_bit(x, 0) | 0x100", 0b1_1000_1100);
+        check(&evaluator, "invert_bit(x, 1 + 1)", 0b1000_1001);
+        check(&evaluator, "swap_bits(x, 1, 7)", swap_bits(0b1000_1101, 1, 7).unwrap());
+        check(&evaluator, "remove_bit(remove_bit(x, 31), 0)", remove_bit(0b1000_1101, 0).unwrap());
+        check(&evaluator, "hob(x) + count(x)", 7 + 4);
+        check(&evaluator, "set_bit(1 << hob(x), count(x))", 1 << 7 | 1 << 4);
+        let steps = evaluator.evaluate("set_bit(x, 1)").unwrap();
+        assert_eq!(steps.last().unwrap().operation, "set_bit: number | mask");
+        assert_eq!(steps[0].operation, "x");
+    }
+
+    #[test]
+    fn test_run() {
+        let mut evaluator = Evaluator::new();
+        assert_eq!(evaluator.run("a = 5").unwrap().last().unwrap().value, 5);
+        evaluator.run("b=a<<1").unwrap();
+        evaluator.run("a = a ^ b").unwrap();
+        assert_eq!(evaluator.variables().collect::<Vec<_>>(), vec![("a", 15), ("b", 10)]);
+        assert_eq!(evaluator.run("a + b").unwrap().last().unwrap().value, 25);
+        assert_eq!(evaluator.run("hob = 1").unwrap_err().message, "'hob' can't be assigned");
+        assert!(evaluator.run("1x = 1").is_err());
+        assert_eq!(evaluator.run("c = a + ").unwrap_err().position, 8);
+        assert_eq!(evaluator.variable("c"), None);
+        assert_eq!(evaluator.set_variable("count", 1), None);
+        assert_eq!(evaluator.set_variable("9", 1), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        let evaluator = Evaluator::new();
+        for (expression, position) in [("", 0), ("1 +", 3), ("(1", 2), ("1 2", 2), ("0x1_0000_0000", 0), ("y", 0),
+                                       ("set_bit(1)", 0), ("set_bit 1", 8), ("set_bit(1, 32)", 0), ("hob(0)", 0),
+                                       ("swap_bits(1, 2 3)", 15), ("1 == 1", 2), ("@", 0)] {
+            assert_eq!(evaluator.evaluate(expression).unwrap_err().position, position, "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let evaluator = Evaluator::new();
+        // The deepest allowed expressions still work
+        let parentheses = format!("{}1{}", "(".repeat(MAX_DEPTH - 1), ")".repeat(MAX_DEPTH - 1));
+        assert_eq!(evaluator.evaluate(&parentheses).unwrap().last().unwrap().value, 1);
+        let nots = format!("{}1", "~".repeat(MAX_DEPTH - 1));
+        assert_eq!(evaluator.evaluate(&nots).unwrap().last().unwrap().value, !1);
+        // Deeper ones are errors rather than a stack overflow
+        for expression in [format!("{}1", "~".repeat(200_000)), "(".repeat(100_000), format!("count({}1", "set_bit(".repeat(100_000))] {
+            let error = evaluator.evaluate(&expression).unwrap_err();
+            assert_eq!(error.message, "expression nested too deeply");
+        }
+    }
+}
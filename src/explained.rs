@@ -0,0 +1,772 @@
+//! Step-by-step variants of the bit manipulations of the crate root, of the right shifts of *shift* and of
+//! *crc::crc_bitwise*. Every function returns the intermediate values the corresponding function computes, each with
+//! the expression that produced it and a short description; the value of the last step is the result. Every step
+//! also carries a *BitStep*, the operation in a structured form referring to the earlier steps by index, so the steps
+//! can be replayed or animated without parsing the text. A *Tracer* streams the steps to any *Write* object as
+//! they're recorded
+
+use std::io::{self, Write};
+use crate::crc::CrcParams;
+
+/// Direction of a shift
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShiftDirection {
+    /// Towards the higher bits
+    Left,
+    /// Towards the lower bits, filled with zeros
+    Right,
+}
+
+/// Operation of a step. The operands are indexes of earlier steps of the same operation.
+/// More operations may be added, so matches outside the crate need a wildcard arm
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum BitStep {
+    /// Value given to the operation
+    Input { value: u32 },
+    /// Value known in advance
+    Constant { value: u32 },
+    /// Shift of the operand; the bits shifted out are lost, shifts by 32 or more give 0
+    Shift { source: usize, direction: ShiftDirection, amount: u32 },
+    /// AND of the operand with the mask
+    Mask { source: usize, mask: usize },
+    /// OR of the operands
+    Or { left: usize, right: usize },
+    /// XOR of the operands
+    Xor { left: usize, right: usize },
+    /// NOT of the operand
+    Not { source: usize },
+    /// Wrapping subtraction of the operands
+    Subtract { left: usize, right: usize },
+    /// Wrapping addition of the operands
+    Add { left: usize, right: usize },
+}
+
+impl BitStep {
+    /// Returns the value of the step from the values of the earlier steps,
+    /// or None if an operand isn't the index of an earlier step
+    /// # Arguments
+    /// * `values` - values of the earlier steps
+    pub fn evaluate(&self, values: &[u32]) -> Option<u32> {
+        let value = |index: usize| values.get(index).copied();
+        Some(match *self {
+            BitStep::Input { value } | BitStep::Constant { value } => value,
+            BitStep::Shift { source, direction: ShiftDirection::Left, amount } => value(source)?.checked_shl(amount).unwrap_or(0),
+            BitStep::Shift { source, direction: ShiftDirection::Right, amount } => value(source)?.checked_shr(amount).unwrap_or(0),
+            BitStep::Mask { source, mask } => value(source)? & value(mask)?,
+            BitStep::Or { left, right } => value(left)? | value(right)?,
+            BitStep::Xor { left, right } => value(left)? ^ value(right)?,
+            BitStep::Not { source } => ! value(source)?,
+            BitStep::Subtract { left, right } => value(left)?.wrapping_sub(value(right)?),
+            BitStep::Add { left, right } => value(left)?.wrapping_add(value(right)?),
+        })
+    }
+
+    /// Returns the step with every operand index replaced, used to splice steps into a longer sequence
+    /// # Arguments
+    /// * `f` - new index of an operand
+    pub(crate) fn map_operands(self, f: impl Fn(usize) -> usize) -> Self {
+        match self {
+            BitStep::Input { .. } | BitStep::Constant { .. } => self,
+            BitStep::Shift { source, direction, amount } => BitStep::Shift { source: f(source), direction, amount },
+            BitStep::Mask { source, mask } => BitStep::Mask { source: f(source), mask: f(mask) },
+            BitStep::Or { left, right } => BitStep::Or { left: f(left), right: f(right) },
+            BitStep::Xor { left, right } => BitStep::Xor { left: f(left), right: f(right) },
+            BitStep::Not { source } => BitStep::Not { source: f(source) },
+            BitStep::Subtract { left, right } => BitStep::Subtract { left: f(left), right: f(right) },
+            BitStep::Add { left, right } => BitStep::Add { left: f(left), right: f(right) },
+        }
+    }
+}
+
+/// Returns the result of the operations recomputed from scratch: the value of the last one,
+/// or None if there are no operations or an operand isn't the index of an earlier operation
+/// # Arguments
+/// * `steps` - operations in order
+/// # Examples
+/// ```
+/// # use understanding_bitwise::explained::{replay, swap_bits_explained, BitStep};
+/// let steps = swap_bits_explained(0b100011, 1, 4).unwrap();
+/// let bit_steps: Vec<BitStep> = steps.iter().map(|step| step.kind).collect();
+/// assert_eq!(replay(&bit_steps), Some(0b110001));
+/// // A different input through the same operations
+/// let mut bit_steps = bit_steps;
+/// bit_steps[0] = BitStep::Input { value: 0b10 };
+/// assert_eq!(replay(&bit_steps), Some(0b10000));
+/// ```
+pub fn replay(steps: &[BitStep]) -> Option<u32> {
+    let mut values = Vec::with_capacity(steps.len());
+    for step in steps {
+        values.push(step.evaluate(&values)?);
+    }
+    values.last().copied()
+}
+
+/// Intermediate value of an explained operation
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Step {
+    /// Value after the step
+    pub value: u32,
+    /// Operation that produced the value
+    pub kind: BitStep,
+    /// Expression that produced the value, in terms of the earlier steps
+    pub operation: String,
+    /// What the step does and why
+    pub description: String,
+}
+
+impl Step {
+    /// Returns the step
+    /// # Arguments
+    /// * `value` - value after the step
+    /// * `kind` - operation that produced the value
+    /// * `operation` - expression that produced the value
+    /// * `description` - what the step does and why
+    pub fn new(value: u32, kind: BitStep, operation: impl Into<String>, description: impl Into<String>) -> Self {
+        Step { value, kind, operation: operation.into(), description: description.into() }
+    }
+}
+
+/// Sink writing every recorded step in binary with a line marking the bits changed since the previous step
+/// # Examples
+/// ```
+/// # use understanding_bitwise::explained::{invert_bit_explained, Tracer};
+/// let mut tracer = Tracer::new(Vec::new());
+/// assert_eq!(tracer.run(invert_bit_explained(0b1010, 1)).unwrap(), Some(0b1000));
+/// let text = String::from_utf8(tracer.into_inner()).unwrap();
+/// let lines: Vec<&str> = text.lines().collect();
+/// assert_eq!(lines[0], "00000000000000000000000000001010  number");
+/// assert_eq!(lines[4], "00000000000000000000000000000010  mask = one << 1");
+/// assert_eq!(lines[5], "                            ^ ^");
+/// assert_eq!(lines[6], "00000000000000000000000000001000  number ^ mask");
+/// ```
+pub struct Tracer<W: Write> {
+    out: W,
+    previous: Option<u32>,
+}
+
+impl<W: Write> Tracer<W> {
+    /// Returns the tracer writing to the *Write* object
+    /// # Arguments
+    /// * `out` - *Write* object that gets the trace
+    pub fn new(out: W) -> Self {
+        Tracer { out, previous: None }
+    }
+
+    /// Writes the step: the value in binary with its operation, preceded by a line with *^* under every bit
+    /// that differs from the previous step. Nothing is marked for the first step
+    /// # Arguments
+    /// * `step` - step to be written
+    pub fn record(&mut self, step: &Step) -> io::Result<()> {
+        if let Some(previous) = self.previous {
+            let changed = previous ^ step.value;
+            if changed != 0 {
+                let marks: String = (0..u32::BITS).rev().map(|index| if changed >> index & 1 == 1 { '^' } else { ' ' }).collect();
+                writeln!(self.out, "{}", marks.trim_end())?;
+            }
+        }
+        writeln!(self.out, "{:032b}  {}", step.value, step.operation)?;
+        self.previous = Some(step.value);
+        Ok(())
+    }
+
+    /// Writes the steps of an explained operation followed by an empty line and returns the result,
+    /// or None if the operation had no steps. The marks start afresh for every operation
+    /// # Arguments
+    /// * `steps` - steps returned by one of the *_explained* functions
+    pub fn run(&mut self, steps: Option<Vec<Step>>) -> io::Result<Option<u32>> {
+        self.previous = None;
+        let Some(steps) = steps else {
+            return Ok(None);
+        };
+        for step in &steps {
+            self.record(step)?;
+        }
+        writeln!(self.out)?;
+        self.previous = None;
+        Ok(steps.last().map(|step| step.value))
+    }
+
+    /// Returns the *Write* object
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Helper. Steps of an operation, the values computed by the *BitStep*s
+struct Steps(Vec<Step>);
+
+impl Steps {
+    /// Returns the steps starting with the input number
+    /// # Arguments
+    /// * `number` - number to work with
+    fn new(number: u32) -> Self {
+        Steps(vec![Step::new(number, BitStep::Input { value: number }, "number", "the input")])
+    }
+
+    /// Adds the step and returns its index
+    /// # Arguments
+    /// * `kind` - operation on the earlier steps
+    /// * `operation` - expression of the operation
+    /// * `description` - what the step does and why
+    fn push(&mut self, kind: BitStep, operation: impl Into<String>, description: impl Into<String>) -> usize {
+        let values: Vec<u32> = self.0.iter().map(|step| step.value).collect();
+        let value = kind.evaluate(&values).expect("operands are earlier steps");
+        self.0.push(Step::new(value, kind, operation, description));
+        self.0.len() - 1
+    }
+
+    /// Adds the steps of the mask of the single bit and returns its index
+    /// # Arguments
+    /// * `index` - index of the bit
+    fn single_bit_mask(&mut self, index: u32) -> usize {
+        let one = self.push(BitStep::Constant { value: 1 }, "one = 1", "only the bit 0 is 1");
+        self.push(BitStep::Shift { source: one, direction: ShiftDirection::Left, amount: index },
+                  format!("mask = one << {index}"), format!("only the bit {index} is 1"))
+    }
+}
+
+/// Returns the steps of *set_bit* or None if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::explained::set_bit_explained;
+/// let steps = set_bit_explained(0b101, 1).unwrap();
+/// assert_eq!(steps.iter().map(|step| step.value).collect::<Vec<_>>(), vec![0b101, 1, 0b010, 0b111]);
+/// assert_eq!(steps[3].operation, "number | mask");
+/// ```
+pub fn set_bit_explained(number: u32, index: u32) -> Option<Vec<Step>> {
+    if index >= u32::BITS {
+        return None;
+    }
+    let mut steps = Steps::new(number);
+    let mask = steps.single_bit_mask(index);
+    steps.push(BitStep::Or { left: 0, right: mask }, "number | mask", "OR with 1 sets the bit, OR with 0 keeps the others");
+    Some(steps.0)
+}
+
+/// Returns the steps of *unset_bit* (set, then subtract the bit) or None if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+pub fn unset_bit_explained(number: u32, index: u32) -> Option<Vec<Step>> {
+    if index >= u32::BITS {
+        return None;
+    }
+    let mut steps = Steps::new(number);
+    let mask = steps.single_bit_mask(index);
+    let set = steps.push(BitStep::Or { left: 0, right: mask }, "set = number | mask", "the bit is surely 1 now");
+    steps.push(BitStep::Subtract { left: set, right: mask }, "set - mask", "subtracting a bit known to be 1 clears it without borrowing");
+    Some(steps.0)
+}
+
+/// Returns the steps of *unset_bit_xor* or None if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::explained::unset_bit_xor_explained;
+/// let steps = unset_bit_xor_explained(0b101, 2).unwrap();
+/// assert_eq!(steps.last().unwrap().value, 0b001);
+/// ```
+pub fn unset_bit_xor_explained(number: u32, index: u32) -> Option<Vec<Step>> {
+    if index >= u32::BITS {
+        return None;
+    }
+    let mut steps = Steps::new(number);
+    let mask = steps.single_bit_mask(index);
+    let flipped = steps.push(BitStep::Xor { left: 0, right: mask }, "flipped = number ^ mask", "XOR flips the bit, the others are kept");
+    steps.push(BitStep::Mask { source: 0, mask: flipped }, "number & flipped",
+               "the bit is 1 in at most one of them, so AND clears it; the others agree");
+    Some(steps.0)
+}
+
+/// Returns the steps of *unset_bit_bitwise_not* or None if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+pub fn unset_bit_bitwise_not_explained(number: u32, index: u32) -> Option<Vec<Step>> {
+    if index >= u32::BITS {
+        return None;
+    }
+    let mut steps = Steps::new(number);
+    let mask = steps.single_bit_mask(index);
+    let inverted = steps.push(BitStep::Not { source: mask }, "inverted = !mask", "all bits are 1 except the bit");
+    steps.push(BitStep::Mask { source: 0, mask: inverted }, "number & inverted", "AND with 0 clears the bit, AND with 1 keeps the others");
+    Some(steps.0)
+}
+
+/// Returns the steps of *invert_bit* or None if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+pub fn invert_bit_explained(number: u32, index: u32) -> Option<Vec<Step>> {
+    if index >= u32::BITS {
+        return None;
+    }
+    let mut steps = Steps::new(number);
+    let mask = steps.single_bit_mask(index);
+    steps.push(BitStep::Xor { left: 0, right: mask }, "number ^ mask", "XOR with 1 flips the bit, XOR with 0 keeps the others");
+    Some(steps.0)
+}
+
+/// Returns the steps of *swap_bits_xor* or None if an index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index1` - index of the bit to be swapped
+/// * `index2` - index of the bit to be swapped
+/// # Examples
+/// ```
+/// # use understanding_bitwise::explained::swap_bits_explained;
+/// let steps = swap_bits_explained(0b100011, 1, 4).unwrap();
+/// assert_eq!(steps.last().unwrap().value, 0b110001);
+/// // The bits 1 and 4 differ, so both get flipped
+/// let differ = steps.iter().find(|step| step.operation == "differ = bit1 ^ bit2").unwrap();
+/// assert_eq!(differ.value, 1);
+/// ```
+pub fn swap_bits_explained(number: u32, index1: u32, index2: u32) -> Option<Vec<Step>> {
+    if index1 >= u32::BITS || index2 >= u32::BITS {
+        return None;
+    }
+    let mut steps = Steps::new(number);
+    let one = steps.push(BitStep::Constant { value: 1 }, "one = 1", "only the bit 0 is 1");
+    let mut bits = [0; 2];
+    for (bit, (name, index)) in bits.iter_mut().zip([("bit1", index1), ("bit2", index2)]) {
+        let shifted = steps.push(BitStep::Shift { source: 0, direction: ShiftDirection::Right, amount: index },
+                                 format!("number >> {index}"), format!("the bit {index} moved to the bit 0"));
+        *bit = steps.push(BitStep::Mask { source: shifted, mask: one }, format!("{name} = (number >> {index}) & one"),
+                          format!("the bit {index} alone"));
+    }
+    let differ = steps.push(BitStep::Xor { left: bits[0], right: bits[1] }, "differ = bit1 ^ bit2",
+                            "1 if the bits differ; equal bits need no swap");
+    let at1 = steps.push(BitStep::Shift { source: differ, direction: ShiftDirection::Left, amount: index1 },
+                         format!("differ << {index1}"), format!("the bit {index1} if the bits differ"));
+    let at2 = steps.push(BitStep::Shift { source: differ, direction: ShiftDirection::Left, amount: index2 },
+                         format!("differ << {index2}"), format!("the bit {index2} if the bits differ"));
+    let swapper = steps.push(BitStep::Or { left: at1, right: at2 }, format!("swapper = differ << {index1} | differ << {index2}"),
+                             "both positions if the bits differ");
+    steps.push(BitStep::Xor { left: 0, right: swapper }, "number ^ swapper", "flipping two different bits swaps them");
+    Some(steps.0)
+}
+
+/// Returns the steps of *remove_bit* or None if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the bit to be removed
+/// # Examples
+/// ```
+/// # use understanding_bitwise::explained::remove_bit_explained;
+/// let steps = remove_bit_explained(0b100011, 1).unwrap();
+/// assert_eq!(steps.last().unwrap().value, 0b10001);
+/// ```
+pub fn remove_bit_explained(number: u32, index: u32) -> Option<Vec<Step>> {
+    if index >= u32::BITS {
+        return None;
+    }
+    let mut steps = Steps::new(number);
+    let above = steps.push(BitStep::Shift { source: 0, direction: ShiftDirection::Right, amount: index + 1 },
+                           format!("above = number >> {}", index + 1), "the bits above the removed one, moved down into place");
+    let at = steps.push(BitStep::Shift { source: 0, direction: ShiftDirection::Right, amount: index },
+                        format!("at = number >> {index}"), "the bits from the removed one up, unmoved relative to it");
+    let differ = steps.push(BitStep::Xor { left: above, right: at }, "above ^ at", "where the shifted bits differ from the current ones");
+    let remover = steps.push(BitStep::Shift { source: differ, direction: ShiftDirection::Left, amount: index },
+                             format!("remover = (above ^ at) << {index}"), "the differences moved back to their positions");
+    steps.push(BitStep::Xor { left: 0, right: remover }, "number ^ remover",
+               "XOR replaces the bits from the index up by the shifted ones, the lower bits are kept");
+    Some(steps.0)
+}
+
+/// Returns the steps of *shift::lsr_on_signed*, the bits of the signed number shifted right with zeros filled in
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::explained::lsr_explained;
+/// let steps = lsr_explained(-106, 3);
+/// assert_eq!(steps.last().unwrap().value, 0x1FFF_FFF2);
+/// ```
+pub fn lsr_explained(number: i32, count: u32) -> Vec<Step> {
+    let mut steps = Steps::new(number as u32);
+    steps.push(BitStep::Shift { source: 0, direction: ShiftDirection::Right, amount: count }, format!("number >> {count}"),
+               "zeros fill the vacated positions, the sign bit is lost");
+    steps.0
+}
+
+/// Returns the steps of *shift::asr*: the logical shift with the vacated positions filled by copies of the sign bit
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::explained::asr_explained;
+/// let steps = asr_explained(-106, 3);
+/// assert_eq!(steps.last().unwrap().value, -14i32 as u32);
+/// ```
+pub fn asr_explained(number: i32, count: u32) -> Vec<Step> {
+    let mut steps = Steps::new(number as u32);
+    let shifted = steps.push(BitStep::Shift { source: 0, direction: ShiftDirection::Right, amount: count },
+                             format!("shifted = number >> {count}"), "the logical shift, zeros in the vacated positions");
+    let sign = steps.push(BitStep::Shift { source: 0, direction: ShiftDirection::Right, amount: u32::BITS - 1 },
+                          format!("sign = number >> {}", u32::BITS - 1), "the sign bit alone");
+    let zero = steps.push(BitStep::Constant { value: 0 }, "zero = 0", "no bits set");
+    let copies = steps.push(BitStep::Subtract { left: zero, right: sign }, "copies = zero - sign",
+                            "all ones for a negative number, zeros otherwise");
+    let ones = steps.push(BitStep::Not { source: zero }, "ones = !zero", "all bits set");
+    let kept = steps.push(BitStep::Shift { source: ones, direction: ShiftDirection::Right, amount: count },
+                          format!("kept = ones >> {count}"), "the positions the shift keeps");
+    let vacated = steps.push(BitStep::Not { source: kept }, "vacated = !kept", "the positions the shift vacates");
+    let fill = steps.push(BitStep::Mask { source: copies, mask: vacated }, "fill = copies & vacated",
+                          "copies of the sign bit in the vacated positions");
+    steps.push(BitStep::Or { left: shifted, right: fill }, "shifted | fill", "the sign bit copied into the vacated positions");
+    steps.0
+}
+
+/// Returns the steps of *crc::crc_bitwise* or None if the width isn't in 1..=32. Every data byte is an *Input* step;
+/// for every message bit the feedback bit selects the polynomial with a subtraction from zero instead of a branch
+/// # Arguments
+/// * `params` - parameters of the CRC
+/// * `data` - data to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::crc::{crc_bitwise, CRC_8_SMBUS};
+/// # use understanding_bitwise::explained::crc_bitwise_explained;
+/// let steps = crc_bitwise_explained(&CRC_8_SMBUS, b"A").unwrap();
+/// assert_eq!(steps.last().unwrap().value, crc_bitwise(&CRC_8_SMBUS, b"A"));
+/// ```
+pub fn crc_bitwise_explained(params: &CrcParams, data: &[u8]) -> Option<Vec<Step>> {
+    if !(1..=u32::BITS).contains(&params.width) {
+        return None;
+    }
+    let top = params.width - 1;
+    let register_mask = u32::MAX >> (u32::BITS - params.width);
+    let mut steps = Steps(Vec::new());
+    let one = steps.push(BitStep::Constant { value: 1 }, "one = 1", "only the bit 0 is 1");
+    let zero = steps.push(BitStep::Constant { value: 0 }, "zero = 0", "no bits set");
+    let mask = steps.push(BitStep::Constant { value: register_mask }, format!("mask = {register_mask:#x}"), "the register bits");
+    let poly = steps.push(BitStep::Constant { value: params.poly & register_mask }, format!("poly = {:#x}", params.poly),
+                          "the polynomial without the highest term");
+    let mut register = steps.push(BitStep::Constant { value: params.init & register_mask },
+                                  format!("register = {:#x}", params.init), "the initial value");
+    for (position, &byte) in data.iter().enumerate() {
+        let input = steps.push(BitStep::Input { value: byte as u32 }, format!("byte{position}"),
+                               format!("the byte {position} of the data"));
+        // Reflected input enters the register LSB first
+        let indexes: Vec<u32> = if params.reflect_in { (0..u8::BITS).collect() } else { (0..u8::BITS).rev().collect() };
+        for index in indexes {
+            let leaving = steps.push(BitStep::Shift { source: register, direction: ShiftDirection::Right, amount: top },
+                                     format!("register >> {top}"), "the bit leaving the register top");
+            let entering = steps.push(BitStep::Shift { source: input, direction: ShiftDirection::Right, amount: index },
+                                      format!("byte{position} >> {index}"), format!("the bit {index} of the byte moved to the bit 0"));
+            let sum = steps.push(BitStep::Xor { left: leaving, right: entering }, "leaving ^ entering",
+                                 "the leaving bit plus the message bit in the bit 0");
+            let feedback = steps.push(BitStep::Mask { source: sum, mask: one }, "feedback = (leaving ^ entering) & one",
+                                      "1 if the polynomial is XORed in");
+            let select = steps.push(BitStep::Subtract { left: zero, right: feedback }, "select = zero - feedback",
+                                    "all ones if the feedback is 1, zeros otherwise");
+            let term = steps.push(BitStep::Mask { source: poly, mask: select }, "term = poly & select", "the polynomial or nothing");
+            let shifted = steps.push(BitStep::Shift { source: register, direction: ShiftDirection::Left, amount: 1 },
+                                     "register << 1", "the register shifted by one bit");
+            let kept = steps.push(BitStep::Mask { source: shifted, mask }, "(register << 1) & mask", "the top bit dropped");
+            register = steps.push(BitStep::Xor { left: kept, right: term }, "register = ((register << 1) & mask) ^ term",
+                                  "the feedback XORed into the tapped bits");
+        }
+    }
+    if params.reflect_out {
+        // Every bit moved to the mirrored position, then all of them ORed together
+        let mut reflected = zero;
+        for index in 0..params.width {
+            let moved = steps.push(BitStep::Shift { source: register, direction: ShiftDirection::Right, amount: index },
+                                   format!("register >> {index}"), format!("the bit {index} moved to the bit 0"));
+            let bit = steps.push(BitStep::Mask { source: moved, mask: one }, format!("(register >> {index}) & one"),
+                                 format!("the bit {index} alone"));
+            let mirrored = steps.push(BitStep::Shift { source: bit, direction: ShiftDirection::Left, amount: top - index },
+                                      format!("bit << {}", top - index), format!("the bit {index} in the bit {}", top - index));
+            reflected = steps.push(BitStep::Or { left: reflected, right: mirrored }, "reflected | bit",
+                                   format!("the bits up to {index} reflected"));
+        }
+        register = reflected;
+    }
+    let xor_out = steps.push(BitStep::Constant { value: params.xor_out & register_mask }, format!("xor_out = {:#x}", params.xor_out),
+                             "the final XOR value");
+    steps.push(BitStep::Xor { left: register, right: xor_out }, "register ^ xor_out", "the CRC");
+    Some(steps.0)
+}
+
+/// Operation with an explained variant
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BitOp {
+    /// *set_bit*, inputs: number, index
+    SetBit,
+    /// *unset_bit*, inputs: number, index
+    UnsetBit,
+    /// *unset_bit_xor*, inputs: number, index
+    UnsetBitXor,
+    /// *unset_bit_bitwise_not*, inputs: number, index
+    UnsetBitBitwiseNot,
+    /// *invert_bit*, inputs: number, index
+    InvertBit,
+    /// *swap_bits_xor*, inputs: number, index1, index2
+    SwapBits,
+    /// *remove_bit*, inputs: number, index
+    RemoveBit,
+}
+
+impl BitOp {
+    /// Returns the name of the function of the operation
+    pub fn name(&self) -> &'static str {
+        match self {
+            BitOp::SetBit => "set_bit",
+            BitOp::UnsetBit => "unset_bit",
+            BitOp::UnsetBitXor => "unset_bit_xor",
+            BitOp::UnsetBitBitwiseNot => "unset_bit_bitwise_not",
+            BitOp::InvertBit => "invert_bit",
+            BitOp::SwapBits => "swap_bits_xor",
+            BitOp::RemoveBit => "remove_bit",
+        }
+    }
+
+    /// Returns the steps of the operation or None if the count of inputs is wrong or an index is out of range
+    /// # Arguments
+    /// * `inputs` - number followed by the indexes
+    pub fn steps(&self, inputs: &[u32]) -> Option<Vec<Step>> {
+        match (self, inputs) {
+            (BitOp::SetBit, &[number, index]) => set_bit_explained(number, index),
+            (BitOp::UnsetBit, &[number, index]) => unset_bit_explained(number, index),
+            (BitOp::UnsetBitXor, &[number, index]) => unset_bit_xor_explained(number, index),
+            (BitOp::UnsetBitBitwiseNot, &[number, index]) => unset_bit_bitwise_not_explained(number, index),
+            (BitOp::InvertBit, &[number, index]) => invert_bit_explained(number, index),
+            (BitOp::SwapBits, &[number, index1, index2]) => swap_bits_explained(number, index1, index2),
+            (BitOp::RemoveBit, &[number, index]) => remove_bit_explained(number, index),
+            _ => None,
+        }
+    }
+}
+
+/// Returns a walkthrough of the operation: a line with the call and its result, then every step as a sentence
+/// built from its *BitStep* and description, with its value in binary under it. Returns None if the count of inputs
+/// is wrong or an index is out of range
+/// # Arguments
+/// * `operation` - operation to be explained
+/// * `inputs` - number followed by the indexes
+/// # Examples
+/// ```
+/// # use understanding_bitwise::explained::{explain, BitOp};
+/// let text = explain(BitOp::UnsetBitXor, &[0b101, 2]).unwrap();
+/// assert!(text.starts_with("unset_bit_xor(0b101, 2) = 0b1\n"));
+/// assert!(text.contains("XOR number with mask: XOR flips the bit, the others are kept"));
+/// assert!(text.contains("    001  number & flipped"));
+/// ```
+pub fn explain(operation: BitOp, inputs: &[u32]) -> Option<String> {
+    let steps = operation.steps(inputs)?;
+    // Names are given by the operations of the form "name = expression" and by single words like "number"
+    let name = |index: usize| match steps[index].operation.split_once(" = ") {
+        Some((name, _)) => name.to_owned(),
+        None if !steps[index].operation.contains(' ') => steps[index].operation.clone(),
+        None => format!("step {}", index + 1),
+    };
+    let width = steps.iter().map(|step| u32::BITS - step.value.leading_zeros()).max().unwrap_or(0).max(1) as usize;
+    let arguments: Vec<String> = inputs.iter().enumerate()
+        .map(|(index, input)| if index == 0 { format!("{input:#b}") } else { input.to_string() }).collect();
+    let result = steps.last().expect("an operation has steps").value;
+    let mut text = format!("{}({}) = {result:#b}\n", operation.name(), arguments.join(", "));
+    for (index, step) in steps.iter().enumerate() {
+        let sentence = match step.kind {
+            BitStep::Input { .. } => "Start from the input".to_owned(),
+            BitStep::Constant { value } => format!("Take the constant {value}"),
+            BitStep::Shift { source, direction, amount } => {
+                let direction = if direction == ShiftDirection::Left { "left" } else { "right" };
+                format!("Shift {} {direction} by {amount}", name(source))
+            }
+            BitStep::Mask { source, mask } => format!("AND {} with {}", name(source), name(mask)),
+            BitStep::Or { left, right } => format!("OR {} with {}", name(left), name(right)),
+            BitStep::Xor { left, right } => format!("XOR {} with {}", name(left), name(right)),
+            BitStep::Not { source } => format!("Invert every bit of {}", name(source)),
+            BitStep::Subtract { left, right } => format!("Subtract {} from {}", name(right), name(left)),
+            BitStep::Add { left, right } => format!("Add {} to {}", name(right), name(left)),
+        };
+        text += &format!("{}. {sentence}: {}\n", index + 1, step.description);
+        text += &format!("    {:0width$b}  {}\n", step.value & (u32::MAX >> (u32::BITS - width as u32)), step.operation);
+    }
+    Some(text)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+    use crate::crc::{crc_bitwise, CRC_16_CCITT_FALSE, CRC_16_KERMIT, CRC_16_MODBUS, CRC_32, CRC_32_BZIP2, CRC_8_MAXIM, CRC_8_SMBUS};
+    use crate::shift::{asr, lsr_on_signed};
+    use crate::{invert_bit, remove_bit, set_bit, swap_bits, unset_bit};
+
+    fn numbers() -> impl Iterator<Item = u32> {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        std::iter::repeat_with(move || rng.next_u32()).take(20).chain([0, u32::MAX])
+    }
+
+    fn result(steps: Option<Vec<Step>>) -> Option<u32> {
+        let steps = steps?;
+        let kinds: Vec<BitStep> = steps.iter().map(|step| step.kind).collect();
+        // The structured operations give the same value at every step
+        for (index, step) in steps.iter().enumerate() {
+            assert_eq!(replay(&kinds[..=index]), Some(step.value));
+        }
+        Some(steps.last().unwrap().value)
+    }
+
+    #[test]
+    fn test_single_bit_operations() {
+        for number in numbers() {
+            for index in 0..u32::BITS {
+                assert_eq!(result(set_bit_explained(number, index)), set_bit(number, index));
+                assert_eq!(result(unset_bit_explained(number, index)), unset_bit(number, index));
+                assert_eq!(result(unset_bit_xor_explained(number, index)), unset_bit(number, index));
+                assert_eq!(result(unset_bit_bitwise_not_explained(number, index)), unset_bit(number, index));
+                assert_eq!(result(invert_bit_explained(number, index)), invert_bit(number, index));
+                assert_eq!(result(remove_bit_explained(number, index)), remove_bit(number, index));
+            }
+            assert_eq!(set_bit_explained(number, 32), None);
+            assert_eq!(remove_bit_explained(number, 32), None);
+        }
+    }
+
+    #[test]
+    fn test_swap_bits() {
+        for number in numbers() {
+            for (index1, index2) in [(0, 31), (4, 1), (7, 7), (16, 17)] {
+                assert_eq!(result(swap_bits_explained(number, index1, index2)), swap_bits(number, index1, index2));
+            }
+            assert_eq!(swap_bits_explained(number, 0, 32), None);
+        }
+    }
+
+    #[test]
+    fn test_steps() {
+        let steps = unset_bit_xor_explained(0b110, 1).unwrap();
+        assert_eq!(steps[0], Step::new(0b110, BitStep::Input { value: 0b110 }, "number", "the input"));
+        assert_eq!(steps[2].operation, "mask = one << 1");
+        assert_eq!(steps[2].kind, BitStep::Shift { source: 1, direction: ShiftDirection::Left, amount: 1 });
+        assert!(steps.iter().all(|step| !step.description.is_empty()));
+    }
+
+    #[test]
+    fn test_replay() {
+        assert_eq!(replay(&[]), None);
+        // Operands must be earlier steps
+        assert_eq!(replay(&[BitStep::Input { value: 1 }, BitStep::Not { source: 1 }]), None);
+        assert_eq!(replay(&[BitStep::Input { value: 1 }, BitStep::Xor { left: 0, right: 2 }]), None);
+        let steps = [
+            BitStep::Input { value: 0xF0 },
+            BitStep::Constant { value: 0x3C },
+            BitStep::Subtract { left: 1, right: 0 },
+            BitStep::Shift { source: 2, direction: ShiftDirection::Right, amount: 32 },
+            BitStep::Or { left: 3, right: 1 },
+        ];
+        assert_eq!(replay(&steps[..3]), Some(0x3Cu32.wrapping_sub(0xF0)));
+        assert_eq!(replay(&steps), Some(0x3C));
+    }
+
+    #[test]
+    fn test_right_shifts() {
+        for number in numbers().map(|number| number as i32) {
+            for count in 0..=u32::BITS {
+                let steps = asr_explained(number, count);
+                assert_eq!(steps.last().unwrap().value, asr(number, count) as u32);
+                let kinds: Vec<BitStep> = steps.iter().map(|step| step.kind).collect();
+                assert_eq!(replay(&kinds), Some(asr(number, count) as u32));
+                assert_eq!(lsr_explained(number, count).last().unwrap().value, lsr_on_signed(number, count) as u32);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crc_bitwise() {
+        for params in [CRC_32, CRC_32_BZIP2, CRC_8_MAXIM, CRC_8_SMBUS, CRC_16_CCITT_FALSE, CRC_16_KERMIT, CRC_16_MODBUS] {
+            for data in [&b""[..], b"A", b"123456789"] {
+                let steps = crc_bitwise_explained(&params, data).unwrap();
+                assert_eq!(steps.last().unwrap().value, crc_bitwise(&params, data));
+                // The same operations replayed with other bytes give their CRC
+                let mut kinds: Vec<BitStep> = steps.iter().map(|step| step.kind).collect();
+                let other: Vec<u8> = data.iter().map(|byte| byte ^ 0x5A).collect();
+                let mut bytes = other.iter();
+                for kind in &mut kinds {
+                    if let BitStep::Input { value } = kind {
+                        *value = *bytes.next().unwrap() as u32;
+                    }
+                }
+                assert_eq!(replay(&kinds), Some(crc_bitwise(&params, &other)));
+            }
+        }
+        assert_eq!(crc_bitwise_explained(&CrcParams { width: 0, ..CRC_32 }, b"A"), None);
+    }
+
+    #[test]
+    fn test_tracer() {
+        let mut tracer = Tracer::new(Vec::new());
+        assert_eq!(tracer.run(invert_bit_explained(0b10, 0)).unwrap(), Some(0b11));
+        assert_eq!(tracer.run(set_bit_explained(0, 32)).unwrap(), None);
+        // Equal values mark nothing
+        tracer.record(&Step::new(7, BitStep::Constant { value: 7 }, "a", "")).unwrap();
+        tracer.record(&Step::new(7, BitStep::Constant { value: 7 }, "b", "")).unwrap();
+        tracer.record(&Step::new(1 << 31, BitStep::Constant { value: 1 << 31 }, "c", "")).unwrap();
+        let text = String::from_utf8(tracer.into_inner()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], format!("{:032b}  number", 0b10));
+        assert_eq!(lines[1], " ".repeat(30) + "^^");
+        // The mask equals the constant 1
+        assert_eq!(lines[3], format!("{:032b}  mask = one << 0", 1));
+        assert_eq!(lines[5], format!("{:032b}  number ^ mask", 0b11));
+        assert_eq!(lines[6], "");
+        assert_eq!(lines[8], format!("{:032b}  b", 7));
+        assert_eq!(lines[9], "^                            ^^^");
+    }
+
+    #[test]
+    fn test_explain() {
+        for number in numbers().take(5) {
+            for (operation, inputs) in [(BitOp::SetBit, vec![number, 3]), (BitOp::UnsetBit, vec![number, 3]),
+                                        (BitOp::UnsetBitXor, vec![number, 0]), (BitOp::UnsetBitBitwiseNot, vec![number, 31]),
+                                        (BitOp::InvertBit, vec![number, 7]), (BitOp::SwapBits, vec![number, 1, 30]),
+                                        (BitOp::RemoveBit, vec![number, 12])] {
+                let steps = operation.steps(&inputs).unwrap();
+                let text = explain(operation, &inputs).unwrap();
+                let lines: Vec<&str> = text.lines().collect();
+                // The call, then a sentence and a value per step
+                assert_eq!(lines.len(), 1 + 2 * steps.len());
+                assert!(lines[0].starts_with(operation.name()));
+                assert!(lines[0].ends_with(&format!(" = {:#b}", steps.last().unwrap().value)));
+                for (index, step) in steps.iter().enumerate() {
+                    assert!(lines[1 + 2 * index].starts_with(&format!("{}. ", index + 1)));
+                    assert!(lines[1 + 2 * index].ends_with(&step.description));
+                    assert!(lines[2 + 2 * index].ends_with(&step.operation));
+                }
+            }
+        }
+        assert_eq!(explain(BitOp::SetBit, &[1]), None);
+        assert_eq!(explain(BitOp::SwapBits, &[1, 2]), None);
+        assert_eq!(explain(BitOp::RemoveBit, &[1, 32]), None);
+    }
+
+    #[test]
+    fn test_explain_text() {
+        let text = explain(BitOp::InvertBit, &[0b1010, 1]).unwrap();
+        assert_eq!(text, "\
+invert_bit(0b1010, 1) = 0b1000
+1. Start from the input: the input
+    1010  number
+2. Take the constant 1: only the bit 0 is 1
+    0001  one = 1
+3. Shift one left by 1: only the bit 1 is 1
+    0010  mask = one << 1
+4. XOR number with mask: XOR with 1 flips the bit, XOR with 0 keeps the others
+    1000  number ^ mask
+");
+        let text = explain(BitOp::UnsetBit, &[0, 0]).unwrap();
+        assert!(text.contains("5. Subtract mask from set: "));
+    }
+}
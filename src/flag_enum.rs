@@ -0,0 +1,139 @@
+//! Flags backed by fieldless enums
+
+use crate::{get_bits, set_bit};
+
+/// Fieldless enum whose variants are flags. Every variant maps to the bit position given by its discriminant
+/// # Examples
+/// ```
+/// # use understanding_bitwise::flag_enum::{decode_mask, mask_of, FlagEnum};
+/// #[derive(Clone, Copy, PartialEq, Debug)]
+/// enum Day { Mon, Tue, Wed }
+///
+/// impl FlagEnum for Day {
+///     const VARIANTS: &'static [Self] = &[Day::Mon, Day::Tue, Day::Wed];
+///
+///     fn bit_index(self) -> u32 {
+///         self as u32
+///     }
+/// }
+///
+/// assert_eq!(mask_of([Day::Mon, Day::Wed]), Some(0b101));
+/// assert_eq!(decode_mask::<Day>(0b110), vec![Day::Tue, Day::Wed]);
+/// ```
+pub trait FlagEnum: Copy + 'static {
+    /// All the variants of the enum
+    const VARIANTS: &'static [Self];
+
+    /// Returns the bit position of the variant. Usually implemented as `self as u32`
+    fn bit_index(self) -> u32;
+}
+
+/// Returns the mask with the bits of the flags set or None if some flag's bit position is out of range
+/// # Arguments
+/// * `flags` - flags to be set
+pub fn mask_of<E, I>(flags: I) -> Option<u32>
+where
+    E: FlagEnum,
+    I: IntoIterator<Item = E> {
+    flags.into_iter().try_fold(0, |mask, flag| set_bit(mask, flag.bit_index()))
+}
+
+/// Returns an iterator over the variants whose bits are set in the mask, in the declaration order
+/// # Arguments
+/// * `mask` - mask to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::flag_enum::{iter_flags, FlagEnum};
+/// #[derive(Clone, Copy, PartialEq, Debug)]
+/// enum Pin { Reset = 3, Power = 7 }
+///
+/// impl FlagEnum for Pin {
+///     const VARIANTS: &'static [Self] = &[Pin::Reset, Pin::Power];
+///
+///     fn bit_index(self) -> u32 {
+///         self as u32
+///     }
+/// }
+///
+/// assert_eq!(iter_flags::<Pin>(0b1000_1000).count(), 2);
+/// assert_eq!(iter_flags::<Pin>(0b1000).next(), Some(Pin::Reset));
+/// ```
+pub fn iter_flags<E: FlagEnum>(mask: u32) -> impl Iterator<Item = E> {
+    E::VARIANTS.iter().copied().filter(move |flag| get_bits(mask, flag.bit_index(), 1) == Some(1))
+}
+
+/// Returns the variants whose bits are set in the mask, in the declaration order
+/// # Arguments
+/// * `mask` - mask to work with
+pub fn decode_mask<E: FlagEnum>(mask: u32) -> Vec<E> {
+    iter_flags(mask).collect()
+}
+
+/// Returns the bits of the mask that don't correspond to any variant
+/// # Arguments
+/// * `mask` - mask to work with
+pub fn unknown_bits<E: FlagEnum>(mask: u32) -> u32 {
+    let known = E::VARIANTS.iter().fold(0, |known, flag| set_bit(known, flag.bit_index()).unwrap_or(known));
+    mask & ! known
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum Event {
+        Read,
+        Write,
+        Close = 5,
+        Error = 31,
+    }
+
+    impl FlagEnum for Event {
+        const VARIANTS: &'static [Self] = &[Event::Read, Event::Write, Event::Close, Event::Error];
+
+        fn bit_index(self) -> u32 {
+            self as u32
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum TooHigh {
+        Bit = 40,
+    }
+
+    impl FlagEnum for TooHigh {
+        const VARIANTS: &'static [Self] = &[TooHigh::Bit];
+
+        fn bit_index(self) -> u32 {
+            self as u32
+        }
+    }
+
+    #[test]
+    fn test_mask_of() {
+        assert_eq!(mask_of([Event::Read, Event::Close]), Some(0b100001));
+        assert_eq!(mask_of([Event::Error]), Some(1 << 31));
+        assert_eq!(mask_of([Event::Write, Event::Write]), Some(0b10));
+        assert_eq!(mask_of::<Event, _>([]), Some(0));
+        assert_eq!(mask_of(vec![TooHigh::Bit]), None);
+    }
+
+    #[test]
+    fn test_decode_mask() {
+        assert_eq!(decode_mask::<Event>(0b100011), vec![Event::Read, Event::Write, Event::Close]);
+        assert_eq!(decode_mask::<Event>(0), vec![]);
+        assert_eq!(decode_mask::<TooHigh>(u32::MAX), vec![]);
+        for flags in [vec![Event::Write, Event::Error], vec![Event::Read, Event::Write, Event::Close, Event::Error]] {
+            assert_eq!(decode_mask::<Event>(mask_of(flags.iter().copied()).unwrap()), flags);
+        }
+    }
+
+    #[test]
+    fn test_unknown_bits() {
+        assert_eq!(unknown_bits::<Event>(0b1100011), 0b1000000);
+        assert_eq!(unknown_bits::<Event>(0b100011), 0);
+        assert_eq!(unknown_bits::<TooHigh>(0b1), 0b1);
+    }
+}
@@ -0,0 +1,223 @@
+//! Declarative typed flags
+
+/// Declares a typed flags struct over *u32*. Every flag is a constant mask (usually a single bit made by *1 << index*).
+/// The struct gets *contains*, *insert*, *remove*, *toggle*, iteration over the set flags, set operators and binary *Display*
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bit_flags;
+/// bit_flags! {
+///     /// Access rights
+///     pub struct Access: u32 {
+///         const READ = 1 << 0;
+///         const WRITE = 1 << 1;
+///         const EXECUTE = 1 << 2;
+///     }
+/// }
+///
+/// let mut access = Access::READ | Access::EXECUTE;
+/// assert!(access.contains(Access::READ));
+/// access.insert(Access::WRITE);
+/// access.remove(Access::READ);
+/// access.toggle(Access::EXECUTE);
+/// assert_eq!(access, Access::WRITE);
+/// assert_eq!(access.to_string(), "10");
+/// assert_eq!((Access::READ | Access::WRITE).iter_names().collect::<Vec<_>>(), vec!["READ", "WRITE"]);
+/// assert_eq!(Access::from_bits(0b1000), None);
+/// ```
+#[macro_export]
+macro_rules! bit_flags {
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $name:ident: u32 {
+            $(
+                $(#[$inner:meta])*
+                const $flag:ident = $value:expr;
+            )*
+        }
+    ) => {
+        $(#[$outer])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+        $vis struct $name(u32);
+
+        #[allow(dead_code)]
+        impl $name {
+            $(
+                $(#[$inner])*
+                pub const $flag: $name = $name($value);
+            )*
+
+            /// All the declared flags with their names
+            pub const FLAGS: &'static [(&'static str, $name)] = &[$((stringify!($flag), $name::$flag)),*];
+
+            /// Returns the value without flags
+            pub const fn empty() -> Self {
+                $name(0)
+            }
+
+            /// Returns the value with all the declared flags
+            pub const fn all() -> Self {
+                $name(0 $(| $value)*)
+            }
+
+            /// Returns the raw bits
+            pub const fn bits(&self) -> u32 {
+                self.0
+            }
+
+            /// Returns the value of the raw bits or None if some bit doesn't belong to the declared flags
+            pub const fn from_bits(bits: u32) -> Option<Self> {
+                if bits & ! Self::all().0 == 0 {
+                    Some($name(bits))
+                } else {
+                    None
+                }
+            }
+
+            /// Returns the value of the raw bits, dropping the bits that don't belong to the declared flags
+            pub const fn from_bits_truncate(bits: u32) -> Self {
+                $name(bits & Self::all().0)
+            }
+
+            /// Returns true if no flag is set
+            pub const fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+
+            /// Returns true if all the flags of *other* are set
+            pub const fn contains(&self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            /// Sets the flags of *other*
+            pub fn insert(&mut self, other: Self) {
+                self.0 |= other.0;
+            }
+
+            /// Unsets the flags of *other*
+            pub fn remove(&mut self, other: Self) {
+                self.0 &= ! other.0;
+            }
+
+            /// Inverts the flags of *other*
+            pub fn toggle(&mut self, other: Self) {
+                self.0 ^= other.0;
+            }
+
+            /// Returns an iterator over the declared flags that are set
+            pub fn iter(&self) -> impl Iterator<Item = Self> + '_ {
+                self.iter_named().map(|(_, flag)| flag)
+            }
+
+            /// Returns an iterator over the names of the declared flags that are set
+            pub fn iter_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+                self.iter_named().map(|(name, _)| name)
+            }
+
+            /// Helper function. Returns an iterator over the declared flags that are set with their names
+            fn iter_named(&self) -> impl Iterator<Item = (&'static str, Self)> + '_ {
+                Self::FLAGS.iter().copied().filter(move |&(_, flag)| flag.0 != 0 && self.contains(flag))
+            }
+        }
+
+        impl ::core::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, other: Self) -> Self {
+                $name(self.0 | other.0)
+            }
+        }
+
+        impl ::core::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, other: Self) -> Self {
+                $name(self.0 & other.0)
+            }
+        }
+
+        impl ::core::ops::BitXor for $name {
+            type Output = Self;
+
+            fn bitxor(self, other: Self) -> Self {
+                $name(self.0 ^ other.0)
+            }
+        }
+
+        impl ::core::ops::Not for $name {
+            type Output = Self;
+
+            fn not(self) -> Self {
+                Self::from_bits_truncate(! self.0)
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let mut binary = ::std::vec::Vec::<u8>::with_capacity(u32::BITS as usize);
+                $crate::write_binary_representation(self.0, &mut binary);
+                f.write_str(::core::str::from_utf8(&binary).map_err(|_| ::core::fmt::Error)?)
+            }
+        }
+    };
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    bit_flags! {
+        struct Color: u32 {
+            const RED = 1 << 0;
+            const GREEN = 1 << 1;
+            const BLUE = 1 << 2;
+            const YELLOW = 1 << 0 | 1 << 1;
+            const ALPHA = 1 << 31;
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        let color = Color::RED | Color::GREEN;
+        assert!(color.contains(Color::RED));
+        assert!(color.contains(Color::YELLOW));
+        assert!(! color.contains(Color::BLUE));
+        assert!(color.contains(Color::empty()));
+        assert!(Color::all().contains(color));
+    }
+
+    #[test]
+    fn test_insert_remove_toggle() {
+        let mut color = Color::empty();
+        color.insert(Color::BLUE);
+        color.insert(Color::YELLOW);
+        assert_eq!(color.bits(), 0b111);
+        color.remove(Color::GREEN);
+        assert_eq!(color, Color::RED | Color::BLUE);
+        color.toggle(Color::RED | Color::ALPHA);
+        assert_eq!(color, Color::BLUE | Color::ALPHA);
+        color.toggle(Color::BLUE | Color::ALPHA);
+        assert!(color.is_empty());
+    }
+
+    #[test]
+    fn test_from_bits() {
+        assert_eq!(Color::from_bits(0b101), Some(Color::RED | Color::BLUE));
+        assert_eq!(Color::from_bits(0b1000), None);
+        assert_eq!(Color::from_bits_truncate(0b1101), Color::RED | Color::BLUE);
+        assert_eq!(! Color::RED, Color::GREEN | Color::BLUE | Color::ALPHA);
+    }
+
+    #[test]
+    fn test_iter() {
+        let color = Color::RED | Color::GREEN | Color::ALPHA;
+        assert_eq!(color.iter_names().collect::<Vec<_>>(), vec!["RED", "GREEN", "YELLOW", "ALPHA"]);
+        assert_eq!(color.iter().count(), 4);
+        assert_eq!(Color::empty().iter().count(), 0);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!((Color::RED | Color::BLUE).to_string(), "101");
+        assert_eq!(Color::empty().to_string(), "0");
+        assert_eq!(Color::ALPHA.to_string(), "1".to_owned() + &"0".repeat(31));
+    }
+}
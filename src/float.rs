@@ -0,0 +1,465 @@
+//! Bit layout of [*IEEE 754*](https://en.wikipedia.org/wiki/Single-precision_floating-point_format) floats.
+//! An *f32* is, from the highest bit, 1 sign bit, 8 exponent bits and 23 mantissa bits; a normal number is
+//! (-1)ˢⁱᵍⁿ × 1.mantissa × 2^(exponent - 127). An *f64* has 11 exponent bits with the bias 1023 and 52 mantissa bits.
+//!
+//! Below the sign bit the bits of a float grow with its magnitude, so reading them as a sign-magnitude integer maps
+//! the floats onto consecutive integers in the order of their values, both zeros onto 0. Neighbors and distances in
+//! [*ULP*](https://en.wikipedia.org/wiki/Unit_in_the_last_place)s are then integer increments and differences
+
+use std::io::{self, Write};
+
+/// Bits count of the exponent of *f32*
+pub const F32_EXPONENT_BITS: u32 = 8;
+
+/// Bits count of the mantissa (fraction) of *f32*
+pub const F32_MANTISSA_BITS: u32 = 23;
+
+/// Bias of the exponent of *f32*: the raw exponent of 1.0
+pub const F32_EXPONENT_BIAS: i32 = 127;
+
+/// Bits count of the exponent of *f64*
+pub const F64_EXPONENT_BITS: u32 = 11;
+
+/// Bits count of the mantissa (fraction) of *f64*
+pub const F64_MANTISSA_BITS: u32 = 52;
+
+/// Bias of the exponent of *f64*: the raw exponent of 1.0
+pub const F64_EXPONENT_BIAS: i32 = 1023;
+
+/// Fields of an *f32*
+/// # Examples
+/// ```
+/// # use understanding_bitwise::float::FloatParts;
+/// let parts = FloatParts::from_f32(-6.5);
+/// // -1.101 × 2²
+/// assert_eq!((parts.sign, parts.exponent, parts.mantissa), (1, 129, 0b101 << 20));
+/// assert_eq!(parts.unbiased_exponent(), 2);
+/// assert_eq!(parts.to_f32(), -6.5);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FloatParts {
+    /// Sign bit: 1 for negative numbers
+    pub sign: u32,
+    /// Raw (biased) exponent
+    pub exponent: u32,
+    /// Mantissa without the implicit leading 1
+    pub mantissa: u32,
+}
+
+impl FloatParts {
+    /// Returns the parts or None if a field has more bits than in *f32*
+    /// # Arguments
+    /// * `sign` - sign bit
+    /// * `exponent` - raw exponent
+    /// * `mantissa` - mantissa without the implicit leading 1
+    pub fn new(sign: u32, exponent: u32, mantissa: u32) -> Option<Self> {
+        if sign > 1 || exponent >> F32_EXPONENT_BITS != 0 || mantissa >> F32_MANTISSA_BITS != 0 {
+            return None;
+        }
+        Some(FloatParts { sign, exponent, mantissa })
+    }
+
+    /// Returns the fields of the float
+    /// # Arguments
+    /// * `number` - float to be decomposed
+    pub fn from_f32(number: f32) -> Self {
+        let bits = number.to_bits();
+        FloatParts {
+            sign: bits >> (F32_EXPONENT_BITS + F32_MANTISSA_BITS),
+            exponent: bits >> F32_MANTISSA_BITS & (1 << F32_EXPONENT_BITS) - 1,
+            mantissa: bits & (1 << F32_MANTISSA_BITS) - 1,
+        }
+    }
+
+    /// Returns the float assembled from the fields
+    pub fn to_f32(&self) -> f32 {
+        f32::from_bits(self.sign << (F32_EXPONENT_BITS + F32_MANTISSA_BITS) | self.exponent << F32_MANTISSA_BITS | self.mantissa)
+    }
+
+    /// Returns the exponent without the bias. Zero and subnormals (raw exponent 0) give -127
+    /// though they scale by 2⁻¹²⁶, infinities and NaN (raw exponent 255) give 128
+    pub fn unbiased_exponent(&self) -> i32 {
+        self.exponent as i32 - F32_EXPONENT_BIAS
+    }
+
+    /// Writes the bits of the fields separated by spaces: sign, exponent, mantissa
+    /// # Arguments
+    /// * `out` - *Write* object that gets the bits
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::float::FloatParts;
+    /// let mut vec = Vec::new();
+    /// FloatParts::from_f32(1.5).write_binary(&mut vec).unwrap();
+    /// assert_eq!(String::from_utf8(vec).unwrap(), "0 01111111 10000000000000000000000");
+    /// ```
+    pub fn write_binary<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(out, "{} {:0exponent$b} {:0mantissa$b}", self.sign, self.exponent, self.mantissa,
+               exponent = F32_EXPONENT_BITS as usize, mantissa = F32_MANTISSA_BITS as usize)
+    }
+}
+
+/// Fields of an *f64*, see *FloatParts*
+/// # Examples
+/// ```
+/// # use understanding_bitwise::float::FloatParts64;
+/// let parts = FloatParts64::from_f64(-6.5);
+/// assert_eq!((parts.sign, parts.exponent, parts.mantissa), (1, 1025, 0b101 << 49));
+/// assert_eq!(parts.unbiased_exponent(), 2);
+/// assert_eq!(parts.to_f64(), -6.5);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FloatParts64 {
+    /// Sign bit: 1 for negative numbers
+    pub sign: u32,
+    /// Raw (biased) exponent
+    pub exponent: u32,
+    /// Mantissa without the implicit leading 1
+    pub mantissa: u64,
+}
+
+impl FloatParts64 {
+    /// Returns the parts or None if a field has more bits than in *f64*
+    /// # Arguments
+    /// * `sign` - sign bit
+    /// * `exponent` - raw exponent
+    /// * `mantissa` - mantissa without the implicit leading 1
+    pub fn new(sign: u32, exponent: u32, mantissa: u64) -> Option<Self> {
+        if sign > 1 || exponent >> F64_EXPONENT_BITS != 0 || mantissa >> F64_MANTISSA_BITS != 0 {
+            return None;
+        }
+        Some(FloatParts64 { sign, exponent, mantissa })
+    }
+
+    /// Returns the fields of the float
+    /// # Arguments
+    /// * `number` - float to be decomposed
+    pub fn from_f64(number: f64) -> Self {
+        let bits = number.to_bits();
+        FloatParts64 {
+            sign: (bits >> (F64_EXPONENT_BITS + F64_MANTISSA_BITS)) as u32,
+            exponent: (bits >> F64_MANTISSA_BITS) as u32 & (1 << F64_EXPONENT_BITS) - 1,
+            mantissa: bits & (1 << F64_MANTISSA_BITS) - 1,
+        }
+    }
+
+    /// Returns the float assembled from the fields
+    pub fn to_f64(&self) -> f64 {
+        let sign = (self.sign as u64) << (F64_EXPONENT_BITS + F64_MANTISSA_BITS);
+        f64::from_bits(sign | (self.exponent as u64) << F64_MANTISSA_BITS | self.mantissa)
+    }
+
+    /// Returns the exponent without the bias. Zero and subnormals give -1023, infinities and NaN give 1024
+    pub fn unbiased_exponent(&self) -> i32 {
+        self.exponent as i32 - F64_EXPONENT_BIAS
+    }
+
+    /// Writes the bits of the fields separated by spaces: sign, exponent, mantissa
+    /// # Arguments
+    /// * `out` - *Write* object that gets the bits
+    pub fn write_binary<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(out, "{} {:0exponent$b} {:0mantissa$b}", self.sign, self.exponent, self.mantissa,
+               exponent = F64_EXPONENT_BITS as usize, mantissa = F64_MANTISSA_BITS as usize)
+    }
+}
+
+/// Helper function. Returns the position of the float on the integer line of floats: 0 for both zeros, ±1 for
+/// the smallest subnormals and so on, so that neighbors differ by 1
+/// # Arguments
+/// * `number` - float other than NaN
+fn ordered_f32(number: f32) -> i32 {
+    let bits = number.to_bits() as i32;
+    if bits < 0 { i32::MIN.wrapping_sub(bits) } else { bits }
+}
+
+/// Helper function. Returns the float at the position of the integer line of floats, the inverse of *ordered_f32*.
+/// Position 0 gives +0
+/// # Arguments
+/// * `position` - position of the float
+fn from_ordered_f32(position: i32) -> f32 {
+    f32::from_bits(if position < 0 { i32::MIN.wrapping_sub(position) } else { position } as u32)
+}
+
+/// Helper function. Returns the position of the float on the integer line of floats, see *ordered_f32*
+/// # Arguments
+/// * `number` - float other than NaN
+fn ordered_f64(number: f64) -> i64 {
+    let bits = number.to_bits() as i64;
+    if bits < 0 { i64::MIN.wrapping_sub(bits) } else { bits }
+}
+
+/// Helper function. Returns the float at the position of the integer line of floats, see *from_ordered_f32*
+/// # Arguments
+/// * `position` - position of the float
+fn from_ordered_f64(position: i64) -> f64 {
+    f64::from_bits(if position < 0 { i64::MIN.wrapping_sub(position) } else { position } as u64)
+}
+
+/// Returns the count of floats from one float to the other, or None if one of them is NaN. Zeros of both signs
+/// are the same float, so the distance between the smallest subnormals of opposite signs is 2
+/// # Arguments
+/// * `a` - first float
+/// * `b` - second float
+/// # Examples
+/// ```
+/// # use understanding_bitwise::float::ulp_distance;
+/// assert_eq!(ulp_distance(1.0, 1.0 + f32::EPSILON), Some(1));
+/// assert_eq!(ulp_distance(-0.0, 0.0), Some(0));
+/// assert_eq!(ulp_distance(f32::MAX, f32::INFINITY), Some(1));
+/// assert_eq!(ulp_distance(1.0, f32::NAN), None);
+/// ```
+pub fn ulp_distance(a: f32, b: f32) -> Option<u32> {
+    if a.is_nan() || b.is_nan() {
+        return None;
+    }
+    Some((ordered_f32(a) as i64 - ordered_f32(b) as i64).unsigned_abs() as u32)
+}
+
+/// Returns the count of floats from one float to the other, or None if one of them is NaN. See *ulp_distance*
+/// # Arguments
+/// * `a` - first float
+/// * `b` - second float
+pub fn ulp_distance_f64(a: f64, b: f64) -> Option<u64> {
+    if a.is_nan() || b.is_nan() {
+        return None;
+    }
+    Some((ordered_f64(a) as i128 - ordered_f64(b) as i128).unsigned_abs() as u64)
+}
+
+/// Returns the smallest float greater than the number. NaN and +∞ are returned as is,
+/// and the next float of the negative smallest subnormal is +0
+/// # Arguments
+/// * `number` - float to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::float::next_up;
+/// assert_eq!(next_up(1.0), 1.0 + f32::EPSILON);
+/// assert_eq!(next_up(0.0), f32::from_bits(1));
+/// assert_eq!(next_up(f32::MAX), f32::INFINITY);
+/// assert_eq!(next_up(f32::NEG_INFINITY), f32::MIN);
+/// ```
+pub fn next_up(number: f32) -> f32 {
+    if number.is_nan() || number == f32::INFINITY {
+        return number;
+    }
+    from_ordered_f32(ordered_f32(number) + 1)
+}
+
+/// Returns the largest float less than the number. NaN and -∞ are returned as is,
+/// and the previous float of the smallest subnormal is +0
+/// # Arguments
+/// * `number` - float to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::float::next_down;
+/// assert_eq!(next_down(1.0), 1.0 - f32::EPSILON / 2.0);
+/// assert_eq!(next_down(0.0), -f32::from_bits(1));
+/// ```
+pub fn next_down(number: f32) -> f32 {
+    if number.is_nan() || number == f32::NEG_INFINITY {
+        return number;
+    }
+    from_ordered_f32(ordered_f32(number) - 1)
+}
+
+/// Returns the smallest float greater than the number, see *next_up*
+/// # Arguments
+/// * `number` - float to work with
+pub fn next_up_f64(number: f64) -> f64 {
+    if number.is_nan() || number == f64::INFINITY {
+        return number;
+    }
+    from_ordered_f64(ordered_f64(number) + 1)
+}
+
+/// Returns the largest float less than the number, see *next_down*
+/// # Arguments
+/// * `number` - float to work with
+pub fn next_down_f64(number: f64) -> f64 {
+    if number.is_nan() || number == f64::NEG_INFINITY {
+        return number;
+    }
+    from_ordered_f64(ordered_f64(number) - 1)
+}
+
+/// Class of a float by its bits
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FloatClass {
+    /// Exponent and mantissa 0
+    Zero,
+    /// Exponent 0, mantissa not 0: no implicit leading 1, fixed scale 2⁻¹²⁶
+    Subnormal,
+    /// Exponent neither all zeros nor all ones
+    Normal,
+    /// Exponent all ones, mantissa 0
+    Infinity,
+    /// Exponent all ones, mantissa not 0. The highest mantissa bit tells a quiet NaN from a signaling one,
+    /// the lower 22 bits are the payload
+    Nan { quiet: bool, payload: u32 },
+}
+
+/// Mask of the exponent bits of *f32*
+const F32_EXPONENT_MASK: u32 = (1 << F32_EXPONENT_BITS) - 1 << F32_MANTISSA_BITS;
+
+/// Mask of the mantissa bits of *f32*
+const F32_MANTISSA_MASK: u32 = (1 << F32_MANTISSA_BITS) - 1;
+
+/// Highest mantissa bit, set in quiet NaNs
+const F32_QUIET_BIT: u32 = 1 << (F32_MANTISSA_BITS - 1);
+
+/// Returns the class of the float determined by masking its bits; the sign doesn't matter
+/// # Arguments
+/// * `number` - float to be classified
+/// # Examples
+/// ```
+/// # use understanding_bitwise::float::{classify_bits, FloatClass};
+/// assert_eq!(classify_bits(-0.0), FloatClass::Zero);
+/// assert_eq!(classify_bits(f32::MIN_POSITIVE / 2.0), FloatClass::Subnormal);
+/// assert_eq!(classify_bits(f32::NEG_INFINITY), FloatClass::Infinity);
+/// // Signaling NaN with the payload 5
+/// assert_eq!(classify_bits(f32::from_bits(0x7F80_0005)), FloatClass::Nan { quiet: false, payload: 5 });
+/// ```
+pub fn classify_bits(number: f32) -> FloatClass {
+    let bits = number.to_bits();
+    let exponent = bits & F32_EXPONENT_MASK;
+    let mantissa = bits & F32_MANTISSA_MASK;
+    match (exponent, mantissa) {
+        (0, 0) => FloatClass::Zero,
+        (0, _) => FloatClass::Subnormal,
+        (F32_EXPONENT_MASK, 0) => FloatClass::Infinity,
+        (F32_EXPONENT_MASK, _) => FloatClass::Nan { quiet: mantissa & F32_QUIET_BIT != 0, payload: mantissa & ! F32_QUIET_BIT },
+        _ => FloatClass::Normal,
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_parts() {
+        assert_eq!(FloatParts::from_f32(1.0), FloatParts { sign: 0, exponent: 127, mantissa: 0 });
+        assert_eq!(FloatParts::from_f32(-0.0), FloatParts { sign: 1, exponent: 0, mantissa: 0 });
+        assert_eq!(FloatParts::from_f32(f32::INFINITY), FloatParts { sign: 0, exponent: 255, mantissa: 0 });
+        // The smallest subnormal: only the lowest mantissa bit
+        assert_eq!(FloatParts::from_f32(f32::from_bits(1)), FloatParts { sign: 0, exponent: 0, mantissa: 1 });
+        let max = FloatParts::from_f32(f32::MAX);
+        assert_eq!((max.exponent, max.mantissa, max.unbiased_exponent()), (254, (1 << 23) - 1, 127));
+        assert_eq!(FloatParts::from_f32(f32::MIN_POSITIVE).unbiased_exponent(), -126);
+        assert!(FloatParts::from_f32(f32::NAN).to_f32().is_nan());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let parts = FloatParts::from_f32(f32::from_bits(state));
+            // NaN payloads are kept too
+            assert_eq!(parts.to_f32().to_bits(), state);
+            assert_eq!(FloatParts::new(parts.sign, parts.exponent, parts.mantissa), Some(parts));
+        }
+        assert_eq!(FloatParts::new(2, 0, 0), None);
+        assert_eq!(FloatParts::new(0, 256, 0), None);
+        assert_eq!(FloatParts::new(0, 0, 1 << 23), None);
+    }
+
+    #[test]
+    fn test_write_binary() {
+        let mut vec = Vec::new();
+        FloatParts::from_f32(-f32::MAX).write_binary(&mut vec).unwrap();
+        assert_eq!(String::from_utf8(vec).unwrap(), format!("1 11111110 {}", "1".repeat(23)));
+    }
+
+    #[test]
+    fn test_parts64() {
+        assert_eq!(FloatParts64::from_f64(1.0), FloatParts64 { sign: 0, exponent: 1023, mantissa: 0 });
+        assert_eq!(FloatParts64::from_f64(f64::NEG_INFINITY), FloatParts64 { sign: 1, exponent: 2047, mantissa: 0 });
+        assert_eq!(FloatParts64::from_f64(f64::MIN_POSITIVE).unbiased_exponent(), -1022);
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let bits = (state as u64) << 32 | state.rotate_left(7) as u64;
+            let parts = FloatParts64::from_f64(f64::from_bits(bits));
+            assert_eq!(parts.to_f64().to_bits(), bits);
+            assert_eq!(FloatParts64::new(parts.sign, parts.exponent, parts.mantissa), Some(parts));
+        }
+        assert_eq!(FloatParts64::new(0, 2048, 0), None);
+        assert_eq!(FloatParts64::new(0, 0, 1 << 52), None);
+        let mut vec = Vec::new();
+        FloatParts64::from_f64(2.0).write_binary(&mut vec).unwrap();
+        assert_eq!(String::from_utf8(vec).unwrap(), format!("0 10000000000 {}", "0".repeat(52)));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let number = f32::from_bits(state);
+            if number.is_nan() {
+                assert!(next_up(number).is_nan() && ulp_distance(number, 0.0).is_none());
+                continue;
+            }
+            // The standard library gives the same neighbors, up to the sign of zero
+            assert_eq!(next_up(number), number.next_up());
+            assert_eq!(next_down(number), number.next_down());
+            assert!(next_up(number) > number || number == f32::INFINITY);
+            assert_eq!(next_down(next_up(number)), number);
+            assert_eq!(ulp_distance(number, next_up(number)), Some((number != f32::INFINITY) as u32));
+            let wide = number as f64;
+            assert_eq!(next_up_f64(wide), wide.next_up());
+            assert_eq!(next_down_f64(wide), wide.next_down());
+        }
+        assert_eq!(next_up(-f32::from_bits(1)).to_bits(), 0);
+        assert_eq!(next_down(f32::from_bits(1)).to_bits(), 0);
+        assert_eq!((next_up(f32::INFINITY), next_down(f32::NEG_INFINITY)), (f32::INFINITY, f32::NEG_INFINITY));
+        assert_eq!(ulp_distance(f32::NEG_INFINITY, f32::INFINITY), Some(0xFF00_0000));
+        assert_eq!(ulp_distance(-f32::from_bits(1), f32::from_bits(1)), Some(2));
+        assert_eq!(ulp_distance_f64(f64::NEG_INFINITY, f64::INFINITY), Some(0xFFE0_0000_0000_0000));
+        assert_eq!(ulp_distance_f64(1.0, 1.0 + f64::EPSILON * 3.0), Some(3));
+        assert_eq!(ulp_distance_f64(f64::NAN, 1.0), None);
+        assert_eq!(next_up_f64(-0.0), f64::from_bits(1));
+        assert!(next_down_f64(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_classify_bits() {
+        use std::num::FpCategory;
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            // Every class comes up: the exponent is taken from a few values
+            let exponent = [0, 1, 127, 254, 255][(state % 5) as usize];
+            let bits = state & (1 << 31 | F32_MANTISSA_MASK) | exponent << F32_MANTISSA_BITS;
+            let number = f32::from_bits(bits);
+            let expected = match number.classify() {
+                FpCategory::Zero => FloatClass::Zero,
+                FpCategory::Subnormal => FloatClass::Subnormal,
+                FpCategory::Normal => FloatClass::Normal,
+                FpCategory::Infinite => FloatClass::Infinity,
+                FpCategory::Nan => FloatClass::Nan { quiet: bits >> 22 & 1 == 1, payload: bits & 0x3F_FFFF },
+            };
+            assert_eq!(classify_bits(number), expected);
+        }
+    }
+
+    #[test]
+    fn test_nan_payloads() {
+        assert_eq!(classify_bits(f32::NAN), FloatClass::Nan { quiet: true, payload: 0 });
+        assert_eq!(classify_bits(f32::from_bits(0xFFC0_0001)), FloatClass::Nan { quiet: true, payload: 1 });
+        assert_eq!(classify_bits(f32::from_bits(0x7FBF_FFFF)), FloatClass::Nan { quiet: false, payload: 0x3F_FFFF });
+        // A signaling NaN needs a payload, otherwise it's an infinity
+        assert_eq!(classify_bits(f32::from_bits(0x7F80_0000)), FloatClass::Infinity);
+        for payload in [1, 0x1234, 0x3F_FFFF] {
+            let signaling = f32::from_bits(0x7F80_0000 | payload);
+            assert_eq!(classify_bits(signaling), FloatClass::Nan { quiet: false, payload });
+            let parts = FloatParts::from_f32(signaling);
+            assert_eq!((parts.exponent, parts.mantissa), (255, payload));
+        }
+        assert_eq!(classify_bits(f32::from_bits(1)), FloatClass::Subnormal);
+        assert_eq!(classify_bits(f32::MIN_POSITIVE), FloatClass::Normal);
+        assert_eq!(classify_bits(f32::MAX), FloatClass::Normal);
+    }
+}
@@ -0,0 +1,216 @@
+//! [*Geohash*](https://en.wikipedia.org/wiki/Geohash): latitude and longitude are quantized, their bits are interleaved
+//! starting with the longitude at the highest bit, and every 5 bits become a base-32 digit.
+//! Every extra digit splits the cell into 32 subcells, so hashes sharing a prefix lie in the same cell
+
+use crate::morton::{deinterleave, interleave};
+
+/// Longest supported geohash: 12 digits take 60 bits
+pub const GEOHASH_MAX_PRECISION: usize = 12;
+
+/// Bits count of a base-32 digit
+const DIGIT_BITS: u32 = 5;
+
+/// Digits of the geohash base 32, without *a*, *i*, *l* and *o*
+const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Cell of a geohash, bounds in degrees
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GeohashBox {
+    /// Southern bound, inclusive
+    pub min_latitude: f64,
+    /// Northern bound, exclusive unless it's the pole at 90°
+    pub max_latitude: f64,
+    /// Western bound, inclusive
+    pub min_longitude: f64,
+    /// Eastern bound, exclusive unless it's the antimeridian at 180°
+    pub max_longitude: f64,
+}
+
+impl GeohashBox {
+    /// Returns the center of the cell as latitude and longitude
+    pub fn center(&self) -> (f64, f64) {
+        ((self.min_latitude + self.max_latitude) / 2.0, (self.min_longitude + self.max_longitude) / 2.0)
+    }
+
+    /// Returns true if the point is inside the cell. Lower bounds are inclusive and upper bounds exclusive,
+    /// so a point on an edge belongs to one cell only, the one its geohash names. The last cells keep
+    /// the 90° latitude and the 180° longitude
+    /// # Arguments
+    /// * `latitude` - latitude in degrees
+    /// * `longitude` - longitude in degrees
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        within(latitude, self.min_latitude, self.max_latitude, 90.0)
+            && within(longitude, self.min_longitude, self.max_longitude, 180.0)
+    }
+}
+
+/// Helper function. Returns true if the value is in the half-open range, or is its upper bound at the end of the scale
+/// # Arguments
+/// * `value` - value to check
+/// * `min` - lowest value of the range
+/// * `max` - upper bound of the range
+/// * `limit` - highest value of the scale
+fn within(value: f64, min: f64, max: f64, limit: f64) -> bool {
+    (min..max).contains(&value) || value == max && max == limit
+}
+
+/// Helper function. Returns the bits counts of the latitude and the longitude, the longitude takes the odd bit
+/// # Arguments
+/// * `precision` - count of digits
+fn coordinate_bits(precision: usize) -> (u32, u32) {
+    let bits = precision as u32 * DIGIT_BITS;
+    (bits / 2, bits - bits / 2)
+}
+
+/// Helper function. Returns the index of the cell holding the value, the range is split into 2ᵇⁱᵗˢ cells
+/// # Arguments
+/// * `value` - value within the range
+/// * `min` - lowest value of the range
+/// * `max` - highest value of the range
+/// * `bits` - bits count of the index
+fn quantize(value: f64, min: f64, max: f64, bits: u32) -> u32 {
+    let cells = (1u64 << bits) as f64;
+    // The highest value belongs to the last cell
+    (((value - min) / (max - min) * cells) as u64).min((1 << bits) - 1) as u32
+}
+
+/// Helper function. Returns the bounds of the cell with the index, inverse of *quantize*
+/// # Arguments
+/// * `index` - index of the cell
+/// * `min` - lowest value of the range
+/// * `max` - highest value of the range
+/// * `bits` - bits count of the index
+fn dequantize(index: u32, min: f64, max: f64, bits: u32) -> (f64, f64) {
+    let size = (max - min) / (1u64 << bits) as f64;
+    (min + index as f64 * size, min + (index as f64 + 1.0) * size)
+}
+
+/// Returns the geohash of the point, or None if the precision isn't in 1..=*GEOHASH_MAX_PRECISION*
+/// or the coordinates are out of range or NaN
+/// # Arguments
+/// * `latitude` - latitude in degrees, from -90 to 90
+/// * `longitude` - longitude in degrees, from -180 to 180
+/// * `precision` - count of digits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::geohash::geohash_encode;
+/// assert_eq!(geohash_encode(57.64911, 10.40744, 11).as_deref(), Some("u4pruydqqvj"));
+/// assert_eq!(geohash_encode(42.6, -5.6, 5).as_deref(), Some("ezs42"));
+/// assert_eq!(geohash_encode(91.0, 0.0, 5), None);
+/// ```
+pub fn geohash_encode(latitude: f64, longitude: f64, precision: usize) -> Option<String> {
+    if !(1..=GEOHASH_MAX_PRECISION).contains(&precision)
+        || !(-90.0..=90.0).contains(&latitude)
+        || !(-180.0..=180.0).contains(&longitude) {
+        return None;
+    }
+    let (latitude_bits, longitude_bits) = coordinate_bits(precision);
+    let latitude = quantize(latitude, -90.0, 90.0, latitude_bits);
+    let longitude = quantize(longitude, -180.0, 180.0, longitude_bits);
+    // The highest bit is even for an odd bits count, so the longitude goes first then
+    let words = if longitude_bits > latitude_bits { [longitude, latitude] } else { [latitude, longitude] };
+    let code = interleave(&words)?;
+    let digits = (0..precision).rev().map(|index| ALPHABET[(code >> (index as u32 * DIGIT_BITS) & 0x1F) as usize] as char);
+    Some(digits.collect())
+}
+
+/// Returns the cell of the geohash, or None if it's empty, longer than *GEOHASH_MAX_PRECISION*
+/// or has a character out of the alphabet. Upper case is accepted
+/// # Arguments
+/// * `hash` - geohash
+/// # Examples
+/// ```
+/// # use understanding_bitwise::geohash::geohash_decode;
+/// let cell = geohash_decode("ezs42").unwrap();
+/// let (latitude, longitude) = cell.center();
+/// assert!((latitude - 42.605).abs() < 0.001 && (longitude + 5.603).abs() < 0.001);
+/// assert_eq!(geohash_decode("ezs4a"), None);
+/// ```
+pub fn geohash_decode(hash: &str) -> Option<GeohashBox> {
+    let precision = hash.len();
+    if !(1..=GEOHASH_MAX_PRECISION).contains(&precision) {
+        return None;
+    }
+    let code = hash.bytes().try_fold(0u128, |code, char| {
+        let digit = ALPHABET.iter().position(|&digit| digit == char.to_ascii_lowercase())?;
+        Some(code << DIGIT_BITS | digit as u128)
+    })?;
+    let (latitude_bits, longitude_bits) = coordinate_bits(precision);
+    let words = deinterleave(code, 2)?;
+    let (latitude, longitude) = if longitude_bits > latitude_bits { (words[1], words[0]) } else { (words[0], words[1]) };
+    let (min_latitude, max_latitude) = dequantize(latitude, -90.0, 90.0, latitude_bits);
+    let (min_longitude, max_longitude) = dequantize(longitude, -180.0, 180.0, longitude_bits);
+    Some(GeohashBox { min_latitude, max_latitude, min_longitude, max_longitude })
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_known_hashes() {
+        assert_eq!(geohash_encode(0.0, 0.0, 1).as_deref(), Some("s"));
+        assert_eq!(geohash_encode(-90.0, -180.0, 12).as_deref(), Some("000000000000"));
+        assert_eq!(geohash_encode(90.0, 180.0, 12).as_deref(), Some("zzzzzzzzzzzz"));
+        let cell = geohash_decode("u4pruydqqvj").unwrap();
+        assert!(cell.contains(57.64911, 10.40744));
+        assert_eq!(geohash_decode("S"), geohash_decode("s"));
+        assert_eq!(geohash_decode("s").unwrap(), GeohashBox {
+            min_latitude: 0.0, max_latitude: 45.0, min_longitude: 0.0, max_longitude: 45.0,
+        });
+    }
+
+    #[test]
+    fn test_edges() {
+        let cell = geohash_decode("s").unwrap();
+        assert!(cell.contains(0.0, 0.0));
+        assert!(!cell.contains(45.0, 10.0) && !cell.contains(10.0, 45.0));
+        // A point on an edge is in the cell its hash names only
+        for (latitude, longitude) in [(45.0, 10.0), (10.0, 45.0), (45.0, 45.0), (0.0, 0.0)] {
+            let hash = geohash_encode(latitude, longitude, 1).unwrap();
+            for other in ALPHABET.iter().map(|&digit| (digit as char).to_string()) {
+                assert_eq!(geohash_decode(&other).unwrap().contains(latitude, longitude), other == hash, "{other}");
+            }
+        }
+        assert!(geohash_decode("zzzzzzzzzzzz").unwrap().contains(90.0, 180.0));
+        assert!(geohash_decode("000000000000").unwrap().contains(-90.0, -180.0));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(geohash_encode(0.0, 0.0, 0), None);
+        assert_eq!(geohash_encode(0.0, 0.0, GEOHASH_MAX_PRECISION + 1), None);
+        assert_eq!(geohash_encode(f64::NAN, 0.0, 5), None);
+        assert_eq!(geohash_encode(0.0, -180.5, 5), None);
+        assert_eq!(geohash_decode(""), None);
+        assert_eq!(geohash_decode("0123456789bcd"), None);
+        for char in ["a", "i", "l", "o", "-", "é"] {
+            assert_eq!(geohash_decode(char), None);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        let mut next = move || rng.next_u32() as f64 / u32::MAX as f64;
+        for _ in 0..500 {
+            let (latitude, longitude) = (next() * 180.0 - 90.0, next() * 360.0 - 180.0);
+            let mut previous: Option<String> = None;
+            for precision in 1..=GEOHASH_MAX_PRECISION {
+                let hash = geohash_encode(latitude, longitude, precision).unwrap();
+                let cell = geohash_decode(&hash).unwrap();
+                assert!(cell.contains(latitude, longitude), "{hash} {latitude} {longitude}");
+                let (latitude_bits, longitude_bits) = coordinate_bits(precision);
+                assert_eq!(cell.max_latitude - cell.min_latitude, 180.0 / (1u64 << latitude_bits) as f64);
+                assert_eq!(cell.max_longitude - cell.min_longitude, 360.0 / (1u64 << longitude_bits) as f64);
+                // Longer hashes refine the shorter ones
+                if let Some(previous) = previous {
+                    assert!(hash.starts_with(&previous));
+                }
+                previous = Some(hash);
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Arithmetic in [*GF(2⁸)*](https://en.wikipedia.org/wiki/Finite_field_arithmetic), the field used by AES and
+//! Reed–Solomon codes. A byte is a polynomial over GF(2) of degree below 8, the bit *i* is the coefficient of xⁱ.
+//! Addition is XOR, multiplication is carry-less multiplication reduced modulo the Rijndael polynomial
+//! x⁸ + x⁴ + x³ + x + 1. The field has a generator (3), so every nonzero byte is a power of it and multiplication
+//! turns into addition of logarithms
+
+/// Rijndael polynomial x⁸ + x⁴ + x³ + x + 1
+pub const RIJNDAEL_POLYNOMIAL: u16 = 0x11B;
+
+/// Generator of the multiplicative group used for the logarithm tables: x + 1
+pub const GENERATOR: u8 = 3;
+
+/// Helper function. Returns the byte multiplied by x: shifted left and reduced if the bit 8 came out
+/// # Arguments
+/// * `byte` - byte to work with
+const fn xtime(byte: u8) -> u8 {
+    let shifted = (byte as u16) << 1;
+    (shifted ^ RIJNDAEL_POLYNOMIAL * (shifted >> 8)) as u8
+}
+
+/// Helper function. Returns the multiplication of the bytes by the shift-and-add method, see *gf256_mul*
+/// # Arguments
+/// * `a` - first factor
+/// * `b` - second factor
+const fn peasant_mul(a: u8, b: u8) -> u8 {
+    let (mut a, mut b, mut product) = (a, b, 0);
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+/// Powers of the generator, twice over so a sum of two logarithms needs no reduction modulo 255
+const EXP_TABLE: [u8; 510] = {
+    let mut table = [0; 510];
+    let mut power = 1;
+    let mut index = 0;
+    while index < table.len() {
+        table[index] = power;
+        power = peasant_mul(power, GENERATOR);
+        index += 1;
+    }
+    table
+};
+
+/// Logarithms to the base of the generator, the element 0 is unused
+const LOG_TABLE: [u8; 256] = {
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < 255 {
+        table[EXP_TABLE[index] as usize] = index as u8;
+        index += 1;
+    }
+    table
+};
+
+/// Returns the product of the bytes by the "Russian peasant" method: for every one of *b* the current *a* is added,
+/// and *a* is multiplied by x, reduced by XOR with the polynomial whenever it overflows 8 bits
+/// # Arguments
+/// * `a` - first factor
+/// * `b` - second factor
+/// # Examples
+/// ```
+/// # use understanding_bitwise::gf256::gf256_mul;
+/// // Example of the AES specification
+/// assert_eq!(gf256_mul(0x57, 0x83), 0xC1);
+/// assert_eq!(gf256_mul(0x57, 0x13), 0xFE);
+/// ```
+pub fn gf256_mul(a: u8, b: u8) -> u8 {
+    peasant_mul(a, b)
+}
+
+/// Returns the product of the bytes by the logarithm tables: *exp(log a + log b)*
+/// # Arguments
+/// * `a` - first factor
+/// * `b` - second factor
+/// # Examples
+/// ```
+/// # use understanding_bitwise::gf256::gf256_mul_table;
+/// assert_eq!(gf256_mul_table(0x57, 0x83), 0xC1);
+/// assert_eq!(gf256_mul_table(0x57, 0), 0);
+/// ```
+pub fn gf256_mul_table(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    EXP_TABLE[LOG_TABLE[a as usize] as usize + LOG_TABLE[b as usize] as usize]
+}
+
+/// Returns the multiplicative inverse of the byte or None for 0: *exp(255 - log a)*
+/// # Arguments
+/// * `byte` - byte to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::gf256::{gf256_inverse, gf256_mul};
+/// assert_eq!(gf256_inverse(0x53), Some(0xCA));
+/// assert_eq!(gf256_mul(0x53, 0xCA), 1);
+/// assert_eq!(gf256_inverse(0), None);
+/// ```
+pub fn gf256_inverse(byte: u8) -> Option<u8> {
+    (byte != 0).then(|| EXP_TABLE[255 - LOG_TABLE[byte as usize] as usize])
+}
+
+/// Returns the inverse of the byte computed as *byte²⁵⁴* by repeated squaring, or None for 0.
+/// Every nonzero byte satisfies *byte²⁵⁵ = 1*
+/// # Arguments
+/// * `byte` - byte to work with
+pub fn gf256_inverse_pow(byte: u8) -> Option<u8> {
+    if byte == 0 {
+        return None;
+    }
+    let (mut base, mut exponent, mut result) = (byte, 254u32, 1);
+    while exponent != 0 {
+        if exponent & 1 == 1 {
+            result = gf256_mul(result, base);
+        }
+        base = gf256_mul(base, base);
+        exponent >>= 1;
+    }
+    Some(result)
+}
+
+/// Returns the quotient of the bytes or None if the divisor is 0: *exp(log a - log b)*
+/// # Arguments
+/// * `a` - dividend
+/// * `b` - divisor
+/// # Examples
+/// ```
+/// # use understanding_bitwise::gf256::gf256_div;
+/// assert_eq!(gf256_div(0xC1, 0x83), Some(0x57));
+/// assert_eq!(gf256_div(1, 0), None);
+/// ```
+pub fn gf256_div(a: u8, b: u8) -> Option<u8> {
+    if b == 0 {
+        return None;
+    }
+    if a == 0 {
+        return Some(0);
+    }
+    Some(EXP_TABLE[LOG_TABLE[a as usize] as usize + 255 - LOG_TABLE[b as usize] as usize])
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables() {
+        // The generator walks through all 255 nonzero bytes
+        let mut seen = [false; 256];
+        for &power in &EXP_TABLE[..255] {
+            assert!(!seen[power as usize]);
+            seen[power as usize] = true;
+        }
+        assert!(!seen[0]);
+        assert_eq!(EXP_TABLE[255], 1);
+        assert_eq!(LOG_TABLE[1], 0);
+        assert_eq!(LOG_TABLE[GENERATOR as usize], 1);
+    }
+
+    #[test]
+    fn test_multiplication() {
+        for a in 0..=255 {
+            for b in 0..=255 {
+                let product = gf256_mul(a, b);
+                assert_eq!(gf256_mul_table(a, b), product);
+                assert_eq!(gf256_mul(b, a), product);
+                if b != 0 {
+                    assert_eq!(gf256_div(product, b), Some(a));
+                }
+            }
+        }
+        // Distributivity over XOR
+        for (a, b, c) in [(0x57, 0x83, 0x13), (0xFF, 0x01, 0x80), (0x12, 0x34, 0x56)] {
+            assert_eq!(gf256_mul(a, b ^ c), gf256_mul(a, b) ^ gf256_mul(a, c));
+        }
+        assert_eq!(gf256_mul(0x80, 2), 0x1B);
+        assert_eq!(gf256_div(0, 7), Some(0));
+    }
+
+    #[test]
+    fn test_inverse() {
+        assert_eq!(gf256_inverse(1), Some(1));
+        assert_eq!(gf256_inverse_pow(0), None);
+        for byte in 1..=255 {
+            let inverse = gf256_inverse(byte).unwrap();
+            assert_eq!(gf256_mul(byte, inverse), 1);
+            assert_eq!(gf256_inverse_pow(byte), Some(inverse));
+            assert_eq!(gf256_div(1, byte), Some(inverse));
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! [*Hamming codes*](https://en.wikipedia.org/wiki/Hamming_code): Hamming(7,4) and SECDED over 32-bit words. Codeword bit *i* is the position *i + 1*.
+//! Parity bits sit at the positions that are powers of two, the parity bit at 2ᵏ covers every position with the bit *k* set,
+//! so XOR of the positions of all ones (the syndrome) is 0 for a valid codeword and the position of a single flipped bit otherwise
+
+use std::error::Error;
+use std::fmt;
+use crate::{invert_bit, pdep, pext};
+use crate::parity::parity_of;
+
+/// Codeword bits holding the data of Hamming(7,4): positions 3, 5, 6, 7
+const HAMMING74_DATA: u32 = 0b0111_0100;
+
+/// Codeword bits covered by the parity bits of Hamming(7,4): positions with the bit *k* set, for *k* = 0, 1, 2
+const HAMMING74_COVERAGE: [u32; 3] = [0b0101_0101, 0b0110_0110, 0b0111_1000];
+
+/// Result of the decoding of a codeword with at most one flipped bit
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Corrected {
+    /// The codeword was valid
+    Clean,
+    /// The bit of the codeword with the index was flipped and has been corrected
+    Bit(u32),
+}
+
+/// Error of the decoding: two bits of the codeword are flipped, which is detected but can't be corrected
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DoubleError;
+
+impl fmt::Display for DoubleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "double bit error detected, the codeword can't be corrected")
+    }
+}
+
+impl Error for DoubleError {}
+
+/// Helper function. Returns the syndrome of the 7-bit Hamming(7,4) codeword: parity of every covered group is one syndrome bit
+/// # Arguments
+/// * `codeword` - codeword to work with
+fn hamming74_syndrome(codeword: u32) -> u32 {
+    HAMMING74_COVERAGE.iter().enumerate().map(|(bit, &coverage)| parity_of(codeword & coverage) << bit).sum()
+}
+
+/// Returns the codeword of the low nibble: Hamming(7,4) in the bits 0..7 and the overall parity in the bit 7
+/// (extended Hamming code), so double errors are detected rather than miscorrected. Higher bits of the input are ignored
+/// # Arguments
+/// * `nibble` - data to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hamming::hamming74_encode;
+/// assert_eq!(hamming74_encode(0b1011), 0b0101_0101);
+/// assert_eq!(hamming74_encode(0), 0);
+/// ```
+pub fn hamming74_encode(nibble: u8) -> u8 {
+    let data = pdep(nibble as u32, HAMMING74_DATA);
+    // Setting the parity bit at 2ᵏ for every syndrome bit *k* makes the syndrome 0
+    let syndrome = hamming74_syndrome(data);
+    let codeword = data | pdep(syndrome, 0b1011);
+    (codeword | parity_of(codeword) << 7) as u8
+}
+
+/// Returns the nibble of the codeword made by *hamming74_encode* and whether a single flipped bit was corrected,
+/// or an error if two bits are flipped
+/// # Arguments
+/// * `codeword` - codeword to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hamming::{hamming74_decode, hamming74_encode, Corrected, DoubleError};
+/// let codeword = hamming74_encode(0b1011);
+/// assert_eq!(hamming74_decode(codeword), Ok((0b1011, Corrected::Clean)));
+/// assert_eq!(hamming74_decode(codeword ^ 0b100), Ok((0b1011, Corrected::Bit(2))));
+/// assert_eq!(hamming74_decode(codeword ^ 0b101), Err(DoubleError));
+/// ```
+pub fn hamming74_decode(codeword: u8) -> Result<(u8, Corrected), DoubleError> {
+    let codeword = codeword as u32;
+    let syndrome = hamming74_syndrome(codeword);
+    let (codeword, corrected) = match (syndrome, parity_of(codeword)) {
+        (0, 0) => (codeword, Corrected::Clean),
+        // Odd count of flipped bits, the syndrome is the position of the single one (0 is the overall parity bit)
+        (0, _) => (codeword, Corrected::Bit(7)),
+        (position, 1) => (invert_bit(codeword, position - 1).unwrap(), Corrected::Bit(position - 1)),
+        // Even count of flipped bits with a nonzero syndrome
+        _ => return Err(DoubleError),
+    };
+    Ok((pext(codeword, HAMMING74_DATA) as u8, corrected))
+}
+
+/// Bits count of the SECDED codeword: 32 data bits, 6 Hamming parity bits and the overall parity bit
+pub const SECDED_BITS: u32 = 39;
+
+/// Helper function. Returns the syndrome of the 38-bit Hamming part of the SECDED codeword: XOR of the positions of all ones
+/// # Arguments
+/// * `codeword` - codeword to work with
+fn secded_syndrome(codeword: u64) -> u32 {
+    let mut ones = codeword & (1 << (SECDED_BITS - 1)) - 1;
+    let mut syndrome = 0;
+    while ones != 0 {
+        syndrome ^= ones.trailing_zeros() + 1;
+        ones &= ones - 1;
+    }
+    syndrome
+}
+
+/// Helper function. Returns the indexes of the SECDED codeword bits holding the data, lowest first
+fn secded_data_indexes() -> impl Iterator<Item = u32> {
+    (0..SECDED_BITS - 1).filter(|index| !(index + 1).is_power_of_two())
+}
+
+/// Returns the 39-bit SECDED (single error correction, double error detection) codeword of the word, as used by ECC memory.
+/// The data fills the positions 1..=38 that aren't powers of two, the overall parity is the bit 38
+/// # Arguments
+/// * `data` - data to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hamming::{secded_decode, secded_encode, Corrected};
+/// let codeword = secded_encode(0xDEAD_BEEF);
+/// assert!(codeword < 1 << 39);
+/// assert_eq!(secded_decode(codeword ^ 1 << 20), Ok((0xDEAD_BEEF, Corrected::Bit(20))));
+/// ```
+pub fn secded_encode(data: u32) -> u64 {
+    let mut codeword = 0u64;
+    for (data_index, index) in secded_data_indexes().enumerate() {
+        codeword |= ((data >> data_index & 1) as u64) << index;
+    }
+    let syndrome = secded_syndrome(codeword);
+    for bit in 0..u32::BITS - syndrome.leading_zeros() {
+        codeword |= ((syndrome >> bit & 1) as u64) << ((1 << bit) - 1);
+    }
+    codeword | ((codeword.count_ones() % 2) as u64) << (SECDED_BITS - 1)
+}
+
+/// Returns the data of the codeword made by *secded_encode* and whether a single flipped bit was corrected,
+/// or an error if two bits are flipped. Bits above the codeword are ignored
+/// # Arguments
+/// * `codeword` - codeword to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hamming::{secded_decode, secded_encode, Corrected, DoubleError};
+/// let codeword = secded_encode(42);
+/// assert_eq!(secded_decode(codeword), Ok((42, Corrected::Clean)));
+/// assert_eq!(secded_decode(codeword ^ 0b11), Err(DoubleError));
+/// ```
+pub fn secded_decode(codeword: u64) -> Result<(u32, Corrected), DoubleError> {
+    let codeword = codeword & (1 << SECDED_BITS) - 1;
+    let syndrome = secded_syndrome(codeword);
+    let (codeword, corrected) = match (syndrome, codeword.count_ones() % 2) {
+        (0, 0) => (codeword, Corrected::Clean),
+        (0, _) => (codeword, Corrected::Bit(SECDED_BITS - 1)),
+        // A syndrome beyond the codeword means more than two flipped bits, reported as uncorrectable too
+        (position, 1) if position < SECDED_BITS => (codeword ^ 1 << (position - 1), Corrected::Bit(position - 1)),
+        _ => return Err(DoubleError),
+    };
+    let data = secded_data_indexes().enumerate().map(|(data_index, index)| ((codeword >> index & 1) as u32) << data_index).sum();
+    Ok((data, corrected))
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_hamming74_codewords() {
+        let codewords: Vec<u8> = (0..16).map(hamming74_encode).collect();
+        for (nibble, &codeword) in codewords.iter().enumerate() {
+            assert_eq!(hamming74_syndrome(codeword as u32), 0);
+            assert_eq!(codeword.count_ones() % 2, 0);
+            assert_eq!(hamming74_decode(codeword), Ok((nibble as u8, Corrected::Clean)));
+            // Minimal distance of the extended code is 4
+            for &other in &codewords[nibble + 1..] {
+                assert!((codeword ^ other).count_ones() >= 4);
+            }
+        }
+        assert_eq!(hamming74_encode(0xF3), hamming74_encode(0x3));
+    }
+
+    #[test]
+    fn test_hamming74_single_errors() {
+        for nibble in 0..16 {
+            let codeword = hamming74_encode(nibble);
+            for bit in 0..8 {
+                assert_eq!(hamming74_decode(codeword ^ 1 << bit), Ok((nibble, Corrected::Bit(bit))));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hamming74_double_errors() {
+        for nibble in 0..16 {
+            let codeword = hamming74_encode(nibble);
+            for bit1 in 0..8 {
+                for bit2 in bit1 + 1..8 {
+                    assert_eq!(hamming74_decode(codeword ^ 1 << bit1 ^ 1 << bit2), Err(DoubleError));
+                }
+            }
+        }
+        assert_eq!(DoubleError.to_string(), "double bit error detected, the codeword can't be corrected");
+    }
+
+    fn words() -> Vec<u32> {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        let random = (0..20).map(|_| rng.next_u32());
+        [0, 1, u32::MAX, 0x8000_0000].into_iter().chain(random).collect()
+    }
+
+    #[test]
+    fn test_secded_single_errors() {
+        for data in words() {
+            let codeword = secded_encode(data);
+            assert_eq!(codeword >> SECDED_BITS, 0);
+            assert_eq!(secded_decode(codeword), Ok((data, Corrected::Clean)));
+            for bit in 0..SECDED_BITS {
+                assert_eq!(secded_decode(codeword ^ 1 << bit), Ok((data, Corrected::Bit(bit))));
+            }
+            assert_eq!(secded_decode(codeword | 1 << 40), Ok((data, Corrected::Clean)));
+        }
+    }
+
+    #[test]
+    fn test_secded_double_errors() {
+        for data in words() {
+            let codeword = secded_encode(data);
+            for bit1 in 0..SECDED_BITS {
+                for bit2 in bit1 + 1..SECDED_BITS {
+                    assert_eq!(secded_decode(codeword ^ 1 << bit1 ^ 1 << bit2), Err(DoubleError));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! Non-cryptographic hashing built from XOR, multiplication and shifts:
+//! [*FNV-1a*](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function), avalanche finalizers,
+//! and the mixing steps of xxHash/wyhash-style hashes composed into a *Hasher*
+
+use std::hash::{BuildHasher, Hasher};
+
+/// Offset basis of 32-bit FNV
+pub const FNV_OFFSET_32: u32 = 0x811C_9DC5;
+
+/// Prime of 32-bit FNV
+pub const FNV_PRIME_32: u32 = 0x0100_0193;
+
+/// Offset basis of 64-bit FNV
+pub const FNV_OFFSET_64: u64 = 0xCBF2_9CE4_8422_2325;
+
+/// Prime of 64-bit FNV
+pub const FNV_PRIME_64: u64 = 0x0000_0100_0000_01B3;
+
+/// Returns the 32-bit FNV-1a hash of the data: every byte is XORed into the low bits and spread up by the prime multiplication
+/// # Arguments
+/// * `data` - data to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hash::fnv1a_32;
+/// assert_eq!(fnv1a_32(b"foobar"), 0xBF9C_F968);
+/// ```
+pub fn fnv1a_32(data: &[u8]) -> u32 {
+    data.iter().fold(FNV_OFFSET_32, |hash, &byte| (hash ^ byte as u32).wrapping_mul(FNV_PRIME_32))
+}
+
+/// Returns the 64-bit FNV-1a hash of the data
+/// # Arguments
+/// * `data` - data to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hash::fnv1a_64;
+/// assert_eq!(fnv1a_64(b"foobar"), 0x8594_4171_F739_67E8);
+/// ```
+pub fn fnv1a_64(data: &[u8]) -> u64 {
+    data.iter().fold(FNV_OFFSET_64, |hash, &byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME_64))
+}
+
+/// Returns the 32-bit finalizer of MurmurHash3. XOR with the high half moves high bits down, multiplication moves low bits up,
+/// so every input bit affects every output bit. It's a bijection and maps 0 to 0
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hash::fmix32;
+/// assert_eq!(fmix32(0), 0);
+/// assert_ne!(fmix32(1), fmix32(2));
+/// ```
+pub fn fmix32(number: u32) -> u32 {
+    let mut hash = number;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85EB_CA6B);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xC2B2_AE35);
+    hash ^ hash >> 16
+}
+
+/// Returns the 64-bit finalizer of MurmurHash3. It's a bijection and maps 0 to 0
+/// # Arguments
+/// * `number` - number to work with
+pub fn fmix64(number: u64) -> u64 {
+    let mut hash = number;
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xC4CE_B9FE_1A85_EC53);
+    hash ^ hash >> 33
+}
+
+/// Advances the state of [*SplitMix64*](https://prng.di.unimi.it/splitmix64.c) by the golden ratio constant
+/// and returns the mixed state. Consecutive states differ in few bits, the mixing makes the outputs look independent
+/// # Arguments
+/// * `state` - state to be advanced
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hash::splitmix64;
+/// let mut state = 0;
+/// assert_eq!(splitmix64(&mut state), 0xE220_A839_7B1D_CDAF);
+/// assert_eq!(state, 0x9E37_79B9_7F4A_7C15);
+/// ```
+pub fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut mixed = *state;
+    mixed = (mixed ^ mixed >> 30).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    mixed = (mixed ^ mixed >> 27).wrapping_mul(0x94D0_49BB_1331_11EB);
+    mixed ^ mixed >> 31
+}
+
+/// Primes of xxHash64
+pub const XXH_PRIME64: [u64; 3] = [0x9E37_79B1_85EB_CA87, 0xC2B2_AE3D_27D4_EB4F, 0x1656_67B1_9E37_79F9];
+
+/// Secret constants of wyhash
+pub const WY_SECRET: [u64; 3] = [0xA076_1D64_78BD_642F, 0xE703_7ED1_A0B4_28DB, 0x8EBC_6AF0_9C88_C6E3];
+
+/// Returns the number XORed with itself shifted right: moves the well-mixed high bits down into the low bits
+/// # Arguments
+/// * `number` - number to work with
+/// * `shift` - shift amount
+pub fn xor_shift_right(number: u64, shift: u32) -> u64 {
+    number ^ number >> shift
+}
+
+/// Returns the product of the number with the odd constant modulo 2⁶⁴: every bit affects only itself and higher bits,
+/// so multiplication is paired with *xor_shift_right* or a rotation
+/// # Arguments
+/// * `number` - number to work with
+/// * `constant` - multiplier
+pub fn multiply(number: u64, constant: u64) -> u64 {
+    number.wrapping_mul(constant)
+}
+
+/// Returns the rotated number multiplied by the constant: the rotation brings the high bits, which the previous
+/// multiplication mixed best, down to where the next multiplication spreads them
+/// # Arguments
+/// * `number` - number to work with
+/// * `rotation` - rotation to the left
+/// * `constant` - multiplier
+pub fn rotate_multiply(number: u64, rotation: u32, constant: u64) -> u64 {
+    number.rotate_left(rotation).wrapping_mul(constant)
+}
+
+/// Returns XOR of the low and the high halves of the 128-bit product (the "mum" of wyhash).
+/// The high half depends on all bits of both operands, which a 64-bit product doesn't give
+/// # Arguments
+/// * `a` - first operand
+/// * `b` - second operand
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hash::mum;
+/// assert_eq!(mum(1 << 63, 4), 2);
+/// assert_eq!(mum(3, 5), 15);
+/// ```
+pub fn mum(a: u64, b: u64) -> u64 {
+    let product = a as u128 * b as u128;
+    (product as u64) ^ (product >> 64) as u64
+}
+
+/// Returns the accumulator after one round of xxHash64: the input is multiplied in, then rotated and multiplied again
+/// # Arguments
+/// * `acc` - accumulator
+/// * `input` - 8 bytes of input
+pub fn xxh64_round(acc: u64, input: u64) -> u64 {
+    rotate_multiply(acc.wrapping_add(multiply(input, XXH_PRIME64[1])), 31, XXH_PRIME64[0])
+}
+
+/// Returns the final avalanche of xxHash64: alternating *xor_shift_right* and multiplications
+/// # Arguments
+/// * `hash` - hash to work with
+pub fn xxh64_avalanche(hash: u64) -> u64 {
+    let hash = multiply(xor_shift_right(hash, 33), XXH_PRIME64[1]);
+    let hash = multiply(xor_shift_right(hash, 29), XXH_PRIME64[2]);
+    xor_shift_right(hash, 32)
+}
+
+/// Small *Hasher* built from the mixing steps: every 8 bytes (little endian, the tail padded with zeros)
+/// are folded in by *mum*, and the length and *xxh64_avalanche* finish the hash.
+/// Not resistant to collision attacks, so don't use it for untrusted keys
+/// # Examples
+/// ```
+/// # use std::collections::HashMap;
+/// # use std::hash::Hasher;
+/// # use understanding_bitwise::hash::{BuildMixHasher, MixHasher};
+/// let mut hasher = MixHasher::with_seed(1);
+/// hasher.write(b"bits");
+/// assert_ne!(hasher.finish(), MixHasher::with_seed(2).finish());
+///
+/// let mut map = HashMap::with_hasher(BuildMixHasher::default());
+/// map.insert("key", 1);
+/// assert_eq!(map.get("key"), Some(&1));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MixHasher {
+    state: u64,
+    length: u64,
+}
+
+impl MixHasher {
+    /// Returns the hasher of no data
+    /// # Arguments
+    /// * `seed` - seed selecting the hash function
+    pub fn with_seed(seed: u64) -> Self {
+        MixHasher { state: seed ^ WY_SECRET[0], length: 0 }
+    }
+}
+
+impl Default for MixHasher {
+    fn default() -> Self {
+        Self::with_seed(0)
+    }
+}
+
+impl Hasher for MixHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.state = mum(self.state ^ u64::from_le_bytes(word), WY_SECRET[1]);
+        }
+        self.length = self.length.wrapping_add(bytes.len() as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        xxh64_avalanche(mum(self.state ^ self.length, WY_SECRET[2]))
+    }
+}
+
+/// Builder of *MixHasher* for hash maps
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct BuildMixHasher {
+    /// Seed of the built hashers
+    pub seed: u64,
+}
+
+impl BuildHasher for BuildMixHasher {
+    type Hasher = MixHasher;
+
+    fn build_hasher(&self) -> MixHasher {
+        MixHasher::with_seed(self.seed)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift64;
+
+    /// Checks the avalanche property: flipping any input bit flips every output bit in about half of the cases
+    fn general_test_avalanche(hash: fn(u64) -> u64, input_bits: u32, output_bits: u32) {
+        const SAMPLES: u32 = 2000;
+        let mut rng = Xorshift64::new(0x00C0_FFEE).unwrap();
+        let mut flips = vec![[0u32; 64]; input_bits as usize];
+        for _ in 0..SAMPLES {
+            let state = rng.next_u64();
+            let input = state & u64::MAX >> (u64::BITS - input_bits);
+            for (bit, counts) in flips.iter_mut().enumerate() {
+                let difference = hash(input) ^ hash(input ^ 1 << bit);
+                for (output, count) in counts.iter_mut().enumerate().take(output_bits as usize) {
+                    *count += (difference >> output & 1) as u32;
+                }
+            }
+        }
+        for (bit, counts) in flips.iter().enumerate() {
+            for (output, &count) in counts.iter().enumerate().take(output_bits as usize) {
+                assert!((SAMPLES * 2 / 5..=SAMPLES * 3 / 5).contains(&count), "input bit {bit}, output bit {output}: {count}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a_32(b""), FNV_OFFSET_32);
+        assert_eq!(fnv1a_32(b"a"), 0xE40C_292C);
+        assert_eq!(fnv1a_32(b"foobar"), 0xBF9C_F968);
+        assert_eq!(fnv1a_64(b""), FNV_OFFSET_64);
+        assert_eq!(fnv1a_64(b"a"), 0xAF63_DC4C_8601_EC8C);
+        assert_eq!(fnv1a_64(b"foobar"), 0x8594_4171_F739_67E8);
+    }
+
+    #[test]
+    fn test_fmix_bijection() {
+        assert_eq!(fmix32(0), 0);
+        assert_eq!(fmix64(0), 0);
+        let mut outputs: Vec<u32> = (0..1 << 16).map(fmix32).collect();
+        outputs.sort_unstable();
+        outputs.dedup();
+        assert_eq!(outputs.len(), 1 << 16);
+    }
+
+    #[test]
+    fn test_avalanche() {
+        general_test_avalanche(|number| fmix32(number as u32) as u64, 32, 32);
+        general_test_avalanche(fmix64, 64, 64);
+        general_test_avalanche(|mut state| splitmix64(&mut state), 64, 64);
+    }
+
+    #[test]
+    fn test_splitmix64() {
+        let mut state = 0;
+        let outputs: Vec<u64> = (0..3).map(|_| splitmix64(&mut state)).collect();
+        assert_eq!(outputs, [0xE220_A839_7B1D_CDAF, 0x6E78_9E6A_A1B9_65F4, 0x06C4_5D18_8009_454F]);
+    }
+
+    fn mix_hash(number: u64) -> u64 {
+        let mut hasher = MixHasher::default();
+        hasher.write_u64(number);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_mix_steps() {
+        assert_eq!(xor_shift_right(0x8000_0000_0000_0000, 63), 0x8000_0000_0000_0001);
+        assert_eq!(multiply(3, u64::MAX), u64::MAX - 2);
+        assert_eq!(rotate_multiply(1 << 63, 1, 5), 5);
+        assert_eq!(mum(u64::MAX, u64::MAX), 1 ^ (u64::MAX - 1));
+        // The round and the avalanche are bijections of the accumulator, distinct inputs give distinct outputs
+        for step in [|acc| xxh64_round(acc, 7), xxh64_avalanche] {
+            let mut outputs: Vec<u64> = (0..1 << 12).map(step).collect();
+            outputs.sort_unstable();
+            outputs.dedup();
+            assert_eq!(outputs.len(), 1 << 12);
+        }
+        general_test_avalanche(xxh64_avalanche, 64, 64);
+    }
+
+    #[test]
+    fn test_mix_hasher() {
+        general_test_avalanche(mix_hash, 64, 64);
+        // Output bits are balanced for sequential keys, counted by the crate's bit statistics
+        for half in [0, 32] {
+            let counts = crate::bit_position_counts((0..1 << 12).map(|number| (mix_hash(number) >> half) as u32));
+            assert!(counts.iter().all(|&count| (1843..=2253).contains(&count)), "{counts:?}");
+        }
+        let hash = |bytes: &[u8]| {
+            let mut hasher = MixHasher::default();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        assert_ne!(hash(b"a"), hash(b"a\0"));
+        assert_ne!(hash(b""), hash(b"\0"));
+        assert_ne!(hash(b"12345678"), hash(b"12345679"));
+        assert_eq!(BuildMixHasher { seed: 3 }.hash_one("key"), BuildMixHasher { seed: 3 }.hash_one("key"));
+        assert_ne!(BuildMixHasher { seed: 3 }.hash_one("key"), BuildMixHasher { seed: 4 }.hash_one("key"));
+    }
+}
@@ -0,0 +1,243 @@
+//! Hardware fast paths with runtime detection. On x86/x86_64 the functions use *popcnt*, *lzcnt*, BMI2 and PCLMULQDQ instructions
+//! when the CPU has them, otherwise they fall back to the portable methods of the crate
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86 as arch;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64 as arch;
+use crate::{binary_ones_count_sub_method, hob_thr, pdep_swar, pext_swar};
+use crate::clmul as portable;
+
+/// Implementation selected for an operation
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Path {
+    /// Dedicated CPU instruction
+    Hardware,
+    /// Portable bitwise method
+    Portable,
+}
+
+/// Helper macro. Evaluates to *Path::Hardware* if the CPU feature is detected at runtime
+macro_rules! detect {
+    ($feature:tt) => {{
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        let detected = std::is_x86_feature_detected!($feature);
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        let detected = false;
+        if detected { Path::Hardware } else { Path::Portable }
+    }};
+}
+
+/// Returns the path used by *binary_ones_count*
+pub fn binary_ones_count_path() -> Path {
+    detect!("popcnt")
+}
+
+/// Returns the path used by *hob*
+pub fn hob_path() -> Path {
+    detect!("lzcnt")
+}
+
+/// Returns the path used by *pext* and *pdep*
+pub fn pext_pdep_path() -> Path {
+    detect!("bmi2")
+}
+
+/// Returns the path used by *clmul* and *clmul64*
+pub fn clmul_path() -> Path {
+    detect!("pclmulqdq")
+}
+
+/// Returns the count of ones in binary representation of the number. Uses *popcnt* instruction if available
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::intrinsics::binary_ones_count;
+/// assert_eq!(binary_ones_count(0b101), 2);
+/// ```
+pub fn binary_ones_count(number: u32) -> u32 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if binary_ones_count_path() == Path::Hardware {
+        // SAFETY: the instruction is supported by the CPU
+        return unsafe { popcnt(number) };
+    }
+    binary_ones_count_sub_method(number)
+}
+
+/// Returns [*highest order bit*](https://commoncog.com/blog/highest-order-bit/) or None if number can't have hob.
+/// Uses *lzcnt* instruction if available
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::intrinsics::hob;
+/// assert_eq!(hob(0), None);
+/// assert_eq!(hob(0b100), Some(2));
+/// ```
+pub fn hob(number: u32) -> Option<u32> {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if hob_path() == Path::Hardware {
+        // SAFETY: the instruction is supported by the CPU
+        let leading_zeros = unsafe { lzcnt(number) };
+        return (u32::BITS - 1).checked_sub(leading_zeros);
+    }
+    hob_thr(number)
+}
+
+/// Returns the bits of the number selected by the mask, packed together into the lowest bits. Uses BMI2 *pext* instruction if available
+/// # Arguments
+/// * `number` - number to work with
+/// * `mask` - mask selecting the bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::intrinsics::pext;
+/// assert_eq!(pext(0b1011_0110, 0b1111_0000), 0b1011);
+/// ```
+pub fn pext(number: u32, mask: u32) -> u32 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if pext_pdep_path() == Path::Hardware {
+        // SAFETY: the instruction is supported by the CPU
+        return unsafe { bmi2_pext(number, mask) };
+    }
+    pext_swar(number, mask)
+}
+
+/// Returns the lowest bits of the number scattered to the positions of the mask bits. Uses BMI2 *pdep* instruction if available
+/// # Arguments
+/// * `number` - number to work with
+/// * `mask` - mask receiving the bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::intrinsics::pdep;
+/// assert_eq!(pdep(0b1011, 0b1111_0000), 0b1011_0000);
+/// ```
+pub fn pdep(number: u32, mask: u32) -> u32 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if pext_pdep_path() == Path::Hardware {
+        // SAFETY: the instruction is supported by the CPU
+        return unsafe { bmi2_pdep(number, mask) };
+    }
+    pdep_swar(number, mask)
+}
+
+/// Returns the carry-less product of the numbers. Uses PCLMULQDQ instruction if available
+/// # Arguments
+/// * `a` - first factor
+/// * `b` - second factor
+/// # Examples
+/// ```
+/// # use understanding_bitwise::intrinsics::clmul;
+/// assert_eq!(clmul(0b11, 0b11), 0b101);
+/// ```
+pub fn clmul(a: u32, b: u32) -> u64 {
+    clmul64(a as u64, b as u64) as u64
+}
+
+/// Returns the carry-less product of the 64-bit numbers. Uses PCLMULQDQ instruction if available
+/// # Arguments
+/// * `a` - first factor
+/// * `b` - second factor
+pub fn clmul64(a: u64, b: u64) -> u128 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if clmul_path() == Path::Hardware {
+        // SAFETY: the instruction is supported by the CPU
+        return unsafe { pclmulqdq(a, b) };
+    }
+    portable::clmul64(a, b)
+}
+
+/// Helper function. Performs *popcnt* instruction
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "popcnt")]
+unsafe fn popcnt(number: u32) -> u32 {
+    arch::_popcnt32(number as i32) as u32
+}
+
+/// Helper function. Performs *lzcnt* instruction
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "lzcnt")]
+unsafe fn lzcnt(number: u32) -> u32 {
+    arch::_lzcnt_u32(number)
+}
+
+/// Helper function. Performs BMI2 *pext* instruction
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "bmi2")]
+unsafe fn bmi2_pext(number: u32, mask: u32) -> u32 {
+    arch::_pext_u32(number, mask)
+}
+
+/// Helper function. Performs BMI2 *pdep* instruction
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "bmi2")]
+unsafe fn bmi2_pdep(number: u32, mask: u32) -> u32 {
+    arch::_pdep_u32(number, mask)
+}
+
+/// Helper function. Performs PCLMULQDQ instruction on the low 64-bit lanes
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "pclmulqdq,sse2")]
+unsafe fn pclmulqdq(a: u64, b: u64) -> u128 {
+    let product = arch::_mm_clmulepi64_si128(arch::_mm_set_epi64x(0, a as i64), arch::_mm_set_epi64x(0, b as i64), 0);
+    // The low lane is the low half of u128 on little-endian x86
+    std::mem::transmute::<arch::__m128i, u128>(product)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    /// Pseudo-random words for comparisons with the portable methods
+    fn words() -> impl Iterator<Item = u32> {
+        let mut rng = Xorshift32::new(0x9E37_79B9).unwrap();
+        std::iter::repeat_with(move || rng.next_u32()).take(10_000).chain([0, 1, u32::MAX, 1 << 31])
+    }
+
+    #[test]
+    fn test_binary_ones_count() {
+        for number in words() {
+            assert_eq!(binary_ones_count(number), crate::binary_ones_count(number));
+        }
+    }
+
+    #[test]
+    fn test_hob() {
+        for number in words() {
+            assert_eq!(hob(number), crate::hob(number));
+        }
+    }
+
+    #[test]
+    fn test_pext_pdep() {
+        let masks: Vec<u32> = words().take(100).collect();
+        for number in words().take(100) {
+            for &mask in &masks {
+                assert_eq!(pext(number, mask), crate::pext(number, mask));
+                assert_eq!(pdep(number, mask), crate::pdep(number, mask));
+            }
+        }
+    }
+
+    #[test]
+    fn test_clmul() {
+        let numbers: Vec<u32> = words().take(300).collect();
+        for pair in numbers.windows(2) {
+            assert_eq!(clmul(pair[0], pair[1]), portable::clmul(pair[0], pair[1]));
+            let (a, b) = ((pair[0] as u64) << 32 | pair[1] as u64, (pair[1] as u64) << 32 | pair[0] as u64);
+            assert_eq!(clmul64(a, b), portable::clmul64(a, b));
+        }
+    }
+
+    #[test]
+    fn test_paths() {
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        assert_eq!(binary_ones_count_path(), Path::Portable);
+        // Detection is stable between calls
+        assert_eq!(hob_path(), hob_path());
+        assert_eq!(pext_pdep_path(), pext_pdep_path());
+        assert_eq!(clmul_path(), clmul_path());
+    }
+}
@@ -0,0 +1,198 @@
+//! Runtime described bit layouts over byte buffers (register/packet dissector)
+
+use std::fmt;
+pub use crate::endian::Endianness;
+use crate::endian::{read_bits, write_bits};
+
+/// Description of a named field of the layout
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FieldSpec {
+    /// Name of the field, unique in the layout
+    pub name: String,
+    /// Bit offset in the buffer
+    pub offset: usize,
+    /// Bits count, from 1 to 64
+    pub width: u32,
+    /// Bit order of the field
+    pub endianness: Endianness,
+}
+
+impl FieldSpec {
+    /// Returns the field spec
+    /// # Arguments
+    /// * `name` - name of the field
+    /// * `offset` - bit offset in the buffer
+    /// * `width` - bits count
+    /// * `endianness` - bit order
+    pub fn new(name: impl Into<String>, offset: usize, width: u32, endianness: Endianness) -> Self {
+        FieldSpec { name: name.into(), offset, width, endianness }
+    }
+
+    /// Returns the bit offset following the field or None if it overflows usize
+    pub fn end(&self) -> Option<usize> {
+        self.offset.checked_add(self.width as usize)
+    }
+}
+
+/// Set of fields describing a register or a packet
+/// # Examples
+/// ```
+/// # use understanding_bitwise::layout::{Endianness, FieldSpec, Layout};
+/// let layout = Layout::new()
+///     .with_field(FieldSpec::new("version", 0, 4, Endianness::Big)).unwrap()
+///     .with_field(FieldSpec::new("ihl", 4, 4, Endianness::Big)).unwrap()
+///     .with_field(FieldSpec::new("length", 16, 16, Endianness::Big)).unwrap();
+/// let decoded = layout.decode(&[0x45, 0x00, 0x00, 0x54]).unwrap();
+/// assert_eq!(decoded.get("ihl"), Some(5));
+/// assert_eq!(decoded.get("length"), Some(84));
+/// assert_eq!(decoded.to_string(), "version [0..4) = 4 (0100)\nihl [4..8) = 5 (0101)\nlength [16..32) = 84 (0000000001010100)\n");
+///
+/// let mut bytes = [0; 4];
+/// layout.encode(&[("version", 4), ("ihl", 6)], &mut bytes).unwrap();
+/// assert_eq!(bytes, [0x46, 0, 0, 0]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Layout {
+    fields: Vec<FieldSpec>,
+}
+
+impl Layout {
+    /// Returns the layout without fields
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the layout with the field added or None if the width isn't in 1..=64, the end of the field overflows
+    /// usize or the name is already taken. Fields may overlap (e.g. whole register and its parts)
+    /// # Arguments
+    /// * `spec` - field to be added
+    pub fn with_field(mut self, spec: FieldSpec) -> Option<Self> {
+        if ! (1..=u64::BITS).contains(&spec.width) || spec.end().is_none() || self.field(&spec.name).is_some() {
+            return None;
+        }
+        self.fields.push(spec);
+        Some(self)
+    }
+
+    /// Returns the fields in the order they were added
+    pub fn fields(&self) -> &[FieldSpec] {
+        &self.fields
+    }
+
+    /// Returns the field by the name
+    /// # Arguments
+    /// * `name` - name of the field
+    pub fn field(&self, name: &str) -> Option<&FieldSpec> {
+        self.fields.iter().find(|spec| spec.name == name)
+    }
+
+    /// Returns the count of bytes needed to hold all the fields
+    pub fn byte_len(&self) -> usize {
+        self.fields.iter().map(|spec| spec.end().expect("ends are checked when fields are added")).max().unwrap_or(0).div_ceil(8)
+    }
+
+    /// Returns the values of all fields or None if the buffer is too short
+    /// # Arguments
+    /// * `bytes` - buffer to work with
+    pub fn decode(&self, bytes: &[u8]) -> Option<Decoded<'_>> {
+        let values = self.fields.iter()
+            .map(|spec| read_bits(bytes, spec.offset, spec.width, spec.endianness))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Decoded { layout: self, values })
+    }
+
+    /// Writes the values of the named fields, other bits are left untouched.
+    /// Returns None (and leaves the buffer untouched) if some name is unknown, some value doesn't fit into its field or the buffer is too short
+    /// # Arguments
+    /// * `values` - pairs of field name and value
+    /// * `bytes` - buffer to work with
+    pub fn encode(&self, values: &[(&str, u64)], bytes: &mut [u8]) -> Option<()> {
+        let mut encoded = bytes.to_vec();
+        for &(name, value) in values {
+            let spec = self.field(name)?;
+            write_bits(&mut encoded, spec.offset, spec.width, spec.endianness, value)?;
+        }
+        bytes.copy_from_slice(&encoded);
+        Some(())
+    }
+}
+
+/// Values of the layout fields. *Display* dumps every field with its bit range, value and binary value
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Decoded<'a> {
+    layout: &'a Layout,
+    values: Vec<u64>,
+}
+
+impl Decoded<'_> {
+    /// Returns the value of the field by the name
+    /// # Arguments
+    /// * `name` - name of the field
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.iter().find(|(spec, _)| spec.name == name).map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the fields and their values
+    pub fn iter(&self) -> impl Iterator<Item = (&FieldSpec, u64)> + '_ {
+        self.layout.fields.iter().zip(self.values.iter().copied())
+    }
+}
+
+impl fmt::Display for Decoded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (spec, value) in self.iter() {
+            writeln!(f, "{} [{}..{}) = {} ({:0width$b})", spec.name, spec.offset, spec.end().expect("ends are checked when fields are added"), value, value, width = spec.width as usize)?;
+        }
+        Ok(())
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        assert!(Layout::new().with_field(FieldSpec::new("a", 0, 0, Endianness::Big)).is_none());
+        assert!(Layout::new().with_field(FieldSpec::new("a", 0, 65, Endianness::Big)).is_none());
+        assert!(Layout::new().with_field(FieldSpec::new("a", usize::MAX - 7, 8, Endianness::Big)).is_none());
+        assert_eq!(FieldSpec::new("a", usize::MAX - 8, 8, Endianness::Big).end(), Some(usize::MAX));
+        let layout = Layout::new()
+            .with_field(FieldSpec::new("low", 0, 12, Endianness::Little)).unwrap()
+            .with_field(FieldSpec::new("high", 12, 20, Endianness::Little)).unwrap()
+            .with_field(FieldSpec::new("all", 0, 32, Endianness::Little)).unwrap();
+        assert!(layout.clone().with_field(FieldSpec::new("low", 40, 1, Endianness::Big)).is_none());
+        assert_eq!(layout.byte_len(), 4);
+
+        let bytes = 0x12345678u32.to_le_bytes();
+        let decoded = layout.decode(&bytes).unwrap();
+        assert_eq!(decoded.get("low"), Some(0x678));
+        assert_eq!(decoded.get("high"), Some(0x12345));
+        assert_eq!(decoded.get("all"), Some(0x12345678));
+        assert_eq!(decoded.get("none"), None);
+        assert_eq!(layout.decode(&bytes[..3]), None);
+    }
+
+    #[test]
+    fn test_encode() {
+        let layout = Layout::new()
+            .with_field(FieldSpec::new("a", 0, 3, Endianness::Big)).unwrap()
+            .with_field(FieldSpec::new("b", 3, 13, Endianness::Big)).unwrap();
+        let mut bytes = [0u8; 2];
+        layout.encode(&[("a", 0b101), ("b", 0x1FFF)], &mut bytes).unwrap();
+        assert_eq!(bytes, [0b1011_1111, 0xFF]);
+        assert_eq!(layout.encode(&[("a", 0), ("b", 0x2000)], &mut bytes), None);
+        assert_eq!(layout.encode(&[("a", 0), ("c", 0)], &mut bytes), None);
+        assert_eq!(bytes, [0b1011_1111, 0xFF]);
+    }
+
+    #[test]
+    fn test_display() {
+        let layout = Layout::new()
+            .with_field(FieldSpec::new("flag", 0, 1, Endianness::Big)).unwrap()
+            .with_field(FieldSpec::new("value", 1, 7, Endianness::Big)).unwrap();
+        let decoded = layout.decode(&[0b1000_0011]).unwrap();
+        assert_eq!(decoded.to_string(), "flag [0..1) = 1 (1)\nvalue [1..8) = 3 (0000011)\n");
+    }
+}
@@ -0,0 +1,245 @@
+//! [*Linear-feedback shift registers*](https://en.wikipedia.org/wiki/Linear-feedback_shift_register) shifting right,
+//! the output is the bit 0. Bit *k - 1* of the taps stands for the term xᵏ of the feedback polynomial,
+//! the constant term is implied, so x¹⁶ + x¹⁴ + x¹³ + x¹¹ + 1 is 0xB400.
+//! *ShiftRegister* shifts left and writes the same polynomial the CRC way, bit *k* for xᵏ with the highest term
+//! implied, as 0x6801: the taps here are those shifted right by one with the top bit set, see *to_shift_register_taps*
+
+/// Wiring of the feedback
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LfsrConfig {
+    /// The output bit is XORed into every tapped bit while shifting (one XOR gate per tap, all in parallel)
+    Galois,
+    /// XOR of the tapped bits is shifted in at the top (a chain of XOR gates)
+    Fibonacci,
+}
+
+/// Widest register checked by *is_maximal_length*
+pub const MAX_CHECKED_WIDTH: u32 = 24;
+
+/// Linear-feedback shift register of the width from 1 to 32 bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::lfsr::{Lfsr, LfsrConfig};
+/// let mut lfsr = Lfsr::new(16, 0xB400, 0xACE1, LfsrConfig::Galois).unwrap();
+/// assert!(lfsr.next_bit());
+/// assert_eq!(lfsr.state(), 0xE270);
+/// let mut lfsr = Lfsr::new(16, 0xB400, 0xACE1, LfsrConfig::Fibonacci).unwrap();
+/// assert!(lfsr.next_bit());
+/// assert_eq!(lfsr.state(), 0x5670);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Lfsr {
+    width: u32,
+    taps: u32,
+    state: u32,
+    config: LfsrConfig,
+    /// Taps as the mask of the state bits, the term xᵏ reads the bit *width - k*
+    fibonacci_mask: u32,
+}
+
+impl Lfsr {
+    /// Returns the register or None if the width isn't in 1..=32, the taps don't fit into the width
+    /// or miss the term xʷⁱᵈᵗʰ, or the seed is 0 or doesn't fit into the width
+    /// # Arguments
+    /// * `width` - bits count of the register
+    /// * `taps` - feedback polynomial
+    /// * `seed` - initial state
+    /// * `config` - wiring of the feedback
+    pub fn new(width: u32, taps: u32, seed: u32, config: LfsrConfig) -> Option<Self> {
+        if !(1..=u32::BITS).contains(&width) {
+            return None;
+        }
+        let mask = u32::MAX >> (u32::BITS - width);
+        if taps & ! mask != 0 || taps >> (width - 1) != 1 || seed == 0 || seed & ! mask != 0 {
+            return None;
+        }
+        let fibonacci_mask = taps.reverse_bits() >> (u32::BITS - width);
+        Some(Lfsr { width, taps, state: seed, config, fibonacci_mask })
+    }
+
+    /// Returns the bits count of the register
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the feedback polynomial
+    pub fn taps(&self) -> u32 {
+        self.taps
+    }
+
+    /// Returns the current state
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+
+    /// Returns the wiring of the feedback
+    pub fn config(&self) -> LfsrConfig {
+        self.config
+    }
+
+    /// Shifts the register and returns the bit shifted out
+    pub fn next_bit(&mut self) -> bool {
+        let output = self.state & 1;
+        self.state = match self.config {
+            LfsrConfig::Galois => self.state >> 1 ^ self.taps & output.wrapping_neg(),
+            LfsrConfig::Fibonacci => {
+                let feedback = (self.state & self.fibonacci_mask).count_ones() & 1;
+                self.state >> 1 | feedback << (self.width - 1)
+            }
+        };
+        output == 1
+    }
+
+    /// Returns the next 32 output bits, the first one in the bit 0
+    pub fn next_word(&mut self) -> u32 {
+        (0..u32::BITS).fold(0, |word, index| word | (self.next_bit() as u32) << index)
+    }
+
+    /// Returns the count of shifts until the state repeats. Takes up to 2ʷⁱᵈᵗʰ - 1 shifts
+    pub fn period(&self) -> u64 {
+        let mut lfsr = *self;
+        let mut period = 1;
+        lfsr.next_bit();
+        while lfsr.state != self.state {
+            lfsr.next_bit();
+            period += 1;
+        }
+        period
+    }
+}
+
+/// Returns true if the register with the taps walks through all 2ʷⁱᵈᵗʰ - 1 nonzero states (the polynomial is primitive),
+/// or None if the taps are invalid (see *Lfsr::new*) or the width is above *MAX_CHECKED_WIDTH*
+/// # Arguments
+/// * `width` - bits count of the register
+/// * `taps` - feedback polynomial
+/// # Examples
+/// ```
+/// # use understanding_bitwise::lfsr::is_maximal_length;
+/// assert_eq!(is_maximal_length(16, 0xB400), Some(true));
+/// // x⁴ + x² + 1 = (x² + x + 1)²
+/// assert_eq!(is_maximal_length(4, 0b1010), Some(false));
+/// ```
+pub fn is_maximal_length(width: u32, taps: u32) -> Option<bool> {
+    if width > MAX_CHECKED_WIDTH {
+        return None;
+    }
+    let lfsr = Lfsr::new(width, taps, 1, LfsrConfig::Galois)?;
+    Some(lfsr.period() == (1 << width) - 1)
+}
+
+/// Returns the same polynomial in the convention of *ShiftRegister* (bit *k* stands for xᵏ, the term xʷⁱᵈᵗʰ is
+/// implied) or None if the taps are invalid (see *Lfsr::new*)
+/// # Arguments
+/// * `width` - bits count of the register
+/// * `taps` - feedback polynomial
+/// # Examples
+/// ```
+/// # use understanding_bitwise::lfsr::to_shift_register_taps;
+/// assert_eq!(to_shift_register_taps(16, 0xB400), Some(0x6801));
+/// ```
+pub fn to_shift_register_taps(width: u32, taps: u32) -> Option<u32> {
+    Lfsr::new(width, taps, 1, LfsrConfig::Galois)?;
+    Some((taps << 1 | 1) & u32::MAX >> (u32::BITS - width))
+}
+
+/// Returns the taps of the polynomial written in the convention of *ShiftRegister* or None if the width isn't
+/// in 1..=32, the taps don't fit into the width or miss the constant term, which is implied here
+/// # Arguments
+/// * `width` - bits count of the register
+/// * `taps` - polynomial without the term xʷⁱᵈᵗʰ
+/// # Examples
+/// ```
+/// # use understanding_bitwise::lfsr::from_shift_register_taps;
+/// assert_eq!(from_shift_register_taps(16, 0x6801), Some(0xB400));
+/// // CRC-16/ARC polynomial x¹⁶ + x¹⁵ + x² + 1
+/// assert_eq!(from_shift_register_taps(16, 0x8005), Some(0xC002));
+/// assert_eq!(from_shift_register_taps(16, 0x6800), None);
+/// ```
+pub fn from_shift_register_taps(width: u32, taps: u32) -> Option<u32> {
+    if !(1..=u32::BITS).contains(&width) || taps & !(u32::MAX >> (u32::BITS - width)) != 0 || taps & 1 == 0 {
+        return None;
+    }
+    Some(taps >> 1 | 1 << (width - 1))
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shift_register::ShiftRegister;
+
+    #[test]
+    fn test_new() {
+        assert!(Lfsr::new(0, 0, 1, LfsrConfig::Galois).is_none());
+        assert!(Lfsr::new(33, 1, 1, LfsrConfig::Galois).is_none());
+        assert!(Lfsr::new(4, 0b0100, 1, LfsrConfig::Galois).is_none());
+        assert!(Lfsr::new(4, 0b1_1000, 1, LfsrConfig::Galois).is_none());
+        assert!(Lfsr::new(4, 0b1100, 0, LfsrConfig::Fibonacci).is_none());
+        assert!(Lfsr::new(4, 0b1100, 0b1_0000, LfsrConfig::Fibonacci).is_none());
+        let lfsr = Lfsr::new(32, 0x8020_0003, 1, LfsrConfig::Fibonacci).unwrap();
+        assert_eq!((lfsr.width(), lfsr.taps(), lfsr.state(), lfsr.config()), (32, 0x8020_0003, 1, LfsrConfig::Fibonacci));
+    }
+
+    #[test]
+    fn test_maximal_length_counts() {
+        // Count of primitive polynomials of degree n is φ(2ⁿ - 1) / n
+        for (width, primitive) in [(2, 1), (3, 2), (4, 2), (5, 6), (6, 6), (7, 18), (8, 16)] {
+            let count = (1 << (width - 1)..1 << width).filter(|&taps| is_maximal_length(width, taps) == Some(true)).count();
+            assert_eq!(count, primitive, "width {width}");
+        }
+        assert_eq!(is_maximal_length(24, 0xE1_0000), Some(true));
+        assert_eq!(is_maximal_length(25, 0x120_0000), None);
+        assert_eq!(is_maximal_length(4, 0), None);
+    }
+
+    #[test]
+    fn test_configs_have_same_period() {
+        for taps in 0b1000..0b1_0000 {
+            let galois = Lfsr::new(4, taps, 1, LfsrConfig::Galois).unwrap();
+            let fibonacci = Lfsr::new(4, taps, 1, LfsrConfig::Fibonacci).unwrap();
+            assert_eq!(galois.period(), fibonacci.period(), "taps {taps:#b}");
+        }
+        assert_eq!(Lfsr::new(16, 0xB400, 0xACE1, LfsrConfig::Fibonacci).unwrap().period(), 0xFFFF);
+    }
+
+    #[test]
+    fn test_shift_register_taps() {
+        for width in 1..=8 {
+            for taps in 1 << (width - 1)..1 << width {
+                let converted = to_shift_register_taps(width, taps).unwrap();
+                assert_eq!(from_shift_register_taps(width, converted), Some(taps));
+                // Both registers step by the same polynomial, so they share the period
+                let mut register = ShiftRegister::new(width, converted).unwrap();
+                register.load(1);
+                let mut period = 1;
+                register.clock(false);
+                while register.state() != 1 && period < 1 << width {
+                    register.clock(false);
+                    period += 1;
+                }
+                assert_eq!(period == (1 << width) - 1, is_maximal_length(width, taps).unwrap(), "taps {taps:#b}");
+            }
+        }
+        assert_eq!(to_shift_register_taps(32, 0x8020_0003), Some(0x0040_0007));
+        assert_eq!(from_shift_register_taps(32, 0x0040_0007), Some(0x8020_0003));
+        assert_eq!(to_shift_register_taps(4, 0b0100), None);
+        assert_eq!(from_shift_register_taps(4, 0b1_0001), None);
+    }
+
+    #[test]
+    fn test_next_word() {
+        for config in [LfsrConfig::Galois, LfsrConfig::Fibonacci] {
+            let mut lfsr = Lfsr::new(16, 0xB400, 0xACE1, config).unwrap();
+            let mut copy = lfsr;
+            let word = lfsr.next_word();
+            for index in 0..32 {
+                assert_eq!(copy.next_bit(), word >> index & 1 == 1);
+            }
+            assert_eq!(copy, lfsr);
+        }
+        // Fibonacci output of a maximal register repeats after the period, the first 16 bits are the seed
+        let mut lfsr = Lfsr::new(16, 0xB400, 0xACE1, LfsrConfig::Fibonacci).unwrap();
+        assert_eq!(lfsr.next_word() & 0xFFFF, 0xACE1);
+    }
+}
@@ -1,13 +1,169 @@
 #![allow(clippy::precedence)]
 
+use std::error::Error;
+use std::fmt;
 use std::io::Write;
-use std::collections::LinkedList;
-use std::ops::{Shl, Shr, BitXor};
+use std::ops::{Shl, Shr, BitAnd, BitXor, Not, Range};
 use std::mem;
 
-/// Unit. Used when something is overflowed. Meant to be used as *E* parameter of *Err* type inside the *Result* type
-#[derive(PartialEq, Debug)]
-pub struct Overflow;
+pub mod atomic;
+pub mod bit_matrix;
+pub mod bit_string;
+pub mod bitboard;
+pub mod bitfield;
+pub mod bitslice;
+pub mod boolean_function;
+pub mod checksum;
+pub mod cipher;
+pub mod clmul;
+#[cfg(feature = "bitvec")]
+pub mod bitvec_interop;
+pub mod compare;
+pub mod constant_time;
+pub mod crc;
+pub mod date;
+pub mod diagram;
+pub mod editor;
+pub mod endian;
+pub mod evaluator;
+pub mod explained;
+pub mod flag_enum;
+pub mod flags;
+pub mod float;
+pub mod geohash;
+pub mod gf256;
+pub mod hamming;
+pub mod hash;
+#[cfg(feature = "intrinsics")]
+pub mod intrinsics;
+pub mod layout;
+pub mod lfsr;
+pub mod life;
+pub mod morton;
+pub mod network;
+pub mod packed_id;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod parity;
+pub mod permissions;
+pub mod permutation;
+pub mod pixel;
+pub mod prng;
+pub mod protocols;
+pub mod quiz;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod register;
+pub mod rle;
+pub mod shift;
+pub mod shift_register;
+pub mod slice;
+pub mod transpose;
+pub mod unicode;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod wide;
+pub mod xor_basis;
+pub mod xor_query;
+#[cfg(feature = "serde")]
+mod serialization;
+
+/// Used when something is overflowed. Meant to be used as *E* parameter of *Err* type inside the *Result* type
+/// # Examples
+/// ```
+/// # use understanding_bitwise::power_of_two;
+/// let error = power_of_two(45).unwrap_err();
+/// assert_eq!(error.to_string(), "2^45 overflows, the maximum representable power is 31");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Overflow {
+    /// Requested power
+    pub power: u32,
+    /// Maximum power that can be represented
+    pub max_power: u32,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "2^{} overflows, the maximum representable power is {}", self.power, self.max_power)
+    }
+}
+
+impl Error for Overflow {}
+
+/// Error of an indexed operation. Carries the operation name, the offending index and the range of valid indexes
+/// # Examples
+/// ```
+/// # use understanding_bitwise::try_set_bit;
+/// let error = try_set_bit(0, 45).unwrap_err();
+/// assert_eq!(error.index(), 45);
+/// assert_eq!(error.to_string(), "set_bit: index 45 is out of range 0..32");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BitError {
+    operation: &'static str,
+    index: u32,
+    valid: Range<u32>,
+    step: Option<usize>,
+}
+
+impl BitError {
+    /// Returns the error of the index out of the valid range
+    /// # Arguments
+    /// * `operation` - name of the failed operation
+    /// * `index` - offending index
+    /// * `valid` - range of valid indexes
+    pub fn new(operation: &'static str, index: u32, valid: Range<u32>) -> Self {
+        BitError { operation, index, valid, step: None }
+    }
+
+    /// Returns the error of the index out of the range of the word bits
+    /// # Arguments
+    /// * `operation` - name of the failed operation
+    /// * `index` - offending index
+    pub fn index_out_of_range(operation: &'static str, index: u32) -> Self {
+        Self::new(operation, index, 0..u32::BITS)
+    }
+
+    /// Returns the error marked as raised by the step of a multi-step edit
+    /// # Arguments
+    /// * `step` - index of the failed step
+    pub fn with_step(mut self, step: usize) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    /// Returns the index of the failed step of a multi-step edit (see *BitEditor*)
+    pub fn step(&self) -> Option<usize> {
+        self.step
+    }
+
+    /// Returns the name of the failed operation
+    pub fn operation(&self) -> &'static str {
+        self.operation
+    }
+
+    /// Returns the offending index
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the range of valid indexes
+    pub fn valid_range(&self) -> Range<u32> {
+        self.valid.clone()
+    }
+}
+
+impl fmt::Display for BitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(step) = self.step {
+            write!(f, "step {step}: ")?;
+        }
+        write!(f, "{}: index {} is out of range {}..{}", self.operation, self.index, self.valid.start, self.valid.end)
+    }
+}
+
+impl Error for BitError {}
 
 /// Returns the result of raising two to a power or error if resulting value is not in available integer range
 /// # Arguments
@@ -16,13 +172,13 @@ pub struct Overflow;
 /// ```
 /// # use understanding_bitwise::{Overflow, power_of_two};
 /// assert_eq!(power_of_two(3), Ok(8));
-/// assert_eq!(power_of_two(45), Err(Overflow));
+/// assert_eq!(power_of_two(45), Err(Overflow { power: 45, max_power: 31 }));
 /// ```
 pub fn power_of_two(power: u32) -> Result<u32, Overflow> {
     if power < u32::BITS {
         Ok(1 << power)
     } else {
-        Err(Overflow)
+        Err(Overflow { power, max_power: u32::BITS - 1 })
     }
 }
 
@@ -58,13 +214,100 @@ where
 /// ```
 pub fn write_binary_representation<W: Write>(number: u32, bw: &mut W) {
     // ASCII/UTF-8 code of char 0. The next number is code of char 1.
-    const ZERO_CHAR_NUM: u32 = 48;
-    let mut binary_number_list = LinkedList::<u8>::new();
-    let write = |number| {
-        binary_number_list.push_front(u8::try_from(ZERO_CHAR_NUM + (number & 1)).unwrap());
+    const ZERO_CHAR_NUM: u8 = b'0';
+    // Digits are produced from the lowest bit, so the buffer is filled from the end
+    let mut buffer = [0u8; u32::BITS as usize];
+    let mut start = buffer.len();
+    process_binary_until_hob(number, |number| {
+        start -= 1;
+        buffer[start] = ZERO_CHAR_NUM + (number & 1) as u8;
+    });
+    bw.write_all(&buffer[start..]).unwrap();
+}
+
+/// Returns the bits of the number as booleans. Element *i* is the bit *i*
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::to_bool_array;
+/// let bits = to_bool_array(0b101);
+/// assert_eq!(bits[..4], [true, false, true, false]);
+/// ```
+pub fn to_bool_array(number: u32) -> [bool; u32::BITS as usize] {
+    std::array::from_fn(|index| number >> index & 1 == 1)
+}
+
+/// Returns the bits of the number as booleans up to the hob (at least one element). Element *i* is the bit *i*
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::to_bool_vec;
+/// assert_eq!(to_bool_vec(0b110), vec![false, true, true]);
+/// assert_eq!(to_bool_vec(0), vec![false]);
+/// ```
+pub fn to_bool_vec(number: u32) -> Vec<bool> {
+    let mut bits = Vec::with_capacity(u32::BITS as usize);
+    process_binary_until_hob(number, |number| bits.push(number & 1 == 1));
+    bits
+}
+
+/// Returns the number built from the booleans. Element *i* is the bit *i*
+/// # Arguments
+/// * `bits` - bits of the number
+/// # Examples
+/// ```
+/// # use understanding_bitwise::{from_bool_array, to_bool_array};
+/// assert_eq!(from_bool_array(to_bool_array(0xDEAD_BEEF)), 0xDEAD_BEEF);
+/// ```
+pub fn from_bool_array(bits: [bool; u32::BITS as usize]) -> u32 {
+    from_bits(bits).unwrap()
+}
+
+/// Returns the number built from the booleans or error if there are more than 32 of them. Element *i* is the bit *i*
+/// # Arguments
+/// * `bits` - bits of the number
+/// # Examples
+/// ```
+/// # use understanding_bitwise::{from_bits, Overflow};
+/// assert_eq!(from_bits([true, false, true]), Ok(0b101));
+/// assert_eq!(from_bits(vec![false; 33]), Err(Overflow { power: 32, max_power: 31 }));
+/// ```
+pub fn from_bits(bits: impl IntoIterator<Item = bool>) -> Result<u32, Overflow> {
+    bits.into_iter().enumerate().try_fold(0, |number, (index, bit)| {
+        let power = u32::try_from(index).unwrap_or(u32::MAX);
+        Ok(number | (bit as u32) * power_of_two(power)?)
+    })
+}
+
+/// Returns the number written in binary (*0b* prefix), hexadecimal (*0x* prefix) or decimal,
+/// or None if the text isn't such a number or doesn't fit into u32. Underscores between digits are ignored
+/// # Arguments
+/// * `text` - text to be parsed
+/// # Examples
+/// ```
+/// # use understanding_bitwise::parse_number;
+/// assert_eq!(parse_number("0b1010_0101"), Some(0xA5));
+/// assert_eq!(parse_number("0xFF"), Some(255));
+/// assert_eq!(parse_number("42"), Some(42));
+/// assert_eq!(parse_number("0x1_0000_0000"), None);
+/// ```
+pub fn parse_number(text: &str) -> Option<u32> {
+    let (digits, radix) = match text.get(..2) {
+        Some("0b" | "0B") => (&text[2..], 2),
+        Some("0x" | "0X") => (&text[2..], 16),
+        _ => (text, 10),
     };
-    process_binary_until_hob(number, write);
-    bw.write_all(binary_number_list.into_iter().collect::<Vec<u8>>().as_slice()).unwrap();
+    if digits.starts_with('_') || digits.ends_with('_') {
+        return None;
+    }
+    let digits = digits.replace('_', "");
+    // from_str_radix accepts a sign
+    if digits.starts_with('+') {
+        return None;
+    }
+    u32::from_str_radix(&digits, radix).ok()
 }
 
 /// Returns the count of ones in binary representation of the number
@@ -108,6 +351,26 @@ pub fn binary_ones_count_sub_method(number: u32) -> u32 {
     }
 }
 
+/// Returns the count of ones in binary representation of the number.
+/// Uses SWAR (SIMD within a register) method: counts of neighbouring bit groups are added in parallel
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::binary_ones_count_swar;
+/// assert_eq!(binary_ones_count_swar(0b101), 2);
+/// ```
+pub fn binary_ones_count_swar(number: u32) -> u32 {
+    // Counts of 2 bit groups: a pair holding "ab" becomes a + b
+    let number = number - (number >> 1 & 0x5555_5555);
+    // Counts of 4 bit groups
+    let number = (number & 0x3333_3333) + (number >> 2 & 0x3333_3333);
+    // Counts of bytes. Sums fit into 4 bits, so the mask can be applied after the addition
+    let number = number + (number >> 4) & 0x0F0F_0F0F;
+    // Sum of all bytes gathers in the highest byte
+    number.wrapping_mul(0x0101_0101) >> 24
+}
+
 /// Returns true if number can't have hob
 /// # Arguments
 /// * `number` - number to work with
@@ -180,6 +443,209 @@ pub fn hob_comp_pot(number: u32) -> Option<u32> {
     None
 }
 
+/// Returns [*highest order bit*](https://commoncog.com/blog/highest-order-bit/) or None if number can't have hob. Uses binary search method:
+/// the range of candidate bits is halved by testing whether its upper half has any ones (top 16, then 8, 4, 2 and 1 bits)
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hob_binary_search;
+/// assert_eq!(hob_binary_search(0), None);
+/// assert_eq!(hob_binary_search(0b100), Some(2));
+/// ```
+pub fn hob_binary_search(number: u32) -> Option<u32> {
+    if no_hob(number) {
+        return None;
+    }
+
+    let mut number = number;
+    let mut index = 0;
+    let mut half = u32::BITS / 2;
+    while half > 0 {
+        if number >> half != 0 {
+            number >>= half;
+            index += half;
+        }
+        half /= 2;
+    }
+    Some(index)
+}
+
+/// Table of the ones counts of all bytes. Generated at compile time
+const BYTE_ONES_COUNT: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut byte = 1;
+    while byte < 256 {
+        // The byte has one more one than the byte shifted right if its lowest bit is set
+        table[byte] = table[byte >> 1] + (byte & 1) as u8;
+        byte += 1;
+    }
+    table
+};
+
+/// Table of the hobs of all bytes. The entry of byte 0 is unused. Generated at compile time
+const BYTE_HOB: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut byte = 2;
+    while byte < 256 {
+        table[byte] = table[byte >> 1] + 1;
+        byte += 1;
+    }
+    table
+};
+
+/// Returns the count of ones in binary representation of the number. Uses lookup table method
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::binary_ones_count_lut;
+/// assert_eq!(binary_ones_count_lut(0b101), 2);
+/// ```
+pub fn binary_ones_count_lut(number: u32) -> u32 {
+    number.to_le_bytes().iter().map(|&byte| BYTE_ONES_COUNT[byte as usize] as u32).sum()
+}
+
+/// Returns [*highest order bit*](https://commoncog.com/blog/highest-order-bit/) or None if number can't have hob. Uses lookup table method.
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hob_lut;
+/// assert_eq!(hob_lut(0), None);
+/// assert_eq!(hob_lut(0b100), Some(2));
+/// ```
+pub fn hob_lut(number: u32) -> Option<u32> {
+    if no_hob(number) {
+        return None;
+    }
+
+    // Index of the highest non-zero byte
+    let byte_index = number.to_le_bytes().iter().rposition(|&byte| byte != 0)?;
+    let byte = (number >> byte_index * 8) as u8;
+    Some(byte_index as u32 * 8 + BYTE_HOB[byte as usize] as u32)
+}
+
+/// Returns the binary [*de Bruijn sequence*](https://en.wikipedia.org/wiki/De_Bruijn_sequence) of the order or None if the order isn't in 1..=6.
+/// Every *order* bits long word appears exactly once as a cyclic window of the *2^order* bits long sequence.
+/// The sequence starts with *order* zeros, so the windows are also the top bits of the sequence shifted left
+/// # Arguments
+/// * `order` - length of the windows
+/// # Examples
+/// ```
+/// # use understanding_bitwise::de_bruijn_sequence;
+/// // Windows of 0011 (cyclic): 00, 01, 11, 10
+/// assert_eq!(de_bruijn_sequence(2), Some(0b0011));
+/// assert_eq!(de_bruijn_sequence(7), None);
+/// ```
+pub const fn de_bruijn_sequence(order: u32) -> Option<u64> {
+    if order == 0 || order > 6 {
+        return None;
+    }
+    // "Prefer one" construction: append 1 if the resulting window wasn't seen yet, otherwise append 0
+    let mask = (1u64 << order) - 1;
+    let mut seen = [false; 1 << 6];
+    seen[0] = true;
+    let mut window = 0u64;
+    let mut sequence = 0u64;
+    let mut length = order;
+    while length < 1 << order {
+        let bit = if seen[((window << 1 | 1) & mask) as usize] { 0 } else { 1 };
+        window = (window << 1 | bit) & mask;
+        seen[window as usize] = true;
+        sequence = sequence << 1 | bit;
+        length += 1;
+    }
+    Some(sequence)
+}
+
+/// De Bruijn sequence of order 5 used by the multiply-and-lookup methods. Generated at compile time
+const DE_BRUIJN_32: u32 = match de_bruijn_sequence(5) {
+    Some(sequence) => sequence as u32,
+    None => panic!("order 5 is supported"),
+};
+
+/// Table of the bit indexes by the top 5 bits of *DE_BRUIJN_32* shifted left by the index. Generated at compile time
+const DE_BRUIJN_POSITIONS: [u8; 32] = {
+    let mut table = [0u8; 32];
+    let mut index = 0;
+    while index < u32::BITS {
+        table[(DE_BRUIJN_32 << index >> 27) as usize] = index as u8;
+        index += 1;
+    }
+    table
+};
+
+/// Helper function. Returns the index of the only set bit of the power of two. Multiplication by the power of two is a shift
+/// of the de Bruijn sequence, so the top 5 bits are unique for every index
+/// # Arguments
+/// * `power_of_two` - number with exactly one bit set
+fn de_bruijn_position(power_of_two: u32) -> u32 {
+    DE_BRUIJN_POSITIONS[(power_of_two.wrapping_mul(DE_BRUIJN_32) >> 27) as usize] as u32
+}
+
+/// Returns [*highest order bit*](https://commoncog.com/blog/highest-order-bit/) or None if number can't have hob. Uses de Bruijn multiplication method.
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::hob_debruijn;
+/// assert_eq!(hob_debruijn(0), None);
+/// assert_eq!(hob_debruijn(0b100), Some(2));
+/// ```
+pub fn hob_debruijn(number: u32) -> Option<u32> {
+    if no_hob(number) {
+        return None;
+    }
+
+    // Spread the hob to all lower bits, then keep the hob only
+    let mut number = number;
+    number |= number >> 1;
+    number |= number >> 2;
+    number |= number >> 4;
+    number |= number >> 8;
+    number |= number >> 16;
+    Some(de_bruijn_position(number - (number >> 1)))
+}
+
+/// *hob* variant with its name
+pub type NamedHob = (&'static str, &'static dyn Fn(u32) -> Option<u32>);
+
+/// Every *hob* variant with its name, in the shape *compare::compare_methods* takes
+/// # Examples
+/// ```
+/// # use understanding_bitwise::HOB_METHODS;
+/// # use understanding_bitwise::compare::{compare_methods, CompareOptions};
+/// let timings = compare_methods(&HOB_METHODS, |index| index as u32, CompareOptions { inputs: 100, rounds: 3 });
+/// assert!(timings.iter().any(|timing| timing.name == "hob_binary_search"));
+/// ```
+pub const HOB_METHODS: [NamedHob; 6] = [
+    ("hob", &hob),
+    ("hob_thr", &hob_thr),
+    ("hob_comp_pot", &hob_comp_pot),
+    ("hob_binary_search", &hob_binary_search),
+    ("hob_lut", &hob_lut),
+    ("hob_debruijn", &hob_debruijn),
+];
+
+/// Returns the count of zeros below the lowest set bit (index of the lowest order bit) or None if the number is 0. Uses de Bruijn multiplication method.
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::trailing_zeros_debruijn;
+/// assert_eq!(trailing_zeros_debruijn(0), None);
+/// assert_eq!(trailing_zeros_debruijn(0b1100), Some(2));
+/// ```
+pub fn trailing_zeros_debruijn(number: u32) -> Option<u32> {
+    if number == 0 {
+        return None;
+    }
+
+    // Two's complement negation keeps the lowest set bit and inverts all bits above it
+    Some(de_bruijn_position(number & number.wrapping_neg()))
+}
+
 /// Helper function, base function. Used to check if the bit index is in a valid range
 /// # Arguments
 /// * `index` - index of the bit to be manipulated with
@@ -325,6 +791,47 @@ pub fn circular_shr(byte: u8, count: u32) -> u8 {
     circular_sh_base(byte, count, u8::shr, u8::shl)
 }
 
+/// Returns the smallest count *circular_shl* shifts the first byte by to get the second one
+/// or None if the second byte isn't a rotation of the first one
+/// # Arguments
+/// * `a` - byte to be rotated
+/// * `b` - byte to be matched
+/// # Examples
+/// ```
+/// # use understanding_bitwise::is_rotation_of;
+/// assert_eq!(is_rotation_of(0b10000011, 0b00001110), Some(2));
+/// assert_eq!(is_rotation_of(0b10000011, 0b00000111), Some(1));
+/// assert_eq!(is_rotation_of(0b10000011, 0b00000011), None);
+/// // Patterns repeating within the byte match at the smallest count
+/// assert_eq!(is_rotation_of(0b01010101, 0b01010101), Some(0));
+/// ```
+pub fn is_rotation_of(a: u8, b: u8) -> Option<u32> {
+    // Rotations keep the count of ones, so most pairs are rejected without trying
+    if a.count_ones() != b.count_ones() {
+        return None;
+    }
+    (0..u8::BITS).find(|&count| circular_shl(a, count) == b)
+}
+
+/// Returns the smallest count the first number is rotated left by to get the second one
+/// or None if the second number isn't a rotation of the first one. *u32* version of *is_rotation_of*
+/// # Arguments
+/// * `a` - number to be rotated
+/// * `b` - number to be matched
+/// # Examples
+/// ```
+/// # use understanding_bitwise::is_rotation_of_u32;
+/// assert_eq!(is_rotation_of_u32(0x8000_0001, 0x0000_0003), Some(1));
+/// assert_eq!(is_rotation_of_u32(0x1234_5678, 0x5678_1234), Some(16));
+/// assert_eq!(is_rotation_of_u32(0x1234_5678, 0x1234_5679), None);
+/// ```
+pub fn is_rotation_of_u32(a: u32, b: u32) -> Option<u32> {
+    if a.count_ones() != b.count_ones() {
+        return None;
+    }
+    (0..u32::BITS).find(|&count| a.rotate_left(count) == b)
+}
+
 /// Returns the number that represents a sequence of consecutive ones
 /// # Arguments
 /// * `consecutive_ones_count` - count of consecutive ones in a sequence
@@ -367,6 +874,32 @@ pub fn consecutive_ones_entries_count(number: u32, consecutive_ones_count: u32)
     Some(matches)
 }
 
+/// Returns number of entries matching the consecutive ones sequence in the number.
+/// Uses run extraction method: every run of *n* ones contains *n - count + 1* entries, so only the runs are visited
+/// # Arguments
+/// * `number` - number to work with
+/// * `consecutive_ones_count` - count of consecutive ones in a sequence
+/// # Examples
+/// ```
+/// # use understanding_bitwise::consecutive_ones_entries_count_runs;
+/// assert_eq!(consecutive_ones_entries_count_runs(0b1001110, 2), Some(2));
+/// assert_eq!(consecutive_ones_entries_count_runs(0b1001110, 0), None);
+/// assert_eq!(consecutive_ones_entries_count_runs(0b1001110, 45), None);
+/// ```
+pub fn consecutive_ones_entries_count_runs(number: u32, consecutive_ones_count: u32) -> Option<u32> {
+    consecutive_ones_number(consecutive_ones_count)?;
+    let mut number = number;
+    let mut matches = 0;
+    while number != 0 {
+        // Drop the zeros below the run, then measure the run by the zeros below it in the inverted number
+        number >>= number.trailing_zeros();
+        let run = (! number).trailing_zeros();
+        matches += (run + 1).saturating_sub(consecutive_ones_count);
+        number = number.checked_shr(run).unwrap_or(0);
+    }
+    Some(matches)
+}
+
 /// Helper function, base function. Checks indexes equality and that indexes are in valid limits. If ok, performs the swap.
 /// # Arguments
 /// * `number` - number to work with
@@ -440,6 +973,7 @@ pub fn swap_bits_xor(number: u32, index1: u32, index2: u32) -> Option<u32> {
 /// ```
 /// # use understanding_bitwise::remove_bit;
 /// assert_eq!(remove_bit(0b100011, 1), Some(0b10001));
+/// assert_eq!(remove_bit(u32::MAX, 31), Some(u32::MAX >> 1));
 /// assert_eq!(remove_bit(0b100011, 300), None);
 /// ```
 pub fn remove_bit(number: u32, index: u32) -> Option<u32> {
@@ -447,69 +981,1046 @@ pub fn remove_bit(number: u32, index: u32) -> Option<u32> {
         return None;
     }
 
-    let mut remover = number >> index + 1 ^ number >> index;
+    // The bit 31 has nothing above it: shifting by 32 gives 0
+    let mut remover = number.checked_shr(index + 1).unwrap_or(0) ^ number >> index;
     remover <<= index;
     Some(number ^ remover)
 }
 
-/// Finds element that doesn't have duplicate. Other elements must have number of entries divisible by two. There must be only one unique element.
+/// Returns a copy of the original number with the specific bit set to 1 or the error if the index is out of range. See *set_bit*
 /// # Arguments
-/// * `vals` - IntoIterator instance. You can pass a read-only reference of a collection
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
 /// # Examples
 /// ```
-/// # use understanding_bitwise::find_unique;
-/// assert_eq!(find_unique(&[45, 32, 777, 10, 45, 10, 32]), Some(777));
-/// assert_eq!(find_unique(&[0u32; 0]), None);
+/// # use understanding_bitwise::try_set_bit;
+/// assert_eq!(try_set_bit(0b101, 1), Ok(0b111));
+/// assert!(try_set_bit(0b100, 45).is_err());
 /// ```
-pub fn find_unique<'a, I, B: 'a>(vals: I) -> Option<B>
-where
-    I: IntoIterator<Item = &'a B>,
-    B: BitXor<Output = B> + Copy {
-    vals.into_iter().fold(None, |acc, &val| {
-        acc.map_or_else(|| Some(val), |acc| Some(acc ^ val))
-    })
+pub fn try_set_bit(number: u32, index: u32) -> Result<u32, BitError> {
+    set_bit(number, index).ok_or_else(|| BitError::index_out_of_range("set_bit", index))
 }
 
-/// This module contains tests
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_power_of_two() {
-        assert_eq!(power_of_two(0), Ok(1));
-        assert_eq!(power_of_two(2), Ok(4));
-        assert_eq!(power_of_two(3), Ok(8));
-        assert_eq!(power_of_two(31), Ok(2147483648));
-        assert_eq!(power_of_two(32), Err(Overflow));
-        assert_eq!(power_of_two(u32::MAX), Err(Overflow));
-    }
+/// Returns a copy of the original number with the specific bit set to 0 or the error if the index is out of range. See *unset_bit*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::try_unset_bit;
+/// assert_eq!(try_unset_bit(0b101, 2), Ok(0b001));
+/// assert!(try_unset_bit(0b101, 45).is_err());
+/// ```
+pub fn try_unset_bit(number: u32, index: u32) -> Result<u32, BitError> {
+    unset_bit(number, index).ok_or_else(|| BitError::index_out_of_range("unset_bit", index))
+}
 
-    #[test]
-    fn test_write_binary_representation() {
-        let bin_rep_str = |number| -> String {
-            let mut vec = Vec::<u8>::with_capacity(u32::BITS as usize);
-            write_binary_representation(number, &mut vec);
-            std::str::from_utf8(vec.as_slice()).unwrap().to_owned()
-        };
-        
-        let mut str = bin_rep_str(0b11100100);
-        assert_eq!(str, String::from("11100100"));
+/// Returns a copy of the original number with the specific bit inverted or the error if the index is out of range. See *invert_bit*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::try_invert_bit;
+/// assert_eq!(try_invert_bit(0b101, 1), Ok(0b111));
+/// assert!(try_invert_bit(0b101, 45).is_err());
+/// ```
+pub fn try_invert_bit(number: u32, index: u32) -> Result<u32, BitError> {
+    invert_bit(number, index).ok_or_else(|| BitError::index_out_of_range("invert_bit", index))
+}
 
-        str = bin_rep_str(u32::MAX);
-        assert_eq!(str, String::from("11111111111111111111111111111111"));
-        
-        str = bin_rep_str(u32::MIN);
-        assert_eq!(str, String::from("0"));
-        
-        str = bin_rep_str(1);
-        assert_eq!(str, String::from("1"));
-    }
+/// Returns the number with the specific bits swapped or the error naming the first index out of range. See *swap_bits*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index1` - index of the bit to be swapped
+/// * `index2` - index of the bit to be swapped
+/// # Examples
+/// ```
+/// # use understanding_bitwise::try_swap_bits;
+/// assert_eq!(try_swap_bits(0b101, 0, 1), Ok(0b110));
+/// assert_eq!(try_swap_bits(0b101, 0, 40).unwrap_err().index(), 40);
+/// ```
+pub fn try_swap_bits(number: u32, index1: u32, index2: u32) -> Result<u32, BitError> {
+    swap_bits(number, index1, index2).ok_or_else(|| {
+        let index = if index1 >= u32::BITS { index1 } else { index2 };
+        BitError::index_out_of_range("swap_bits", index)
+    })
+}
 
-    fn general_test_binary_ones_count<F>(f: F)
-    where
-        F: Fn(u32) -> u32 {
-        let mut count = f(0b11100100);
+/// Returns the number with specific bit removed or the error if the index is out of range. See *remove_bit*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the bit to be removed
+/// # Examples
+/// ```
+/// # use understanding_bitwise::try_remove_bit;
+/// assert_eq!(try_remove_bit(0b100011, 1), Ok(0b10001));
+/// assert!(try_remove_bit(0b100011, 300).is_err());
+/// ```
+pub fn try_remove_bit(number: u32, index: u32) -> Result<u32, BitError> {
+    remove_bit(number, index).ok_or_else(|| BitError::index_out_of_range("remove_bit", index))
+}
+
+/// Returns a copy of the original number with the specific bit set to 1. The index isn't checked in release builds
+/// (the shift amount wraps), debug builds assert it. Meant for loops over already validated indexes
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit, must be less than 32
+/// # Examples
+/// ```
+/// # use understanding_bitwise::set_bit_unchecked;
+/// assert_eq!(set_bit_unchecked(0b101, 1), 0b111);
+/// ```
+pub fn set_bit_unchecked(number: u32, index: u32) -> u32 {
+    debug_assert!(index < u32::BITS, "set_bit: index {index} is out of range 0..32");
+    number | 1 << (index & u32::BITS - 1)
+}
+
+/// Returns a copy of the original number with the specific bit set to 0. The index isn't checked in release builds. See *set_bit_unchecked*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit, must be less than 32
+/// # Examples
+/// ```
+/// # use understanding_bitwise::unset_bit_unchecked;
+/// assert_eq!(unset_bit_unchecked(0b101, 2), 0b001);
+/// ```
+pub fn unset_bit_unchecked(number: u32, index: u32) -> u32 {
+    debug_assert!(index < u32::BITS, "unset_bit: index {index} is out of range 0..32");
+    number & ! (1 << (index & u32::BITS - 1))
+}
+
+/// Returns a copy of the original number with the specific bit inverted. The index isn't checked in release builds. See *set_bit_unchecked*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit, must be less than 32
+/// # Examples
+/// ```
+/// # use understanding_bitwise::invert_bit_unchecked;
+/// assert_eq!(invert_bit_unchecked(0b101, 0), 0b100);
+/// ```
+pub fn invert_bit_unchecked(number: u32, index: u32) -> u32 {
+    debug_assert!(index < u32::BITS, "invert_bit: index {index} is out of range 0..32");
+    number ^ 1 << (index & u32::BITS - 1)
+}
+
+/// Returns the number with the specific bits swapped. The indexes aren't checked in release builds. See *set_bit_unchecked*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index1` - index of the bit to be swapped, must be less than 32
+/// * `index2` - index of the bit to be swapped, must be less than 32
+/// # Examples
+/// ```
+/// # use understanding_bitwise::swap_bits_unchecked;
+/// assert_eq!(swap_bits_unchecked(0b100011, 1, 4), 0b110001);
+/// ```
+pub fn swap_bits_unchecked(number: u32, index1: u32, index2: u32) -> u32 {
+    debug_assert!(index1 < u32::BITS, "swap_bits: index {index1} is out of range 0..32");
+    debug_assert!(index2 < u32::BITS, "swap_bits: index {index2} is out of range 0..32");
+    let (index1, index2) = (index1 & u32::BITS - 1, index2 & u32::BITS - 1);
+    let swapper = (number >> index1 ^ number >> index2) & 1;
+    number ^ (swapper << index1 | swapper << index2)
+}
+
+/// Returns the number with specific bit removed. The index isn't checked in release builds. See *set_bit_unchecked* and *remove_bit*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the bit to be removed, must be less than 32
+/// # Examples
+/// ```
+/// # use understanding_bitwise::remove_bit_unchecked;
+/// assert_eq!(remove_bit_unchecked(0b100011, 1), 0b10001);
+/// ```
+pub fn remove_bit_unchecked(number: u32, index: u32) -> u32 {
+    debug_assert!(index < u32::BITS, "remove_bit: index {index} is out of range 0..32");
+    let below = (1 << (index & u32::BITS - 1)) - 1;
+    number & below | number >> 1 & ! below
+}
+
+/// Returns a copy of the original number with the specific bit set to 1. Panics with the *BitError* message if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::set_bit_panicking;
+/// assert_eq!(set_bit_panicking(0b101, 1), 0b111);
+/// ```
+/// ```should_panic
+/// # use understanding_bitwise::set_bit_panicking;
+/// set_bit_panicking(0b101, 45);
+/// ```
+pub fn set_bit_panicking(number: u32, index: u32) -> u32 {
+    try_set_bit(number, index).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Returns a copy of the original number with the specific bit set to 0. Panics with the *BitError* message if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+pub fn unset_bit_panicking(number: u32, index: u32) -> u32 {
+    try_unset_bit(number, index).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Returns a copy of the original number with the specific bit inverted. Panics with the *BitError* message if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+pub fn invert_bit_panicking(number: u32, index: u32) -> u32 {
+    try_invert_bit(number, index).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Returns the number with the specific bits swapped. Panics with the *BitError* message if some index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index1` - index of the bit to be swapped
+/// * `index2` - index of the bit to be swapped
+pub fn swap_bits_panicking(number: u32, index1: u32, index2: u32) -> u32 {
+    try_swap_bits(number, index1, index2).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Returns the number with specific bit removed. Panics with the *BitError* message if the index is out of range
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the bit to be removed
+pub fn remove_bit_panicking(number: u32, index: u32) -> u32 {
+    try_remove_bit(number, index).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Helper function. Returns the index reduced modulo the bit width, like the count of *circular_shl*
+/// # Arguments
+/// * `index` - index of the bit
+fn wrap_index(index: u32) -> u32 {
+    index % u32::BITS
+}
+
+/// Returns a copy of the original number with the specific bit set to 1. The index is taken modulo 32, so the function never fails
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::set_bit_wrapping;
+/// assert_eq!(set_bit_wrapping(0b101, 1), 0b111);
+/// assert_eq!(set_bit_wrapping(0b101, 33), 0b111);
+/// ```
+pub fn set_bit_wrapping(number: u32, index: u32) -> u32 {
+    set_bit_unchecked(number, wrap_index(index))
+}
+
+/// Returns a copy of the original number with the specific bit set to 0. The index is taken modulo 32
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::unset_bit_wrapping;
+/// assert_eq!(unset_bit_wrapping(0b101, 34), 0b001);
+/// ```
+pub fn unset_bit_wrapping(number: u32, index: u32) -> u32 {
+    unset_bit_unchecked(number, wrap_index(index))
+}
+
+/// Returns a copy of the original number with the specific bit inverted. The index is taken modulo 32
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::invert_bit_wrapping;
+/// assert_eq!(invert_bit_wrapping(0b101, 64), 0b100);
+/// ```
+pub fn invert_bit_wrapping(number: u32, index: u32) -> u32 {
+    invert_bit_unchecked(number, wrap_index(index))
+}
+
+/// Returns the number with the specific bits swapped. The indexes are taken modulo 32
+/// # Arguments
+/// * `number` - number to work with
+/// * `index1` - index of the bit to be swapped
+/// * `index2` - index of the bit to be swapped
+/// # Examples
+/// ```
+/// # use understanding_bitwise::swap_bits_wrapping;
+/// assert_eq!(swap_bits_wrapping(0b100011, 33, 36), 0b110001);
+/// ```
+pub fn swap_bits_wrapping(number: u32, index1: u32, index2: u32) -> u32 {
+    swap_bits_unchecked(number, wrap_index(index1), wrap_index(index2))
+}
+
+/// Returns the number with specific bit removed. The index is taken modulo 32
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the bit to be removed
+/// # Examples
+/// ```
+/// # use understanding_bitwise::remove_bit_wrapping;
+/// assert_eq!(remove_bit_wrapping(0b100011, 33), 0b10001);
+/// ```
+pub fn remove_bit_wrapping(number: u32, index: u32) -> u32 {
+    remove_bit_unchecked(number, wrap_index(index))
+}
+
+/// Helper function. Returns the index as u32 or None if it doesn't fit, which also means it's out of range
+/// # Arguments
+/// * `index` - index of the bit
+fn narrow_index(index: usize) -> Option<u32> {
+    u32::try_from(index).ok()
+}
+
+/// Returns a copy of the original number with the specific bit set to 1. Accepts *usize* index (e.g. from *enumerate*). See *set_bit*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::set_bit_usize;
+/// let number = [true, false, true].iter().enumerate()
+///     .filter(|(_, &bit)| bit)
+///     .try_fold(0, |number, (index, _)| set_bit_usize(number, index));
+/// assert_eq!(number, Some(0b101));
+/// assert_eq!(set_bit_usize(0, usize::MAX), None);
+/// ```
+pub fn set_bit_usize(number: u32, index: usize) -> Option<u32> {
+    set_bit(number, narrow_index(index)?)
+}
+
+/// Returns a copy of the original number with the specific bit set to 0. Accepts *usize* index. See *unset_bit*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+pub fn unset_bit_usize(number: u32, index: usize) -> Option<u32> {
+    unset_bit(number, narrow_index(index)?)
+}
+
+/// Returns a copy of the original number with the specific bit inverted. Accepts *usize* index. See *invert_bit*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the specific bit
+pub fn invert_bit_usize(number: u32, index: usize) -> Option<u32> {
+    invert_bit(number, narrow_index(index)?)
+}
+
+/// Returns the number with the specific bits swapped. Accepts *usize* indexes. See *swap_bits*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index1` - index of the bit to be swapped
+/// * `index2` - index of the bit to be swapped
+pub fn swap_bits_usize(number: u32, index1: usize, index2: usize) -> Option<u32> {
+    swap_bits(number, narrow_index(index1)?, narrow_index(index2)?)
+}
+
+/// Returns the number with specific bit removed. Accepts *usize* index. See *remove_bit*
+/// # Arguments
+/// * `number` - number to work with
+/// * `index` - index of the bit to be removed
+pub fn remove_bit_usize(number: u32, index: usize) -> Option<u32> {
+    remove_bit(number, narrow_index(index)?)
+}
+
+/// Helper function. Returns the number with the *width* lowest bits set to 1 or None if the bit field doesn't fit into the word
+/// # Arguments
+/// * `offset` - index of the lowest bit of the field
+/// * `width` - bits count of the field
+/// * `bits` - bits count of the word
+fn field_ones(offset: u32, width: u32, bits: u32) -> Option<u64> {
+    if offset.checked_add(width)? > bits {
+        return None;
+    }
+
+    Some(match width {
+        0 => 0,
+        width => u64::MAX >> u64::BITS - width,
+    })
+}
+
+/// Returns the value of the bit field (*width* bits starting at *offset*) or None if the field doesn't fit into the number
+/// # Arguments
+/// * `number` - number to work with
+/// * `offset` - index of the lowest bit of the field
+/// * `width` - bits count of the field
+/// # Examples
+/// ```
+/// # use understanding_bitwise::get_bits;
+/// assert_eq!(get_bits(0b1101100, 2, 3), Some(0b011));
+/// assert_eq!(get_bits(0b1101100, 30, 3), None);
+/// ```
+pub fn get_bits(number: u32, offset: u32, width: u32) -> Option<u32> {
+    let ones = field_ones(offset, width, u32::BITS)? as u32;
+    Some(number.checked_shr(offset).unwrap_or(0) & ones)
+}
+
+/// Returns a copy of the original number with the bit field (*width* bits starting at *offset*) replaced by the value
+/// or None if the field doesn't fit into the number or the value doesn't fit into the field
+/// # Arguments
+/// * `number` - number to work with
+/// * `offset` - index of the lowest bit of the field
+/// * `width` - bits count of the field
+/// * `value` - new value of the field
+/// # Examples
+/// ```
+/// # use understanding_bitwise::set_bits;
+/// assert_eq!(set_bits(0b1101100, 2, 3, 0b110), Some(0b1111000));
+/// assert_eq!(set_bits(0b1101100, 2, 3, 0b1000), None);
+/// assert_eq!(set_bits(0b1101100, 30, 3, 0), None);
+/// ```
+pub fn set_bits(number: u32, offset: u32, width: u32, value: u32) -> Option<u32> {
+    let ones = field_ones(offset, width, u32::BITS)? as u32;
+    if value & ! ones != 0 {
+        return None;
+    }
+
+    let mask = ones.checked_shl(offset).unwrap_or(0);
+    Some(number & ! mask | value.checked_shl(offset).unwrap_or(0))
+}
+
+/// Returns the value of the bit field (*width* bits starting at *offset*) or None if the field doesn't fit into the number.
+/// *u64* version of *get_bits*
+/// # Arguments
+/// * `number` - number to work with
+/// * `offset` - index of the lowest bit of the field
+/// * `width` - bits count of the field
+/// # Examples
+/// ```
+/// # use understanding_bitwise::get_bits_u64;
+/// assert_eq!(get_bits_u64(0xABCD << 40, 44, 8), Some(0xBC));
+/// assert_eq!(get_bits_u64(0, 60, 8), None);
+/// ```
+pub fn get_bits_u64(number: u64, offset: u32, width: u32) -> Option<u64> {
+    let ones = field_ones(offset, width, u64::BITS)?;
+    Some(number.checked_shr(offset).unwrap_or(0) & ones)
+}
+
+/// Returns a copy of the original number with the bit field (*width* bits starting at *offset*) replaced by the value
+/// or None if the field doesn't fit into the number or the value doesn't fit into the field. *u64* version of *set_bits*
+/// # Arguments
+/// * `number` - number to work with
+/// * `offset` - index of the lowest bit of the field
+/// * `width` - bits count of the field
+/// * `value` - new value of the field
+/// # Examples
+/// ```
+/// # use understanding_bitwise::set_bits_u64;
+/// assert_eq!(set_bits_u64(0, 60, 4, 0xF), Some(0xF << 60));
+/// assert_eq!(set_bits_u64(0, 60, 4, 0x10), None);
+/// ```
+pub fn set_bits_u64(number: u64, offset: u32, width: u32, value: u64) -> Option<u64> {
+    let ones = field_ones(offset, width, u64::BITS)?;
+    if value & ! ones != 0 {
+        return None;
+    }
+
+    let mask = ones.checked_shl(offset).unwrap_or(0);
+    Some(number & ! mask | value.checked_shl(offset).unwrap_or(0))
+}
+
+/// Returns the value of the *from_bits* lowest bits interpreted as a two's complement signed number.
+/// Higher bits of the value are ignored
+/// # Arguments
+/// * `value` - number to work with
+/// * `from_bits` - bits count of the signed number, from 1 to 32
+/// # Panics
+/// Panics if *from_bits* is not in 1..=32
+/// # Examples
+/// ```
+/// # use understanding_bitwise::sign_extend;
+/// assert_eq!(sign_extend(0b0111, 4), 7);
+/// assert_eq!(sign_extend(0b1000, 4), -8);
+/// assert_eq!(sign_extend(0b1111, 4), -1);
+/// ```
+pub fn sign_extend(value: u32, from_bits: u32) -> i32 {
+    assert!((1..=u32::BITS).contains(&from_bits), "from_bits must be in 1..=32");
+    // Moving the sign bit to the highest position and then performing arithmetic right shift copies it to the vacated positions
+    let unused_bits = u32::BITS - from_bits;
+    (value << unused_bits) as i32 >> unused_bits
+}
+
+/// Returns the value of the *from_bits* lowest bits interpreted as a two's complement signed number
+/// or None if *from_bits* is not in 1..=32 or some higher bit of the value is set
+/// # Arguments
+/// * `value` - number to work with
+/// * `from_bits` - bits count of the signed number
+/// # Examples
+/// ```
+/// # use understanding_bitwise::sign_extend_checked;
+/// assert_eq!(sign_extend_checked(0b1000, 4), Some(-8));
+/// assert_eq!(sign_extend_checked(0b11000, 4), None);
+/// assert_eq!(sign_extend_checked(0b1000, 0), None);
+/// ```
+pub fn sign_extend_checked(value: u32, from_bits: u32) -> Option<i32> {
+    if from_bits == 0 || get_bits(value, 0, from_bits)? != value {
+        return None;
+    }
+    Some(sign_extend(value, from_bits))
+}
+
+/// Returns the value of the *from_bits* lowest bits, with higher bits set to 0
+/// # Arguments
+/// * `value` - number to work with
+/// * `from_bits` - bits count to be kept, from 0 to 32
+/// # Panics
+/// Panics if *from_bits* is greater than 32
+/// # Examples
+/// ```
+/// # use understanding_bitwise::zero_extend;
+/// assert_eq!(zero_extend(0b1101_1000, 4), 0b1000);
+/// assert_eq!(zero_extend(u32::MAX, 32), u32::MAX);
+/// ```
+pub fn zero_extend(value: u32, from_bits: u32) -> u32 {
+    get_bits(value, 0, from_bits).expect("from_bits must be in 0..=32")
+}
+
+/// Returns the value of the *from_bits* lowest bits or None if *from_bits* is greater than 32 or some higher bit of the value is set
+/// # Arguments
+/// * `value` - number to work with
+/// * `from_bits` - bits count to be kept
+/// # Examples
+/// ```
+/// # use understanding_bitwise::zero_extend_checked;
+/// assert_eq!(zero_extend_checked(0b1000, 4), Some(0b1000));
+/// assert_eq!(zero_extend_checked(0b11000, 4), None);
+/// assert_eq!(zero_extend_checked(0, 33), None);
+/// ```
+pub fn zero_extend_checked(value: u32, from_bits: u32) -> Option<u32> {
+    get_bits(value, 0, from_bits).filter(|&extended| extended == value)
+}
+
+/// Returns the bits of the number selected by the mask, packed together into the lowest bits (parallel bits extract, PEXT).
+/// Iterates over the set bits of the mask
+/// # Arguments
+/// * `number` - number to work with
+/// * `mask` - mask selecting the bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::pext;
+/// assert_eq!(pext(0b1011_0110, 0b1111_0000), 0b1011);
+/// assert_eq!(pext(0b1011_0110, 0b1010_1010), 0b1101);
+/// ```
+pub fn pext(number: u32, mask: u32) -> u32 {
+    let mut mask = mask;
+    let mut result = 0;
+    let mut index = 0;
+    while mask != 0 {
+        let lowest = mask & mask.wrapping_neg();
+        if number & lowest != 0 {
+            result |= 1 << index;
+        }
+        index += 1;
+        mask ^= lowest;
+    }
+    result
+}
+
+/// Helper function. Returns the word with every bit set to the xor of itself and all lower bits (parallel prefix/suffix xor)
+/// # Arguments
+/// * `number` - number to work with
+pub(crate) fn prefix_xor(number: u32) -> u32 {
+    let mut number = number;
+    let mut shift = 1;
+    while shift < u32::BITS {
+        number ^= number << shift;
+        shift <<= 1;
+    }
+    number
+}
+
+/// Returns the bits of the number selected by the mask, packed together into the lowest bits (parallel bits extract, PEXT).
+/// Uses SWAR method: on stage *i* the bits that must move right by an odd multiple of 2ⁱ are moved by 2ⁱ all at once
+/// # Arguments
+/// * `number` - number to work with
+/// * `mask` - mask selecting the bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::pext_swar;
+/// assert_eq!(pext_swar(0b1011_0110, 0b1010_1010), 0b1101);
+/// ```
+pub fn pext_swar(number: u32, mask: u32) -> u32 {
+    let mut number = number & mask;
+    let mut mask = mask;
+    // Bit is set for every position with a zero of the mask to the right of it
+    let mut zeros_to_the_right = ! mask << 1;
+    for stage in 0..u32::BITS.trailing_zeros() {
+        // Bit is set if the count of zeros to the right is odd, so the bit must move on this stage
+        let moves = prefix_xor(zeros_to_the_right);
+        let moving_mask = moves & mask;
+        mask = mask ^ moving_mask | moving_mask >> (1 << stage);
+        let moving_bits = number & moving_mask;
+        number = number ^ moving_bits | moving_bits >> (1 << stage);
+        zeros_to_the_right &= ! moves;
+    }
+    number
+}
+
+/// Returns the lowest bits of the number scattered to the positions of the mask bits (parallel bits deposit, PDEP).
+/// Iterates over the set bits of the mask
+/// # Arguments
+/// * `number` - number to work with
+/// * `mask` - mask receiving the bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::pdep;
+/// assert_eq!(pdep(0b1011, 0b1111_0000), 0b1011_0000);
+/// assert_eq!(pdep(0b1001, 0b1010_1010), 0b1000_0010);
+/// ```
+pub fn pdep(number: u32, mask: u32) -> u32 {
+    let mut mask = mask;
+    let mut result = 0;
+    let mut index = 0;
+    while mask != 0 {
+        let lowest = mask & mask.wrapping_neg();
+        if number >> index & 1 == 1 {
+            result |= lowest;
+        }
+        index += 1;
+        mask ^= lowest;
+    }
+    result
+}
+
+/// Returns the lowest bits of the number scattered to the positions of the mask bits (parallel bits deposit, PDEP).
+/// Uses SWAR method: the moves of *pext_swar* are computed and then performed in reverse order, to the left
+/// # Arguments
+/// * `number` - number to work with
+/// * `mask` - mask receiving the bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::pdep_swar;
+/// assert_eq!(pdep_swar(0b1001, 0b1010_1010), 0b1000_0010);
+/// ```
+pub fn pdep_swar(number: u32, mask: u32) -> u32 {
+    const STAGES: usize = u32::BITS.trailing_zeros() as usize;
+    let original_mask = mask;
+    let mut mask = mask;
+    let mut zeros_to_the_right = ! mask << 1;
+    let mut moving_masks = [0u32; STAGES];
+    for (stage, moving_mask) in moving_masks.iter_mut().enumerate() {
+        let moves = prefix_xor(zeros_to_the_right);
+        *moving_mask = moves & mask;
+        mask = mask ^ *moving_mask | *moving_mask >> (1 << stage);
+        zeros_to_the_right &= ! moves;
+    }
+
+    let mut number = number;
+    for (stage, &moving_mask) in moving_masks.iter().enumerate().rev() {
+        let moved = number << (1 << stage);
+        number = number & ! moving_mask | moved & moving_mask;
+    }
+    number & original_mask
+}
+
+/// Finds element that doesn't have duplicate. Other elements must have number of entries divisible by two. There must be only one unique element.
+/// # Arguments
+/// * `vals` - IntoIterator instance. You can pass a read-only reference of a collection
+/// # Examples
+/// ```
+/// # use understanding_bitwise::find_unique;
+/// assert_eq!(find_unique(&[45, 32, 777, 10, 45, 10, 32]), Some(777));
+/// assert_eq!(find_unique(&[0u32; 0]), None);
+/// ```
+pub fn find_unique<'a, I, B>(vals: I) -> Option<B>
+where
+    I: IntoIterator<Item = &'a B>,
+    B: 'a + BitXor<Output = B> + Copy {
+    find_unique_owned(vals.into_iter().copied())
+}
+
+/// Finds element that doesn't have duplicate. Same as *find_unique*, but takes the elements by value,
+/// so mapped or generated values don't have to be collected first
+/// # Arguments
+/// * `vals` - IntoIterator instance yielding the elements
+/// # Examples
+/// ```
+/// # use understanding_bitwise::find_unique_owned;
+/// assert_eq!(find_unique_owned([1, 2, 3, 2, 1].map(|val| val * 10)), Some(30));
+/// assert_eq!(find_unique_owned((0..10).chain(0..11)), Some(10));
+/// assert_eq!(find_unique_owned(std::iter::empty::<u32>()), None);
+/// ```
+pub fn find_unique_owned<I, B>(vals: I) -> Option<B>
+where
+    I: IntoIterator<Item = B>,
+    B: BitXor<Output = B> {
+    vals.into_iter().reduce(|acc, val| acc ^ val)
+}
+
+/// Integer word used by the XOR puzzle functions (*find_two_uniques* etc.)
+pub trait XorWord: Copy + Eq + BitXor<Output = Self> + BitAnd<Output = Self> + Not<Output = Self> {
+    /// Word with all bits set to 0
+    const ZERO: Self;
+
+    /// Returns the word with only the lowest set bit of this word kept
+    fn lowest_set_bit(self) -> Self;
+}
+
+/// Helper macro. Implements *XorWord* for the integer types
+macro_rules! impl_xor_word {
+    ($($word:ty),*) => {$(
+        impl XorWord for $word {
+            const ZERO: Self = 0;
+
+            fn lowest_set_bit(self) -> Self {
+                self & self.wrapping_neg()
+            }
+        }
+    )*};
+}
+
+impl_xor_word!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Finds the two elements that don't have duplicates. Other elements must have number of entries divisible by two.
+/// XOR of all elements is XOR of the two uniques, any of its set bits differs between them, so the elements are split
+/// by that bit into two groups, each containing one unique. Returns the uniques in ascending order or None if XOR of all elements is 0
+/// # Arguments
+/// * `vals` - IntoIterator instance, iterated twice
+/// # Examples
+/// ```
+/// # use understanding_bitwise::find_two_uniques;
+/// assert_eq!(find_two_uniques([4, 1, 2, 1, 2, 3]), Some((3, 4)));
+/// assert_eq!(find_two_uniques([5u32, 5]), None);
+/// ```
+pub fn find_two_uniques<I, B>(vals: I) -> Option<(B, B)>
+where
+    I: IntoIterator<Item = B>,
+    I::IntoIter: Clone,
+    B: XorWord + Ord {
+    let vals = vals.into_iter();
+    let both = find_unique_owned(vals.clone())?;
+    if both == B::ZERO {
+        return None;
+    }
+
+    let splitter = both.lowest_set_bit();
+    let first = find_unique_owned(vals.filter(|&val| val & splitter == B::ZERO)).unwrap_or(B::ZERO);
+    let second = first ^ both;
+    Some((first.min(second), first.max(second)))
+}
+
+/// Finds element that appears once. Other elements must appear exactly three times (or a multiple of three times).
+/// Uses two-accumulator state machine: every bit position counts its ones modulo 3, *ones* holds the positions with count 1 and *twos* with count 2
+/// # Arguments
+/// * `vals` - IntoIterator instance yielding the elements
+/// # Examples
+/// ```
+/// # use understanding_bitwise::find_unique_among_triples;
+/// assert_eq!(find_unique_among_triples([5, 9, 5, 5, 7, 7, 7]), Some(9));
+/// assert_eq!(find_unique_among_triples(std::iter::empty::<u32>()), None);
+/// ```
+pub fn find_unique_among_triples<I, B>(vals: I) -> Option<B>
+where
+    I: IntoIterator<Item = B>,
+    B: XorWord {
+    let mut vals = vals.into_iter().peekable();
+    vals.peek()?;
+    let (ones, _) = vals.fold((B::ZERO, B::ZERO), |(ones, twos), val| {
+        let ones = (ones ^ val) & ! twos;
+        let twos = (twos ^ val) & ! ones;
+        (ones, twos)
+    });
+    Some(ones)
+}
+
+/// Returns how many numbers have each bit set. Element *i* is the count for the bit *i*.
+/// Counting modulo *k* finds the element among *k*-plicates, see *find_unique_among_triples*
+/// # Arguments
+/// * `vals` - IntoIterator instance yielding the numbers
+/// # Examples
+/// ```
+/// # use understanding_bitwise::bit_position_counts;
+/// let counts = bit_position_counts([0b011, 0b110, 0b010]);
+/// assert_eq!(counts[..4], [1, 3, 1, 0]);
+/// ```
+pub fn bit_position_counts<I>(vals: I) -> [u64; u32::BITS as usize]
+where
+    I: IntoIterator<Item = u32> {
+    let mut counts = [0; u32::BITS as usize];
+    for val in vals {
+        for (index, count) in counts.iter_mut().enumerate() {
+            *count += (val >> index & 1) as u64;
+        }
+    }
+    counts
+}
+
+/// Returns the word with each bit set if it's set in more than half of the numbers (bitwise majority vote,
+/// e.g. for repeated noisy reads), or None if there are no numbers
+/// # Arguments
+/// * `vals` - IntoIterator instance yielding the numbers
+/// # Examples
+/// ```
+/// # use understanding_bitwise::majority_word;
+/// assert_eq!(majority_word([0b1011, 0b0011, 0b1110]), Some(0b1011));
+/// assert_eq!(majority_word([]), None);
+/// ```
+pub fn majority_word<I>(vals: I) -> Option<u32>
+where
+    I: IntoIterator<Item = u32> {
+    let mut total = 0u64;
+    let counts = bit_position_counts(vals.into_iter().inspect(|_| total += 1));
+    if total == 0 {
+        return None;
+    }
+    Some(counts.iter().enumerate().fold(0, |word, (index, &count)| word | ((count * 2 > total) as u32) << index))
+}
+
+/// Helper function. Returns XOR of all numbers of 0..end in O(1): XOR of 0..=n cycles through n, 1, n + 1, 0 depending on n % 4
+/// # Arguments
+/// * `end` - end of the range, excluded
+fn xor_up_to(end: u32) -> u32 {
+    match end.checked_sub(1) {
+        None => 0,
+        Some(last) => match last % 4 {
+            0 => last,
+            1 => 1,
+            2 => last + 1,
+            _ => 0,
+        },
+    }
+}
+
+/// Helper function. Returns XOR of all numbers of the range and all elements, with the count of elements
+/// # Arguments
+/// * `vals` - IntoIterator instance yielding the elements
+/// * `range` - range of the numbers
+fn xor_against_range<I>(vals: I, range: &Range<u32>) -> (u32, usize)
+where
+    I: IntoIterator<Item = u32> {
+    let range_xor = xor_up_to(range.end) ^ xor_up_to(range.start);
+    vals.into_iter().fold((range_xor, 0), |(acc, count), val| (acc ^ val, count + 1))
+}
+
+/// Finds the number of the range missing among the elements. The elements must be all numbers of the range except one, in any order.
+/// Returns None if the count of elements doesn't match or the result isn't in the range
+/// # Arguments
+/// * `vals` - IntoIterator instance yielding the elements
+/// * `range` - range of the numbers
+/// # Examples
+/// ```
+/// # use understanding_bitwise::find_missing_in_range;
+/// assert_eq!(find_missing_in_range([3, 0, 1], 0..4), Some(2));
+/// assert_eq!(find_missing_in_range([3, 0, 1, 2], 0..4), None);
+/// ```
+pub fn find_missing_in_range<I>(vals: I, range: Range<u32>) -> Option<u32>
+where
+    I: IntoIterator<Item = u32> {
+    let (missing, count) = xor_against_range(vals, &range);
+    (count + 1 == range.len() && range.contains(&missing)).then_some(missing)
+}
+
+/// Finds the number of the range that appears twice. The elements must be all numbers of the range plus one extra copy of one of them, in any order.
+/// Returns None if the count of elements doesn't match or the result isn't in the range
+/// # Arguments
+/// * `vals` - IntoIterator instance yielding the elements
+/// * `range` - range of the numbers
+/// # Examples
+/// ```
+/// # use understanding_bitwise::find_duplicate_with_one_extra;
+/// assert_eq!(find_duplicate_with_one_extra([1, 3, 4, 2, 3], 1..5), Some(3));
+/// assert_eq!(find_duplicate_with_one_extra([1, 3, 4, 2], 1..5), None);
+/// ```
+pub fn find_duplicate_with_one_extra<I>(vals: I, range: Range<u32>) -> Option<u32>
+where
+    I: IntoIterator<Item = u32> {
+    let (duplicate, count) = xor_against_range(vals, &range);
+    (count == range.len() + 1 && range.contains(&duplicate)).then_some(duplicate)
+}
+
+/// Error of *find_unique_checked*
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum UniqueError<B> {
+    /// There are no elements
+    Empty,
+    /// XOR of all elements appears an even number of times, so the elements break the precondition
+    EvenOccurrences { candidate: B, occurrences: usize },
+}
+
+impl<B: fmt::Debug> fmt::Display for UniqueError<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UniqueError::Empty => write!(f, "no elements to search"),
+            UniqueError::EvenOccurrences { candidate, occurrences } => write!(
+                f,
+                "XOR of all elements is {candidate:?}, but it occurs {occurrences} times; other elements must occur an even number of times"
+            ),
+        }
+    }
+}
+
+impl<B: fmt::Debug> Error for UniqueError<B> {}
+
+/// Finds element that doesn't have duplicate like *find_unique*, but verifies the result by one extra pass:
+/// the found element must occur an odd number of times, otherwise the precondition is broken and the XOR residue is garbage
+/// # Arguments
+/// * `vals` - IntoIterator instance, iterated twice
+/// # Examples
+/// ```
+/// # use understanding_bitwise::{find_unique_checked, UniqueError};
+/// assert_eq!(find_unique_checked([45, 32, 777, 45, 32]), Ok(777));
+/// assert_eq!(find_unique_checked([1, 2]), Err(UniqueError::EvenOccurrences { candidate: 3, occurrences: 0 }));
+/// ```
+pub fn find_unique_checked<I, B>(vals: I) -> Result<B, UniqueError<B>>
+where
+    I: IntoIterator<Item = B>,
+    I::IntoIter: Clone,
+    B: XorWord {
+    let vals = vals.into_iter();
+    let candidate = find_unique_owned(vals.clone()).ok_or(UniqueError::Empty)?;
+    let occurrences = vals.filter(|&val| val == candidate).count();
+    if occurrences % 2 == 1 {
+        Ok(candidate)
+    } else {
+        Err(UniqueError::EvenOccurrences { candidate, occurrences })
+    }
+}
+
+/// Incremental variant of *find_unique* for elements arriving in chunks (network frames, file blocks)
+/// # Examples
+/// ```
+/// # use understanding_bitwise::UniqueFinder;
+/// let mut finder = UniqueFinder::new();
+/// finder.push(45);
+/// finder.extend([32, 777]);
+/// finder.extend(&[45, 32]);
+/// assert_eq!(finder.finish(), Some(777));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UniqueFinder<B> {
+    acc: Option<B>,
+}
+
+impl<B> Default for UniqueFinder<B> {
+    fn default() -> Self {
+        UniqueFinder { acc: None }
+    }
+}
+
+impl<B: BitXor<Output = B> + Copy> UniqueFinder<B> {
+    /// Returns the finder without elements
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the element
+    /// # Arguments
+    /// * `val` - element to be added
+    pub fn push(&mut self, val: B) {
+        self.acc = Some(self.acc.map_or(val, |acc| acc ^ val));
+    }
+
+    /// Returns the XOR of all elements so far or None if there are no elements. The finder can be used further
+    pub fn current(&self) -> Option<B> {
+        self.acc
+    }
+
+    /// Returns the unique element or None if there were no elements
+    pub fn finish(self) -> Option<B> {
+        self.acc
+    }
+}
+
+impl<B: BitXor<Output = B> + Copy> Extend<B> for UniqueFinder<B> {
+    fn extend<T: IntoIterator<Item = B>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|val| self.push(val));
+    }
+}
+
+impl<'a, B: 'a + BitXor<Output = B> + Copy> Extend<&'a B> for UniqueFinder<B> {
+    fn extend<T: IntoIterator<Item = &'a B>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_power_of_two() {
+        assert_eq!(power_of_two(0), Ok(1));
+        assert_eq!(power_of_two(2), Ok(4));
+        assert_eq!(power_of_two(3), Ok(8));
+        assert_eq!(power_of_two(31), Ok(2147483648));
+        assert_eq!(power_of_two(32), Err(Overflow { power: 32, max_power: 31 }));
+        assert_eq!(power_of_two(u32::MAX), Err(Overflow { power: u32::MAX, max_power: 31 }));
+
+        let boxed: Box<dyn Error> = power_of_two(40).unwrap_err().into();
+        assert_eq!(boxed.to_string(), "2^40 overflows, the maximum representable power is 31");
+    }
+
+    #[test]
+    fn test_write_binary_representation() {
+        let bin_rep_str = |number| -> String {
+            let mut vec = Vec::<u8>::with_capacity(u32::BITS as usize);
+            write_binary_representation(number, &mut vec);
+            std::str::from_utf8(vec.as_slice()).unwrap().to_owned()
+        };
+        
+        let mut str = bin_rep_str(0b11100100);
+        assert_eq!(str, String::from("11100100"));
+
+        str = bin_rep_str(u32::MAX);
+        assert_eq!(str, String::from("11111111111111111111111111111111"));
+        
+        str = bin_rep_str(u32::MIN);
+        assert_eq!(str, String::from("0"));
+        
+        str = bin_rep_str(1);
+        assert_eq!(str, String::from("1"));
+    }
+
+    #[test]
+    fn test_write_binary_representation_single_write() {
+        /// Writer accepting everything in a single call and counting the calls
+        struct CountingWriter(usize);
+
+        impl Write for CountingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0 += 1;
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = CountingWriter(0);
+        write_binary_representation(u32::MAX, &mut writer);
+        assert_eq!(writer.0, 1);
+    }
+
+    #[test]
+    fn test_bool_conversions() {
+        for number in [0, 1, 0b1011_0010, 1 << 31, u32::MAX, 0xDEAD_BEEF] {
+            let array = to_bool_array(number);
+            assert_eq!(array.iter().filter(|&&bit| bit).count() as u32, number.count_ones());
+            assert_eq!(from_bool_array(array), number);
+            assert_eq!(from_bits(to_bool_vec(number)), Ok(number));
+            assert_eq!(to_bool_vec(number).len() as u32, hob(number).unwrap_or(0) + 1);
+        }
+        assert_eq!(from_bits([]), Ok(0));
+        assert_eq!(from_bits(std::iter::repeat_n(true, 32)), Ok(u32::MAX));
+        assert_eq!(from_bits(std::iter::repeat(false)), Err(Overflow { power: 32, max_power: 31 }));
+    }
+
+    #[test]
+    fn test_parse_number() {
+        for number in [0, 1, 0b1011_0010, 1 << 31, u32::MAX, 0xDEAD_BEEF] {
+            assert_eq!(parse_number(&format!("{number:#b}")), Some(number));
+            assert_eq!(parse_number(&format!("{number:#X}")), Some(number));
+            assert_eq!(parse_number(&number.to_string()), Some(number));
+        }
+        assert_eq!(parse_number("1_000"), Some(1000));
+        assert_eq!(parse_number("0B11"), Some(3));
+        for text in ["", "0x", "0b2", "_1", "1_", "+1", "-1", "0x+1", "4294967296", "1e3"] {
+            assert_eq!(parse_number(text), None, "{text}");
+        }
+    }
+
+    fn general_test_binary_ones_count<F>(f: F)
+    where
+        F: Fn(u32) -> u32 {
+        let mut count = f(0b11100100);
         assert_eq!(count, 4);
         
         count = f(u32::MAX);
@@ -532,6 +2043,16 @@ mod tests {
         general_test_binary_ones_count(binary_ones_count_sub_method);
     }
 
+    #[test]
+    fn test_binary_ones_count_swar() {
+        general_test_binary_ones_count(binary_ones_count_swar);
+    }
+
+    #[test]
+    fn test_binary_ones_count_lut() {
+        general_test_binary_ones_count(binary_ones_count_lut);
+    }
+
     fn general_test_hob<F>(f: F)
     where
         F: Fn(u32) -> Option<u32> {
@@ -572,6 +2093,69 @@ mod tests {
         general_test_hob(hob_comp_pot);
     }
     
+    #[test]
+    fn test_byte_tables() {
+        for byte in 0..=u8::MAX {
+            assert_eq!(BYTE_ONES_COUNT[byte as usize] as u32, byte.count_ones());
+            if byte != 0 {
+                assert_eq!(BYTE_HOB[byte as usize] as u32, u8::BITS - 1 - byte.leading_zeros());
+            }
+        }
+    }
+
+    #[test]
+    fn test_hob_binary_search() {
+        general_test_hob(hob_binary_search);
+    }
+
+    #[test]
+    fn test_hob_lut() {
+        general_test_hob(hob_lut);
+    }
+
+    #[test]
+    fn test_hob_debruijn() {
+        general_test_hob(hob_debruijn);
+    }
+
+    #[test]
+    fn test_hob_methods() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for (name, method) in HOB_METHODS {
+            general_test_hob(method);
+            for number in (0..1000).map(|_| rng.next_u32()).chain((0..u32::BITS).map(|index| 1 << index)) {
+                assert_eq!(method(number), number.checked_ilog2(), "{name}({number:#x})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_trailing_zeros_debruijn() {
+        assert_eq!(trailing_zeros_debruijn(0), None);
+        for index in 0..u32::BITS {
+            assert_eq!(trailing_zeros_debruijn(1 << index), Some(index));
+            assert_eq!(trailing_zeros_debruijn(u32::MAX << index), Some(index));
+        }
+        assert_eq!(trailing_zeros_debruijn(0b1011_0000), Some(4));
+    }
+
+    #[test]
+    fn test_de_bruijn_sequence() {
+        for order in 1..=6 {
+            let sequence = de_bruijn_sequence(order).unwrap();
+            let length = 1u32 << order;
+            let mut windows: Vec<u64> = (0..length)
+                .map(|start| (start..start + order)
+                    .map(|position| sequence >> length - 1 - position % length & 1)
+                    .fold(0, |window, bit| window << 1 | bit))
+                .collect();
+            windows.sort();
+            assert_eq!(windows, (0..1 << order).collect::<Vec<u64>>());
+        }
+        assert_eq!(de_bruijn_sequence(0), None);
+        assert_eq!(de_bruijn_sequence(7), None);
+    }
+
     #[test]
     fn test_set_bit() {
         assert_eq!(set_bit(9, 1), Some(11));
@@ -656,6 +2240,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_rotation_of() {
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                match is_rotation_of(a, b) {
+                    Some(count) => {
+                        assert_eq!(circular_shl(a, count), b);
+                        assert!((0..count).all(|smaller| circular_shl(a, smaller) != b));
+                        // The other way round is a rotation too
+                        assert_eq!(circular_shr(b, count), a);
+                        assert!(is_rotation_of(b, a).is_some());
+                    }
+                    None => assert!((0..u8::BITS).all(|count| circular_shl(a, count) != b)),
+                }
+            }
+        }
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let count = state % 32;
+            let found = is_rotation_of_u32(state, state.rotate_left(count)).unwrap();
+            assert_eq!(state.rotate_left(found), state.rotate_left(count));
+            assert!(found <= count);
+            assert_eq!(is_rotation_of_u32(state, state.rotate_left(count) ^ 1), None);
+        }
+        assert_eq!((is_rotation_of_u32(0, 0), is_rotation_of_u32(u32::MAX, u32::MAX)), (Some(0), Some(0)));
+    }
+
     #[test]
     fn test_consecutive_ones_number() {
         let mut number = 1;
@@ -684,6 +2296,22 @@ mod tests {
         assert_eq!(consecutive_ones_entries_count(0, 1).unwrap(), 0);
     }
 
+    #[test]
+    fn test_consecutive_ones_entries_count_runs() {
+        let mut rng = Xorshift32::new(0x1234_5678).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            for number in [state, state & state >> 1, state | state << 3] {
+                for count in 0..=u32::BITS + 1 {
+                    assert_eq!(consecutive_ones_entries_count_runs(number, count), consecutive_ones_entries_count(number, count));
+                }
+            }
+        }
+        assert_eq!(consecutive_ones_entries_count_runs(u32::MAX, 32), Some(1));
+        assert_eq!(consecutive_ones_entries_count_runs(u32::MAX, 1), Some(32));
+        assert_eq!(consecutive_ones_entries_count_runs(0, 1), Some(0));
+    }
+
     fn general_test_swap_bits<F>(f: F)
     where
         F: Fn(u32, u32, u32) -> Option<u32> {
@@ -724,9 +2352,249 @@ mod tests {
         assert_eq!(remove_bit(0b1011, 1), Some(0b101));
         assert_eq!(remove_bit(0, 0), Some(0));
         assert_eq!(remove_bit(228, 228), None);
+        assert_eq!(remove_bit(1 << 31 | 5, 31), Some(5));
         assert_eq!(1, (0..u32::BITS-1).fold(u32::MAX, |acc, _| remove_bit(acc, 0).unwrap()));
     }
 
+    #[test]
+    fn test_try_variants() {
+        assert_eq!(try_set_bit(0, 31), Ok(1 << 31));
+        assert_eq!(try_unset_bit(u32::MAX, 0), Ok(u32::MAX - 1));
+        assert_eq!(try_invert_bit(1, 0), Ok(0));
+        assert_eq!(try_swap_bits(0b10, 1, 0), Ok(0b01));
+        assert_eq!(try_remove_bit(0b110, 0), Ok(0b11));
+
+        let error = try_invert_bit(0, 32).unwrap_err();
+        assert_eq!(error.operation(), "invert_bit");
+        assert_eq!(error.index(), 32);
+        assert_eq!(error.valid_range(), 0..32);
+        assert_eq!(try_swap_bits(0, 33, 34).unwrap_err().index(), 33);
+        assert_eq!(try_remove_bit(0, u32::MAX).unwrap_err().to_string(), "remove_bit: index 4294967295 is out of range 0..32");
+
+        let boxed: Box<dyn Error> = Box::new(try_unset_bit(0, 40).unwrap_err());
+        assert_eq!(boxed.to_string(), "unset_bit: index 40 is out of range 0..32");
+    }
+
+    #[test]
+    fn test_unchecked_variants() {
+        let mut rng = Xorshift32::new(0xDEAD_BEEF).unwrap();
+        for _ in 0..100 {
+            let state = rng.next_u32();
+            for index in 0..u32::BITS {
+                assert_eq!(Some(set_bit_unchecked(state, index)), set_bit(state, index));
+                assert_eq!(Some(unset_bit_unchecked(state, index)), unset_bit(state, index));
+                assert_eq!(Some(invert_bit_unchecked(state, index)), invert_bit(state, index));
+                assert_eq!(Some(swap_bits_unchecked(state, index, 31 - index)), swap_bits(state, index, 31 - index));
+                assert_eq!(Some(set_bit_panicking(state, index)), set_bit(state, index));
+                assert_eq!(Some(unset_bit_panicking(state, index)), unset_bit(state, index));
+                assert_eq!(Some(invert_bit_panicking(state, index)), invert_bit(state, index));
+                assert_eq!(Some(swap_bits_panicking(state, index, 0)), swap_bits(state, index, 0));
+                assert_eq!(Some(remove_bit_unchecked(state, index)), remove_bit(state, index));
+                assert_eq!(Some(remove_bit_panicking(state, index)), remove_bit(state, index));
+            }
+        }
+        assert_eq!(remove_bit_unchecked(u32::MAX, 31), i32::MAX as u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "swap_bits: index 32 is out of range 0..32")]
+    fn test_swap_bits_panicking_out_of_range() {
+        swap_bits_panicking(0, 1, 32);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "set_bit: index 32 is out of range 0..32")]
+    fn test_set_bit_unchecked_debug_assertion() {
+        set_bit_unchecked(0, 32);
+    }
+
+    #[test]
+    fn test_wrapping_variants() {
+        let number = 0b1010_0110u32;
+        for index in [0, 5, 31, 32, 37, 63, u32::MAX] {
+            let wrapped = index % 32;
+            assert_eq!(set_bit_wrapping(number, index), set_bit_unchecked(number, wrapped));
+            assert_eq!(unset_bit_wrapping(number, index), unset_bit_unchecked(number, wrapped));
+            assert_eq!(invert_bit_wrapping(number, index), invert_bit_unchecked(number, wrapped));
+            assert_eq!(swap_bits_wrapping(number, index, 2), swap_bits_unchecked(number, wrapped, 2));
+            assert_eq!(remove_bit_wrapping(number, index), remove_bit_unchecked(number, wrapped));
+        }
+        assert_eq!(set_bit_wrapping(0, u32::MAX), 1 << 31);
+    }
+
+    #[test]
+    fn test_usize_variants() {
+        let number = 0b1010_0110u32;
+        for index in 0..u32::BITS {
+            let wide = index as usize;
+            assert_eq!(set_bit_usize(number, wide), set_bit(number, index));
+            assert_eq!(unset_bit_usize(number, wide), unset_bit(number, index));
+            assert_eq!(invert_bit_usize(number, wide), invert_bit(number, index));
+            assert_eq!(swap_bits_usize(number, wide, 1), swap_bits(number, index, 1));
+            assert_eq!(remove_bit_usize(number, wide), remove_bit(number, index));
+        }
+        for index in [32, u32::MAX as usize, usize::MAX] {
+            assert_eq!(set_bit_usize(number, index), None);
+            assert_eq!(unset_bit_usize(number, index), None);
+            assert_eq!(invert_bit_usize(number, index), None);
+            assert_eq!(swap_bits_usize(number, 0, index), None);
+            assert_eq!(remove_bit_usize(number, index), None);
+        }
+    }
+
+    #[test]
+    fn test_get_bits() {
+        assert_eq!(get_bits(0b1011_0110, 0, 4), Some(0b0110));
+        assert_eq!(get_bits(0b1011_0110, 4, 4), Some(0b1011));
+        assert_eq!(get_bits(u32::MAX, 0, 32), Some(u32::MAX));
+        assert_eq!(get_bits(1 << 31, 31, 1), Some(1));
+        assert_eq!(get_bits(u32::MAX, 32, 0), Some(0));
+        assert_eq!(get_bits(u32::MAX, 5, 0), Some(0));
+        assert_eq!(get_bits(u32::MAX, 1, 32), None);
+        assert_eq!(get_bits(u32::MAX, u32::MAX, 2), None);
+        for index in 0..u32::BITS {
+            assert_eq!(get_bits(0b10101010101010101010101010101010, index, 1), Some(index % 2));
+        }
+    }
+
+    #[test]
+    fn test_set_bits() {
+        assert_eq!(set_bits(0, 0, 4, 0b1010), Some(0b1010));
+        assert_eq!(set_bits(u32::MAX, 4, 8, 0), Some(0xFFFF_F00F));
+        assert_eq!(set_bits(0, 0, 32, u32::MAX), Some(u32::MAX));
+        assert_eq!(set_bits(0, 31, 1, 1), Some(1 << 31));
+        assert_eq!(set_bits(7, 32, 0, 0), Some(7));
+        assert_eq!(set_bits(0, 0, 4, 0b10000), None);
+        assert_eq!(set_bits(0, 30, 4, 0), None);
+        for offset in 0..u32::BITS {
+            for width in 0..=u32::BITS - offset {
+                let value = 0x5A5A_5A5A & consecutive_ones_number(width).unwrap_or(0);
+                let number = set_bits(0xDEAD_BEEF, offset, width, value).unwrap();
+                assert_eq!(get_bits(number, offset, width), Some(value));
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_set_bits_u64() {
+        assert_eq!(get_bits_u64(u64::MAX, 0, 64), Some(u64::MAX));
+        assert_eq!(get_bits_u64(0xF0, 4, 4), Some(0xF));
+        assert_eq!(get_bits_u64(0, 64, 1), None);
+        assert_eq!(set_bits_u64(0, 32, 32, u32::MAX as u64), Some(0xFFFF_FFFF_0000_0000));
+        assert_eq!(set_bits_u64(0, 63, 1, 2), None);
+        assert_eq!(set_bits_u64(1, 64, 0, 0), Some(1));
+    }
+
+    #[test]
+    fn test_sign_extend() {
+        assert_eq!(sign_extend(0, 1), 0);
+        assert_eq!(sign_extend(1, 1), -1);
+        assert_eq!(sign_extend(0x7F, 8), 127);
+        assert_eq!(sign_extend(0x80, 8), -128);
+        assert_eq!(sign_extend(0xFF80, 8), -128);
+        assert_eq!(sign_extend(0x8000_0000, 32), i32::MIN);
+        assert_eq!(sign_extend(u32::MAX, 32), -1);
+        for from_bits in 1..=16 {
+            for value in -(1i32 << from_bits - 1)..(1 << from_bits - 1) {
+                let truncated = value as u32 & consecutive_ones_number(from_bits).unwrap();
+                assert_eq!(sign_extend(truncated, from_bits), value);
+                assert_eq!(sign_extend_checked(truncated, from_bits), Some(value));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sign_extend_zero_bits() {
+        sign_extend(0, 0);
+    }
+
+    #[test]
+    fn test_sign_extend_checked() {
+        assert_eq!(sign_extend_checked(0x80, 8), Some(-128));
+        assert_eq!(sign_extend_checked(0x180, 8), None);
+        assert_eq!(sign_extend_checked(0, 0), None);
+        assert_eq!(sign_extend_checked(0, 33), None);
+        assert_eq!(sign_extend_checked(u32::MAX, 32), Some(-1));
+    }
+
+    #[test]
+    fn test_zero_extend() {
+        assert_eq!(zero_extend(0xFFFF_FFFF, 0), 0);
+        assert_eq!(zero_extend(0xABCD, 8), 0xCD);
+        assert_eq!(zero_extend(0xABCD, 32), 0xABCD);
+        assert_eq!(zero_extend_checked(0xCD, 8), Some(0xCD));
+        assert_eq!(zero_extend_checked(0xABCD, 8), None);
+        assert_eq!(zero_extend_checked(0, 0), Some(0));
+        assert_eq!(zero_extend_checked(0, 33), None);
+    }
+
+    fn general_test_pext<F>(f: F)
+    where
+        F: Fn(u32, u32) -> u32 {
+        assert_eq!(f(0b1011_0110, 0b1111_0000), 0b1011);
+        assert_eq!(f(0b1011_0110, 0b1010_1010), 0b1101);
+        assert_eq!(f(u32::MAX, 0), 0);
+        assert_eq!(f(0x1234_5678, u32::MAX), 0x1234_5678);
+        assert_eq!(f(1 << 31, 1 << 31), 1);
+        assert_eq!(f(0xDEAD_BEEF, 0xFFFF_0000), 0xDEAD);
+        // Exhaustive over 8-bit values and masks
+        for mask in 0..=u8::MAX as u32 {
+            for number in 0..=u8::MAX as u32 {
+                let mut expected = 0;
+                let mut count = 0;
+                for index in 0..u8::BITS {
+                    if mask >> index & 1 == 1 {
+                        expected |= (number >> index & 1) << count;
+                        count += 1;
+                    }
+                }
+                assert_eq!(f(number, mask), expected);
+                assert_eq!(f(number << 24, mask << 24), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pext() {
+        general_test_pext(pext);
+    }
+
+    #[test]
+    fn test_pext_swar() {
+        general_test_pext(pext_swar);
+    }
+
+    fn general_test_pdep<F>(f: F)
+    where
+        F: Fn(u32, u32) -> u32 {
+        assert_eq!(f(0b1011, 0b1111_0000), 0b1011_0000);
+        assert_eq!(f(0b1001, 0b1010_1010), 0b1000_0010);
+        assert_eq!(f(u32::MAX, 0), 0);
+        assert_eq!(f(0x1234_5678, u32::MAX), 0x1234_5678);
+        assert_eq!(f(1, 1 << 31), 1 << 31);
+        assert_eq!(f(0xDEAD, 0xFFFF_0000), 0xDEAD_0000);
+        // Exhaustive over 8-bit values and masks
+        for mask in 0..=u8::MAX as u32 {
+            for number in 0..=u8::MAX as u32 {
+                assert_eq!(pext(f(number, mask), mask), number & consecutive_ones_number(binary_ones_count(mask)).unwrap_or(0));
+                assert_eq!(f(pext(number, mask), mask), number & mask);
+                assert_eq!(f(number, mask << 24), f(number, mask) << 24);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pdep() {
+        general_test_pdep(pdep);
+    }
+
+    #[test]
+    fn test_pdep_swar() {
+        general_test_pdep(pdep_swar);
+    }
+
     #[test]
     fn test_find_unique() {
         assert_eq!(find_unique(&[1, 0, 2, 2, 0, 228, 1]), Some(228));
@@ -735,4 +2603,110 @@ mod tests {
         assert_eq!(find_unique(&vec![0u32; 0]), None);
         assert_eq!(find_unique(&Vec::<u32>::new()), None);
     }
+
+    #[test]
+    fn test_find_two_uniques() {
+        assert_eq!(find_two_uniques(vec![1, 0, 2, 2, 0, 228, 1, 5]), Some((5, 228)));
+        assert_eq!(find_two_uniques([0u8, 255]), Some((0, 255)));
+        assert_eq!(find_two_uniques([7u64, 7, 9, 9]), None);
+        assert_eq!(find_two_uniques(std::iter::empty::<u32>()), None);
+        assert_eq!(find_two_uniques((0..1000u32).chain(2..1000)), Some((0, 1)));
+        assert_eq!(find_two_uniques([u128::MAX, 1 << 100]), Some((1 << 100, u128::MAX)));
+    }
+
+    #[test]
+    fn test_find_unique_among_triples() {
+        assert_eq!(find_unique_among_triples([2, 2, 3, 2]), Some(3));
+        assert_eq!(find_unique_among_triples([0u8, 1, 0, 1, 0, 1, 99]), Some(99));
+        assert_eq!(find_unique_among_triples([-4i32, -4, -4, -5]), Some(-5));
+        assert_eq!(find_unique_among_triples([7u64, 7, 7, 0]), Some(0));
+        assert_eq!(find_unique_among_triples((0..100u32).chain([42]).chain(0..100).chain(0..100)), Some(42));
+        assert_eq!(find_unique_among_triples([u32::MAX]), Some(u32::MAX));
+    }
+
+    #[test]
+    fn test_bit_position_counts() {
+        assert_eq!(bit_position_counts([]), [0; 32]);
+        assert_eq!(bit_position_counts([u32::MAX; 5]), [5; 32]);
+        let vals = [7u32, 7, 7, 12, 12, 12, 9];
+        let unique = bit_position_counts(vals).iter().enumerate().fold(0, |word, (index, &count)| word | ((count % 3) as u32) << index);
+        assert_eq!(Some(unique), find_unique_among_triples(vals));
+    }
+
+    #[test]
+    fn test_majority_word() {
+        assert_eq!(majority_word([0xDEAD_BEEF]), Some(0xDEAD_BEEF));
+        assert_eq!(majority_word([0b01, 0b10]), Some(0));
+        assert_eq!(majority_word([0xFF00, 0x0FF0, 0x00FF]), Some(0x0FF0));
+        let noisy = [0xCAFE_F00D, 0xCAFE_F00D ^ 1, 0xCAFE_F00D ^ 1 << 31, 0xCAFE_F00D, 0xCAFE_F00D ^ 0x100];
+        assert_eq!(majority_word(noisy), Some(0xCAFE_F00D));
+    }
+
+    #[test]
+    fn test_xor_up_to() {
+        let mut expected = 0;
+        for end in 0..100 {
+            assert_eq!(xor_up_to(end), expected);
+            expected ^= end;
+        }
+        // Last number is 2^32 - 2, which is 2 modulo 4
+        assert_eq!(xor_up_to(u32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn test_find_missing_in_range() {
+        for missing in 5..50 {
+            let vals = (5..50).filter(|&val| val != missing).rev();
+            assert_eq!(find_missing_in_range(vals, 5..50), Some(missing));
+        }
+        assert_eq!(find_missing_in_range([], 7..8), Some(7));
+        assert_eq!(find_missing_in_range([], 7..7), None);
+        assert_eq!(find_missing_in_range([1, 1], 0..3), None);
+        assert_eq!(find_missing_in_range([0, 1, 2], 0..3), None);
+    }
+
+    #[test]
+    fn test_find_duplicate_with_one_extra() {
+        for duplicate in 0..30 {
+            let vals = (0..30).chain([duplicate]);
+            assert_eq!(find_duplicate_with_one_extra(vals, 0..30), Some(duplicate));
+        }
+        assert_eq!(find_duplicate_with_one_extra([9, 9], 9..10), Some(9));
+        assert_eq!(find_duplicate_with_one_extra([1, 2, 3], 1..3), None);
+        assert_eq!(find_duplicate_with_one_extra([0, 1, 2], 0..3), None);
+    }
+
+    #[test]
+    fn test_find_unique_checked() {
+        assert_eq!(find_unique_checked(vec![1, 0, 2, 2, 0, 228, 1]), Ok(228));
+        assert_eq!(find_unique_checked([5u8, 5, 5]), Ok(5));
+        assert_eq!(find_unique_checked(std::iter::empty::<u32>()), Err(UniqueError::Empty));
+        assert_eq!(find_unique_checked([4, 4]), Err(UniqueError::EvenOccurrences { candidate: 0, occurrences: 0 }));
+        assert_eq!(find_unique_checked([1u64, 2, 3, 0, 0]), Err(UniqueError::EvenOccurrences { candidate: 0, occurrences: 2 }));
+        let error: Box<dyn Error> = Box::new(find_unique_checked([1, 2]).unwrap_err());
+        assert_eq!(error.to_string(), "XOR of all elements is 3, but it occurs 0 times; other elements must occur an even number of times");
+        assert_eq!(UniqueError::<u32>::Empty.to_string(), "no elements to search");
+    }
+
+    #[test]
+    fn test_unique_finder() {
+        let vals = [1, 0, 2, 2, 0, 228, 1];
+        for chunk_size in 1..=vals.len() {
+            let mut finder = UniqueFinder::new();
+            for chunk in vals.chunks(chunk_size) {
+                finder.extend(chunk);
+            }
+            assert_eq!(finder.current(), find_unique(&vals));
+            assert_eq!(finder.finish(), Some(228));
+        }
+        assert_eq!(UniqueFinder::<u32>::new().finish(), None);
+    }
+
+    #[test]
+    fn test_find_unique_owned() {
+        assert_eq!(find_unique_owned(vec![1, 0, 2, 2, 0, 228, 1]), Some(228));
+        assert_eq!(find_unique_owned((1..=100u64).chain(1..100)), Some(100));
+        assert_eq!(find_unique_owned(Vec::<String>::new().iter().map(String::len)), None);
+        assert_eq!(find_unique_owned([true, false, true, true]), Some(true));
+    }
 }
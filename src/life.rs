@@ -0,0 +1,133 @@
+//! [*Conway's Game of Life*](https://en.wikipedia.org/wiki/Conway%27s_Game_of_Life) on rows of 64 cells,
+//! the cell *i* of a row is the bit *i*. The 8 neighbours of all cells of a row are shifted rows, so a tree of
+//! bit-parallel full adders counts the neighbours of the 64 cells at once, each count bit in its own word (bit-sliced)
+
+/// Treatment of the cells beyond the edges
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Edges {
+    /// Cells beyond the edges are dead
+    Dead,
+    /// The grid is a torus: the first row neighbours the last one and the bit 0 neighbours the bit 63
+    Wrap,
+}
+
+/// Helper function. Returns the sum and the carry bits of the three numbers added bit by bit
+/// # Arguments
+/// * `a` - first addend
+/// * `b` - second addend
+/// * `c` - third addend
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, a & b | partial & c)
+}
+
+/// Helper function. Returns the row and its neighbours on both sides: the cells *i - 1* and *i + 1* of every cell *i*
+/// # Arguments
+/// * `row` - row to work with
+/// * `edges` - treatment of the cells beyond the edges
+fn with_sides(row: u64, edges: Edges) -> [u64; 3] {
+    match edges {
+        Edges::Dead => [row << 1, row, row >> 1],
+        Edges::Wrap => [row.rotate_left(1), row, row.rotate_right(1)],
+    }
+}
+
+/// Returns the next generation of the rows. Alive cells with 2 or 3 alive neighbours survive,
+/// dead cells with 3 alive neighbours are born
+/// # Arguments
+/// * `rows` - cells, one bit per cell
+/// * `edges` - treatment of the cells beyond the edges
+/// # Examples
+/// ```
+/// # use understanding_bitwise::life::{life_step, Edges};
+/// // Blinker turns from horizontal to vertical
+/// let rows = [0, 0b111, 0];
+/// assert_eq!(life_step(&rows, Edges::Dead), vec![0b010, 0b010, 0b010]);
+/// ```
+pub fn life_step(rows: &[u64], edges: Edges) -> Vec<u64> {
+    let count = rows.len();
+    let row = |index: Option<usize>| index.map_or(0, |index| rows[index]);
+    (0..count).map(|index| {
+        let (above, below) = match edges {
+            Edges::Dead => (index.checked_sub(1), Some(index + 1).filter(|&below| below < count)),
+            Edges::Wrap => (Some((index + count - 1) % count), Some((index + 1) % count)),
+        };
+        let [north_west, north, north_east] = with_sides(row(above), edges);
+        let [west, alive, east] = with_sides(rows[index], edges);
+        let [south_west, south, south_east] = with_sides(row(below), edges);
+        // Every triple is summed into a 2-bit count, then the three counts are added
+        let (north_ones, north_twos) = full_add(north_west, north, north_east);
+        let (side_ones, side_twos) = full_add(west, east, 0);
+        let (south_ones, south_twos) = full_add(south_west, south, south_east);
+        let (ones, carry) = full_add(north_ones, side_ones, south_ones);
+        let (twos_partial, fours_partial) = full_add(north_twos, side_twos, south_twos);
+        let twos = twos_partial ^ carry;
+        // 8 neighbours wrap to 0, which doesn't matter: only 2 and 3 keep a cell alive
+        let fours = fours_partial ^ twos_partial & carry;
+        twos & ! fours & (ones | alive)
+    }).collect()
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    fn life_step_naive(rows: &[u64], edges: Edges) -> Vec<u64> {
+        let count = rows.len() as i64;
+        let cell = |row: i64, column: i64| -> u64 {
+            let (row, column) = match edges {
+                Edges::Dead if !(0..count).contains(&row) || !(0..64).contains(&column) => return 0,
+                Edges::Dead => (row, column),
+                Edges::Wrap => (row.rem_euclid(count), column.rem_euclid(64)),
+            };
+            rows[row as usize] >> column & 1
+        };
+        (0..count).map(|row| {
+            (0..64).fold(0, |next, column| {
+                let neighbours: u64 = (-1..=1).flat_map(|dr| (-1..=1).map(move |dc| (dr, dc)))
+                    .filter(|&offset| offset != (0, 0))
+                    .map(|(dr, dc)| cell(row + dr, column + dc))
+                    .sum();
+                let alive = neighbours == 3 || neighbours == 2 && cell(row, column) == 1;
+                next | (alive as u64) << column
+            })
+        }).collect()
+    }
+
+    #[test]
+    fn test_patterns() {
+        // Block is still
+        let block = [0, 0b0110, 0b0110, 0];
+        assert_eq!(life_step(&block, Edges::Dead), block);
+        // Glider moves by one cell diagonally in 4 generations
+        let glider = vec![0b010, 0b100, 0b111, 0, 0];
+        let moved = (0..4).fold(glider.clone(), |rows, _| life_step(&rows, Edges::Dead));
+        assert_eq!(moved, vec![0, 0b0100, 0b1000, 0b1110, 0]);
+        // Wrapping blinker across the bit 63 and the last row
+        let blinker = [1, 1, 0, 1];
+        assert_eq!(life_step(&blinker, Edges::Wrap), [1 << 63 | 0b11, 0, 0, 0]);
+        assert_eq!(life_step(&blinker, Edges::Dead), [0, 0, 0, 0]);
+        assert_eq!(life_step(&[], Edges::Wrap), Vec::<u64>::new());
+        assert_eq!(life_step(&[u64::MAX], Edges::Dead), [0x7FFF_FFFF_FFFF_FFFE]);
+    }
+
+    #[test]
+    fn test_random_grids() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        let mut next = move || rng.next_u32() as u64;
+        for count in [1, 2, 3, 7, 16] {
+            let rows: Vec<u64> = (0..count).map(|_| next() << 32 | next()).collect();
+            for edges in [Edges::Dead, Edges::Wrap] {
+                let mut expected = rows.clone();
+                let mut actual = rows.clone();
+                for _ in 0..5 {
+                    expected = life_step_naive(&expected, edges);
+                    actual = life_step(&actual, edges);
+                    assert_eq!(actual, expected, "{count} rows, {edges:?}");
+                }
+            }
+        }
+    }
+}
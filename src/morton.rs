@@ -0,0 +1,667 @@
+//! [*Morton codes*](https://en.wikipedia.org/wiki/Z-order_curve) (Z-order): the bits of the coordinates are interleaved,
+//! so points close in space tend to have close codes. In 2D the bit *i* of *x* becomes the bit *2i* of the code
+//! and the bit *i* of *y* becomes the bit *2i + 1*. In 3D the bits of *x*, *y* and *z* take every third bit
+
+use std::ops::RangeInclusive;
+#[cfg(feature = "intrinsics")]
+use crate::intrinsics::{pdep, pext};
+#[cfg(not(feature = "intrinsics"))]
+use crate::{pdep, pext};
+
+/// Bits of the 2D code holding *x*
+const EVEN_BITS: u32 = 0x5555_5555;
+
+/// Bits of the 2D code holding *y*
+const ODD_BITS: u32 = 0xAAAA_AAAA;
+
+/// Widest coordinate of a 3D code in u64
+pub const MORTON_3D_MAX_BITS: u32 = 21;
+
+/// Bits of the 3D code holding *x*
+const EVERY_THIRD_BIT: u64 = 0x1249_2492_4924_9249;
+
+/// Returns the number with its bit *i* moved to the bit *2i*, zeros between. Every step halves the distance
+/// of the moved groups: 8-bit halves apart by 8, then nibbles by 4, bit pairs by 2 and single bits by 1
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::spread_bits_2d;
+/// assert_eq!(spread_bits_2d(0b1011), 0b0100_0101);
+/// ```
+pub fn spread_bits_2d(number: u16) -> u32 {
+    let mut spread = number as u32;
+    spread = (spread | spread << 8) & 0x00FF_00FF;
+    spread = (spread | spread << 4) & 0x0F0F_0F0F;
+    spread = (spread | spread << 2) & 0x3333_3333;
+    (spread | spread << 1) & EVEN_BITS
+}
+
+/// Returns the even bits of the number packed together, the inverse of *spread_bits_2d*. Odd bits are ignored
+/// # Arguments
+/// * `number` - number to work with
+pub fn compact_bits_2d(number: u32) -> u16 {
+    let mut compact = number & EVEN_BITS;
+    compact = (compact | compact >> 1) & 0x3333_3333;
+    compact = (compact | compact >> 2) & 0x0F0F_0F0F;
+    compact = (compact | compact >> 4) & 0x00FF_00FF;
+    (compact | compact >> 8) as u16
+}
+
+/// Returns the Morton code of the point. Interleaves the bits one by one
+/// # Arguments
+/// * `x` - first coordinate, in the even bits
+/// * `y` - second coordinate, in the odd bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::morton_encode_2d_naive;
+/// assert_eq!(morton_encode_2d_naive(0b11, 0b01), 0b0111);
+/// ```
+pub fn morton_encode_2d_naive(x: u16, y: u16) -> u32 {
+    (0..u16::BITS).fold(0, |code, index| {
+        code | ((x >> index & 1) as u32) << (2 * index) | ((y >> index & 1) as u32) << (2 * index + 1)
+    })
+}
+
+/// Returns the Morton code of the point. Uses SWAR spreading by masks, see *spread_bits_2d*
+/// # Arguments
+/// * `x` - first coordinate, in the even bits
+/// * `y` - second coordinate, in the odd bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::morton_encode_2d;
+/// assert_eq!(morton_encode_2d(0b11, 0b01), 0b0111);
+/// assert_eq!(morton_encode_2d(u16::MAX, 0), 0x5555_5555);
+/// ```
+pub fn morton_encode_2d(x: u16, y: u16) -> u32 {
+    spread_bits_2d(x) | spread_bits_2d(y) << 1
+}
+
+/// Returns the Morton code of the point. Deposits the coordinates into the even and odd bits by *pdep*,
+/// which is a single instruction with the `intrinsics` feature on CPUs with BMI2
+/// # Arguments
+/// * `x` - first coordinate, in the even bits
+/// * `y` - second coordinate, in the odd bits
+pub fn morton_encode_2d_pdep(x: u16, y: u16) -> u32 {
+    pdep(x as u32, EVEN_BITS) | pdep(y as u32, ODD_BITS)
+}
+
+/// Returns the point of the Morton code. Extracts the bits one by one
+/// # Arguments
+/// * `code` - Morton code
+pub fn morton_decode_2d_naive(code: u32) -> (u16, u16) {
+    (0..u16::BITS).fold((0, 0), |(x, y), index| {
+        (x | ((code >> (2 * index) & 1) as u16) << index, y | ((code >> (2 * index + 1) & 1) as u16) << index)
+    })
+}
+
+/// Returns the point of the Morton code. Uses SWAR compacting by masks, see *compact_bits_2d*
+/// # Arguments
+/// * `code` - Morton code
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::morton_decode_2d;
+/// assert_eq!(morton_decode_2d(0b0111), (0b11, 0b01));
+/// ```
+pub fn morton_decode_2d(code: u32) -> (u16, u16) {
+    (compact_bits_2d(code), compact_bits_2d(code >> 1))
+}
+
+/// Returns the point of the Morton code. Extracts the even and odd bits by *pext*
+/// # Arguments
+/// * `code` - Morton code
+pub fn morton_decode_2d_pext(code: u32) -> (u16, u16) {
+    (pext(code, EVEN_BITS) as u16, pext(code, ODD_BITS) as u16)
+}
+
+/// Returns the lowest 21 bits of the number with the bit *i* moved to the bit *3i*, two zeros between.
+/// Like *spread_bits_2d*, but groups move apart by 32, 16, 8, 4 and 2 bits. Higher bits are ignored
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::spread_bits_3d;
+/// assert_eq!(spread_bits_3d(0b1011), 0b001_000_001_001);
+/// ```
+pub fn spread_bits_3d(number: u32) -> u64 {
+    let mut spread = (number & (1 << MORTON_3D_MAX_BITS) - 1) as u64;
+    spread = (spread | spread << 32) & 0x001F_0000_0000_FFFF;
+    spread = (spread | spread << 16) & 0x001F_0000_FF00_00FF;
+    spread = (spread | spread << 8) & 0x100F_00F0_0F00_F00F;
+    spread = (spread | spread << 4) & 0x10C3_0C30_C30C_30C3;
+    (spread | spread << 2) & EVERY_THIRD_BIT
+}
+
+/// Returns every third bit of the number (bits 0, 3, 6, ...) packed together, the inverse of *spread_bits_3d*
+/// # Arguments
+/// * `number` - number to work with
+pub fn compact_bits_3d(number: u64) -> u32 {
+    let mut compact = number & EVERY_THIRD_BIT;
+    compact = (compact | compact >> 2) & 0x10C3_0C30_C30C_30C3;
+    compact = (compact | compact >> 4) & 0x100F_00F0_0F00_F00F;
+    compact = (compact | compact >> 8) & 0x001F_0000_FF00_00FF;
+    compact = (compact | compact >> 16) & 0x001F_0000_0000_FFFF;
+    ((compact | compact >> 32) & (1 << MORTON_3D_MAX_BITS) - 1) as u32
+}
+
+/// Returns the 48-bit Morton code of the point
+/// # Arguments
+/// * `x` - first coordinate, in the bits 0, 3, 6, ...
+/// * `y` - second coordinate, in the bits 1, 4, 7, ...
+/// * `z` - third coordinate, in the bits 2, 5, 8, ...
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::morton_encode_3d;
+/// assert_eq!(morton_encode_3d(1, 1, 0), 0b011);
+/// assert_eq!(morton_encode_3d(0, 0, 0b10), 0b100_000);
+/// ```
+pub fn morton_encode_3d(x: u16, y: u16, z: u16) -> u64 {
+    spread_bits_3d(x as u32) | spread_bits_3d(y as u32) << 1 | spread_bits_3d(z as u32) << 2
+}
+
+/// Returns the point of the Morton code made by *morton_encode_3d* or None if the code has bits above the bit 47
+/// # Arguments
+/// * `code` - Morton code
+pub fn morton_decode_3d(code: u64) -> Option<(u16, u16, u16)> {
+    if code >> (3 * u16::BITS) != 0 {
+        return None;
+    }
+    Some((compact_bits_3d(code) as u16, compact_bits_3d(code >> 1) as u16, compact_bits_3d(code >> 2) as u16))
+}
+
+/// Returns the 63-bit Morton code of the point with 21-bit coordinates or None if some coordinate doesn't fit into 21 bits
+/// # Arguments
+/// * `x` - first coordinate
+/// * `y` - second coordinate
+/// * `z` - third coordinate
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::{morton_decode_3d_21, morton_encode_3d_21};
+/// let code = morton_encode_3d_21(0x1F_FFFF, 0, 5).unwrap();
+/// assert_eq!(morton_decode_3d_21(code), Some((0x1F_FFFF, 0, 5)));
+/// assert_eq!(morton_encode_3d_21(1 << 21, 0, 0), None);
+/// ```
+pub fn morton_encode_3d_21(x: u32, y: u32, z: u32) -> Option<u64> {
+    if (x | y | z) >> MORTON_3D_MAX_BITS != 0 {
+        return None;
+    }
+    Some(spread_bits_3d(x) | spread_bits_3d(y) << 1 | spread_bits_3d(z) << 2)
+}
+
+/// Returns the point of the Morton code made by *morton_encode_3d_21* or None if the bit 63 is set
+/// # Arguments
+/// * `code` - Morton code
+pub fn morton_decode_3d_21(code: u64) -> Option<(u32, u32, u32)> {
+    if code >> (3 * MORTON_3D_MAX_BITS) != 0 {
+        return None;
+    }
+    Some((compact_bits_3d(code), compact_bits_3d(code >> 1), compact_bits_3d(code >> 2)))
+}
+
+/// Helper function. Returns the mask of the blocks of *block* bits placed *block × stride* bits apart,
+/// covering the lowest *bits* bits of the compacted number
+/// # Arguments
+/// * `block` - bits count of a block
+/// * `stride` - distance of the bits of the spread number
+/// * `bits` - bits count of the compacted number
+fn block_mask(block: u32, stride: u32, bits: u32) -> u128 {
+    let ones = u128::MAX >> (u128::BITS - block);
+    (0..bits.div_ceil(block)).fold(0, |mask, index| mask | ones << (index * block * stride))
+}
+
+/// Returns the widest word interleaved into u128 with the words count, at most 32
+/// # Arguments
+/// * `count` - count of the interleaved words
+fn interleave_bits(count: usize) -> u32 {
+    (u128::BITS / count as u32).min(u32::BITS)
+}
+
+/// Returns the number with its bit *i* moved to the bit *i × stride*, zeros between, or None if the stride is 0
+/// or the number doesn't fit into 128 / *stride* bits. Generalizes *spread_bits_2d* and *spread_bits_3d*:
+/// 16-bit halves move apart first, then bytes, nibbles, bit pairs and single bits
+/// # Arguments
+/// * `number` - number to work with
+/// * `stride` - distance of the spread bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::spread_bits;
+/// assert_eq!(spread_bits(0b1011, 4), Some(0b0001_0000_0001_0001));
+/// assert_eq!(spread_bits(u32::MAX, 5), None);
+/// ```
+pub fn spread_bits(number: u32, stride: u32) -> Option<u128> {
+    if stride == 0 || stride > u128::BITS {
+        return None;
+    }
+    let bits = (u128::BITS / stride).min(u32::BITS);
+    if bits < u32::BITS && number >> bits != 0 {
+        return None;
+    }
+    let mut spread = number as u128;
+    for block in [16, 8, 4, 2, 1] {
+        // Odd blocks move up by the growth of their distance, copies of even blocks land in the gaps and are masked out
+        let moved = spread.checked_shl(block * (stride - 1)).unwrap_or(0);
+        spread = (spread | moved) & block_mask(block, stride, bits);
+    }
+    Some(spread)
+}
+
+/// Returns the bits *i × stride* of the number packed together, the inverse of *spread_bits*, or None if the stride
+/// isn't in 1..=128. Bits between are ignored
+/// # Arguments
+/// * `number` - number to work with
+/// * `stride` - distance of the spread bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::compact_bits;
+/// assert_eq!(compact_bits(0b0001_1000_0001_0001, 4), Some(0b1011));
+/// ```
+pub fn compact_bits(number: u128, stride: u32) -> Option<u32> {
+    if stride == 0 || stride > u128::BITS {
+        return None;
+    }
+    let bits = (u128::BITS / stride).min(u32::BITS);
+    let mut compact = number & block_mask(1, stride, bits);
+    for block in [1, 2, 4, 8, 16] {
+        let moved = compact.checked_shr(block * (stride - 1)).unwrap_or(0);
+        compact = (compact | moved) & block_mask(2 * block, stride, bits);
+    }
+    Some(compact as u32)
+}
+
+/// Returns the code with the bit *j* of the word *i* moved to the bit *j × count + i*, where *count* is the count
+/// of the words, or None if there are no words, more than 128 words, or a word doesn't fit into 128 / *count* bits.
+/// Two words give *morton_encode_2d*, three give *morton_encode_3d_21*
+/// # Arguments
+/// * `words` - words to be interleaved, the first one in the lowest bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::interleave;
+/// assert_eq!(interleave(&[0b11, 0b01, 0b10, 0b00]), Some(0b0101_0011));
+/// assert_eq!(interleave(&[u32::MAX; 4]), Some(u128::MAX));
+/// assert_eq!(interleave(&[1 << 25; 5]), None);
+/// ```
+pub fn interleave(words: &[u32]) -> Option<u128> {
+    if words.is_empty() || words.len() > u128::BITS as usize {
+        return None;
+    }
+    let stride = words.len() as u32;
+    words.iter().enumerate().try_fold(0, |code, (index, &word)| Some(code | spread_bits(word, stride)? << index))
+}
+
+/// Returns the words interleaved into the code, the inverse of *interleave*, or None if the count isn't in 1..=128
+/// or the code has ones above the bits of the words
+/// # Arguments
+/// * `code` - interleaved words
+/// * `count` - count of the words
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::deinterleave;
+/// assert_eq!(deinterleave(0b0101_0011, 4), Some(vec![0b11, 0b01, 0b10, 0b00]));
+/// assert_eq!(deinterleave(1 << 127, 5), None);
+/// ```
+pub fn deinterleave(code: u128, count: usize) -> Option<Vec<u32>> {
+    if count == 0 || count > u128::BITS as usize {
+        return None;
+    }
+    let used_bits = interleave_bits(count) * count as u32;
+    if code.checked_shr(used_bits).unwrap_or(0) != 0 {
+        return None;
+    }
+    (0..count).map(|index| compact_bits(code >> index, count as u32)).collect()
+}
+
+/// Helper function. Returns true if the corners describe a non-empty rectangle
+/// # Arguments
+/// * `min` - lowest corner
+/// * `max` - highest corner
+fn is_rectangle(min: (u16, u16), max: (u16, u16)) -> bool {
+    min.0 <= max.0 && min.1 <= max.1
+}
+
+/// Helper function. Returns true if the point of the 2D code is inside the rectangle
+/// # Arguments
+/// * `code` - Morton code
+/// * `min` - lowest corner
+/// * `max` - highest corner
+fn contains(code: u32, min: (u16, u16), max: (u16, u16)) -> bool {
+    let (x, y) = morton_decode_2d(code);
+    (min.0..=max.0).contains(&x) && (min.1..=max.1).contains(&y)
+}
+
+/// Helper function. Returns the bits of the same coordinate as the bit below it
+/// # Arguments
+/// * `bit` - index of the bit of the code
+fn lower_bits_of_coordinate(bit: u32) -> u32 {
+    let coordinate = if bit.is_multiple_of(2) { EVEN_BITS } else { ODD_BITS };
+    coordinate & (1 << bit) - 1
+}
+
+/// Helper function. Returns the code with the bit set and the lower bits of its coordinate cleared
+/// ("load 1000" of Tropf and Herzog): the lowest code of the upper part of a split
+/// # Arguments
+/// * `code` - Morton code
+/// * `bit` - index of the bit
+fn load_upper(code: u32, bit: u32) -> u32 {
+    (code | 1 << bit) & ! lower_bits_of_coordinate(bit)
+}
+
+/// Helper function. Returns the code with the bit cleared and the lower bits of its coordinate set
+/// ("load 0111" of Tropf and Herzog): the highest code of the lower part of a split
+/// # Arguments
+/// * `code` - Morton code
+/// * `bit` - index of the bit
+fn load_lower(code: u32, bit: u32) -> u32 {
+    code & ! (1 << bit) | lower_bits_of_coordinate(bit)
+}
+
+/// Returns the smallest 2D Morton code greater than *code* whose point is inside the rectangle (BIGMIN),
+/// or None if there is no such code or the corners are swapped. A range scan leaving the rectangle jumps there.
+/// Walks the bits from the highest one, halving the rectangle at every bit where its corners differ
+/// # Arguments
+/// * `code` - Morton code
+/// * `min` - lowest corner of the rectangle, inclusive
+/// * `max` - highest corner of the rectangle, inclusive
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::{bigmin, morton_encode_2d};
+/// // Rectangle 2..=3 x 0..=1 holds the codes 4..=7
+/// assert_eq!(bigmin(0, (2, 0), (3, 1)), Some(4));
+/// assert_eq!(bigmin(5, (2, 0), (3, 1)), Some(6));
+/// assert_eq!(bigmin(7, (2, 0), (3, 1)), None);
+/// ```
+pub fn bigmin(code: u32, min: (u16, u16), max: (u16, u16)) -> Option<u32> {
+    if !is_rectangle(min, max) {
+        return None;
+    }
+    let next = code.checked_add(1)?;
+    if contains(next, min, max) {
+        return Some(next);
+    }
+    let (mut zmin, mut zmax) = (morton_encode_2d(min.0, min.1), morton_encode_2d(max.0, max.1));
+    let mut bigmin = None;
+    for bit in (0..u32::BITS).rev() {
+        match (next >> bit & 1, zmin >> bit & 1, zmax >> bit & 1) {
+            // The rectangle is split, the candidate is the lowest code of the upper part, the scan goes on in the lower part
+            (0, 0, 1) => {
+                bigmin = Some(load_upper(zmin, bit));
+                zmax = load_lower(zmax, bit);
+            }
+            // The whole rectangle is above the code
+            (0, 1, 1) => return Some(zmin),
+            // The whole rectangle is below the code
+            (1, 0, 0) => return bigmin,
+            // The code is in the upper part
+            (1, 0, 1) => zmin = load_upper(zmin, bit),
+            _ => {}
+        }
+    }
+    bigmin
+}
+
+/// Returns the largest 2D Morton code less than *code* whose point is inside the rectangle (LITMAX),
+/// or None if there is no such code or the corners are swapped. Mirror of *bigmin*
+/// # Arguments
+/// * `code` - Morton code
+/// * `min` - lowest corner of the rectangle, inclusive
+/// * `max` - highest corner of the rectangle, inclusive
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::litmax;
+/// assert_eq!(litmax(100, (2, 0), (3, 1)), Some(7));
+/// assert_eq!(litmax(4, (2, 0), (3, 1)), None);
+/// ```
+pub fn litmax(code: u32, min: (u16, u16), max: (u16, u16)) -> Option<u32> {
+    if !is_rectangle(min, max) {
+        return None;
+    }
+    let previous = code.checked_sub(1)?;
+    if contains(previous, min, max) {
+        return Some(previous);
+    }
+    let (mut zmin, mut zmax) = (morton_encode_2d(min.0, min.1), morton_encode_2d(max.0, max.1));
+    let mut litmax = None;
+    for bit in (0..u32::BITS).rev() {
+        match (previous >> bit & 1, zmin >> bit & 1, zmax >> bit & 1) {
+            // The code is in the lower part
+            (0, 0, 1) => zmax = load_lower(zmax, bit),
+            // The whole rectangle is above the code
+            (0, 1, 1) => return litmax,
+            // The whole rectangle is below the code
+            (1, 0, 0) => return Some(zmax),
+            // The rectangle is split, the candidate is the highest code of the lower part, the scan goes on in the upper part
+            (1, 0, 1) => {
+                litmax = Some(load_lower(zmax, bit));
+                zmin = load_upper(zmin, bit);
+            }
+            _ => {}
+        }
+    }
+    litmax
+}
+
+/// Returns the sorted ranges of 2D Morton codes covering exactly the points of the rectangle, or None if the corners
+/// are swapped. The rectangle is split at the highest bit where the codes of its corners differ, the lower part ends
+/// at its LITMAX and the upper part starts at its BIGMIN, until a part fills its whole code range.
+/// Thin rectangles give many ranges
+/// # Arguments
+/// * `min` - lowest corner of the rectangle, inclusive
+/// * `max` - highest corner of the rectangle, inclusive
+/// # Examples
+/// ```
+/// # use understanding_bitwise::morton::morton_ranges_2d;
+/// // 3x2 rectangle at the origin: codes 0..=3 and 4, 6
+/// assert_eq!(morton_ranges_2d((0, 0), (2, 1)), Some(vec![0..=4, 6..=6]));
+/// ```
+pub fn morton_ranges_2d(min: (u16, u16), max: (u16, u16)) -> Option<Vec<RangeInclusive<u32>>> {
+    if !is_rectangle(min, max) {
+        return None;
+    }
+    let mut ranges: Vec<RangeInclusive<u32>> = Vec::new();
+    split_into_ranges(morton_encode_2d(min.0, min.1), morton_encode_2d(max.0, max.1), &mut ranges);
+    Some(ranges)
+}
+
+/// Helper function. Appends the ranges of the rectangle with the corner codes, merging adjacent ranges
+/// # Arguments
+/// * `zmin` - code of the lowest corner
+/// * `zmax` - code of the highest corner
+/// * `ranges` - ranges found so far
+fn split_into_ranges(zmin: u32, zmax: u32, ranges: &mut Vec<RangeInclusive<u32>>) {
+    let (min, max) = (morton_decode_2d(zmin), morton_decode_2d(zmax));
+    let area = ((max.0 - min.0) as u64 + 1) * ((max.1 - min.1) as u64 + 1);
+    // All points of the rectangle lie between the corner codes, so equal counts mean the range is filled
+    if area == (zmax - zmin) as u64 + 1 {
+        match ranges.last_mut() {
+            Some(last) if *last.end() + 1 == zmin => *last = *last.start()..=zmax,
+            _ => ranges.push(zmin..=zmax),
+        }
+        return;
+    }
+    let bit = u32::BITS - 1 - (zmin ^ zmax).leading_zeros();
+    split_into_ranges(zmin, load_lower(zmax, bit), ranges);
+    split_into_ranges(load_upper(zmin, bit), zmax, ranges);
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    fn points() -> Vec<(u16, u16)> {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        let random = std::iter::repeat_with(move || {
+            let state = rng.next_u32();
+            ((state >> 16) as u16, state as u16)
+        }).take(1000);
+        [(0, 0), (u16::MAX, u16::MAX), (1, 0), (0, 1), (u16::MAX, 0)].into_iter().chain(random).collect()
+    }
+
+    fn general_test_encode<F>(f: F)
+    where
+        F: Fn(u16, u16) -> u32 {
+        assert_eq!(f(0, 0), 0);
+        assert_eq!(f(1, 0), 0b01);
+        assert_eq!(f(0, 1), 0b10);
+        assert_eq!(f(0b101, 0b110), 0b11_10_01);
+        assert_eq!(f(u16::MAX, u16::MAX), u32::MAX);
+        for (x, y) in points() {
+            assert_eq!(f(x, y), morton_encode_2d_naive(x, y));
+        }
+    }
+
+    fn general_test_decode<F>(f: F)
+    where
+        F: Fn(u32) -> (u16, u16) {
+        for (x, y) in points() {
+            assert_eq!(f(morton_encode_2d_naive(x, y)), (x, y));
+        }
+    }
+
+    #[test]
+    fn test_morton_encode_2d() {
+        general_test_encode(morton_encode_2d_naive);
+        general_test_encode(morton_encode_2d);
+        general_test_encode(morton_encode_2d_pdep);
+    }
+
+    #[test]
+    fn test_morton_decode_2d() {
+        general_test_decode(morton_decode_2d_naive);
+        general_test_decode(morton_decode_2d);
+        general_test_decode(morton_decode_2d_pext);
+    }
+
+    #[test]
+    fn test_z_order() {
+        // Codes of the 4x4 grid visit the quadrants in Z order: 2x2 blocks are contiguous
+        let mut grid = [[0; 4]; 4];
+        for (y, row) in grid.iter_mut().enumerate() {
+            for (x, code) in row.iter_mut().enumerate() {
+                *code = morton_encode_2d(x as u16, y as u16);
+            }
+        }
+        assert_eq!(grid, [[0, 1, 4, 5], [2, 3, 6, 7], [8, 9, 12, 13], [10, 11, 14, 15]]);
+        for number in [0, 1, 0xBEEF, u16::MAX] {
+            assert_eq!(compact_bits_2d(spread_bits_2d(number)), number);
+        }
+    }
+
+    fn morton_encode_3d_naive(x: u32, y: u32, z: u32) -> u64 {
+        (0..MORTON_3D_MAX_BITS).fold(0, |code, index| {
+            let bits = (x >> index & 1) as u64 | ((y >> index & 1) as u64) << 1 | ((z >> index & 1) as u64) << 2;
+            code | bits << (3 * index)
+        })
+    }
+
+    #[test]
+    fn test_morton_3d() {
+        for (x, y) in points() {
+            let z = x ^ y.rotate_left(5);
+            let code = morton_encode_3d(x, y, z);
+            assert_eq!(code, morton_encode_3d_naive(x as u32, y as u32, z as u32));
+            assert_eq!(morton_decode_3d(code), Some((x, y, z)));
+            let (x, y, z) = ((x as u32) << 5 | 0x1F, (y as u32) << 3, z as u32 | 0x1F_0000);
+            let code = morton_encode_3d_21(x, y, z).unwrap();
+            assert_eq!(code, morton_encode_3d_naive(x, y, z));
+            assert_eq!(morton_decode_3d_21(code), Some((x, y, z)));
+        }
+        assert_eq!(morton_encode_3d(u16::MAX, u16::MAX, u16::MAX), (1 << 48) - 1);
+        assert_eq!(morton_encode_3d_21(0x1F_FFFF, 0x1F_FFFF, 0x1F_FFFF), Some((1 << 63) - 1));
+        assert_eq!(morton_decode_3d(1 << 48), None);
+        assert_eq!(morton_decode_3d_21(1 << 63), None);
+        assert_eq!(morton_encode_3d_21(0, 0, u32::MAX), None);
+        assert_eq!(spread_bits_3d(u32::MAX), EVERY_THIRD_BIT);
+        assert_eq!(compact_bits_3d(u64::MAX), 0x1F_FFFF);
+    }
+
+    fn rectangles() -> Vec<((u16, u16), (u16, u16))> {
+        let mut rng = Xorshift32::new(0x0BAD_CAFE).unwrap();
+        let mut next = move || (rng.next_u32() % 16) as u16;
+        (0..100).map(|_| {
+            let (x1, x2, y1, y2) = (next(), next(), next(), next());
+            ((x1.min(x2), y1.min(y2)), (x1.max(x2), y1.max(y2)))
+        }).chain([((0, 0), (15, 15)), ((3, 3), (3, 3)), ((0, 5), (15, 5))]).collect()
+    }
+
+    #[test]
+    fn test_bigmin_litmax() {
+        for (min, max) in rectangles() {
+            let inside: Vec<u32> = (0..256).filter(|&code| contains(code, min, max)).collect();
+            for code in 0..260 {
+                assert_eq!(bigmin(code, min, max), inside.iter().copied().find(|&other| other > code), "{code} {min:?} {max:?}");
+                assert_eq!(litmax(code, min, max), inside.iter().copied().rev().find(|&other| other < code), "{code} {min:?} {max:?}");
+            }
+        }
+        assert_eq!(bigmin(u32::MAX, (0, 0), (1, 1)), None);
+        assert_eq!(litmax(0, (0, 0), (1, 1)), None);
+        assert_eq!(bigmin(0, (2, 0), (1, 1)), None);
+        assert_eq!(litmax(u32::MAX, (0, 0), (u16::MAX, u16::MAX)), Some(u32::MAX - 1));
+    }
+
+    #[test]
+    fn test_morton_ranges_2d() {
+        for (min, max) in rectangles() {
+            let ranges = morton_ranges_2d(min, max).unwrap();
+            let codes: Vec<u32> = ranges.iter().cloned().flatten().collect();
+            let inside: Vec<u32> = (0..256).filter(|&code| contains(code, min, max)).collect();
+            assert_eq!(codes, inside, "{min:?} {max:?}");
+            // Ranges are maximal: there is a gap between every two of them
+            for pair in ranges.windows(2) {
+                assert!(pair[0].end() + 1 < *pair[1].start());
+            }
+        }
+        assert_eq!(morton_ranges_2d((0, 0), (u16::MAX, u16::MAX)), Some(vec![0..=u32::MAX]));
+        assert_eq!(morton_ranges_2d((1, 0), (0, 0)), None);
+    }
+
+    #[test]
+    fn test_spread_bits() {
+        for (x, y) in points() {
+            let z = x ^ y.rotate_left(5);
+            assert_eq!(spread_bits(x as u32, 2), Some(spread_bits_2d(x) as u128));
+            assert_eq!(compact_bits(spread_bits_2d(y) as u128 | 0xAAAA, 2), Some(y as u32));
+            let z = z as u32 | (x as u32 & 0x1F) << 16;
+            assert_eq!(spread_bits(z, 3), Some(spread_bits_3d(z) as u128));
+            assert_eq!(compact_bits(spread_bits_3d(z) as u128, 3), Some(z));
+        }
+        for stride in 1..=u128::BITS {
+            let bits = (u128::BITS / stride).min(u32::BITS);
+            let number = 0xDEAD_BEEF & (u32::MAX >> (u32::BITS - bits));
+            let naive = (0..bits).fold(0u128, |spread, index| spread | ((number >> index & 1) as u128) << (index * stride));
+            assert_eq!(spread_bits(number, stride), Some(naive), "stride {stride}");
+            assert_eq!(compact_bits(naive | ! spread_bits(u32::MAX >> (u32::BITS - bits), stride).unwrap(), stride), Some(number));
+        }
+        assert_eq!(spread_bits(1, 0), None);
+        assert_eq!(spread_bits(1, 129), None);
+        assert_eq!(spread_bits(2, 128), None);
+        assert_eq!(compact_bits(1, 0), None);
+    }
+
+    #[test]
+    fn test_interleave() {
+        for (x, y) in points() {
+            let z = x ^ y.rotate_left(5);
+            assert_eq!(interleave(&[x as u32, y as u32]), Some(morton_encode_2d(x, y) as u128));
+            assert_eq!(interleave(&[x as u32, y as u32, z as u32]), Some(morton_encode_3d(x, y, z) as u128));
+            let code = morton_encode_3d(x, y, z) as u128;
+            assert_eq!(deinterleave(code, 3), Some(vec![x as u32, y as u32, z as u32]));
+        }
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for count in 1..=u128::BITS as usize {
+            let bits = interleave_bits(count);
+            let words: Vec<u32> = (0..count).map(|_| {
+                let state = rng.next_u32();
+                state & (u32::MAX >> (u32::BITS - bits))
+            }).collect();
+            let code = interleave(&words).unwrap();
+            assert_eq!(deinterleave(code, count), Some(words), "count {count}");
+        }
+        assert_eq!(interleave(&[]), None);
+        assert_eq!(interleave(&[0; 129]), None);
+        assert_eq!(interleave(&[0, 1 << 25, 0, 0, 0]), None);
+        assert_eq!(deinterleave(0, 0), None);
+        assert_eq!(deinterleave(1 << 96, 3), None);
+        assert_eq!(deinterleave(u128::MAX, 4), Some(vec![u32::MAX; 4]));
+    }
+}
@@ -0,0 +1,405 @@
+//! IPv4 subnets as bit operations on *u32* addresses, the first octet in the highest byte (as *u32::from(Ipv4Addr)*).
+//! A prefix of length *n* is the *n* highest bits: the mask has them set, the network address keeps only them and
+//! the broadcast address sets all the rest. MAC addresses are *[u8; 6]* or the 48 lowest bits of *u64*, the first
+//! octet in the highest byte; its two lowest bits tell a multicast address and a locally administered one
+
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::BitError;
+
+/// Helper function. Returns the error unless the prefix length is in 0..=32
+/// # Arguments
+/// * `operation` - name of the operation
+/// * `prefix_len` - length of the prefix
+fn check_prefix(operation: &'static str, prefix_len: u32) -> Result<(), BitError> {
+    if prefix_len > u32::BITS {
+        return Err(BitError::new(operation, prefix_len, 0..u32::BITS + 1));
+    }
+    Ok(())
+}
+
+/// Helper function. Returns the mask of the prefix of the length in 0..=32
+/// # Arguments
+/// * `prefix_len` - length of the prefix
+fn mask(prefix_len: u32) -> u32 {
+    // Shift by 32 is out of range, /0 is the empty mask
+    u32::MAX.checked_shl(u32::BITS - prefix_len).unwrap_or(0)
+}
+
+/// Returns the subnet mask with the *prefix_len* highest bits set or error if the length is above 32
+/// # Arguments
+/// * `prefix_len` - length of the prefix
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::subnet_mask;
+/// assert_eq!(subnet_mask(24), Ok(0xFFFF_FF00));
+/// assert_eq!(subnet_mask(0), Ok(0));
+/// assert_eq!(subnet_mask(33).unwrap_err().to_string(), "subnet_mask: index 33 is out of range 0..33");
+/// ```
+pub fn subnet_mask(prefix_len: u32) -> Result<u32, BitError> {
+    check_prefix("subnet_mask", prefix_len)?;
+    Ok(mask(prefix_len))
+}
+
+/// Returns the address with the host bits cleared or error if the prefix length is above 32
+/// # Arguments
+/// * `address` - address in the subnet
+/// * `prefix_len` - length of the prefix
+/// # Examples
+/// ```
+/// # use std::net::Ipv4Addr;
+/// # use understanding_bitwise::network::network_address;
+/// let address = u32::from(Ipv4Addr::new(192, 168, 1, 77));
+/// assert_eq!(network_address(address, 26).map(Ipv4Addr::from), Ok(Ipv4Addr::new(192, 168, 1, 64)));
+/// ```
+pub fn network_address(address: u32, prefix_len: u32) -> Result<u32, BitError> {
+    check_prefix("network_address", prefix_len)?;
+    Ok(address & mask(prefix_len))
+}
+
+/// Returns the address with the host bits set or error if the prefix length is above 32
+/// # Arguments
+/// * `address` - address in the subnet
+/// * `prefix_len` - length of the prefix
+/// # Examples
+/// ```
+/// # use std::net::Ipv4Addr;
+/// # use understanding_bitwise::network::broadcast_address;
+/// let address = u32::from(Ipv4Addr::new(192, 168, 1, 77));
+/// assert_eq!(broadcast_address(address, 26).map(Ipv4Addr::from), Ok(Ipv4Addr::new(192, 168, 1, 127)));
+/// ```
+pub fn broadcast_address(address: u32, prefix_len: u32) -> Result<u32, BitError> {
+    check_prefix("broadcast_address", prefix_len)?;
+    Ok(address | !mask(prefix_len))
+}
+
+/// Returns the count of usable host addresses: all but the network and broadcast ones, except */31* with two
+/// point-to-point hosts ([RFC 3021](https://www.rfc-editor.org/rfc/rfc3021)) and */32* with a single one.
+/// Error if the prefix length is above 32
+/// # Arguments
+/// * `prefix_len` - length of the prefix
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::hosts_count;
+/// assert_eq!(hosts_count(24), Ok(254));
+/// assert_eq!(hosts_count(31), Ok(2));
+/// assert_eq!(hosts_count(0), Ok(u32::MAX - 1));
+/// ```
+pub fn hosts_count(prefix_len: u32) -> Result<u32, BitError> {
+    check_prefix("hosts_count", prefix_len)?;
+    Ok(match prefix_len {
+        32 => 1,
+        31 => 2,
+        // 2^(32 - n) addresses, which is the inverted mask plus one
+        _ => !mask(prefix_len) - 1,
+    })
+}
+
+/// IPv4 subnet in the CIDR notation: network address and prefix length
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Cidr {
+    address: u32,
+    prefix_len: u32,
+}
+
+impl Cidr {
+    /// Returns the subnet of the address with the host bits cleared or error if the prefix length is above 32
+    /// # Arguments
+    /// * `address` - any address in the subnet
+    /// * `prefix_len` - length of the prefix
+    pub fn new(address: u32, prefix_len: u32) -> Result<Self, BitError> {
+        Ok(Cidr { address: network_address(address, prefix_len)?, prefix_len })
+    }
+
+    /// Returns the network address
+    pub fn address(&self) -> u32 {
+        self.address
+    }
+
+    /// Returns the length of the prefix
+    pub fn prefix_len(&self) -> u32 {
+        self.prefix_len
+    }
+
+    /// Returns the subnet mask
+    pub fn mask(&self) -> u32 {
+        mask(self.prefix_len)
+    }
+
+    /// Returns the broadcast address
+    pub fn broadcast(&self) -> u32 {
+        self.address | !self.mask()
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", Ipv4Addr::from(self.address), self.prefix_len)
+    }
+}
+
+/// Returns whether the address is in the subnet: its prefix matches the network address
+/// # Arguments
+/// * `cidr` - subnet
+/// * `address` - address to check
+/// # Examples
+/// ```
+/// # use std::net::Ipv4Addr;
+/// # use understanding_bitwise::network::{contains, Cidr};
+/// let cidr = Cidr::new(u32::from(Ipv4Addr::new(10, 0, 0, 0)), 8).unwrap();
+/// assert_eq!(cidr.to_string(), "10.0.0.0/8");
+/// assert!(contains(cidr, u32::from(Ipv4Addr::new(10, 200, 3, 4))));
+/// assert!(!contains(cidr, u32::from(Ipv4Addr::new(11, 0, 0, 1))));
+/// ```
+pub fn contains(cidr: Cidr, address: u32) -> bool {
+    (address ^ cidr.address) & cidr.mask() == 0
+}
+
+/// Bit of the first MAC octet set in group (multicast) addresses
+const MULTICAST_BIT: u8 = 0b01;
+
+/// Bit of the first MAC octet set in locally administered addresses, the U/L bit
+const LOCAL_BIT: u8 = 0b10;
+
+/// Returns the MAC address as the 48 lowest bits of *u64*
+/// # Arguments
+/// * `mac` - MAC address
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::mac_to_u64;
+/// assert_eq!(mac_to_u64([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]), 0x001A_2B3C_4D5E);
+/// ```
+pub fn mac_to_u64(mac: [u8; 6]) -> u64 {
+    mac.iter().fold(0, |number, &octet| number << 8 | octet as u64)
+}
+
+/// Returns the MAC address of the 48 lowest bits or None if a higher bit is set
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::mac_from_u64;
+/// assert_eq!(mac_from_u64(0x001A_2B3C_4D5E), Some([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]));
+/// assert_eq!(mac_from_u64(1 << 48), None);
+/// ```
+pub fn mac_from_u64(number: u64) -> Option<[u8; 6]> {
+    if number >> 48 != 0 {
+        return None;
+    }
+    let mut mac = [0; 6];
+    mac.copy_from_slice(&number.to_be_bytes()[2..]);
+    Some(mac)
+}
+
+/// Returns the organizationally unique identifier: the 24 highest bits of the MAC address
+/// # Arguments
+/// * `mac` - MAC address
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::oui;
+/// assert_eq!(oui([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]), 0x001A2B);
+/// ```
+pub fn oui(mac: [u8; 6]) -> u32 {
+    (mac_to_u64(mac) >> 24) as u32
+}
+
+/// Returns whether the MAC address is a group (multicast) address
+/// # Arguments
+/// * `mac` - MAC address
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::is_multicast;
+/// assert!(is_multicast([0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB]));
+/// assert!(!is_multicast([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]));
+/// ```
+pub fn is_multicast(mac: [u8; 6]) -> bool {
+    mac[0] & MULTICAST_BIT != 0
+}
+
+/// Returns whether the MAC address is locally administered rather than assigned by the vendor
+/// # Arguments
+/// * `mac` - MAC address
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::is_locally_administered;
+/// assert!(is_locally_administered([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]));
+/// assert!(!is_locally_administered([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]));
+/// ```
+pub fn is_locally_administered(mac: [u8; 6]) -> bool {
+    mac[0] & LOCAL_BIT != 0
+}
+
+/// Helper function. Returns the MAC address with the bit of the first octet set or cleared
+/// # Arguments
+/// * `mac` - MAC address
+/// * `bit` - mask of the bit
+/// * `value` - whether the bit is set
+fn with_first_octet_bit(mut mac: [u8; 6], bit: u8, value: bool) -> [u8; 6] {
+    mac[0] = mac[0] & !bit | if value { bit } else { 0 };
+    mac
+}
+
+/// Returns the MAC address made a multicast or a unicast one
+/// # Arguments
+/// * `mac` - MAC address
+/// * `multicast` - whether the address is multicast
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::set_multicast;
+/// assert_eq!(set_multicast([0x00; 6], true), [0x01, 0, 0, 0, 0, 0]);
+/// assert_eq!(set_multicast([0x03; 6], false)[0], 0x02);
+/// ```
+pub fn set_multicast(mac: [u8; 6], multicast: bool) -> [u8; 6] {
+    with_first_octet_bit(mac, MULTICAST_BIT, multicast)
+}
+
+/// Returns the MAC address made a locally administered or a universal one
+/// # Arguments
+/// * `mac` - MAC address
+/// * `local` - whether the address is locally administered
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::set_locally_administered;
+/// assert_eq!(set_locally_administered([0x00; 6], true), [0x02, 0, 0, 0, 0, 0]);
+/// assert_eq!(set_locally_administered([0x03; 6], false)[0], 0x01);
+/// ```
+pub fn set_locally_administered(mac: [u8; 6], local: bool) -> [u8; 6] {
+    with_first_octet_bit(mac, LOCAL_BIT, local)
+}
+
+/// Returns the modified EUI-64 interface identifier of the MAC address
+/// ([RFC 4291](https://www.rfc-editor.org/rfc/rfc4291#appendix-A)): *FFFE* inserted between the OUI and the rest,
+/// the U/L bit inverted
+/// # Arguments
+/// * `mac` - MAC address
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::mac_to_eui64;
+/// assert_eq!(mac_to_eui64([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]), 0x021A_2BFF_FE3C_4D5E);
+/// ```
+pub fn mac_to_eui64(mac: [u8; 6]) -> u64 {
+    let number = mac_to_u64(mac);
+    let eui64 = (number >> 24) << 40 | 0xFFFE << 24 | number & 0xFF_FFFF;
+    eui64 ^ (LOCAL_BIT as u64) << 56
+}
+
+/// Returns the MAC address of the modified EUI-64 interface identifier or None if it has no *FFFE* in the middle
+/// # Arguments
+/// * `eui64` - interface identifier
+/// # Examples
+/// ```
+/// # use understanding_bitwise::network::eui64_to_mac;
+/// assert_eq!(eui64_to_mac(0x021A_2BFF_FE3C_4D5E), Some([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]));
+/// assert_eq!(eui64_to_mac(0x021A_2B00_003C_4D5E), None);
+/// ```
+pub fn eui64_to_mac(eui64: u64) -> Option<[u8; 6]> {
+    if eui64 >> 24 & 0xFFFF != 0xFFFE {
+        return None;
+    }
+    let eui64 = eui64 ^ (LOCAL_BIT as u64) << 56;
+    mac_from_u64(eui64 >> 40 << 24 | eui64 & 0xFF_FFFF)
+}
+
+/// Returns the link-local IPv6 address of the MAC address: the *fe80::/64* prefix and the modified EUI-64
+/// # Arguments
+/// * `mac` - MAC address
+/// # Examples
+/// ```
+/// # use std::net::Ipv6Addr;
+/// # use understanding_bitwise::network::link_local_ipv6;
+/// let address: Ipv6Addr = "fe80::21a:2bff:fe3c:4d5e".parse().unwrap();
+/// assert_eq!(link_local_ipv6([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]), address);
+/// ```
+pub fn link_local_ipv6(mac: [u8; 6]) -> Ipv6Addr {
+    Ipv6Addr::from(0xFE80u128 << 112 | mac_to_eui64(mac) as u128)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_subnet_mask() {
+        for prefix_len in 0..=32 {
+            let mask = subnet_mask(prefix_len).unwrap();
+            assert_eq!(mask.count_ones(), prefix_len);
+            assert_eq!(mask.leading_ones(), prefix_len);
+        }
+        assert_eq!(subnet_mask(32), Ok(u32::MAX));
+        assert_eq!(subnet_mask(8).map(Ipv4Addr::from), Ok(Ipv4Addr::new(255, 0, 0, 0)));
+        assert_eq!(subnet_mask(33), Err(BitError::new("subnet_mask", 33, 0..33)));
+        assert!(network_address(0, 40).is_err() && broadcast_address(0, 40).is_err() && hosts_count(40).is_err());
+    }
+
+    #[test]
+    fn test_addresses() {
+        let address = u32::from(Ipv4Addr::new(172, 16, 45, 200));
+        assert_eq!(network_address(address, 20).map(Ipv4Addr::from), Ok(Ipv4Addr::new(172, 16, 32, 0)));
+        assert_eq!(broadcast_address(address, 20).map(Ipv4Addr::from), Ok(Ipv4Addr::new(172, 16, 47, 255)));
+        assert_eq!(network_address(address, 0), Ok(0));
+        assert_eq!(broadcast_address(address, 0), Ok(u32::MAX));
+        assert_eq!((network_address(address, 32), broadcast_address(address, 32)), (Ok(address), Ok(address)));
+        // Hosts lie strictly between the network and broadcast addresses
+        for prefix_len in 0..=30 {
+            let hosts = broadcast_address(address, prefix_len).unwrap() - network_address(address, prefix_len).unwrap() - 1;
+            assert_eq!(hosts_count(prefix_len), Ok(hosts));
+        }
+        assert_eq!((hosts_count(30), hosts_count(32)), (Ok(2), Ok(1)));
+    }
+
+    #[test]
+    fn test_cidr() {
+        let cidr = Cidr::new(u32::from(Ipv4Addr::new(192, 168, 1, 77)), 26).unwrap();
+        assert_eq!(cidr.to_string(), "192.168.1.64/26");
+        assert_eq!((cidr.prefix_len(), cidr.mask()), (26, 0xFFFF_FFC0));
+        assert_eq!(Ipv4Addr::from(cidr.broadcast()), Ipv4Addr::new(192, 168, 1, 127));
+        for last in 0..=255 {
+            let address = u32::from(Ipv4Addr::new(192, 168, 1, last));
+            assert_eq!(contains(cidr, address), (64..128).contains(&last));
+        }
+        let everything = Cidr::new(0x1234_5678, 0).unwrap();
+        assert!(contains(everything, 0) && contains(everything, u32::MAX));
+        let single = Cidr::new(0x1234_5678, 32).unwrap();
+        assert!(contains(single, 0x1234_5678) && !contains(single, 0x1234_5679));
+        assert!(Cidr::new(0, 33).is_err());
+    }
+
+    #[test]
+    fn test_mac_bits() {
+        let mac = [0x3C, 0x22, 0xFB, 0x12, 0x34, 0x56];
+        assert_eq!(mac_from_u64(mac_to_u64(mac)), Some(mac));
+        assert_eq!(oui(mac), 0x3C22FB);
+        assert!(!is_multicast(mac) && !is_locally_administered(mac));
+        let local = set_locally_administered(mac, true);
+        assert_eq!(local, [0x3E, 0x22, 0xFB, 0x12, 0x34, 0x56]);
+        assert!(is_locally_administered(local) && !is_multicast(local));
+        assert_eq!(set_locally_administered(local, false), mac);
+        // Setting a bit twice changes nothing, the other bits stay
+        assert_eq!(set_multicast(set_multicast(mac, true), true), [0x3D, 0x22, 0xFB, 0x12, 0x34, 0x56]);
+        assert_eq!(set_multicast(mac, false), mac);
+        assert!(is_multicast([0xFF; 6]) && is_locally_administered([0xFF; 6]));
+    }
+
+    #[test]
+    fn test_eui64() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let mut mac = [0; 6];
+            mac[..4].copy_from_slice(&state.to_be_bytes());
+            mac[4..].copy_from_slice(&state.to_le_bytes()[..2]);
+            let eui64 = mac_to_eui64(mac);
+            assert_eq!(eui64 >> 24 & 0xFFFF, 0xFFFE);
+            // Only the U/L bit of the first octet is inverted
+            assert_eq!(eui64.to_be_bytes()[0], mac[0] ^ LOCAL_BIT);
+            assert_eq!(eui64_to_mac(eui64), Some(mac));
+            let address = link_local_ipv6(mac);
+            assert!(address.is_unicast_link_local());
+            assert_eq!(u128::from(address) as u64, eui64);
+        }
+        assert_eq!(mac_to_eui64([0x02, 0, 0, 0, 0, 0x01]), 0x0000_00FF_FE00_0001);
+        assert_eq!(link_local_ipv6([0x02, 0, 0, 0, 0, 0x01]).to_string(), "fe80::ff:fe00:1");
+    }
+}
@@ -0,0 +1,285 @@
+//! Snowflake-style 64-bit identifiers: a timestamp in the highest bits, then a node number, then a sequence number
+//! in the lowest bits. The widths are a bit budget: every bit given to the timestamp doubles its lifetime, every bit
+//! given to the sequence doubles the ids a node can make per tick. Ids sort by time because the timestamp is on top
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Field of a packed id
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum IdField {
+    /// Ticks since the epoch
+    Timestamp,
+    /// Number of the generating node
+    Node,
+    /// Number of the id within the tick of the node
+    Sequence,
+}
+
+impl fmt::Display for IdField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IdField::Timestamp => "timestamp",
+            IdField::Node => "node",
+            IdField::Sequence => "sequence",
+        })
+    }
+}
+
+/// Values of the fields of an id
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct IdParts {
+    /// Time of the id, in the highest bits so that ids sort by it
+    pub timestamp: u64,
+    /// Machine or process that generated the id
+    pub node: u64,
+    /// Counter telling apart the ids of the node within one timestamp
+    pub sequence: u64,
+}
+
+/// Error of packing a value that doesn't fit into its field
+/// # Examples
+/// ```
+/// # use understanding_bitwise::packed_id::{IdParts, PackedId};
+/// let error = PackedId::SNOWFLAKE.pack(IdParts { timestamp: 0, node: 1024, sequence: 0 }).unwrap_err();
+/// assert_eq!(error.to_string(), "node 1024 doesn't fit into 10 bits, the maximum is 1023");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IdOverflow {
+    /// Field that overflowed
+    pub field: IdField,
+    /// Value that doesn't fit
+    pub value: u64,
+    /// Bits count of the field
+    pub bits: u32,
+}
+
+impl fmt::Display for IdOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} doesn't fit into {} bits, the maximum is {}", self.field, self.value, self.bits, ones(self.bits))
+    }
+}
+
+impl Error for IdOverflow {}
+
+/// Helper function. Returns the number with the *bits* lowest bits set
+/// # Arguments
+/// * `bits` - count of ones, up to 64
+fn ones(bits: u32) -> u64 {
+    u64::MAX.checked_shr(u64::BITS - bits).unwrap_or(0)
+}
+
+/// Layout of a packed id: widths of the timestamp, node and sequence fields from the highest to the lowest bits
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PackedId {
+    timestamp_bits: u32,
+    node_bits: u32,
+    sequence_bits: u32,
+}
+
+impl PackedId {
+    /// Twitter's layout: 41-bit milliseconds, 10-bit node, 12-bit sequence; the sign bit stays 0
+    pub const SNOWFLAKE: PackedId = PackedId { timestamp_bits: 41, node_bits: 10, sequence_bits: 12 };
+
+    /// Returns the layout or None if the fields take more than 64 bits
+    /// # Arguments
+    /// * `timestamp_bits` - bits count of the timestamp
+    /// * `node_bits` - bits count of the node number
+    /// * `sequence_bits` - bits count of the sequence number
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::packed_id::PackedId;
+    /// assert!(PackedId::new(42, 10, 12).is_some());
+    /// assert!(PackedId::new(42, 11, 12).is_none());
+    /// ```
+    pub fn new(timestamp_bits: u32, node_bits: u32, sequence_bits: u32) -> Option<Self> {
+        if timestamp_bits.checked_add(node_bits)?.checked_add(sequence_bits)? > u64::BITS {
+            return None;
+        }
+        Some(PackedId { timestamp_bits, node_bits, sequence_bits })
+    }
+
+    /// Returns the bits count of the field
+    /// # Arguments
+    /// * `field` - field of the id
+    pub fn bits(&self, field: IdField) -> u32 {
+        match field {
+            IdField::Timestamp => self.timestamp_bits,
+            IdField::Node => self.node_bits,
+            IdField::Sequence => self.sequence_bits,
+        }
+    }
+
+    /// Returns the index of the lowest bit of the field
+    /// # Arguments
+    /// * `field` - field of the id
+    pub fn offset(&self, field: IdField) -> u32 {
+        match field {
+            IdField::Timestamp => self.node_bits + self.sequence_bits,
+            IdField::Node => self.sequence_bits,
+            IdField::Sequence => 0,
+        }
+    }
+
+    /// Returns the largest value of the field
+    /// # Arguments
+    /// * `field` - field of the id
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::packed_id::{IdField, PackedId};
+    /// assert_eq!(PackedId::SNOWFLAKE.max(IdField::Sequence), 4095);
+    /// // About 69 years of milliseconds
+    /// assert_eq!(PackedId::SNOWFLAKE.max(IdField::Timestamp) / (1000 * 60 * 60 * 24 * 365), 69);
+    /// ```
+    pub fn max(&self, field: IdField) -> u64 {
+        ones(self.bits(field))
+    }
+
+    /// Returns the bits count of all the fields
+    pub fn total_bits(&self) -> u32 {
+        self.timestamp_bits + self.node_bits + self.sequence_bits
+    }
+
+    /// Returns the id of the parts or the error of the first field that doesn't fit
+    /// # Arguments
+    /// * `parts` - values of the fields
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::packed_id::{IdParts, PackedId};
+    /// let id = PackedId::SNOWFLAKE.pack(IdParts { timestamp: 1, node: 2, sequence: 3 }).unwrap();
+    /// assert_eq!(id, 1 << 22 | 2 << 12 | 3);
+    /// ```
+    pub fn pack(&self, parts: IdParts) -> Result<u64, IdOverflow> {
+        let fields = [(IdField::Timestamp, parts.timestamp), (IdField::Node, parts.node), (IdField::Sequence, parts.sequence)];
+        fields.iter().try_fold(0, |id, &(field, value)| {
+            let bits = self.bits(field);
+            if value & ! ones(bits) != 0 {
+                return Err(IdOverflow { field, value, bits });
+            }
+            // A zero-width field adds nothing and a 64-bit one is the whole id
+            Ok(id | value.checked_shl(self.offset(field)).unwrap_or(0))
+        })
+    }
+
+    /// Returns the parts of the id or None if bits above the fields are set
+    /// # Arguments
+    /// * `id` - packed id
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::packed_id::{IdParts, PackedId};
+    /// assert_eq!(PackedId::SNOWFLAKE.unpack(1 << 22 | 2 << 12 | 3), Some(IdParts { timestamp: 1, node: 2, sequence: 3 }));
+    /// assert_eq!(PackedId::SNOWFLAKE.unpack(1 << 63), None);
+    /// ```
+    pub fn unpack(&self, id: u64) -> Option<IdParts> {
+        if id & ! ones(self.total_bits()) != 0 {
+            return None;
+        }
+        let field = |field| id.checked_shr(self.offset(field)).unwrap_or(0) & self.max(field);
+        Some(IdParts { timestamp: field(IdField::Timestamp), node: field(IdField::Node), sequence: field(IdField::Sequence) })
+    }
+
+    /// Writes the bits of the fields of the id in binary, timestamp first, with *|* between the fields;
+    /// empty fields and unused bits above the fields are left out. Returns false and writes nothing if some of the
+    /// unused bits are set
+    /// # Arguments
+    /// * `id` - packed id
+    /// * `out` - *Write* object that gets the bits
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::packed_id::{IdParts, PackedId};
+    /// let layout = PackedId::new(8, 4, 4).unwrap();
+    /// let id = layout.pack(IdParts { timestamp: 0xA5, node: 3, sequence: 1 }).unwrap();
+    /// let mut vec = Vec::new();
+    /// assert!(layout.write_binary(id, &mut vec).unwrap());
+    /// assert_eq!(String::from_utf8(vec).unwrap(), "10100101|0011|0001");
+    /// ```
+    pub fn write_binary<W: Write>(&self, id: u64, out: &mut W) -> io::Result<bool> {
+        let Some(parts) = self.unpack(id) else {
+            return Ok(false);
+        };
+        let fields = [(IdField::Timestamp, parts.timestamp), (IdField::Node, parts.node), (IdField::Sequence, parts.sequence)];
+        let groups: Vec<String> = fields.iter().filter(|&&(field, _)| self.bits(field) != 0).map(|&(field, value)| {
+            format!("{value:0width$b}", width = self.bits(field) as usize)
+        }).collect();
+        write!(out, "{}", groups.join("|"))?;
+        Ok(true)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_round_trip() {
+        let layouts = [PackedId::SNOWFLAKE, PackedId::new(32, 16, 16).unwrap(), PackedId::new(64, 0, 0).unwrap(), PackedId::new(0, 1, 2).unwrap()];
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for layout in layouts {
+            for _ in 0..1000 {
+                let state = rng.next_u32();
+                let id = (state as u64) << 32 ^ state.rotate_left(7) as u64;
+                let id = id & ones(layout.total_bits());
+                let parts = layout.unpack(id).unwrap();
+                assert_eq!(layout.pack(parts), Ok(id));
+                for field in [IdField::Timestamp, IdField::Node, IdField::Sequence] {
+                    assert!(layout.bits(field) == 64 || parts_value(parts, field) >> layout.bits(field) == 0);
+                }
+            }
+        }
+    }
+
+    /// Helper function. Returns the value of the field
+    fn parts_value(parts: IdParts, field: IdField) -> u64 {
+        match field {
+            IdField::Timestamp => parts.timestamp,
+            IdField::Node => parts.node,
+            IdField::Sequence => parts.sequence,
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        let layout = PackedId::SNOWFLAKE;
+        let max = IdParts { timestamp: layout.max(IdField::Timestamp), node: 1023, sequence: 4095 };
+        assert_eq!(layout.pack(max), Ok(u64::MAX >> 1));
+        let overflow = |parts| layout.pack(parts).unwrap_err();
+        assert_eq!(overflow(IdParts { sequence: 4096, ..max }), IdOverflow { field: IdField::Sequence, value: 4096, bits: 12 });
+        assert_eq!(overflow(IdParts { timestamp: 1 << 41, ..max }).field, IdField::Timestamp);
+        // The first field that doesn't fit is reported
+        assert_eq!(overflow(IdParts { timestamp: 0, node: 1 << 10, sequence: 1 << 12 }).field, IdField::Node);
+        let empty = PackedId::new(0, 0, 0).unwrap();
+        assert_eq!(empty.pack(IdParts::default()), Ok(0));
+        assert_eq!(empty.pack(IdParts { node: 1, ..IdParts::default() }).unwrap_err().to_string(), "node 1 doesn't fit into 0 bits, the maximum is 0");
+        assert_eq!(empty.unpack(1), None);
+        assert!(PackedId::new(u32::MAX, 1, 0).is_none());
+    }
+
+    #[test]
+    fn test_ordering() {
+        // Later timestamps give larger ids whatever the node and sequence
+        let layout = PackedId::SNOWFLAKE;
+        let early = layout.pack(IdParts { timestamp: 1000, node: 1023, sequence: 4095 }).unwrap();
+        let late = layout.pack(IdParts { timestamp: 1001, node: 0, sequence: 0 }).unwrap();
+        assert!(early < late);
+        assert_eq!((layout.offset(IdField::Timestamp), layout.offset(IdField::Node), layout.total_bits()), (22, 12, 63));
+    }
+
+    #[test]
+    fn test_write_binary() {
+        let layout = PackedId::SNOWFLAKE;
+        let id = layout.pack(IdParts { timestamp: 1, node: 5, sequence: 2 }).unwrap();
+        let mut vec = Vec::new();
+        assert!(layout.write_binary(id, &mut vec).unwrap());
+        let text = String::from_utf8(vec).unwrap();
+        assert_eq!(text, format!("{}1|0000000101|000000000010", "0".repeat(40)));
+        let mut vec = Vec::new();
+        assert!(!layout.write_binary(1 << 63, &mut vec).unwrap());
+        assert!(vec.is_empty());
+        let mut vec = Vec::new();
+        assert!(PackedId::new(0, 2, 0).unwrap().write_binary(2, &mut vec).unwrap());
+        assert_eq!(String::from_utf8(vec).unwrap(), "10");
+    }
+}
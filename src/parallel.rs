@@ -0,0 +1,96 @@
+//! Parallel reductions over huge inputs. XOR and addition are associative, so chunks are reduced independently and the results combined
+
+use std::ops::BitXor;
+use rayon::prelude::*;
+use crate::find_unique;
+use crate::slice::{hamming_distance_slice, ones_count_slice};
+
+/// Count of words processed by one task. Keeps the scheduling overhead small compared to the work
+const CHUNK: usize = 1 << 14;
+
+/// Finds element that doesn't have duplicate like *find_unique*, reducing the chunks in parallel
+/// # Arguments
+/// * `vals` - elements to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::parallel::par_find_unique;
+/// assert_eq!(par_find_unique(&[45, 32, 777, 10, 45, 10, 32]), Some(777));
+/// ```
+pub fn par_find_unique<B>(vals: &[B]) -> Option<B>
+where
+    B: BitXor<Output = B> + Copy + Send + Sync {
+    vals.par_chunks(CHUNK)
+        .filter_map(find_unique)
+        .reduce_with(|left, right| left ^ right)
+}
+
+/// Returns the count of ones in all the words like *ones_count_slice*, counting the chunks in parallel
+/// # Arguments
+/// * `words` - words to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::parallel::par_ones_count_slice;
+/// assert_eq!(par_ones_count_slice(&[0b101; 100_000]), 200_000);
+/// ```
+pub fn par_ones_count_slice(words: &[u32]) -> u64 {
+    words.par_chunks(CHUNK).map(ones_count_slice).sum()
+}
+
+/// Returns the Hamming distance of the bitmaps like *hamming_distance_slice*, comparing the chunks in parallel.
+/// Returns None if the slices have different lengths
+/// # Arguments
+/// * `left` - words to work with
+/// * `right` - words to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::parallel::par_hamming_distance_slice;
+/// assert_eq!(par_hamming_distance_slice(&[0b1010; 100_000], &[0b0110; 100_000]), Some(200_000));
+/// ```
+pub fn par_hamming_distance_slice(left: &[u32], right: &[u32]) -> Option<u64> {
+    if left.len() != right.len() {
+        return None;
+    }
+    left.par_chunks(CHUNK).zip(right.par_chunks(CHUNK))
+        .map(|(left, right)| hamming_distance_slice(left, right))
+        .sum()
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+    use crate::find_unique_owned;
+
+    fn words(count: usize) -> Vec<u32> {
+        let mut rng = Xorshift32::new(0x0BAD_F00D).unwrap();
+        (0..count).map(|_| rng.next_u32()).collect()
+    }
+
+    #[test]
+    fn test_par_find_unique() {
+        let mut vals: Vec<u64> = (0..100_000).chain(0..100_000).collect();
+        vals.push(123_456_789);
+        vals.rotate_left(12_345);
+        assert_eq!(par_find_unique(&vals), Some(123_456_789));
+        assert_eq!(par_find_unique(&vals), find_unique_owned(vals.iter().copied()));
+        assert_eq!(par_find_unique::<u32>(&[]), None);
+    }
+
+    #[test]
+    fn test_par_ones_count_slice() {
+        for count in [0, 1, CHUNK - 1, CHUNK * 3 + 7] {
+            let words = words(count);
+            assert_eq!(par_ones_count_slice(&words), ones_count_slice(&words));
+        }
+    }
+
+    #[test]
+    fn test_par_hamming_distance_slice() {
+        let left = words(CHUNK * 2 + 5);
+        let right: Vec<u32> = left.iter().map(|word| ! word.rotate_right(7)).collect();
+        assert_eq!(par_hamming_distance_slice(&left, &right), hamming_distance_slice(&left, &right));
+        assert_eq!(par_hamming_distance_slice(&left, &left), Some(0));
+        assert_eq!(par_hamming_distance_slice(&left, &right[1..]), None);
+    }
+}
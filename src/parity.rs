@@ -0,0 +1,125 @@
+//! [*Parity bits*](https://en.wikipedia.org/wiki/Parity_bit), the simplest error detection: one extra bit makes
+//! the count of ones in the frame even (or odd), so any odd count of flipped bits is detected
+
+/// Kind of the parity bit
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Parity {
+    /// The count of ones in the frame is even
+    Even,
+    /// The count of ones in the frame is odd
+    Odd,
+}
+
+/// Returns 1 if the count of ones in the number is odd, otherwise 0. Folds the number in halves by XOR,
+/// so the lowest bit ends up holding XOR of all bits
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::parity::parity_of;
+/// assert_eq!(parity_of(0b1011), 1);
+/// assert_eq!(parity_of(0b1001), 0);
+/// ```
+pub fn parity_of(number: u32) -> u32 {
+    let mut folded = number;
+    folded ^= folded >> 16;
+    folded ^= folded >> 8;
+    folded ^= folded >> 4;
+    folded ^= folded >> 2;
+    folded ^= folded >> 1;
+    folded & 1
+}
+
+/// Returns the 9-bit frame: the byte in the low bits and the parity bit in the bit 8, like UART sends it after the data
+/// # Arguments
+/// * `byte` - data byte
+/// * `parity` - kind of the parity bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::parity::{add_parity_bit, Parity};
+/// assert_eq!(add_parity_bit(0b0000_0111, Parity::Even), 0b1_0000_0111);
+/// assert_eq!(add_parity_bit(0b0000_0111, Parity::Odd), 0b0_0000_0111);
+/// ```
+pub fn add_parity_bit(byte: u8, parity: Parity) -> u16 {
+    let bit = parity_of(byte as u32) ^ (parity == Parity::Odd) as u32;
+    (bit as u16) << 8 | byte as u16
+}
+
+/// Returns the data byte of the frame or None if the parity is wrong or bits above the bit 8 are set
+/// # Arguments
+/// * `frame` - 9-bit frame made by *add_parity_bit*
+/// * `parity` - kind of the parity bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::parity::{add_parity_bit, check_parity, Parity};
+/// let frame = add_parity_bit(b'A', Parity::Odd);
+/// assert_eq!(check_parity(frame, Parity::Odd), Some(b'A'));
+/// assert_eq!(check_parity(frame ^ 0b100, Parity::Odd), None);
+/// ```
+pub fn check_parity(frame: u16, parity: Parity) -> Option<u8> {
+    let expected = (parity == Parity::Odd) as u32;
+    (frame >> 9 == 0 && parity_of(frame as u32) == expected).then_some(frame as u8)
+}
+
+/// Returns the frames of the bytes, see *add_parity_bit*
+/// # Arguments
+/// * `bytes` - data bytes
+/// * `parity` - kind of the parity bits
+pub fn add_parity_bits(bytes: &[u8], parity: Parity) -> Vec<u16> {
+    bytes.iter().map(|&byte| add_parity_bit(byte, parity)).collect()
+}
+
+/// Returns the data bytes of the frames or the index of the first invalid frame, see *check_parity*
+/// # Arguments
+/// * `frames` - 9-bit frames
+/// * `parity` - kind of the parity bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::parity::{add_parity_bits, check_parity_slice, Parity};
+/// let mut frames = add_parity_bits(b"bits", Parity::Even);
+/// assert_eq!(check_parity_slice(&frames, Parity::Even), Ok(b"bits".to_vec()));
+/// frames[2] ^= 1 << 8;
+/// assert_eq!(check_parity_slice(&frames, Parity::Even), Err(2));
+/// ```
+pub fn check_parity_slice(frames: &[u16], parity: Parity) -> Result<Vec<u8>, usize> {
+    frames.iter().enumerate().map(|(index, &frame)| check_parity(frame, parity).ok_or(index)).collect()
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parity_of() {
+        for number in (0..1 << 16).chain([u32::MAX, 0x8000_0000, 0xDEAD_BEEF]) {
+            assert_eq!(parity_of(number), number.count_ones() % 2);
+        }
+    }
+
+    #[test]
+    fn test_single_errors_detected() {
+        for parity in [Parity::Even, Parity::Odd] {
+            for byte in 0..=u8::MAX {
+                let frame = add_parity_bit(byte, parity);
+                assert_eq!(check_parity(frame, parity), Some(byte));
+                assert_eq!(frame.count_ones() % 2, (parity == Parity::Odd) as u32);
+                for bit in 0..9 {
+                    assert_eq!(check_parity(frame ^ 1 << bit, parity), None);
+                }
+                // Two flipped bits go unnoticed
+                assert_eq!(check_parity(frame ^ 0b11, parity), Some(byte ^ 0b11));
+            }
+        }
+        assert_eq!(check_parity(1 << 9, Parity::Odd), None);
+    }
+
+    #[test]
+    fn test_slices() {
+        let bytes: Vec<u8> = (0..=u8::MAX).collect();
+        let frames = add_parity_bits(&bytes, Parity::Odd);
+        assert_eq!(check_parity_slice(&frames, Parity::Odd), Ok(bytes));
+        assert_eq!(check_parity_slice(&frames, Parity::Even), Err(0));
+        assert_eq!(check_parity_slice(&[], Parity::Even), Ok(vec![]));
+    }
+}
@@ -0,0 +1,206 @@
+//! Unix file permissions: three *rwx* triplets for the owner, the group and the others from bit 8 down to bit 0,
+//! and the setuid, setgid and sticky bits above them, which is why modes are written in octal: one digit per triplet
+
+use crate::{bit_flags, get_bits, set_bits};
+
+bit_flags! {
+    /// Permission bits of a file mode (*st_mode* without the file type)
+    pub struct FileMode: u32 {
+        /// Run executables as their owner
+        const SETUID = 0o4000;
+        /// Run executables as their group, inherit the group in directories
+        const SETGID = 0o2000;
+        /// Only owners may delete entries of the directory
+        const STICKY = 0o1000;
+        /// Owner may read
+        const OWNER_READ = 0o400;
+        /// Owner may write
+        const OWNER_WRITE = 0o200;
+        /// Owner may execute
+        const OWNER_EXECUTE = 0o100;
+        /// Group may read
+        const GROUP_READ = 0o040;
+        /// Group may write
+        const GROUP_WRITE = 0o020;
+        /// Group may execute
+        const GROUP_EXECUTE = 0o010;
+        /// Others may read
+        const OTHERS_READ = 0o004;
+        /// Others may write
+        const OTHERS_WRITE = 0o002;
+        /// Others may execute
+        const OTHERS_EXECUTE = 0o001;
+    }
+}
+
+/// Whom a triplet of permissions belongs to
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Who {
+    /// Owner of the file
+    Owner,
+    /// Members of the group of the file
+    Group,
+    /// Everybody else
+    Others,
+}
+
+impl Who {
+    /// All the classes from the highest triplet down
+    pub const ALL: [Who; 3] = [Who::Owner, Who::Group, Who::Others];
+
+    /// Helper function. Returns the index of the lowest bit of the triplet
+    fn offset(self) -> u32 {
+        match self {
+            Who::Owner => 6,
+            Who::Group => 3,
+            Who::Others => 0,
+        }
+    }
+
+    /// Helper function. Returns the special bit shown in the execute position of the triplet and its letter
+    fn special(self) -> (FileMode, char) {
+        match self {
+            Who::Owner => (FileMode::SETUID, 's'),
+            Who::Group => (FileMode::SETGID, 's'),
+            Who::Others => (FileMode::STICKY, 't'),
+        }
+    }
+}
+
+impl FileMode {
+    /// Returns the *rwx* triplet as a number in 0..=7: read is 4, write is 2, execute is 1
+    /// # Arguments
+    /// * `who` - whose triplet
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::permissions::{FileMode, Who};
+    /// let mode = FileMode::from_bits(0o754).unwrap();
+    /// assert_eq!((mode.triplet(Who::Owner), mode.triplet(Who::Group), mode.triplet(Who::Others)), (7, 5, 4));
+    /// ```
+    pub fn triplet(&self, who: Who) -> u32 {
+        get_bits(self.bits(), who.offset(), 3).expect("triplets fit into the mode")
+    }
+
+    /// Returns the mode with the triplet replaced or None if it's above 7
+    /// # Arguments
+    /// * `who` - whose triplet
+    /// * `rwx` - new triplet
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::permissions::{FileMode, Who};
+    /// let mode = FileMode::from_bits(0o644).unwrap();
+    /// assert_eq!(mode.with_triplet(Who::Others, 0).map(|mode| mode.bits()), Some(0o640));
+    /// assert_eq!(mode.with_triplet(Who::Group, 8), None);
+    /// ```
+    pub fn with_triplet(&self, who: Who, rwx: u32) -> Option<Self> {
+        set_bits(self.bits(), who.offset(), 3, rwx).map(Self::from_bits_truncate)
+    }
+
+    /// Returns the mode as *ls* shows it, like *rwxr-xr--*. The setuid and setgid bits show as *s* in the execute
+    /// position of the owner and the group, the sticky bit as *t* for the others; capital if the execute bit is unset
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::permissions::FileMode;
+    /// assert_eq!(FileMode::from_bits(0o754).unwrap().symbolic(), "rwxr-xr--");
+    /// assert_eq!(FileMode::from_bits(0o4755).unwrap().symbolic(), "rwsr-xr-x");
+    /// assert_eq!(FileMode::from_bits(0o1776).unwrap().symbolic(), "rwxrwxrwT");
+    /// ```
+    pub fn symbolic(&self) -> String {
+        let mut text = String::with_capacity(9);
+        for who in Who::ALL {
+            let rwx = self.triplet(who);
+            text.push(if rwx & 0b100 != 0 { 'r' } else { '-' });
+            text.push(if rwx & 0b010 != 0 { 'w' } else { '-' });
+            let (special, letter) = who.special();
+            text.push(match (self.contains(special), rwx & 0b001 != 0) {
+                (true, true) => letter,
+                (true, false) => letter.to_ascii_uppercase(),
+                (false, true) => 'x',
+                (false, false) => '-',
+            });
+        }
+        text
+    }
+
+    /// Returns the mode in octal, four digits with the special bits first
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::permissions::FileMode;
+    /// assert_eq!((FileMode::OWNER_READ | FileMode::OWNER_WRITE | FileMode::GROUP_READ).octal(), "0640");
+    /// ```
+    pub fn octal(&self) -> String {
+        format!("{:04o}", self.bits())
+    }
+
+    /// Returns the mode of the octal string like *chmod* takes it: one to four octal digits, or None if it isn't one
+    /// # Arguments
+    /// * `text` - octal digits
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::permissions::FileMode;
+    /// assert_eq!(FileMode::from_octal("755").map(|mode| mode.bits()), Some(0o755));
+    /// assert_eq!(FileMode::from_octal("2775").map(|mode| mode.symbolic()), Some("rwxrwsr-x".to_owned()));
+    /// assert_eq!(FileMode::from_octal("778"), None);
+    /// assert_eq!(FileMode::from_octal("17777"), None);
+    /// ```
+    pub fn from_octal(text: &str) -> Option<Self> {
+        if text.is_empty() || text.len() > 4 {
+            return None;
+        }
+        // Every octal digit is three bits
+        let bits = text.chars().try_fold(0, |bits, digit| Some(bits << 3 | digit.to_digit(8)?))?;
+        Self::from_bits(bits)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triplets() {
+        let mut mode = FileMode::empty();
+        for (who, rwx) in Who::ALL.into_iter().zip([6, 4, 1]) {
+            mode = mode.with_triplet(who, rwx).unwrap();
+        }
+        assert_eq!(mode.bits(), 0o641);
+        assert_eq!(mode, FileMode::OWNER_READ | FileMode::OWNER_WRITE | FileMode::GROUP_READ | FileMode::OTHERS_EXECUTE);
+        // Replacing a triplet keeps the special bits and the other triplets
+        let mode = FileMode::from_bits(0o7777).unwrap().with_triplet(Who::Group, 0).unwrap();
+        assert_eq!(mode.octal(), "7707");
+        assert_eq!(mode.triplet(Who::Group), 0);
+        assert_eq!(FileMode::all().bits(), 0o7777);
+    }
+
+    #[test]
+    fn test_symbolic() {
+        let cases = [
+            (0o000, "---------"),
+            (0o777, "rwxrwxrwx"),
+            (0o640, "rw-r-----"),
+            (0o4644, "rwSr--r--"),
+            (0o2711, "rwx--s--x"),
+            (0o2701, "rwx--S--x"),
+            (0o1777, "rwxrwxrwt"),
+            (0o7000, "--S--S--T"),
+        ];
+        for (bits, text) in cases {
+            assert_eq!(FileMode::from_bits(bits).unwrap().symbolic(), text);
+        }
+    }
+
+    #[test]
+    fn test_octal() {
+        for bits in 0..=0o7777 {
+            let mode = FileMode::from_bits(bits).unwrap();
+            assert_eq!(FileMode::from_octal(&mode.octal()), Some(mode));
+            assert_eq!(FileMode::from_octal(&format!("{bits:o}")), Some(mode));
+        }
+        assert_eq!(FileMode::from_octal("0"), Some(FileMode::empty()));
+        for text in ["", "9", "-1", "+755", "0o755", " 755", "07550"] {
+            assert_eq!(FileMode::from_octal(text), None, "{text}");
+        }
+        assert_eq!(FileMode::from_bits(0o10000), None);
+    }
+}
@@ -0,0 +1,217 @@
+//! Arbitrary permutations of the bits of a word. A permutation maps every output bit *i* to its source bit *perm\[i\]*.
+//! The naive application moves the bits one by one, the [*Beneš network*](https://en.wikipedia.org/wiki/Clos_network#Bene%C5%A1_network_(m_=_n_=_2))
+//! is configured once and then applies the permutation in 9 masked swap stages
+
+/// Count of stages of the Beneš network of 32 bits: 2 × log₂(32) - 1
+pub const BENES_STAGES: usize = 9;
+
+/// Returns the number with the bit pairs *(j, j + shift)* swapped for every bit *j* of the mask (delta swap).
+/// The mask must not have ones at the positions *j + shift* of its own pairs
+/// # Arguments
+/// * `number` - number to work with
+/// * `mask` - lower positions of the pairs to be swapped
+/// * `shift` - distance between the bits of a pair
+/// # Examples
+/// ```
+/// # use understanding_bitwise::permutation::delta_swap;
+/// assert_eq!(delta_swap(0b0001, 0b0011, 2), 0b0100);
+/// assert_eq!(delta_swap(0b0110, 0b0011, 2), 0b1001);
+/// ```
+pub fn delta_swap(number: u32, mask: u32, shift: u32) -> u32 {
+    // Bits that differ from their pair partner, the swap flips both of them
+    let difference = (number >> shift ^ number) & mask;
+    number ^ difference ^ difference << shift
+}
+
+/// Helper function. Returns true if every index from 0 to 31 occurs in the permutation once
+/// # Arguments
+/// * `perm` - source bit of every output bit
+fn is_permutation(perm: &[u32; 32]) -> bool {
+    perm.iter().try_fold(0u32, |seen, &source| {
+        let bit = 1u32.checked_shl(source)?;
+        (seen & bit == 0).then_some(seen | bit)
+    }).is_some()
+}
+
+/// Returns the number with the bits permuted one by one, or None if *perm* isn't a permutation of 0..32
+/// # Arguments
+/// * `number` - number to work with
+/// * `perm` - source bit of every output bit
+/// # Examples
+/// ```
+/// # use understanding_bitwise::permutation::permute_bits;
+/// let mut perm: [u32; 32] = std::array::from_fn(|index| index as u32);
+/// perm.swap(0, 5);
+/// assert_eq!(permute_bits(0b10_0000, &perm), Some(1));
+/// ```
+pub fn permute_bits(number: u32, perm: &[u32; 32]) -> Option<u32> {
+    if !is_permutation(perm) {
+        return None;
+    }
+    Some(perm.iter().enumerate().fold(0, |result, (index, &source)| result | (number >> source & 1) << index))
+}
+
+/// Configured Beneš network: stage *s* swaps the bit pairs at the distance 16, 8, 4, 2, 1, 2, 4, 8, 16
+/// selected by its mask
+/// # Examples
+/// ```
+/// # use understanding_bitwise::permutation::BenesNetwork;
+/// let reverse: [u32; 32] = std::array::from_fn(|index| 31 - index as u32);
+/// let network = BenesNetwork::new(&reverse).unwrap();
+/// assert_eq!(network.apply(0x0000_00F1), 0x8F00_0000);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BenesNetwork {
+    masks: [u32; BENES_STAGES],
+}
+
+impl BenesNetwork {
+    /// Returns the network performing the permutation or None if *perm* isn't a permutation of 0..32
+    /// # Arguments
+    /// * `perm` - source bit of every output bit
+    pub fn new(perm: &[u32; 32]) -> Option<Self> {
+        if !is_permutation(perm) {
+            return None;
+        }
+        let mut destinations = [0usize; 32];
+        for (index, &source) in perm.iter().enumerate() {
+            destinations[source as usize] = index;
+        }
+        let mut masks = [0; BENES_STAGES];
+        route(&destinations, 0, 0, &mut masks);
+        Some(BenesNetwork { masks })
+    }
+
+    /// Returns the distance of the pairs swapped by the stage
+    /// # Arguments
+    /// * `stage` - index of the stage from 0 to 8
+    pub fn stage_shift(stage: usize) -> u32 {
+        16 >> stage.min(BENES_STAGES - 1 - stage)
+    }
+
+    /// Returns the masks of the stages
+    pub fn masks(&self) -> [u32; BENES_STAGES] {
+        self.masks
+    }
+
+    /// Returns the number with the bits permuted
+    /// # Arguments
+    /// * `number` - number to work with
+    pub fn apply(&self, number: u32) -> u32 {
+        self.masks.iter().enumerate().fold(number, |number, (stage, &mask)| delta_swap(number, mask, Self::stage_shift(stage)))
+    }
+}
+
+/// Helper function. Configures the subnetwork of the bits *base..base + n* by the looping algorithm:
+/// the bits are 2-colored so that the two bits of every input pair and of every output pair take different halves,
+/// then both halves are configured recursively
+/// # Arguments
+/// * `destinations` - output position of the bit at every input position of the subnetwork
+/// * `base` - lowest bit of the subnetwork
+/// * `depth` - nesting level, the subnetwork uses the stages *depth* and *8 - depth*
+/// * `masks` - masks of the stages
+fn route(destinations: &[usize], base: usize, depth: usize, masks: &mut [u32; BENES_STAGES]) {
+    let n = destinations.len();
+    let half = n / 2;
+    if n == 2 {
+        if destinations[0] == 1 {
+            masks[depth] |= 1 << base;
+        }
+        return;
+    }
+    let mut sources = vec![0; n];
+    for (input, &output) in destinations.iter().enumerate() {
+        sources[output] = input;
+    }
+    // Half taken by every input bit: 0 is the lower half
+    let mut colors: Vec<Option<usize>> = vec![None; n];
+    for start in 0..n {
+        let mut input = start;
+        while colors[input].is_none() {
+            colors[input] = Some(0);
+            // The input partner takes the other half, and so does the bit sharing its output pair
+            let partner = input ^ half;
+            colors[partner] = Some(1);
+            input = sources[destinations[partner] ^ half];
+        }
+    }
+    let mut halves = vec![vec![0; half]; 2];
+    for (input, &output) in destinations.iter().enumerate() {
+        let color = colors[input].unwrap();
+        if input < half && color == 1 {
+            masks[depth] |= 1 << (base + input);
+        }
+        if output >= half && color == 0 {
+            masks[BENES_STAGES - 1 - depth] |= 1 << (base + output - half);
+        }
+        halves[color][input % half] = output % half;
+    }
+    route(&halves[0], base, depth + 1, masks);
+    route(&halves[1], base + half, depth + 1, masks);
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    fn random_permutations() -> Vec<[u32; 32]> {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        (0..200).map(|_| {
+            let mut perm: [u32; 32] = std::array::from_fn(|index| index as u32);
+            // Fisher-Yates shuffle
+            for index in (1..32).rev() {
+                let state = rng.next_u32();
+                perm.swap(index, state as usize % (index + 1));
+            }
+            perm
+        }).collect()
+    }
+
+    #[test]
+    fn test_delta_swap() {
+        assert_eq!(delta_swap(0xFFFF_0000, 0xFFFF, 16), 0x0000_FFFF);
+        assert_eq!(delta_swap(0b10, 0b01, 1), 0b01);
+        assert_eq!(delta_swap(0x1234_5678, 0, 4), 0x1234_5678);
+    }
+
+    #[test]
+    fn test_invalid_permutations() {
+        let mut perm: [u32; 32] = std::array::from_fn(|index| index as u32);
+        perm[3] = 4;
+        assert_eq!(permute_bits(1, &perm), None);
+        assert_eq!(BenesNetwork::new(&perm), None);
+        perm[3] = 32;
+        assert_eq!(permute_bits(1, &perm), None);
+        assert_eq!(BenesNetwork::new(&perm), None);
+    }
+
+    #[test]
+    fn test_special_permutations() {
+        let identity: [u32; 32] = std::array::from_fn(|index| index as u32);
+        assert_eq!(BenesNetwork::new(&identity).unwrap().masks(), [0; BENES_STAGES]);
+        let reverse: [u32; 32] = std::array::from_fn(|index| 31 - index as u32);
+        let rotate: [u32; 32] = std::array::from_fn(|index| (index as u32 + 29) % 32);
+        let reverse_network = BenesNetwork::new(&reverse).unwrap();
+        let rotate_network = BenesNetwork::new(&rotate).unwrap();
+        for number in [0, 1, 0xDEAD_BEEF, u32::MAX, 0x8000_0001] {
+            assert_eq!(reverse_network.apply(number), number.reverse_bits());
+            assert_eq!(rotate_network.apply(number), number.rotate_left(3));
+            assert_eq!(permute_bits(number, &rotate), Some(number.rotate_left(3)));
+        }
+    }
+
+    #[test]
+    fn test_random_permutations() {
+        for perm in random_permutations() {
+            let network = BenesNetwork::new(&perm).unwrap();
+            for (index, &source) in perm.iter().enumerate() {
+                assert_eq!(network.apply(1 << source), 1 << index);
+            }
+            for number in [0xDEAD_BEEF, 0x0F0F_1234, u32::MAX] {
+                assert_eq!(Some(network.apply(number)), permute_bits(number, &perm));
+            }
+        }
+    }
+}
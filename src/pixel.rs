@@ -0,0 +1,200 @@
+//! Packing of RGBA colors into pixel formats as bit fields. Channels narrower than 8 bits are scaled with rounding
+//! to the nearest value rather than truncated, so that white stays white and every narrow value survives a round trip
+
+use crate::{get_bits, set_bits};
+
+/// Color with 8-bit channels
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Rgba {
+    /// Red channel
+    pub r: u8,
+    /// Green channel
+    pub g: u8,
+    /// Blue channel
+    pub b: u8,
+    /// Alpha channel, 255 is opaque
+    pub a: u8,
+}
+
+impl Rgba {
+    /// Returns the color of the channels
+    /// # Arguments
+    /// * `r` - red channel
+    /// * `g` - green channel
+    /// * `b` - blue channel
+    /// * `a` - alpha channel
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Rgba { r, g, b, a }
+    }
+}
+
+/// Layout of a packed pixel, channels listed from the highest bits
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PixelFormat {
+    /// 8-bit red, green, blue and alpha in 32 bits
+    Rgba8888,
+    /// 8-bit alpha, red, green and blue in 32 bits
+    Argb8888,
+    /// 5-bit red, 6-bit green and 5-bit blue in 16 bits, always opaque
+    Rgb565,
+    /// 5-bit red, green and blue and 1-bit alpha in 16 bits
+    Rgb5551,
+}
+
+impl PixelFormat {
+    /// Returns the bits count of the pixel
+    pub fn bits(self) -> u32 {
+        match self {
+            PixelFormat::Rgba8888 | PixelFormat::Argb8888 => 32,
+            PixelFormat::Rgb565 | PixelFormat::Rgb5551 => 16,
+        }
+    }
+
+    /// Returns the offsets and widths of the red, green, blue and alpha fields; the width of a missing channel is 0
+    pub fn fields(self) -> [(u32, u32); 4] {
+        match self {
+            PixelFormat::Rgba8888 => [(24, 8), (16, 8), (8, 8), (0, 8)],
+            PixelFormat::Argb8888 => [(16, 8), (8, 8), (0, 8), (24, 8)],
+            PixelFormat::Rgb565 => [(11, 5), (5, 6), (0, 5), (0, 0)],
+            PixelFormat::Rgb5551 => [(11, 5), (6, 5), (1, 5), (0, 1)],
+        }
+    }
+
+    /// Returns the color packed into the pixel; narrow channels take the nearest value, a missing alpha is dropped
+    /// # Arguments
+    /// * `color` - color to pack
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::pixel::{PixelFormat, Rgba};
+    /// let orange = Rgba::new(255, 128, 0, 255);
+    /// assert_eq!(PixelFormat::Rgba8888.pack(orange), 0xFF80_00FF);
+    /// assert_eq!(PixelFormat::Argb8888.pack(orange), 0xFFFF_8000);
+    /// // 128 of 255 is nearest to 32 of 63 and alpha is gone
+    /// assert_eq!(PixelFormat::Rgb565.pack(orange), 0b11111_100000_00000);
+    /// assert_eq!(PixelFormat::Rgb5551.pack(orange), 0b11111_10000_00000_1);
+    /// ```
+    pub fn pack(self, color: Rgba) -> u32 {
+        let channels = [color.r, color.g, color.b, color.a];
+        self.fields().iter().zip(channels).fold(0, |pixel, (&(offset, width), channel)| {
+            set_bits(pixel, offset, width, narrow(channel, width)).expect("fields fit into the pixel")
+        })
+    }
+
+    /// Returns the color of the pixel or None if bits above the pixel are set; narrow channels are scaled to the
+    /// nearest 8-bit value, a missing alpha is opaque
+    /// # Arguments
+    /// * `pixel` - packed pixel
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::pixel::{PixelFormat, Rgba};
+    /// assert_eq!(PixelFormat::Argb8888.unpack(0x80FF_8000), Some(Rgba::new(255, 128, 0, 128)));
+    /// assert_eq!(PixelFormat::Rgb565.unpack(0b11111_100000_00000), Some(Rgba::new(255, 130, 0, 255)));
+    /// assert_eq!(PixelFormat::Rgb5551.unpack(0b00000_00000_11111_0), Some(Rgba::new(0, 0, 255, 0)));
+    /// assert_eq!(PixelFormat::Rgb565.unpack(0x1_0000), None);
+    /// ```
+    pub fn unpack(self, pixel: u32) -> Option<Rgba> {
+        if pixel.checked_shr(self.bits()).unwrap_or(0) != 0 {
+            return None;
+        }
+        let [r, g, b, a] = self.fields().map(|(offset, width)| {
+            let value = get_bits(pixel, offset, width).expect("fields fit into the pixel");
+            widen(value, width)
+        });
+        Some(Rgba { r, g, b, a })
+    }
+}
+
+/// Helper function. Returns the 8-bit channel scaled to the width, rounded to nearest: *value · (2^width - 1) / 255*
+/// # Arguments
+/// * `value` - 8-bit channel
+/// * `width` - bits count of the field
+fn narrow(value: u8, width: u32) -> u32 {
+    let max = (1 << width) - 1;
+    (value as u32 * max + 127) / 255
+}
+
+/// Helper function. Returns the channel of the width scaled to 8 bits, rounded to nearest: *value · 255 / (2^width - 1)*.
+/// A missing channel (width 0) is 255
+/// # Arguments
+/// * `value` - channel of the width
+/// * `width` - bits count of the field
+fn widen(value: u32, width: u32) -> u8 {
+    let max = (1 << width) - 1;
+    match width {
+        0 => u8::MAX,
+        _ => ((value * 255 + max / 2) / max) as u8,
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    const FORMATS: [PixelFormat; 4] = [PixelFormat::Rgba8888, PixelFormat::Argb8888, PixelFormat::Rgb565, PixelFormat::Rgb5551];
+
+    #[test]
+    fn test_scaling() {
+        for width in 1..=8 {
+            let max = (1u32 << width) - 1;
+            // Every narrow value survives widening and narrowing back
+            for value in 0..=max {
+                assert_eq!(narrow(widen(value, width), width), value);
+            }
+            // Narrowing picks the nearest narrow value
+            for value in 0..=255u8 {
+                let narrowed = narrow(value, width);
+                let error = (narrowed as f64 * 255.0 / max as f64 - value as f64).abs();
+                assert!(error <= 255.0 / max as f64 / 2.0);
+            }
+            assert_eq!((narrow(0, width), narrow(255, width)), (0, max));
+            assert_eq!((widen(0, width), widen(max, width)), (0, 255));
+        }
+        // Bit replication, the usual shortcut, is off by at most one; plain shifting isn't even that close
+        for value in 0..64 {
+            assert!((widen(value, 6) as u32).abs_diff(value << 2 | value >> 4) <= 1);
+        }
+        assert_ne!(widen(31, 5) as u32, 31 << 3);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        for format in FORMATS {
+            let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+            for _ in 0..1000 {
+                let state = rng.next_u32();
+                let pixel = state.checked_shr(32 - format.bits()).unwrap_or(state);
+                let color = format.unpack(pixel).unwrap();
+                let packed = format.pack(color);
+                // Fields cover every bit of the pixel, so it survives unpacking and packing back
+                assert_eq!(packed, pixel);
+                assert_eq!(format.unpack(packed), Some(color));
+            }
+            assert_eq!(format.pack(Rgba::new(255, 255, 255, 255)) as u64, (1u64 << format.bits()) - 1);
+            assert_eq!(format.unpack(0), Some(Rgba::new(0, 0, 0, if format == PixelFormat::Rgb565 { 255 } else { 0 })));
+        }
+        // 8-bit formats keep the color exactly
+        let color = Rgba::new(0x12, 0x34, 0x56, 0x78);
+        assert_eq!(PixelFormat::Rgba8888.pack(color), 0x1234_5678);
+        assert_eq!(PixelFormat::Argb8888.pack(color), 0x7812_3456);
+        assert_eq!(PixelFormat::Argb8888.unpack(0x7812_3456), Some(color));
+    }
+
+    #[test]
+    fn test_fields() {
+        for format in FORMATS {
+            let fields = format.fields();
+            let mask = fields.iter().fold(0u64, |mask, &(offset, width)| {
+                let field = ((1u64 << width) - 1) << offset;
+                assert_eq!(mask & field, 0);
+                mask | field
+            });
+            // Fields cover the pixel without overlapping (the missing alpha of RGB565 is empty)
+            assert_eq!(mask, (1 << format.bits()) - 1);
+        }
+        assert_eq!(PixelFormat::Rgb5551.pack(Rgba::new(0, 0, 0, 127)), 0);
+        assert_eq!(PixelFormat::Rgb5551.pack(Rgba::new(0, 0, 0, 128)), 1);
+        assert_eq!(PixelFormat::Rgba8888.unpack(u32::MAX), Some(Rgba::new(255, 255, 255, 255)));
+    }
+}
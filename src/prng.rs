@@ -0,0 +1,347 @@
+//! Pseudorandom generators built only from shifts, XORs and rotations:
+//! [*xorshift*](https://en.wikipedia.org/wiki/Xorshift) and [*xoshiro128++*](https://prng.di.unimi.it/).
+//! The state transitions are plain functions, so they can be studied apart from the generators.
+//! Every xorshift step *x ^= x << a* is an invertible linear map over GF(2); only some shift triples
+//! make the product of three such maps have the maximal period 2ⁿ - 1, which is why the constants are fixed
+
+#[cfg(feature = "rand")]
+use rand::RngCore;
+use crate::hash::splitmix64;
+
+/// Shifts of *xorshift32* (left, right, left) giving the period 2³² - 1
+pub const XORSHIFT32_SHIFTS: [u32; 3] = [13, 17, 5];
+
+/// Shifts of *xorshift64* (left, right, left) giving the period 2⁶⁴ - 1
+pub const XORSHIFT64_SHIFTS: [u32; 3] = [13, 7, 17];
+
+/// Returns the next state of the 32-bit xorshift generator
+/// # Arguments
+/// * `state` - current state
+/// * `shifts` - shift amounts (left, right, left), each from 1 to 31, e.g. *XORSHIFT32_SHIFTS*
+/// # Panics
+/// Panics if a shift amount is not in 1..32: a zero shift clears the state and a full one is undefined
+/// # Examples
+/// ```
+/// # use understanding_bitwise::prng::{xorshift32_step, XORSHIFT32_SHIFTS};
+/// assert_eq!(xorshift32_step(1, XORSHIFT32_SHIFTS), 270_369);
+/// assert_eq!(xorshift32_step(0, XORSHIFT32_SHIFTS), 0);
+/// ```
+pub fn xorshift32_step(state: u32, shifts: [u32; 3]) -> u32 {
+    assert!(shifts.iter().all(|shift| (1..u32::BITS).contains(shift)), "xorshift32 shifts must be in 1..32");
+    let mut state = state;
+    state ^= state << shifts[0];
+    state ^= state >> shifts[1];
+    state ^ state << shifts[2]
+}
+
+/// Returns the next state of the 64-bit xorshift generator
+/// # Arguments
+/// * `state` - current state
+/// * `shifts` - shift amounts (left, right, left), each from 1 to 63, e.g. *XORSHIFT64_SHIFTS*
+/// # Panics
+/// Panics if a shift amount is not in 1..64: a zero shift clears the state and a full one is undefined
+pub fn xorshift64_step(state: u64, shifts: [u32; 3]) -> u64 {
+    assert!(shifts.iter().all(|shift| (1..u64::BITS).contains(shift)), "xorshift64 shifts must be in 1..64");
+    let mut state = state;
+    state ^= state << shifts[0];
+    state ^= state >> shifts[1];
+    state ^ state << shifts[2]
+}
+
+/// Returns the next state of *xoshiro128++*. Word 1 is shifted into word 2 and the words are XORed in a ring,
+/// the rotation of word 3 keeps the high bits flowing back
+/// # Arguments
+/// * `state` - current state
+pub fn xoshiro128pp_step(state: [u32; 4]) -> [u32; 4] {
+    let [mut s0, mut s1, mut s2, mut s3] = state;
+    let shifted = s1 << 9;
+    s2 ^= s0;
+    s3 ^= s1;
+    s1 ^= s2;
+    s0 ^= s3;
+    s2 ^= shifted;
+    s3 = s3.rotate_left(11);
+    [s0, s1, s2, s3]
+}
+
+/// Returns the output of *xoshiro128++* for the state. The linear state is scrambled by additions and a rotation,
+/// which hides the linearity of the low bits
+/// # Arguments
+/// * `state` - current state
+pub fn xoshiro128pp_output(state: [u32; 4]) -> u32 {
+    state[0].wrapping_add(state[3]).rotate_left(7).wrapping_add(state[0])
+}
+
+/// 32-bit xorshift generator with the period 2³² - 1. The state 0 is never reached
+/// # Examples
+/// ```
+/// # use understanding_bitwise::prng::Xorshift32;
+/// let mut generator = Xorshift32::new(1).unwrap();
+/// assert_eq!(generator.next_u32(), 270_369);
+/// assert_eq!(generator.state(), 270_369);
+/// assert!(Xorshift32::new(0).is_none());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Xorshift32 {
+    state: u32,
+}
+
+impl Xorshift32 {
+    /// Returns the generator or None if the seed is 0, which is a fixed point of the transition
+    /// # Arguments
+    /// * `seed` - initial state
+    pub fn new(seed: u32) -> Option<Self> {
+        (seed != 0).then_some(Xorshift32 { state: seed })
+    }
+
+    /// Returns the current state
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+
+    /// Advances the state and returns it
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = xorshift32_step(self.state, XORSHIFT32_SHIFTS);
+        self.state
+    }
+}
+
+/// 64-bit xorshift generator with the period 2⁶⁴ - 1. The state 0 is never reached
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Xorshift64 {
+    state: u64,
+}
+
+impl Xorshift64 {
+    /// Returns the generator or None if the seed is 0, which is a fixed point of the transition
+    /// # Arguments
+    /// * `seed` - initial state
+    pub fn new(seed: u64) -> Option<Self> {
+        (seed != 0).then_some(Xorshift64 { state: seed })
+    }
+
+    /// Returns the current state
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Advances the state and returns it
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = xorshift64_step(self.state, XORSHIFT64_SHIFTS);
+        self.state
+    }
+}
+
+/// *xoshiro128++* generator with the period 2¹²⁸ - 1
+/// # Examples
+/// ```
+/// # use understanding_bitwise::prng::Xoshiro128PlusPlus;
+/// let mut generator = Xoshiro128PlusPlus::new([1, 2, 3, 4]).unwrap();
+/// assert_eq!(generator.next_u32(), 641);
+/// assert_eq!(generator.state(), [7, 0, 1026, 12_288]);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Xoshiro128PlusPlus {
+    state: [u32; 4],
+}
+
+impl Xoshiro128PlusPlus {
+    /// Returns the generator or None if all words of the seed are 0
+    /// # Arguments
+    /// * `seed` - initial state
+    pub fn new(seed: [u32; 4]) -> Option<Self> {
+        (seed != [0; 4]).then_some(Xoshiro128PlusPlus { state: seed })
+    }
+
+    /// Returns the generator with the state expanded from the number by *splitmix64*, so similar numbers give unrelated states
+    /// # Arguments
+    /// * `seed` - number to work with
+    pub fn from_u64(seed: u64) -> Self {
+        let mut splitmix = seed;
+        let low = splitmix64(&mut splitmix);
+        let high = splitmix64(&mut splitmix);
+        let state = [low as u32, (low >> 32) as u32, high as u32, (high >> 32) as u32];
+        // splitmix64 outputs every value once per period, so two consecutive outputs aren't both 0
+        Xoshiro128PlusPlus { state }
+    }
+
+    /// Returns the current state
+    pub fn state(&self) -> [u32; 4] {
+        self.state
+    }
+
+    /// Returns the output of the current state and advances the state
+    pub fn next_u32(&mut self) -> u32 {
+        let output = xoshiro128pp_output(self.state);
+        self.state = xoshiro128pp_step(self.state);
+        output
+    }
+}
+
+/// Helper function. Fills the bytes with the little endian outputs of the generator
+/// # Arguments
+/// * `dest` - bytes to be filled
+/// * `next` - generator of the outputs
+#[cfg(feature = "rand")]
+fn fill_bytes_from<const N: usize>(dest: &mut [u8], mut next: impl FnMut() -> [u8; N]) {
+    for chunk in dest.chunks_mut(N) {
+        chunk.copy_from_slice(&next()[..chunk.len()]);
+    }
+}
+
+#[cfg(feature = "rand")]
+impl RngCore for Xorshift32 {
+    fn next_u32(&mut self) -> u32 {
+        Xorshift32::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let low = Xorshift32::next_u32(self) as u64;
+        (Xorshift32::next_u32(self) as u64) << 32 | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_from(dest, || Xorshift32::next_u32(self).to_le_bytes());
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl RngCore for Xorshift64 {
+    fn next_u32(&mut self) -> u32 {
+        (Xorshift64::next_u64(self) >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Xorshift64::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_from(dest, || Xorshift64::next_u64(self).to_le_bytes());
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl RngCore for Xoshiro128PlusPlus {
+    fn next_u32(&mut self) -> u32 {
+        Xoshiro128PlusPlus::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let low = Xoshiro128PlusPlus::next_u32(self) as u64;
+        (Xoshiro128PlusPlus::next_u32(self) as u64) << 32 | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes_from(dest, || Xoshiro128PlusPlus::next_u32(self).to_le_bytes());
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that every bit position is set in about half of the outputs and the top nibbles are spread evenly
+    fn general_test_uniformity(mut next: impl FnMut() -> u32) {
+        const SAMPLES: u32 = 1 << 14;
+        let mut ones = [0u32; 32];
+        let mut nibbles = [0u32; 16];
+        for _ in 0..SAMPLES {
+            let output = next();
+            for (index, count) in ones.iter_mut().enumerate() {
+                *count += output >> index & 1;
+            }
+            nibbles[(output >> 28) as usize] += 1;
+        }
+        for (index, &count) in ones.iter().enumerate() {
+            assert!((SAMPLES * 47 / 100..=SAMPLES * 53 / 100).contains(&count), "bit {index}: {count}");
+        }
+        // Chi-square with 15 degrees of freedom, 0.999 quantile is about 37.7
+        let expected = (SAMPLES / 16) as f64;
+        let chi_square: f64 = nibbles.iter().map(|&count| (count as f64 - expected).powi(2) / expected).sum();
+        assert!(chi_square < 37.7, "chi-square {chi_square}");
+    }
+
+    #[test]
+    fn test_xorshift32() {
+        let mut generator = Xorshift32::new(1).unwrap();
+        let outputs: Vec<u32> = (0..3).map(|_| generator.next_u32()).collect();
+        assert_eq!(outputs, [270_369, 67_634_689, 2_647_435_461]);
+        general_test_uniformity(|| generator.next_u32());
+    }
+
+    #[test]
+    fn test_xorshift64() {
+        assert!(Xorshift64::new(0).is_none());
+        let mut generator = Xorshift64::new(1).unwrap();
+        assert_eq!(generator.next_u64(), 1_082_269_761);
+        general_test_uniformity(|| (generator.next_u64() >> 32) as u32);
+        general_test_uniformity(|| generator.next_u64() as u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "xorshift32 shifts must be in 1..32")]
+    fn test_xorshift32_invalid_shifts() {
+        xorshift32_step(1, [13, 32, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "xorshift64 shifts must be in 1..64")]
+    fn test_xorshift64_invalid_shifts() {
+        xorshift64_step(1, [0, 7, 17]);
+    }
+
+    #[test]
+    fn test_xoshiro128pp() {
+        assert!(Xoshiro128PlusPlus::new([0; 4]).is_none());
+        let mut generator = Xoshiro128PlusPlus::from_u64(0);
+        assert_ne!(generator.state(), [0; 4]);
+        general_test_uniformity(|| generator.next_u32());
+    }
+
+    #[test]
+    fn test_transitions_are_invertible() {
+        // Every step is a bijection, so distinct states stay distinct
+        let mut states: Vec<u32> = (1..1 << 16).map(|state| xorshift32_step(state, XORSHIFT32_SHIFTS)).collect();
+        states.sort_unstable();
+        states.dedup();
+        assert_eq!(states.len(), (1 << 16) - 1);
+        // A bad shift triple: shifts by half of the word only mix the two halves, so the cycle is short
+        let mut state = 1u32;
+        let period = (1..).find(|_| {
+            state = xorshift32_step(state, [16, 16, 16]);
+            state == 1
+        });
+        assert!(period.is_some_and(|period| period < 1 << 10));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_rng_core() {
+        let mut generator = Xoshiro128PlusPlus::from_u64(7);
+        let mut copy = generator;
+        let mut bytes = [0u8; 7];
+        RngCore::fill_bytes(&mut generator, &mut bytes);
+        assert_eq!(bytes[..4], copy.next_u32().to_le_bytes());
+        assert_eq!(bytes[4..], copy.next_u32().to_le_bytes()[..3]);
+        let mut generator = Xorshift32::new(1).unwrap();
+        assert_eq!(RngCore::next_u64(&mut generator), 67_634_689 << 32 | 270_369);
+        let mut generator = Xorshift64::new(1).unwrap();
+        assert_eq!(RngCore::next_u32(&mut generator), 0);
+    }
+}
@@ -0,0 +1,311 @@
+//! Decoders of real network protocol headers. Headers are transmitted in network (big endian) byte order,
+//! so every 32-bit row of the header is read as a big endian word and the fields are taken by *get_bits*.
+//! Bit offsets count from the lowest bit of the row, so the first transmitted bit is the bit 31
+
+use crate::get_bits;
+
+/// Helper function. Returns the big endian word of the header row
+/// # Arguments
+/// * `bytes` - header bytes
+/// * `row` - index of the 32-bit row
+fn row(bytes: &[u8], row: usize) -> u32 {
+    let start = row * 4;
+    u32::from_be_bytes([bytes[start], bytes[start + 1], bytes[start + 2], bytes[start + 3]])
+}
+
+/// Helper function. Returns the field of the row. The field must fit into the row
+/// # Arguments
+/// * `row` - row to work with
+/// * `offset` - index of the lowest bit of the field
+/// * `width` - bits count of the field
+fn field(row: u32, offset: u32, width: u32) -> u32 {
+    get_bits(row, offset, width).unwrap()
+}
+
+/// Control flags of the IPv4 header
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ipv4Flags {
+    /// Reserved, must be false
+    pub reserved: bool,
+    /// Don't Fragment (DF): routers must drop the datagram rather than fragment it
+    pub dont_fragment: bool,
+    /// More Fragments (MF): more fragments of the datagram follow this one
+    pub more_fragments: bool,
+}
+
+/// Fixed part of the IPv4 header ([RFC 791](https://www.rfc-editor.org/rfc/rfc791))
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ipv4Header {
+    /// Version, 4 for IPv4
+    pub version: u8,
+    /// Internet Header Length (IHL) in 32-bit words
+    pub ihl: u8,
+    /// Differentiated Services Code Point (DSCP), the upper 6 bits of the former Type of Service
+    pub dscp: u8,
+    /// Explicit Congestion Notification (ECN), the lower 2 bits of the former Type of Service
+    pub ecn: u8,
+    /// Total Length of the datagram, header and data, in bytes
+    pub total_length: u16,
+    /// Identification shared by the fragments of one datagram
+    pub identification: u16,
+    /// Control flags
+    pub flags: Ipv4Flags,
+    /// Fragment Offset in 8 byte units
+    pub fragment_offset: u16,
+    /// Time to Live (TTL) in hops, decremented by every router
+    pub ttl: u8,
+    /// Protocol of the data, e.g. 6 for TCP and 17 for UDP
+    pub protocol: u8,
+    /// Header Checksum, the ones' complement sum of the header words
+    pub checksum: u16,
+    /// Source Address
+    pub source: u32,
+    /// Destination Address
+    pub destination: u32,
+}
+
+impl Ipv4Header {
+    /// Returns the header length in bytes
+    pub fn header_len(&self) -> usize {
+        self.ihl as usize * 4
+    }
+}
+
+/// Returns the decoded IPv4 header or None if the bytes are too short, the version isn't 4 or the IHL is less than 5
+/// # Arguments
+/// * `bytes` - bytes starting with the header
+/// # Examples
+/// ```
+/// # use understanding_bitwise::protocols::decode_ipv4_header;
+/// let bytes = [
+///     0x45, 0x00, 0x00, 0x54, 0xa6, 0xf2, 0x40, 0x00, 0x40, 0x01, 0x91, 0x8e,
+///     0xc0, 0xa8, 0x01, 0x02, 0xc0, 0xa8, 0x01, 0x01,
+/// ];
+/// let header = decode_ipv4_header(&bytes).unwrap();
+/// assert_eq!(header.total_length, 84);
+/// assert!(header.flags.dont_fragment);
+/// assert_eq!(header.source, 0xc0a80102);
+/// ```
+pub fn decode_ipv4_header(bytes: &[u8]) -> Option<Ipv4Header> {
+    const MIN_LEN: usize = 20;
+    if bytes.len() < MIN_LEN {
+        return None;
+    }
+
+    let row0 = row(bytes, 0);
+    let row1 = row(bytes, 1);
+    let row2 = row(bytes, 2);
+    let header = Ipv4Header {
+        version: field(row0, 28, 4) as u8,
+        ihl: field(row0, 24, 4) as u8,
+        dscp: field(row0, 18, 6) as u8,
+        ecn: field(row0, 16, 2) as u8,
+        total_length: field(row0, 0, 16) as u16,
+        identification: field(row1, 16, 16) as u16,
+        flags: Ipv4Flags {
+            reserved: field(row1, 15, 1) == 1,
+            dont_fragment: field(row1, 14, 1) == 1,
+            more_fragments: field(row1, 13, 1) == 1,
+        },
+        fragment_offset: field(row1, 0, 13) as u16,
+        ttl: field(row2, 24, 8) as u8,
+        protocol: field(row2, 16, 8) as u8,
+        checksum: field(row2, 0, 16) as u16,
+        source: row(bytes, 3),
+        destination: row(bytes, 4),
+    };
+    if header.version != 4 || header.header_len() < MIN_LEN || bytes.len() < header.header_len() {
+        return None;
+    }
+    Some(header)
+}
+
+/// Control flags of the TCP header
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct TcpFlags {
+    /// Congestion Window Reduced (CWR)
+    pub cwr: bool,
+    /// ECN-Echo (ECE)
+    pub ece: bool,
+    /// Urgent pointer field is significant (URG)
+    pub urg: bool,
+    /// Acknowledgment field is significant (ACK)
+    pub ack: bool,
+    /// Push function (PSH)
+    pub psh: bool,
+    /// Reset the connection (RST)
+    pub rst: bool,
+    /// Synchronize sequence numbers (SYN)
+    pub syn: bool,
+    /// No more data from the sender (FIN)
+    pub fin: bool,
+}
+
+impl TcpFlags {
+    /// Returns the flags of the flags byte (the 14th byte of the header)
+    /// # Arguments
+    /// * `byte` - flags byte
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::protocols::TcpFlags;
+    /// let flags = TcpFlags::from_byte(0x12);
+    /// assert!(flags.syn && flags.ack);
+    /// assert!(! flags.fin);
+    /// ```
+    pub fn from_byte(byte: u8) -> Self {
+        let flag = |index| field(byte as u32, index, 1) == 1;
+        TcpFlags {
+            cwr: flag(7),
+            ece: flag(6),
+            urg: flag(5),
+            ack: flag(4),
+            psh: flag(3),
+            rst: flag(2),
+            syn: flag(1),
+            fin: flag(0),
+        }
+    }
+
+    /// Returns the flags byte
+    pub fn to_byte(&self) -> u8 {
+        [self.fin, self.syn, self.rst, self.psh, self.ack, self.urg, self.ece, self.cwr]
+            .iter()
+            .enumerate()
+            .fold(0, |byte, (index, &flag)| byte | (flag as u8) << index)
+    }
+}
+
+/// Fixed part of the TCP header ([RFC 9293](https://www.rfc-editor.org/rfc/rfc9293))
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TcpHeader {
+    /// Source Port
+    pub source_port: u16,
+    /// Destination Port
+    pub destination_port: u16,
+    /// Sequence Number of the first data byte
+    pub sequence_number: u32,
+    /// Acknowledgment Number, the next sequence number the sender expects if ACK is set
+    pub acknowledgment_number: u32,
+    /// Data Offset, the header length in 32-bit words
+    pub data_offset: u8,
+    /// Control bits
+    pub flags: TcpFlags,
+    /// Window size in bytes the sender is willing to receive
+    pub window: u16,
+    /// Checksum over the pseudo-header, header and data
+    pub checksum: u16,
+    /// Urgent Pointer, the offset of the urgent data end from the sequence number if URG is set
+    pub urgent_pointer: u16,
+}
+
+/// Returns the decoded TCP header or None if the bytes are too short or the data offset is less than 5
+/// # Arguments
+/// * `bytes` - bytes starting with the header
+/// # Examples
+/// ```
+/// # use understanding_bitwise::protocols::decode_tcp_header;
+/// let bytes = [
+///     0xc3, 0x50, 0x00, 0x50, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+///     0x50, 0x02, 0xfa, 0xf0, 0x12, 0x34, 0x00, 0x00,
+/// ];
+/// let header = decode_tcp_header(&bytes).unwrap();
+/// assert_eq!(header.destination_port, 80);
+/// assert!(header.flags.syn);
+/// ```
+pub fn decode_tcp_header(bytes: &[u8]) -> Option<TcpHeader> {
+    const MIN_LEN: usize = 20;
+    if bytes.len() < MIN_LEN {
+        return None;
+    }
+
+    let row0 = row(bytes, 0);
+    let row3 = row(bytes, 3);
+    let row4 = row(bytes, 4);
+    let header = TcpHeader {
+        source_port: field(row0, 16, 16) as u16,
+        destination_port: field(row0, 0, 16) as u16,
+        sequence_number: row(bytes, 1),
+        acknowledgment_number: row(bytes, 2),
+        data_offset: field(row3, 28, 4) as u8,
+        flags: TcpFlags::from_byte(field(row3, 16, 8) as u8),
+        window: field(row3, 0, 16) as u16,
+        checksum: field(row4, 16, 16) as u16,
+        urgent_pointer: field(row4, 0, 16) as u16,
+    };
+    if (header.data_offset as usize) * 4 < MIN_LEN || bytes.len() < header.data_offset as usize * 4 {
+        return None;
+    }
+    Some(header)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IPV4: [u8; 20] = [
+        0x45, 0xb9, 0x05, 0xdc, 0x1c, 0x46, 0x20, 0x1f, 0x40, 0x06, 0xb1, 0xe6,
+        0xac, 0x10, 0x0a, 0x63, 0xac, 0x10, 0x0a, 0x0c,
+    ];
+
+    #[test]
+    fn test_decode_ipv4_header() {
+        let header = decode_ipv4_header(&IPV4).unwrap();
+        assert_eq!(header.version, 4);
+        assert_eq!(header.ihl, 5);
+        assert_eq!(header.dscp, 0b101110);
+        assert_eq!(header.ecn, 0b01);
+        assert_eq!(header.total_length, 1500);
+        assert_eq!(header.identification, 0x1c46);
+        assert_eq!(header.flags, Ipv4Flags { reserved: false, dont_fragment: false, more_fragments: true });
+        assert_eq!(header.fragment_offset, 0x1f);
+        assert_eq!(header.ttl, 64);
+        assert_eq!(header.protocol, 6);
+        assert_eq!(header.checksum, 0xb1e6);
+        assert_eq!(header.source, 0xac100a63);
+        assert_eq!(header.destination, 0xac100a0c);
+    }
+
+    #[test]
+    fn test_decode_ipv4_header_invalid() {
+        assert_eq!(decode_ipv4_header(&IPV4[..19]), None);
+        let mut bytes = IPV4;
+        bytes[0] = 0x65;
+        assert_eq!(decode_ipv4_header(&bytes), None);
+        bytes[0] = 0x44;
+        assert_eq!(decode_ipv4_header(&bytes), None);
+        // Options announced but missing
+        bytes[0] = 0x46;
+        assert_eq!(decode_ipv4_header(&bytes), None);
+        assert!(decode_ipv4_header(&[&bytes[..], &[0; 4]].concat()).is_some());
+    }
+
+    #[test]
+    fn test_tcp_flags() {
+        for byte in 0..=u8::MAX {
+            assert_eq!(TcpFlags::from_byte(byte).to_byte(), byte);
+        }
+        assert_eq!(TcpFlags::from_byte(0b1000_0001), TcpFlags { cwr: true, fin: true, ..TcpFlags::default() });
+    }
+
+    #[test]
+    fn test_decode_tcp_header() {
+        let bytes = [
+            0x01, 0xbb, 0xd4, 0x31, 0xde, 0xad, 0xbe, 0xef, 0x00, 0x00, 0x10, 0x00,
+            0x80, 0x18, 0x01, 0xf5, 0xab, 0xcd, 0x00, 0x07, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0,
+        ];
+        let header = decode_tcp_header(&bytes).unwrap();
+        assert_eq!(header.source_port, 443);
+        assert_eq!(header.destination_port, 54321);
+        assert_eq!(header.sequence_number, 0xdeadbeef);
+        assert_eq!(header.acknowledgment_number, 0x1000);
+        assert_eq!(header.data_offset, 8);
+        assert_eq!(header.flags, TcpFlags { ack: true, psh: true, ..TcpFlags::default() });
+        assert_eq!(header.window, 501);
+        assert_eq!(header.checksum, 0xabcd);
+        assert_eq!(header.urgent_pointer, 7);
+        assert_eq!(decode_tcp_header(&bytes[..31]), None);
+        assert_eq!(decode_tcp_header(&bytes[..19]), None);
+    }
+}
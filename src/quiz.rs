@@ -0,0 +1,208 @@
+//! Randomized exercises on the operations of the crate with their answers and explanations, for courseware.
+//! The exercises come from a seeded *Xorshift32*, so the same seed and difficulty give the same quiz
+
+use crate::explained::{invert_bit_explained, remove_bit_explained, set_bit_explained, swap_bits_explained,
+                       unset_bit_explained, Step};
+use crate::prng::Xorshift32;
+use crate::wide::widening_mul;
+use crate::{binary_ones_count, hob};
+
+/// Difficulty of the exercises
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Difficulty {
+    /// Single bit operations on 8-bit numbers written in binary
+    Easy,
+    /// Also swaps, hob and ones count on 16-bit numbers written in binary
+    Medium,
+    /// Also bit removal on 32-bit numbers written in hexadecimal
+    Hard,
+}
+
+impl Difficulty {
+    /// Returns the bits count of the numbers of the exercises
+    pub fn bits(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 8,
+            Difficulty::Medium => 16,
+            Difficulty::Hard => 32,
+        }
+    }
+
+    /// Returns the count of kinds of exercises: the first kinds of *Quiz::next_exercise*
+    fn kinds(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 3,
+            Difficulty::Medium => 6,
+            Difficulty::Hard => 7,
+        }
+    }
+}
+
+/// Exercise with its solution
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Exercise {
+    /// Question
+    pub prompt: String,
+    /// Expected answer
+    pub answer: u32,
+    /// How the answer is found
+    pub explanation: String,
+    /// Difficulty of the exercise
+    pub difficulty: Difficulty,
+}
+
+/// Infinite generator of exercises
+/// # Examples
+/// ```
+/// # use understanding_bitwise::quiz::{Difficulty, Quiz};
+/// let mut quiz = Quiz::new(42, Difficulty::Easy).unwrap();
+/// let exercise = quiz.next_exercise();
+/// assert!(exercise.prompt.ends_with('?'));
+/// assert!(exercise.answer < 1 << 8);
+/// // The same seed gives the same quiz
+/// assert_eq!(Quiz::new(42, Difficulty::Easy).unwrap().next_exercise(), exercise);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Quiz {
+    generator: Xorshift32,
+    difficulty: Difficulty,
+}
+
+impl Quiz {
+    /// Returns the quiz or None if the seed is 0
+    /// # Arguments
+    /// * `seed` - seed of the exercises
+    /// * `difficulty` - difficulty of the exercises
+    pub fn new(seed: u32, difficulty: Difficulty) -> Option<Self> {
+        Some(Quiz { generator: Xorshift32::new(seed)?, difficulty })
+    }
+
+    /// Returns the next exercise
+    pub fn next_exercise(&mut self) -> Exercise {
+        let bits = self.difficulty.bits();
+        let kind = self.below(self.difficulty.kinds());
+        // Never 0, so the hob exists
+        let number = (self.generator.next_u32() >> (u32::BITS - bits)).max(1);
+        let index = self.below(bits);
+        let x = self.format(number);
+        let (prompt, answer, explanation) = match kind {
+            0 => explained(format!("What is x after set_bit(x, {index}) for x = {x}?"), set_bit_explained(number, index)),
+            1 => explained(format!("What is x after unset_bit(x, {index}) for x = {x}?"), unset_bit_explained(number, index)),
+            2 => explained(format!("What is x after invert_bit(x, {index}) for x = {x}?"), invert_bit_explained(number, index)),
+            3 => {
+                let other = self.below(bits);
+                explained(format!("What is x after swap_bits(x, {index}, {other}) for x = {x}?"), swap_bits_explained(number, index, other))
+            }
+            4 => {
+                let answer = hob(number).unwrap();
+                (format!("What's the hob of y = {x}?"), answer,
+                 format!("The highest one of {x} is the bit {answer}, counting from 0 at the right"))
+            }
+            5 => {
+                let answer = binary_ones_count(number);
+                (format!("How many ones has y = {x}?"), answer,
+                 format!("Clearing the lowest one with y & (y - 1) until y is 0 takes {answer} steps"))
+            }
+            _ => explained(format!("What is x after remove_bit(x, {index}) for x = {x}?"), remove_bit_explained(number, index)),
+        };
+        Exercise { prompt, answer, explanation, difficulty: self.difficulty }
+    }
+
+    /// Helper function. Returns a random number below the bound
+    /// # Arguments
+    /// * `bound` - bound above 0
+    fn below(&mut self, bound: u32) -> u32 {
+        // High word of the product scales the random word down to the bound
+        widening_mul(self.generator.next_u32(), bound).0
+    }
+
+    /// Helper function. Returns the number in binary padded to the bits of the difficulty, in hexadecimal for *Hard*
+    /// # Arguments
+    /// * `number` - number to work with
+    fn format(&self, number: u32) -> String {
+        match self.difficulty {
+            Difficulty::Hard => format!("{number:#010x}"),
+            _ => format!("{number:#0width$b}", width = self.difficulty.bits() as usize + 2),
+        }
+    }
+}
+
+impl Iterator for Quiz {
+    type Item = Exercise;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_exercise())
+    }
+}
+
+/// Helper function. Returns the prompt, the result of the steps and their operations one per line as the explanation
+/// # Arguments
+/// * `prompt` - question
+/// * `steps` - steps of an explained operation with valid arguments
+fn explained(prompt: String, steps: Option<Vec<Step>>) -> (String, u32, String) {
+    let steps = steps.expect("indexes are below the bits count");
+    let answer = steps.last().unwrap().value;
+    let explanation = steps.iter().map(|step| format!("{} = {:#b}: {}", step.operation, step.value, step.description))
+        .collect::<Vec<_>>().join("\n");
+    (prompt, answer, explanation)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{invert_bit, remove_bit, set_bit, swap_bits, unset_bit};
+
+    /// Returns the answer computed from the prompt by the functions of the crate
+    fn solve(prompt: &str) -> u32 {
+        let parse = |text: &str| match text.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16).unwrap(),
+            None => u32::from_str_radix(text.strip_prefix("0b").unwrap(), 2).unwrap(),
+        };
+        let number = parse(prompt.rsplit(' ').next().unwrap().trim_end_matches('?'));
+        if prompt.starts_with("What's the hob") {
+            return hob(number).unwrap();
+        }
+        if prompt.starts_with("How many ones") {
+            return number.count_ones();
+        }
+        let call = &prompt[prompt.find("after ").unwrap() + 6..prompt.find(')').unwrap()];
+        let (name, arguments) = call.split_once("(x, ").unwrap();
+        let indexes: Vec<u32> = arguments.split(", ").map(|index| index.parse().unwrap()).collect();
+        match name {
+            "set_bit" => set_bit(number, indexes[0]),
+            "unset_bit" => unset_bit(number, indexes[0]),
+            "invert_bit" => invert_bit(number, indexes[0]),
+            "swap_bits" => swap_bits(number, indexes[0], indexes[1]),
+            "remove_bit" => remove_bit(number, indexes[0]),
+            _ => unreachable!(),
+        }.unwrap()
+    }
+
+    #[test]
+    fn test_answers() {
+        for (difficulty, seed) in [(Difficulty::Easy, 1), (Difficulty::Medium, 0x00C0_FFEE), (Difficulty::Hard, 12345)] {
+            let mut prompts = std::collections::HashSet::new();
+            for exercise in Quiz::new(seed, difficulty).unwrap().take(200) {
+                assert_eq!(exercise.difficulty, difficulty);
+                assert_eq!(exercise.answer, solve(&exercise.prompt), "{}", exercise.prompt);
+                assert!(!exercise.explanation.is_empty());
+                // Answers of the bit operations fit into the numbers of the difficulty
+                assert!(exercise.answer.checked_shr(difficulty.bits()).unwrap_or(0) == 0);
+                prompts.insert(exercise.prompt.split(['(', '=']).next().unwrap().to_owned());
+            }
+            // Every kind of the difficulty comes up
+            assert_eq!(prompts.len() as u32, difficulty.kinds());
+        }
+        assert!(Quiz::new(0, Difficulty::Easy).is_none());
+    }
+
+    #[test]
+    fn test_format() {
+        let exercise = Quiz::new(7, Difficulty::Easy).unwrap().find(|exercise| exercise.prompt.contains("set_bit")).unwrap();
+        let number = exercise.prompt.rsplit(' ').next().unwrap();
+        assert_eq!(number.len(), "0b00000000?".len());
+        let exercise = Quiz::new(7, Difficulty::Hard).unwrap().next_exercise();
+        assert!(exercise.prompt.ends_with('?') && exercise.prompt.contains("0x"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Random masks and bit patterns for fuzz-style testing of bit code, on top of the [`rand`](https://docs.rs/rand) traits.
+//! The generators of the *prng* module implement *RngCore*, so they can drive these functions reproducibly
+
+use rand::Rng;
+use crate::{pdep, prefix_xor};
+
+/// Returns a uniformly random word with exactly *ones* bits set, or None if *ones* is above 32.
+/// Every step deposits a one into a random zero: *pdep* of a single bit selects the zero by its rank
+/// # Arguments
+/// * `rng` - source of randomness
+/// * `ones` - count of ones
+/// # Examples
+/// ```
+/// # use understanding_bitwise::prng::Xoshiro128PlusPlus;
+/// # use understanding_bitwise::random::random_with_ones;
+/// let mut rng = Xoshiro128PlusPlus::from_u64(1);
+/// assert_eq!(random_with_ones(&mut rng, 5).map(u32::count_ones), Some(5));
+/// assert_eq!(random_with_ones(&mut rng, 33), None);
+/// ```
+pub fn random_with_ones<R: Rng + ?Sized>(rng: &mut R, ones: u32) -> Option<u32> {
+    random_with_ones_in(rng, ones, u32::MAX)
+}
+
+/// Helper function. Returns a uniformly random submask of the mask with exactly *ones* bits set,
+/// or None if the mask has fewer bits
+/// # Arguments
+/// * `rng` - source of randomness
+/// * `ones` - count of ones
+/// * `mask` - positions available for the ones
+fn random_with_ones_in<R: Rng + ?Sized>(rng: &mut R, ones: u32, mask: u32) -> Option<u32> {
+    let available = mask.count_ones();
+    if ones > available {
+        return None;
+    }
+    // Placing the minority bits is fewer steps
+    let (minority, invert) = if ones > available / 2 { (available - ones, true) } else { (ones, false) };
+    let mut word = 0u32;
+    for placed in 0..minority {
+        let rank = rng.gen_range(0..available - placed);
+        word |= pdep(1 << rank, mask & ! word);
+    }
+    Some(if invert { mask & ! word } else { word })
+}
+
+/// Returns a uniformly random submask of the mask: every bit of the mask is kept with probability 1/2
+/// # Arguments
+/// * `rng` - source of randomness
+/// * `mask` - mask to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::prng::Xoshiro128PlusPlus;
+/// # use understanding_bitwise::random::random_submask;
+/// let mut rng = Xoshiro128PlusPlus::from_u64(1);
+/// let submask = random_submask(&mut rng, 0b1011_0000);
+/// assert_eq!(submask & ! 0b1011_0000, 0);
+/// ```
+pub fn random_submask<R: Rng + ?Sized>(rng: &mut R, mask: u32) -> u32 {
+    pdep(rng.gen(), mask)
+}
+
+/// Returns a random word made of exactly *runs* alternating runs of ones and zeros, or None if *runs* isn't in 1..=32.
+/// The run boundaries are a random word with *runs - 1* ones above the bit 0, and the prefix XOR turns every boundary
+/// into a switch between ones and zeros. The lowest run is of ones or zeros with probability 1/2
+/// # Arguments
+/// * `rng` - source of randomness
+/// * `runs` - count of runs
+/// # Examples
+/// ```
+/// # use understanding_bitwise::prng::Xoshiro128PlusPlus;
+/// # use understanding_bitwise::random::random_runs;
+/// let mut rng = Xoshiro128PlusPlus::from_u64(1);
+/// let word = random_runs(&mut rng, 3).unwrap();
+/// // Bits differing from the next lower bit mark the boundaries
+/// assert_eq!(((word ^ word << 1) & ! 1).count_ones(), 2);
+/// ```
+pub fn random_runs<R: Rng + ?Sized>(rng: &mut R, runs: u32) -> Option<u32> {
+    if !(1..=u32::BITS).contains(&runs) {
+        return None;
+    }
+    let boundaries = random_with_ones_in(rng, runs - 1, ! 1)?;
+    let word = prefix_xor(boundaries);
+    Some(if rng.gen() { ! word } else { word })
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xoshiro128PlusPlus;
+
+    #[test]
+    fn test_random_with_ones() {
+        let mut rng = Xoshiro128PlusPlus::from_u64(0x00C0_FFEE);
+        for ones in 0..=32 {
+            for _ in 0..20 {
+                assert_eq!(random_with_ones(&mut rng, ones).unwrap().count_ones(), ones);
+            }
+        }
+        // Every position is chosen about equally often
+        let mut counts = [0u32; 32];
+        for _ in 0..3200 {
+            let word = random_with_ones(&mut rng, 1).unwrap();
+            counts[word.trailing_zeros() as usize] += 1;
+        }
+        assert!(counts.iter().all(|count| (60..=140).contains(count)), "{counts:?}");
+    }
+
+    #[test]
+    fn test_random_submask() {
+        let mut rng = Xoshiro128PlusPlus::from_u64(3);
+        assert_eq!(random_submask(&mut rng, 0), 0);
+        let mask = 0xF0F0_1234;
+        let mut union = 0;
+        let mut intersection = u32::MAX;
+        for _ in 0..100 {
+            let submask = random_submask(&mut rng, mask);
+            assert_eq!(submask & ! mask, 0);
+            union |= submask;
+            intersection &= submask;
+        }
+        assert_eq!((union, intersection), (mask, 0));
+    }
+
+    #[test]
+    fn test_random_runs() {
+        let mut rng = Xoshiro128PlusPlus::from_u64(5);
+        for runs in 1..=32 {
+            for _ in 0..20 {
+                let word = random_runs(&mut rng, runs).unwrap();
+                assert_eq!(((word ^ word << 1) & ! 1).count_ones(), runs - 1, "{word:#034b}");
+            }
+        }
+        assert_eq!(random_runs(&mut rng, 0), None);
+        assert_eq!(random_runs(&mut rng, 33), None);
+        assert!([0, u32::MAX].contains(&random_runs(&mut rng, 1).unwrap()));
+    }
+}
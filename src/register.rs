@@ -0,0 +1,304 @@
+//! Hardware register abstraction with read-modify-write semantics.
+//! Registers are simulated in memory by default. Volatile memory-mapped registers need the `mmio` feature
+
+use std::cell::Cell;
+use std::ops::{BitAnd, BitOr, Not};
+#[cfg(feature = "mmio")]
+use std::ptr::NonNull;
+use crate::{get_bits, get_bits_u64, set_bits, set_bits_u64};
+
+/// Word of a register
+pub trait RegisterValue: Copy + Eq + BitAnd<Output = Self> + BitOr<Output = Self> + Not<Output = Self> {
+    /// Word with all bits set to 0
+    const ZERO: Self;
+
+    /// Word with only the bit 0 set to 1
+    const ONE: Self;
+
+    /// Returns the value of the bit field or None if the field doesn't fit into the word. See *get_bits*
+    fn get_field(self, offset: u32, width: u32) -> Option<Self>;
+
+    /// Returns the word with the bit field replaced or None if the field doesn't fit. See *set_bits*
+    fn set_field(self, offset: u32, width: u32, value: Self) -> Option<Self>;
+}
+
+impl RegisterValue for u32 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn get_field(self, offset: u32, width: u32) -> Option<Self> {
+        get_bits(self, offset, width)
+    }
+
+    fn set_field(self, offset: u32, width: u32, value: Self) -> Option<Self> {
+        set_bits(self, offset, width, value)
+    }
+}
+
+impl RegisterValue for u64 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn get_field(self, offset: u32, width: u32) -> Option<Self> {
+        get_bits_u64(self, offset, width)
+    }
+
+    fn set_field(self, offset: u32, width: u32, value: Self) -> Option<Self> {
+        set_bits_u64(self, offset, width, value)
+    }
+}
+
+/// Helper function. Returns the mask of the bit field or None if the field doesn't fit into the word
+/// # Arguments
+/// * `offset` - index of the lowest bit of the field
+/// * `width` - bits count of the field
+fn field_mask<T: RegisterValue>(offset: u32, width: u32) -> Option<T> {
+    T::ZERO.set_field(offset, width, (! T::ZERO).get_field(0, width)?)
+}
+
+/// Storage of the register value
+enum Backing<T> {
+    /// Simulated register. Emulates write-1-to-clear bits
+    Memory(Cell<T>),
+    /// Memory-mapped register accessed with volatile reads and writes
+    #[cfg(feature = "mmio")]
+    Volatile(NonNull<T>),
+}
+
+/// Register value being edited inside *Register::modify*. Failed operations are recorded and cancel the whole write
+pub struct RegisterBits<T: RegisterValue> {
+    value: T,
+    reserved: T,
+    clear: T,
+    write_one_to_clear: T,
+    failed: bool,
+}
+
+impl<T: RegisterValue> RegisterBits<T> {
+    /// Returns the value as it will be written, without the write-1-to-clear requests
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Returns the value of the bit field or None if the field doesn't fit into the register
+    /// # Arguments
+    /// * `offset` - index of the lowest bit of the field
+    /// * `width` - bits count of the field
+    pub fn get_bits(&self, offset: u32, width: u32) -> Option<T> {
+        self.value.get_field(offset, width)
+    }
+
+    /// Replaces the bit field. Fails if the field doesn't fit, the value doesn't fit into the field,
+    /// or the field covers reserved or write-1-to-clear bits
+    /// # Arguments
+    /// * `offset` - index of the lowest bit of the field
+    /// * `width` - bits count of the field
+    /// * `value` - new value of the field
+    pub fn set_bits(&mut self, offset: u32, width: u32, value: T) -> &mut Self {
+        let protected = self.reserved | self.write_one_to_clear;
+        match (self.value.set_field(offset, width, value), field_mask::<T>(offset, width)) {
+            (Some(new_value), Some(mask)) if mask & protected == T::ZERO => self.value = new_value,
+            _ => self.failed = true,
+        }
+        self
+    }
+
+    /// Sets the bit to 1. Fails like *set_bits*
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn set_bit(&mut self, index: u32) -> &mut Self {
+        self.set_bits(index, 1, T::ONE)
+    }
+
+    /// Sets the bit to 0. Fails like *set_bits*
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn unset_bit(&mut self, index: u32) -> &mut Self {
+        self.set_bits(index, 1, T::ZERO)
+    }
+
+    /// Requests clearing of the write-1-to-clear bit (by writing 1 to it). Fails if the bit isn't write-1-to-clear
+    /// # Arguments
+    /// * `index` - index of the bit
+    pub fn clear_flag(&mut self, index: u32) -> &mut Self {
+        match field_mask::<T>(index, 1) {
+            Some(mask) if mask & self.write_one_to_clear == mask => self.clear = self.clear | mask,
+            _ => self.failed = true,
+        }
+        self
+    }
+}
+
+/// Register with reserved bits (preserved by every write) and write-1-to-clear bits (never written back as read)
+/// # Examples
+/// ```
+/// # use understanding_bitwise::register::Register;
+/// // Bits 0..4 are a mode field, bit 7 is reserved, bit 8 is a write-1-to-clear status flag
+/// let register = Register::new(0b1_1000_0000u32).with_reserved(1 << 7).with_write_one_to_clear(1 << 8);
+/// register.modify(|r| { r.set_bits(0, 4, 0b1010); }).unwrap();
+/// // Reading the pending flag as 1 and writing it back doesn't clear it
+/// assert_eq!(register.read(), 0b1_1000_1010);
+/// register.modify(|r| { r.clear_flag(8); }).unwrap();
+/// assert_eq!(register.read(), 0b0_1000_1010);
+/// // Reserved bits can't be modified
+/// assert_eq!(register.modify(|r| { r.unset_bit(7); }), None);
+/// ```
+pub struct Register<T: RegisterValue> {
+    backing: Backing<T>,
+    reserved: T,
+    write_one_to_clear: T,
+}
+
+impl<T: RegisterValue> Register<T> {
+    /// Returns the simulated register
+    /// # Arguments
+    /// * `initial` - initial value
+    pub fn new(initial: T) -> Self {
+        Register { backing: Backing::Memory(Cell::new(initial)), reserved: T::ZERO, write_one_to_clear: T::ZERO }
+    }
+
+    /// Returns the memory-mapped register
+    /// # Arguments
+    /// * `address` - address of the register
+    /// # Safety
+    /// The address must be non-null, aligned and valid for volatile reads and writes of *T* while the register is alive
+    #[cfg(feature = "mmio")]
+    pub unsafe fn from_ptr(address: *mut T) -> Self {
+        Register {
+            backing: Backing::Volatile(NonNull::new(address).expect("register address must be non-null")),
+            reserved: T::ZERO,
+            write_one_to_clear: T::ZERO,
+        }
+    }
+
+    /// Returns the register with the reserved bits mask
+    /// # Arguments
+    /// * `mask` - mask of reserved bits
+    pub fn with_reserved(mut self, mask: T) -> Self {
+        self.reserved = mask;
+        self
+    }
+
+    /// Returns the register with the write-1-to-clear bits mask
+    /// # Arguments
+    /// * `mask` - mask of write-1-to-clear bits
+    pub fn with_write_one_to_clear(mut self, mask: T) -> Self {
+        self.write_one_to_clear = mask;
+        self
+    }
+
+    /// Reads the register
+    pub fn read(&self) -> T {
+        match &self.backing {
+            Backing::Memory(cell) => cell.get(),
+            // SAFETY: validity of the address is guaranteed by the caller of from_ptr
+            #[cfg(feature = "mmio")]
+            Backing::Volatile(address) => unsafe { address.as_ptr().read_volatile() },
+        }
+    }
+
+    /// Helper function. Writes the raw value
+    /// # Arguments
+    /// * `value` - value to be written
+    fn write_raw(&self, value: T) {
+        match &self.backing {
+            Backing::Memory(cell) => {
+                let w1c = self.write_one_to_clear;
+                cell.set(value & ! w1c | cell.get() & w1c & ! value);
+            }
+            // SAFETY: validity of the address is guaranteed by the caller of from_ptr
+            #[cfg(feature = "mmio")]
+            Backing::Volatile(address) => unsafe { address.as_ptr().write_volatile(value) },
+        }
+    }
+
+    /// Writes the value. Reserved bits keep their current values and write-1-to-clear bits are written as given.
+    /// Returns None (and writes nothing) if the value tries to change reserved bits
+    /// # Arguments
+    /// * `value` - value to be written
+    pub fn write(&self, value: T) -> Option<()> {
+        let current = self.read();
+        if value & self.reserved != current & self.reserved {
+            return None;
+        }
+        self.write_raw(value);
+        Some(())
+    }
+
+    /// Reads the register, lets the closure edit the value and writes it back. Write-1-to-clear bits are written as 0
+    /// unless cleared explicitly. Returns None (and writes nothing) if some edit failed
+    /// # Arguments
+    /// * `f` - function editing the value
+    pub fn modify<F>(&self, f: F) -> Option<()>
+    where
+        F: FnOnce(&mut RegisterBits<T>) {
+        let mut bits = RegisterBits {
+            value: self.read(),
+            reserved: self.reserved,
+            clear: T::ZERO,
+            write_one_to_clear: self.write_one_to_clear,
+            failed: false,
+        };
+        f(&mut bits);
+        if bits.failed {
+            return None;
+        }
+        self.write_raw(bits.value & ! self.write_one_to_clear | bits.clear);
+        Some(())
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modify() {
+        let register = Register::new(0xFFFF_0000u32);
+        register.modify(|r| { r.set_bits(0, 8, 0xAB).set_bit(12).unset_bit(31); }).unwrap();
+        assert_eq!(register.read(), 0x7FFF_10AB);
+        assert_eq!(register.modify(|r| { r.set_bits(0, 8, 0x1AB); }), None);
+        assert_eq!(register.modify(|r| { r.set_bits(0, 4, 0).set_bit(32); }), None);
+        assert_eq!(register.read(), 0x7FFF_10AB);
+        register.modify(|r| assert_eq!(r.get_bits(8, 8), Some(0x10))).unwrap();
+    }
+
+    #[test]
+    fn test_reserved() {
+        let register = Register::new(0u64).with_reserved(0xF0);
+        assert_eq!(register.modify(|r| { r.set_bits(0, 4, 0xF); }), Some(()));
+        assert_eq!(register.modify(|r| { r.set_bits(0, 8, 0x0F); }), None);
+        assert_eq!(register.modify(|r| { r.set_bit(4); }), None);
+        assert_eq!(register.write(0x1_0000_000F), Some(()));
+        assert_eq!(register.write(0xFF), None);
+        assert_eq!(register.read(), 0x1_0000_000F);
+    }
+
+    #[test]
+    fn test_write_one_to_clear() {
+        let register = Register::new(0b1100u32).with_write_one_to_clear(0b1100);
+        register.modify(|r| { r.set_bit(0); }).unwrap();
+        assert_eq!(register.read(), 0b1101);
+        assert_eq!(register.modify(|r| { r.unset_bit(2); }), None);
+        assert_eq!(register.modify(|r| { r.clear_flag(0); }), None);
+        register.modify(|r| { r.clear_flag(3); }).unwrap();
+        assert_eq!(register.read(), 0b0101);
+        register.write(0b0100).unwrap();
+        assert_eq!(register.read(), 0b0000);
+    }
+
+    #[cfg(feature = "mmio")]
+    #[test]
+    fn test_volatile() {
+        let mut memory = 0x0000_00FFu32;
+        {
+            // SAFETY: the local variable outlives the register
+            let register = unsafe { Register::from_ptr(&mut memory as *mut u32) }.with_reserved(0x8000_0000);
+            register.modify(|r| { r.set_bits(8, 8, 0x12); }).unwrap();
+            assert_eq!(register.read(), 0x0000_12FF);
+            assert_eq!(register.write(u32::MAX), None);
+        }
+        assert_eq!(memory, 0x0000_12FF);
+    }
+}
@@ -0,0 +1,254 @@
+//! Run-length encoded bit set
+
+/// Bits per word of the word slice representation
+const WORD_BITS: u32 = u32::BITS;
+
+/// Set of bit indexes stored as sorted, non-overlapping and non-adjacent (start, length) runs.
+/// Suits interval-like data (e.g. allocated block maps) where long runs of ones are common.
+/// Valid indexes are `0..u32::MAX`.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct RleBitSet {
+    runs: Vec<(u32, u32)>,
+}
+
+impl RleBitSet {
+    /// Returns an empty set
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::rle::RleBitSet;
+    /// assert!(RleBitSet::new().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the set built from the words or None if a bit out of the valid range is set.
+    /// Bit *i* of the word *w* corresponds to the index *w × 32 + i*
+    /// # Arguments
+    /// * `words` - words to work with
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::rle::RleBitSet;
+    /// let set = RleBitSet::from_words(&[0b1110, 0b1]).unwrap();
+    /// assert_eq!(set.runs().collect::<Vec<_>>(), vec![(1, 3), (32, 1)]);
+    /// ```
+    pub fn from_words(words: &[u32]) -> Option<Self> {
+        let mut set = Self::new();
+        let mut run_start = None;
+        // 2^27 words already hold more bits than u32 counts
+        let mut index = 0u64;
+        for &word in words {
+            for bit in 0..WORD_BITS {
+                let is_set = word >> bit & 1 == 1;
+                if is_set && index >= u32::MAX as u64 {
+                    return None;
+                }
+                // Every index up to the current one is valid, so the run fits into u32
+                match (is_set, run_start) {
+                    (true, None) => run_start = Some(index),
+                    (false, Some(start)) => {
+                        set.runs.push((start as u32, (index - start) as u32));
+                        run_start = None;
+                    }
+                    _ => {}
+                }
+                index += 1;
+            }
+        }
+        if let Some(start) = run_start {
+            set.runs.push((start as u32, (index - start) as u32));
+        }
+        Some(set)
+    }
+
+    /// Returns the words representing the set. The last word is the one containing the highest index
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::rle::RleBitSet;
+    /// let mut set = RleBitSet::new();
+    /// set.insert_range(1, 3);
+    /// set.insert(32);
+    /// assert_eq!(set.to_words(), vec![0b1110, 0b1]);
+    /// ```
+    pub fn to_words(&self) -> Vec<u32> {
+        let words_count = self.runs.last().map_or(0, |&(start, length)| {
+            (start as usize + length as usize).div_ceil(WORD_BITS as usize)
+        });
+        let mut words = vec![0u32; words_count];
+        for index in self.iter() {
+            words[(index / WORD_BITS) as usize] |= 1 << index % WORD_BITS;
+        }
+        words
+    }
+
+    /// Returns true if the set has no indexes
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Returns the count of indexes in the set
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::rle::RleBitSet;
+    /// let set = RleBitSet::from_words(&[0b1011]).unwrap();
+    /// assert_eq!(set.len(), 3);
+    /// ```
+    pub fn len(&self) -> u64 {
+        self.runs.iter().map(|&(_, length)| length as u64).sum()
+    }
+
+    /// Returns true if the index is in the set
+    /// # Arguments
+    /// * `index` - index of the bit
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::rle::RleBitSet;
+    /// let set = RleBitSet::from_words(&[0b100]).unwrap();
+    /// assert!(set.contains(2));
+    /// assert!(!set.contains(3));
+    /// ```
+    pub fn contains(&self, index: u32) -> bool {
+        let following = self.runs.partition_point(|&(start, _)| start <= index);
+        following > 0 && {
+            let (start, length) = self.runs[following - 1];
+            index - start < length
+        }
+    }
+
+    /// Adds the index to the set. Returns true if the index wasn't in the set or None if the index is out of the valid range
+    /// # Arguments
+    /// * `index` - index of the bit
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::rle::RleBitSet;
+    /// let mut set = RleBitSet::new();
+    /// assert_eq!(set.insert(5), Some(true));
+    /// assert_eq!(set.insert(5), Some(false));
+    /// assert_eq!(set.insert(u32::MAX), None);
+    /// ```
+    pub fn insert(&mut self, index: u32) -> Option<bool> {
+        self.insert_range(index, 1).map(|inserted| inserted == 1)
+    }
+
+    /// Adds the range of indexes to the set, merging it with the overlapping and adjacent runs.
+    /// Returns the count of indexes that weren't in the set or None if the range is out of the valid range
+    /// # Arguments
+    /// * `start` - first index of the range
+    /// * `length` - count of indexes in the range
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::rle::RleBitSet;
+    /// let mut set = RleBitSet::new();
+    /// assert_eq!(set.insert_range(0, 4), Some(4));
+    /// assert_eq!(set.insert_range(6, 2), Some(2));
+    /// assert_eq!(set.insert_range(2, 5), Some(2));
+    /// assert_eq!(set.runs().collect::<Vec<_>>(), vec![(0, 8)]);
+    /// assert_eq!(set.insert_range(u32::MAX - 1, 2), None);
+    /// ```
+    pub fn insert_range(&mut self, start: u32, length: u32) -> Option<u32> {
+        let end = start.checked_add(length)?;
+        if length == 0 {
+            return Some(0);
+        }
+
+        // Runs which end before the new range and don't touch it are left untouched, as the runs which start after it
+        let first = self.runs.partition_point(|&(run_start, run_length)| run_start + run_length < start);
+        let last = self.runs.partition_point(|&(run_start, _)| run_start <= end);
+        let merged = &self.runs[first..last];
+        let merged_length: u32 = merged.iter().map(|&(_, run_length)| run_length).sum();
+        let new_start = merged.first().map_or(start, |&(run_start, _)| run_start.min(start));
+        let new_end = merged.last().map_or(end, |&(run_start, run_length)| (run_start + run_length).max(end));
+        self.runs.splice(first..last, [(new_start, new_end - new_start)]);
+        Some(new_end - new_start - merged_length)
+    }
+
+    /// Returns an iterator over the (start, length) runs in ascending order
+    pub fn runs(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.runs.iter().copied()
+    }
+
+    /// Returns an iterator over the indexes in ascending order
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::rle::RleBitSet;
+    /// let set = RleBitSet::from_words(&[0b11001]).unwrap();
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 3, 4]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.runs.iter().flat_map(|&(start, length)| start..start + length)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_words_round_trip() {
+        let words = [0b1011_0000_1111, u32::MAX, 0, 1 << 31, 0x8000_0001];
+        let set = RleBitSet::from_words(&words).unwrap();
+        assert_eq!(set.to_words(), words.to_vec());
+        assert_eq!(set.len(), 7 + 32 + 1 + 2);
+        assert_eq!(RleBitSet::from_words(&[]).unwrap().to_words(), Vec::<u32>::new());
+        assert_eq!(RleBitSet::from_words(&[0, 0]).unwrap().to_words(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_runs_are_merged_across_words() {
+        let set = RleBitSet::from_words(&[1 << 31, 0b11]).unwrap();
+        assert_eq!(set.runs().collect::<Vec<_>>(), vec![(31, 3)]);
+    }
+
+    #[test]
+    fn test_insert_range() {
+        let mut set = RleBitSet::new();
+        assert_eq!(set.insert_range(10, 5), Some(5));
+        assert_eq!(set.insert_range(20, 5), Some(5));
+        assert_eq!(set.insert_range(0, 2), Some(2));
+        assert_eq!(set.insert_range(0, 0), Some(0));
+        assert_eq!(set.runs().collect::<Vec<_>>(), vec![(0, 2), (10, 5), (20, 5)]);
+        // Adjacent runs are merged
+        assert_eq!(set.insert_range(15, 5), Some(5));
+        assert_eq!(set.runs().collect::<Vec<_>>(), vec![(0, 2), (10, 15)]);
+        // Covering range swallows everything
+        assert_eq!(set.insert_range(1, 30), Some(30 - 1 - 15));
+        assert_eq!(set.runs().collect::<Vec<_>>(), vec![(0, 31)]);
+        assert_eq!(set.insert_range(5, 3), Some(0));
+        assert_eq!(set.insert_range(u32::MAX, 1), None);
+        assert_eq!(set.insert_range(u32::MAX - 1, 1), Some(1));
+    }
+
+    #[test]
+    fn test_insert_contains() {
+        let mut set = RleBitSet::new();
+        for index in [7, 3, 5, 4, 100, 6] {
+            assert_eq!(set.insert(index), Some(true));
+        }
+        assert_eq!(set.insert(4), Some(false));
+        assert_eq!(set.runs().collect::<Vec<_>>(), vec![(3, 5), (100, 1)]);
+        for index in 0..128 {
+            assert_eq!(set.contains(index), (3..8).contains(&index) || index == 100);
+        }
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 4, 5, 6, 7, 100]);
+    }
+
+    #[test]
+    fn test_matches_words() {
+        let mut set = RleBitSet::new();
+        let mut words = [0u32; 4];
+        let mut rng = Xorshift32::new(0x2545_f491).unwrap();
+        for _ in 0..200 {
+            let value = rng.next_u32();
+            let start = value % 128;
+            let length = (value >> 8) % 6;
+            let length = length.min(128 - start);
+            set.insert_range(start, length);
+            for index in start..start + length {
+                words[(index / 32) as usize] |= 1 << index % 32;
+            }
+        }
+        assert_eq!(RleBitSet::from_words(&words), Some(set));
+    }
+}
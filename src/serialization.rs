@@ -0,0 +1,74 @@
+//! *Serialize*/*Deserialize* implementations of the bit containers.
+//! *RleBitSet* is stored as its runs, a sequence of (start, length) pairs in ascending order, so the size of the
+//! serialized set follows the count of runs rather than the highest index
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use crate::rle::RleBitSet;
+
+impl Serialize for RleBitSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.runs())
+    }
+}
+
+impl<'de> Deserialize<'de> for RleBitSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let runs = Vec::<(u32, u32)>::deserialize(deserializer)?;
+        let mut set = RleBitSet::new();
+        for (start, length) in runs {
+            // Overlapping and adjacent runs are merged, so any order gives the same set
+            set.insert_range(start, length)
+                .ok_or_else(|| de::Error::custom(format!("run ({start}, {length}) is out of the valid range")))?;
+        }
+        Ok(set)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Configure, Token};
+
+    /// Helper function. Returns the tokens of the runs
+    /// # Arguments
+    /// * `runs` - (start, length) pairs
+    fn run_tokens(runs: &[(u32, u32)]) -> Vec<Token> {
+        let mut tokens = vec![Token::Seq { len: Some(runs.len()) }];
+        for &(start, length) in runs {
+            tokens.extend([Token::Tuple { len: 2 }, Token::U32(start), Token::U32(length), Token::TupleEnd]);
+        }
+        tokens.push(Token::SeqEnd);
+        tokens
+    }
+
+    #[test]
+    fn test_rle_bit_set_runs() {
+        let set = RleBitSet::from_words(&[0b1010, 0b1]).unwrap();
+        assert_tokens(&set.clone().readable(), &run_tokens(&[(1, 1), (3, 1), (32, 1)]));
+        assert_tokens(&set.compact(), &run_tokens(&[(1, 1), (3, 1), (32, 1)]));
+        assert_tokens(&RleBitSet::new(), &run_tokens(&[]));
+    }
+
+    #[test]
+    fn test_sparse_high_index() {
+        // A single run, not 2³² bits of words
+        let mut set = RleBitSet::new();
+        set.insert(u32::MAX - 1);
+        assert_tokens(&set, &run_tokens(&[(u32::MAX - 1, 1)]));
+    }
+
+    #[test]
+    fn test_unordered_runs() {
+        let mut set = RleBitSet::new();
+        set.insert_range(0, 8);
+        assert_de_tokens(&set, &run_tokens(&[(4, 4), (0, 5), (2, 0)]));
+    }
+
+    #[test]
+    fn test_invalid_runs() {
+        assert_de_tokens_error::<RleBitSet>(&run_tokens(&[(u32::MAX, 1)]), "run (4294967295, 1) is out of the valid range");
+        assert_de_tokens_error::<RleBitSet>(&[Token::Str("0101")], "invalid type: string \"0101\", expected a sequence");
+    }
+}
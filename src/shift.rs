@@ -0,0 +1,421 @@
+//! Shifts with their semantics spelled out. Right shift of a signed number is arithmetic in Rust: the vacated
+//! highest bits get copies of the sign bit, so negative numbers stay negative and the shift divides rounding down.
+//! Logical right shift fills them with zeros whatever the sign, which is what *>>* does on unsigned numbers.
+//! Bits shifted out are lost unless they are kept as a carry, the way CPUs keep the last of them in a flag
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Returns the arithmetic right shift of the number: the bits move right and the sign bit fills the vacated
+/// positions. Shifts by 32 or more give 0 or -1, the sign copied into every bit
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::asr;
+/// assert_eq!(asr(-106, 3), -14);
+/// assert_eq!(asr(106, 3), 13);
+/// // Rounding is towards negative infinity, unlike division
+/// assert_eq!((asr(-7, 1), -7 / 2), (-4, -3));
+/// assert_eq!((asr(-1, 40), asr(1, 40)), (-1, 0));
+/// ```
+pub fn asr(number: i32, count: u32) -> i32 {
+    let shifted = lsr_on_signed(number, count) as u32;
+    // Ones in the vacated positions for negative numbers
+    let fill = if number < 0 { ! u32::MAX.checked_shr(count).unwrap_or(0) } else { 0 };
+    (shifted | fill) as i32
+}
+
+/// Returns the logical right shift of the bits of the signed number: the bits move right and zeros fill the vacated
+/// positions, so a negative number becomes non-negative after any shift but 0. Shifts by 32 or more give 0
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::lsr_on_signed;
+/// assert_eq!(lsr_on_signed(-106, 3), 0x1FFF_FFF2);
+/// assert_eq!(lsr_on_signed(106, 3), 13);
+/// assert_eq!(lsr_on_signed(-1, 31), 1);
+/// assert_eq!(lsr_on_signed(-1, 32), 0);
+/// ```
+pub fn lsr_on_signed(number: i32, count: u32) -> i32 {
+    (number as u32).checked_shr(count).unwrap_or(0) as i32
+}
+
+/// Writes the number, its arithmetic and its logical right shifts in binary and decimal one under the other,
+/// then a row with *^* under the vacated positions and the bit that fills them in each shift.
+/// *explained::asr_explained* and *explained::lsr_explained* give the shifts as *BitStep*s for replaying
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// * `out` - *Write* object that gets the comparison
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::write_shift_comparison;
+/// let mut vec = Vec::new();
+/// write_shift_comparison(-106, 3, &mut vec).unwrap();
+/// assert_eq!(String::from_utf8(vec).unwrap(), "\
+/// number  11111111111111111111111110010110 = -106
+/// asr 3   11111111111111111111111111110010 = -14
+/// lsr 3   00011111111111111111111111110010 = 536870898
+///         ^^^ sign bit 1 copied by asr, 0 by lsr
+/// ");
+/// ```
+pub fn write_shift_comparison<W: Write>(number: i32, count: u32, out: &mut W) -> io::Result<()> {
+    let (arithmetic, logical) = (asr(number, count), lsr_on_signed(number, count));
+    writeln!(out, "{:<8}{:032b} = {number}", "number", number)?;
+    writeln!(out, "{:<8}{:032b} = {arithmetic}", format!("asr {count}"), arithmetic)?;
+    writeln!(out, "{:<8}{:032b} = {logical}", format!("lsr {count}"), logical)?;
+    let vacated = count.min(u32::BITS) as usize;
+    if vacated == 0 {
+        return writeln!(out, "{:<8}nothing vacated, both shifts keep the number", "");
+    }
+    writeln!(out, "{:<8}{} sign bit {} copied by asr, 0 by lsr", "", "^".repeat(vacated), (number as u32) >> 31)
+}
+
+/// Returns the number shifted left and the bits shifted out of the top, as the lowest bits of the second number
+/// (the carry). Shifts by 32 or more shift out the whole number
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::shl_with_carry;
+/// assert_eq!(shl_with_carry(0xF000_000F, 4), (0x0000_00F0, 0xF));
+/// assert_eq!(shl_with_carry(0xABCD, 0), (0xABCD, 0));
+/// assert_eq!(shl_with_carry(0xABCD, 32), (0, 0xABCD));
+/// ```
+pub fn shl_with_carry(number: u32, count: u32) -> (u32, u32) {
+    match count {
+        0 => (number, 0),
+        1..=31 => (number << count, number >> (u32::BITS - count)),
+        _ => (0, number),
+    }
+}
+
+/// Returns the number shifted right and the bits shifted out of the bottom, as the lowest bits of the second number
+/// (the carry). Shifts by 32 or more shift out the whole number
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::shr_with_carry;
+/// assert_eq!(shr_with_carry(0xF000_000F, 4), (0x0F00_0000, 0xF));
+/// assert_eq!(shr_with_carry(0b1011, 2), (0b10, 0b11));
+/// assert_eq!(shr_with_carry(0xABCD, 40), (0, 0xABCD));
+/// ```
+pub fn shr_with_carry(number: u32, count: u32) -> (u32, u32) {
+    match count {
+        0 => (number, 0),
+        1..=31 => (number >> count, number & (1 << count) - 1),
+        _ => (0, number),
+    }
+}
+
+/// Helper function. Returns the 9-bit value of the carry flag over the byte rotated left
+/// # Arguments
+/// * `value` - byte to work with
+/// * `carry` - carry flag
+/// * `count` - count of positions, below 9
+fn rotate_9_left(value: u8, carry: bool, count: u32) -> (u8, bool) {
+    let combined = (carry as u16) << 8 | value as u16;
+    let rotated = (combined << count | combined >> (9 - count)) & 0x1FF;
+    (rotated as u8, rotated >> 8 == 1)
+}
+
+/// Returns the byte rotated left through the carry flag and the new carry flag, like the x86 *RCL* instruction:
+/// the carry and the byte form a 9-bit value, the highest bit goes into the carry and the carry into the lowest bit.
+/// As on x86, the count is masked to 5 bits and then taken modulo 9
+/// # Arguments
+/// * `value` - byte to work with
+/// * `carry` - carry flag
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::rcl_u8;
+/// assert_eq!(rcl_u8(0b1000_0001, false, 1), (0b0000_0010, true));
+/// assert_eq!(rcl_u8(0b1000_0001, true, 1), (0b0000_0011, true));
+/// // 9 positions bring everything back
+/// assert_eq!(rcl_u8(0b1000_0001, true, 9), (0b1000_0001, true));
+/// ```
+pub fn rcl_u8(value: u8, carry: bool, count: u32) -> (u8, bool) {
+    rotate_9_left(value, carry, (count & 0x1F) % 9)
+}
+
+/// Returns the byte rotated right through the carry flag and the new carry flag, like the x86 *RCR* instruction:
+/// the lowest bit goes into the carry and the carry into the highest bit. The count is masked to 5 bits and then
+/// taken modulo 9
+/// # Arguments
+/// * `value` - byte to work with
+/// * `carry` - carry flag
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::rcr_u8;
+/// assert_eq!(rcr_u8(0b1000_0001, false, 1), (0b0100_0000, true));
+/// assert_eq!(rcr_u8(0b1000_0001, true, 1), (0b1100_0000, true));
+/// ```
+pub fn rcr_u8(value: u8, carry: bool, count: u32) -> (u8, bool) {
+    // Rotating 9 bits right by n is rotating them left by 9 - n
+    rotate_9_left(value, carry, (9 - (count & 0x1F) % 9) % 9)
+}
+
+/// Returns the number shifted left and the bits lost by the shift, in their places in the original number.
+/// Unlike *u32::overflowing_shl*, which only reports a count of 32 or more and masks it, every lost one counts
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::overflowing_shl_exact;
+/// assert_eq!(overflowing_shl_exact(0x8000_0001, 1), (0x0000_0002, 0x8000_0000));
+/// assert_eq!(overflowing_shl_exact(0xFF, 24), (0xFF00_0000, 0));
+/// assert_eq!(overflowing_shl_exact(0xFF, 40), (0, 0xFF));
+/// ```
+pub fn overflowing_shl_exact(number: u32, count: u32) -> (u32, u32) {
+    let kept = u32::MAX.checked_shr(count).unwrap_or(0);
+    (number.checked_shl(count).unwrap_or(0), number & ! kept)
+}
+
+/// Returns the number shifted right and the bits lost by the shift, in their places in the original number
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::overflowing_shr_exact;
+/// assert_eq!(overflowing_shr_exact(0b1011, 2), (0b10, 0b11));
+/// assert_eq!(overflowing_shr_exact(0b1000, 3), (1, 0));
+/// ```
+pub fn overflowing_shr_exact(number: u32, count: u32) -> (u32, u32) {
+    let kept = u32::MAX.checked_shl(count).unwrap_or(0);
+    (number.checked_shr(count).unwrap_or(0), number & ! kept)
+}
+
+/// Error of a left shift that would shift out set bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::checked_shl_exact;
+/// let error = checked_shl_exact(0xFF, 25).unwrap_err();
+/// assert_eq!(error.to_string(), "0xff << 25 loses set bits, the maximum count keeping them is 24");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ShiftOverflow {
+    /// Number that was shifted
+    pub number: u32,
+    /// Requested count of positions
+    pub count: u32,
+    /// Maximum count of positions keeping every set bit, the leading zeros of the number
+    pub max_count: u32,
+}
+
+impl fmt::Display for ShiftOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x} << {} loses set bits, the maximum count keeping them is {}", self.number, self.count, self.max_count)
+    }
+}
+
+impl Error for ShiftOverflow {}
+
+/// Returns the number shifted left or error if a set bit would be shifted out, that is if multiplying by
+/// *2^count* overflows. The error carries the number, the count and the largest count the number can take
+/// # Arguments
+/// * `number` - number to work with
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::checked_shl_exact;
+/// assert_eq!(checked_shl_exact(0xFF, 24), Ok(0xFF00_0000));
+/// assert_eq!(checked_shl_exact(0xFF, 25).unwrap_err().max_count, 24);
+/// // Zeros are never lost
+/// assert_eq!(checked_shl_exact(0, 100), Ok(0));
+/// ```
+pub fn checked_shl_exact(number: u32, count: u32) -> Result<u32, ShiftOverflow> {
+    match overflowing_shl_exact(number, count) {
+        (shifted, 0) => Ok(shifted),
+        _ => Err(ShiftOverflow { number, count, max_count: number.leading_zeros() }),
+    }
+}
+
+/// Returns the highest 32 bits of the 64-bit concatenation *high:low* shifted left, like LLVM's *fshl* and x86 *SHLD*:
+/// the bits of *high* move up and the highest bits of *low* fill the vacated positions. The count is taken modulo 32.
+/// The same number as both halves makes a rotation
+/// # Arguments
+/// * `high` - highest half of the concatenation
+/// * `low` - lowest half of the concatenation
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::funnel_shl;
+/// assert_eq!(funnel_shl(0x1234_5678, 0x9ABC_DEF0, 8), 0x3456_789A);
+/// assert_eq!(funnel_shl(0x1234_5678, 0x9ABC_DEF0, 0), 0x1234_5678);
+/// assert_eq!(funnel_shl(0x8000_0001, 0x8000_0001, 1), 0x8000_0001u32.rotate_left(1));
+/// ```
+pub fn funnel_shl(high: u32, low: u32, count: u32) -> u32 {
+    match count % u32::BITS {
+        0 => high,
+        count => high << count | low >> (u32::BITS - count),
+    }
+}
+
+/// Returns the lowest 32 bits of the 64-bit concatenation *high:low* shifted right, like LLVM's *fshr* and x86 *SHRD*:
+/// the window of 32 bits starting at the bit *count* of the concatenation. The count is taken modulo 32
+/// # Arguments
+/// * `high` - highest half of the concatenation
+/// * `low` - lowest half of the concatenation
+/// * `count` - count of positions
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift::funnel_shr;
+/// assert_eq!(funnel_shr(0x1234_5678, 0x9ABC_DEF0, 8), 0x789A_BCDE);
+/// // A field crossing the word boundary: bits 28..36 of the concatenation
+/// assert_eq!(funnel_shr(0x1234_5678, 0x9ABC_DEF0, 28) & 0xFF, 0x89);
+/// ```
+pub fn funnel_shr(high: u32, low: u32, count: u32) -> u32 {
+    match count % u32::BITS {
+        0 => low,
+        count => low >> count | high << (u32::BITS - count),
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_asr_lsr() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let number = state as i32;
+            for count in 0..32 {
+                assert_eq!(asr(number, count), number >> count);
+                assert_eq!(lsr_on_signed(number, count), (state >> count) as i32);
+                // Arithmetic shift is division rounding down
+                assert_eq!(asr(number, count) as i64, (number as i64).div_euclid(1 << count));
+                // The shifts differ exactly in the vacated bits of negative numbers
+                let difference = (asr(number, count) ^ lsr_on_signed(number, count)) as u32;
+                assert_eq!(difference.count_ones(), if number < 0 { count } else { 0 });
+            }
+            assert_eq!(asr(number, 32 + state % 100), if number < 0 { -1 } else { 0 });
+            assert_eq!(lsr_on_signed(number, 32 + state % 100), 0);
+        }
+        assert_eq!((asr(i32::MIN, 31), lsr_on_signed(i32::MIN, 31)), (-1, 1));
+        assert_eq!((asr(i32::MAX, 30), lsr_on_signed(i32::MAX, 30)), (1, 1));
+    }
+
+    #[test]
+    fn test_write_shift_comparison() {
+        let mut vec = Vec::new();
+        write_shift_comparison(106, 3, &mut vec).unwrap();
+        let text = String::from_utf8(vec).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[1], lines[2].replace("lsr", "asr"));
+        assert_eq!(lines[3], "        ^^^ sign bit 0 copied by asr, 0 by lsr");
+        let mut vec = Vec::new();
+        write_shift_comparison(-1, 0, &mut vec).unwrap();
+        let text = String::from_utf8(vec).unwrap();
+        assert_eq!(text.lines().nth(2), Some(format!("lsr 0   {} = -1", "1".repeat(32)).as_str()));
+        assert_eq!(text.lines().nth(3), Some("        nothing vacated, both shifts keep the number"));
+        let mut vec = Vec::new();
+        write_shift_comparison(i32::MIN, 40, &mut vec).unwrap();
+        let text = String::from_utf8(vec).unwrap();
+        assert!(text.contains(&format!("asr 40  {} = -1", "1".repeat(32))));
+        assert!(text.contains(&format!("{} sign bit 1", "^".repeat(32))));
+    }
+
+    #[test]
+    fn test_shift_with_carry() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            for count in 0..=40 {
+                // The shifted number and the carry put together are the original bits
+                let (shifted, carry) = shl_with_carry(state, count);
+                let wide = (carry as u64) << 32 | shifted as u64;
+                assert_eq!(wide, (state as u64) << count.min(32));
+                let (shifted, carry) = shr_with_carry(state, count);
+                assert_eq!(shifted as u64, (state as u64) >> count.min(32));
+                assert_eq!(shifted.checked_shl(count).unwrap_or(0) | carry, state);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate_through_carry() {
+        for value in 0..=u8::MAX {
+            for carry in [false, true] {
+                // Rotation by one step at a time
+                let (mut left, mut left_carry) = (value, carry);
+                let (mut right, mut right_carry) = (value, carry);
+                for count in 0..32 {
+                    assert_eq!(rcl_u8(value, carry, count), (left, left_carry));
+                    assert_eq!(rcr_u8(value, carry, count), (right, right_carry));
+                    (left, left_carry) = (left << 1 | left_carry as u8, left >> 7 == 1);
+                    (right, right_carry) = (right >> 1 | (right_carry as u8) << 7, right & 1 == 1);
+                }
+                // Only the 5 lowest bits of the count matter
+                assert_eq!(rcl_u8(value, carry, 33), rcl_u8(value, carry, 1));
+                assert_eq!(rcr_u8(value, carry, 0x25), rcr_u8(value, carry, 5));
+                let (rotated, rotated_carry) = rcl_u8(value, carry, 3);
+                assert_eq!(rcr_u8(rotated, rotated_carry, 3), (value, carry));
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_shifts() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let number = state >> (state % 32);
+            for count in 0..=40 {
+                let (shifted, lost) = overflowing_shl_exact(number, count);
+                assert_eq!(shifted, number.checked_shl(count).unwrap_or(0));
+                // Lost bits are the carry put back in place
+                assert_eq!(lost, shl_with_carry(number, count).1.checked_shl(u32::BITS.saturating_sub(count)).unwrap_or(0));
+                assert_eq!(lost | shifted.checked_shr(count).unwrap_or(0), number);
+                let (shifted, lost) = overflowing_shr_exact(number, count);
+                assert_eq!(lost, shr_with_carry(number, count).1);
+                assert_eq!(lost | shifted.checked_shl(count).unwrap_or(0), number);
+                // Exact when the product fits
+                let product = (number as u64).checked_shl(count).filter(|&product| product <= u32::MAX as u64);
+                match checked_shl_exact(number, count) {
+                    Ok(shifted) => assert_eq!(Some(shifted as u64), product.or((number == 0).then_some(0))),
+                    Err(error) => {
+                        assert!(product.is_none());
+                        assert_eq!(error, ShiftOverflow { number, count, max_count: number.leading_zeros() });
+                        assert!(checked_shl_exact(number, error.max_count).is_ok());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_funnel_shifts() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let (high, low) = (state, state.rotate_left(11) ^ 0x5555_5555);
+            let concatenation = (high as u64) << 32 | low as u64;
+            for count in 0..32 {
+                assert_eq!(funnel_shl(high, low, count), (concatenation << count >> 32) as u32);
+                assert_eq!(funnel_shr(high, low, count), (concatenation >> count) as u32);
+                // Shifting left by n is shifting right by 32 - n, but for 0 the windows are different halves
+                if count != 0 {
+                    assert_eq!(funnel_shl(high, low, count), funnel_shr(high, low, u32::BITS - count));
+                }
+                assert_eq!(funnel_shl(high, high, count), high.rotate_left(count));
+                assert_eq!(funnel_shr(low, low, count), low.rotate_right(count));
+                assert_eq!(funnel_shl(high, low, count + 32), funnel_shl(high, low, count));
+            }
+        }
+    }
+}
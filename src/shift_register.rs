@@ -0,0 +1,176 @@
+//! Clock-by-clock simulation of the shift register circuit behind CRCs and Galois LFSRs.
+//! On every clock the register shifts left by one, the bit leaving the top is XORed with the serial input,
+//! and that feedback bit is XORed into every tapped flip-flop (the XOR gates sit between the flip-flops).
+//! The taps write the polynomial the CRC way, bit *k* for xᵏ with the highest term implied, so x¹⁶ + x¹⁴ + x¹³ + x¹¹ + 1
+//! is 0x6801. *Lfsr* shifts right and implies the constant term instead (0xB400), see *lfsr::from_shift_register_taps*
+
+use std::fmt;
+use crate::crc::CrcParams;
+
+/// Shift register of the width from 1 to 32 flip-flops with XOR gates at the taps
+/// # Examples
+/// ```
+/// # use understanding_bitwise::shift_register::ShiftRegister;
+/// // CRC-3 circuit of x³ + x + 1
+/// let mut register = ShiftRegister::new(3, 0b011).unwrap();
+/// assert!(register.clock(true));
+/// assert_eq!(register.to_string(), "011");
+/// assert!(!register.clock(false));
+/// assert_eq!(register.to_string(), "110");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ShiftRegister {
+    width: u32,
+    taps: u32,
+    state: u32,
+}
+
+impl ShiftRegister {
+    /// Returns the register of zeros or None if the width isn't in 1..=32 or the taps don't fit into the width
+    /// # Arguments
+    /// * `width` - count of flip-flops
+    /// * `taps` - mask of the flip-flops receiving the feedback, the generator polynomial without the highest term
+    pub fn new(width: u32, taps: u32) -> Option<Self> {
+        if !(1..=u32::BITS).contains(&width) || taps & ! mask(width) != 0 {
+            return None;
+        }
+        Some(ShiftRegister { width, taps, state: 0 })
+    }
+
+    /// Returns the register wired for the CRC and loaded with its initial value.
+    /// Input reflection and the final steps are up to the caller, see *crc_bitwise*
+    /// # Arguments
+    /// * `params` - parameters of the CRC
+    pub fn from_crc_params(params: &CrcParams) -> Option<Self> {
+        let mut register = Self::new(params.width, params.poly)?;
+        register.load(params.init);
+        Some(register)
+    }
+
+    /// Returns the count of flip-flops
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the mask of the tapped flip-flops
+    pub fn taps(&self) -> u32 {
+        self.taps
+    }
+
+    /// Returns the values of the flip-flops
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+
+    /// Sets the values of the flip-flops, the bits above the width are dropped
+    /// # Arguments
+    /// * `state` - new values of the flip-flops
+    pub fn load(&mut self, state: u32) {
+        self.state = state & mask(self.width);
+    }
+
+    /// Performs one clock and returns the feedback bit
+    /// # Arguments
+    /// * `input` - value of the serial input
+    pub fn clock(&mut self, input: bool) -> bool {
+        let feedback = (self.state >> (self.width - 1) == 1) ^ input;
+        self.state = self.state << 1 & mask(self.width);
+        if feedback {
+            self.state ^= self.taps;
+        }
+        feedback
+    }
+
+    /// Clocks in every bit of the input and returns the state after the last clock
+    /// # Arguments
+    /// * `bits` - values of the serial input, one per clock
+    pub fn clock_bits(&mut self, bits: impl IntoIterator<Item = bool>) -> u32 {
+        for bit in bits {
+            self.clock(bit);
+        }
+        self.state
+    }
+
+    /// Clocks in the bytes highest bit first and returns the state after the last clock
+    /// # Arguments
+    /// * `bytes` - bytes to work with
+    pub fn clock_bytes(&mut self, bytes: &[u8]) -> u32 {
+        self.clock_bits(bytes.iter().flat_map(|&byte| (0..u8::BITS).rev().map(move |index| byte >> index & 1 == 1)))
+    }
+
+    /// Runs the free-running register (input held at 0) for the count of clocks and returns the states after every clock
+    /// # Arguments
+    /// * `cycles` - count of clocks
+    /// # Examples
+    /// ```
+    /// # use understanding_bitwise::shift_register::ShiftRegister;
+    /// let mut register = ShiftRegister::new(3, 0b011).unwrap();
+    /// register.load(1);
+    /// // x³ + x + 1 is primitive, so the register walks through all 7 nonzero states
+    /// assert_eq!(register.run(7), [0b010, 0b100, 0b011, 0b110, 0b111, 0b101, 0b001]);
+    /// ```
+    pub fn run(&mut self, cycles: usize) -> Vec<u32> {
+        (0..cycles).map(|_| {
+            self.clock(false);
+            self.state
+        }).collect()
+    }
+}
+
+/// Helper function. Returns the mask of the lowest *width* bits
+/// # Arguments
+/// * `width` - bits count from 1 to 32
+fn mask(width: u32) -> u32 {
+    u32::MAX >> (u32::BITS - width)
+}
+
+impl fmt::Display for ShiftRegister {
+    /// Writes all flip-flops, the highest (the output) first
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:0width$b}", self.state, width = self.width as usize)
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::{crc_bitwise, CRC_16_XMODEM, CRC_32, CRC_32_BZIP2, CRC_8_SMBUS};
+
+    #[test]
+    fn test_new() {
+        assert!(ShiftRegister::new(0, 0).is_none());
+        assert!(ShiftRegister::new(33, 0).is_none());
+        assert!(ShiftRegister::new(3, 0b1000).is_none());
+        let register = ShiftRegister::new(32, 0x04C1_1DB7).unwrap();
+        assert_eq!((register.width(), register.taps(), register.state()), (32, 0x04C1_1DB7, 0));
+        assert_eq!(register.to_string(), "0".repeat(32));
+    }
+
+    #[test]
+    fn test_matches_crc() {
+        for params in [CRC_8_SMBUS, CRC_16_XMODEM, CRC_32_BZIP2] {
+            let mut register = ShiftRegister::from_crc_params(&params).unwrap();
+            let state = register.clock_bytes(b"123456789");
+            assert_eq!(state ^ params.xor_out, crc_bitwise(&params, b"123456789"), "{params:?}");
+        }
+        // Reflected input is fed lowest bit first, the result is read reversed
+        let mut register = ShiftRegister::from_crc_params(&CRC_32).unwrap();
+        let state = register.clock_bits(b"123456789".iter().flat_map(|&byte| (0..8).map(move |index| byte >> index & 1 == 1)));
+        assert_eq!(state.reverse_bits() ^ CRC_32.xor_out, 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_run_period() {
+        // x¹⁶ + x¹⁴ + x¹³ + x¹¹ + 1 is primitive: the period is 2¹⁶ - 1
+        let mut register = ShiftRegister::new(16, 0x6801).unwrap();
+        register.load(1);
+        let states = register.run(0xFFFF);
+        assert_eq!(states.last(), Some(&1));
+        assert!(!states[..0xFFFE].contains(&1));
+        assert!(!states.contains(&0));
+        // Zero state stays zero
+        register.load(0);
+        assert_eq!(register.run(5), [0; 5]);
+    }
+}
@@ -0,0 +1,409 @@
+//! Operations over slices of words treated as one long bitmap
+
+use crate::{binary_ones_count_sub_method, binary_ones_count_swar, get_bits, invert_bit, set_bit, unset_bit};
+
+/// Count of independent accumulators. Independent lanes let the compiler vectorize the loop
+const LANES: usize = 8;
+
+/// Returns the count of ones in all the words. Counts one word at a time
+/// # Arguments
+/// * `words` - words to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::ones_count_slice_scalar;
+/// assert_eq!(ones_count_slice_scalar(&[0b101, u32::MAX]), 34);
+/// ```
+pub fn ones_count_slice_scalar(words: &[u32]) -> u64 {
+    words.iter().map(|&word| binary_ones_count_sub_method(word) as u64).sum()
+}
+
+/// Returns the count of ones in all the words. Counts in independent lanes with *binary_ones_count_swar*, so the loop is autovectorized
+/// # Arguments
+/// * `words` - words to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::ones_count_slice;
+/// assert_eq!(ones_count_slice(&[0b101; 1000]), 2000);
+/// ```
+pub fn ones_count_slice(words: &[u32]) -> u64 {
+    let chunks = words.chunks_exact(LANES);
+    let remainder = chunks.remainder();
+    let mut lanes = [0u64; LANES];
+    for chunk in chunks {
+        for (lane, &word) in lanes.iter_mut().zip(chunk) {
+            *lane += binary_ones_count_swar(word) as u64;
+        }
+    }
+    lanes.iter().sum::<u64>() + ones_count_slice_scalar(remainder)
+}
+
+/// Returns the count of ones in all the bytes. Groups the bytes into words and counts them like *ones_count_slice*
+/// # Arguments
+/// * `bytes` - bytes to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::ones_count_slice_u8;
+/// assert_eq!(ones_count_slice_u8(&[0xFF, 0x01, 0x80]), 10);
+/// ```
+pub fn ones_count_slice_u8(bytes: &[u8]) -> u64 {
+    const WORD_BYTES: usize = (u32::BITS / u8::BITS) as usize;
+    const CHUNK_BYTES: usize = WORD_BYTES * LANES;
+    let chunks = bytes.chunks_exact(CHUNK_BYTES);
+    let remainder = chunks.remainder();
+    let mut lanes = [0u64; LANES];
+    for chunk in chunks {
+        for (lane, word) in lanes.iter_mut().zip(chunk.chunks_exact(WORD_BYTES)) {
+            *lane += binary_ones_count_swar(u32::from_le_bytes([word[0], word[1], word[2], word[3]])) as u64;
+        }
+    }
+    lanes.iter().sum::<u64>() + remainder.iter().map(|&byte| binary_ones_count_sub_method(byte as u32) as u64).sum::<u64>()
+}
+
+/// Returns the [*Hamming distance*](https://en.wikipedia.org/wiki/Hamming_distance) of the bitmaps (count of differing bits)
+/// or None if the slices have different lengths
+/// # Arguments
+/// * `left` - words to work with
+/// * `right` - words to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::hamming_distance_slice;
+/// assert_eq!(hamming_distance_slice(&[0b1010, 0], &[0b0110, 1]), Some(3));
+/// assert_eq!(hamming_distance_slice(&[0], &[]), None);
+/// ```
+pub fn hamming_distance_slice(left: &[u32], right: &[u32]) -> Option<u64> {
+    if left.len() != right.len() {
+        return None;
+    }
+    let chunks = left.chunks_exact(LANES).zip(right.chunks_exact(LANES));
+    let mut lanes = [0u64; LANES];
+    for (left_chunk, right_chunk) in chunks {
+        for ((lane, &left_word), &right_word) in lanes.iter_mut().zip(left_chunk).zip(right_chunk) {
+            *lane += binary_ones_count_swar(left_word ^ right_word) as u64;
+        }
+    }
+    let tail = left.len() / LANES * LANES;
+    let remainder = left[tail..].iter().zip(&right[tail..]).map(|(&left_word, &right_word)| binary_ones_count_swar(left_word ^ right_word) as u64);
+    Some(lanes.iter().sum::<u64>() + remainder.sum::<u64>())
+}
+
+/// Helper function. Returns the index of the word and the index of the bit inside it or None if the bit is out of the slice
+/// # Arguments
+/// * `words` - words to work with
+/// * `index` - index of the bit in the slice
+fn locate(words: &[u32], index: usize) -> Option<(usize, u32)> {
+    let word = index / u32::BITS as usize;
+    (word < words.len()).then_some((word, (index % u32::BITS as usize) as u32))
+}
+
+/// Helper function. Applies the function to every word touched by the range with the mask of the range bits in that word.
+/// Returns None (and applies nothing) if the range is out of the slice
+/// # Arguments
+/// * `words` - words to work with
+/// * `start` - index of the first bit of the range
+/// * `length` - bits count of the range
+/// * `f` - function receiving the word and the mask
+fn manipulate_range<F>(words: &mut [u32], start: usize, length: usize, mut f: F) -> Option<()>
+where
+    F: FnMut(&mut u32, u32) {
+    let end = start.checked_add(length)?;
+    if end > words.len() * u32::BITS as usize {
+        return None;
+    }
+    let bits = u32::BITS as usize;
+    for (word_index, word) in words.iter_mut().enumerate().take(end.div_ceil(bits)).skip(start / bits) {
+        let low = start.saturating_sub(word_index * bits).min(bits) as u32;
+        let high = (end - word_index * bits).min(bits) as u32;
+        if high > low {
+            f(word, u32::MAX >> (u32::BITS - (high - low)) << low);
+        }
+    }
+    Some(())
+}
+
+/// Returns the value of the specific bit of the slice or None if the index is out of the slice.
+/// Bit *index* is bit *index % 32* of word *index / 32*
+/// # Arguments
+/// * `words` - words to work with
+/// * `index` - index of the bit in the slice
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::get_bit_in_slice;
+/// assert_eq!(get_bit_in_slice(&[0, 0b10], 33), Some(true));
+/// assert_eq!(get_bit_in_slice(&[0, 0b10], 64), None);
+/// ```
+pub fn get_bit_in_slice(words: &[u32], index: usize) -> Option<bool> {
+    let (word, bit) = locate(words, index)?;
+    get_bits(words[word], bit, 1).map(|value| value == 1)
+}
+
+/// Sets the specific bit of the slice to 1. Returns None if the index is out of the slice
+/// # Arguments
+/// * `words` - words to work with
+/// * `index` - index of the bit in the slice
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::set_bit_in_slice;
+/// let mut words = [0; 2];
+/// set_bit_in_slice(&mut words, 33).unwrap();
+/// assert_eq!(words, [0, 0b10]);
+/// ```
+pub fn set_bit_in_slice(words: &mut [u32], index: usize) -> Option<()> {
+    let (word, bit) = locate(words, index)?;
+    words[word] = set_bit(words[word], bit)?;
+    Some(())
+}
+
+/// Sets the specific bit of the slice to 0. Returns None if the index is out of the slice
+/// # Arguments
+/// * `words` - words to work with
+/// * `index` - index of the bit in the slice
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::unset_bit_in_slice;
+/// let mut words = [u32::MAX; 2];
+/// unset_bit_in_slice(&mut words, 0).unwrap();
+/// assert_eq!(words, [u32::MAX - 1, u32::MAX]);
+/// ```
+pub fn unset_bit_in_slice(words: &mut [u32], index: usize) -> Option<()> {
+    let (word, bit) = locate(words, index)?;
+    words[word] = unset_bit(words[word], bit)?;
+    Some(())
+}
+
+/// Inverts the specific bit of the slice. Returns None if the index is out of the slice
+/// # Arguments
+/// * `words` - words to work with
+/// * `index` - index of the bit in the slice
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::invert_bit_in_slice;
+/// let mut words = [0, 0b11];
+/// invert_bit_in_slice(&mut words, 32).unwrap();
+/// assert_eq!(words, [0, 0b10]);
+/// assert_eq!(invert_bit_in_slice(&mut words, 64), None);
+/// ```
+pub fn invert_bit_in_slice(words: &mut [u32], index: usize) -> Option<()> {
+    let (word, bit) = locate(words, index)?;
+    words[word] = invert_bit(words[word], bit)?;
+    Some(())
+}
+
+/// Sets the range of bits of the slice to 1. Returns None (and changes nothing) if the range is out of the slice
+/// # Arguments
+/// * `words` - words to work with
+/// * `start` - index of the first bit of the range
+/// * `length` - bits count of the range
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::set_range_in_slice;
+/// let mut words = [0; 3];
+/// set_range_in_slice(&mut words, 28, 40).unwrap();
+/// assert_eq!(words, [0xF000_0000, u32::MAX, 0xF]);
+/// ```
+pub fn set_range_in_slice(words: &mut [u32], start: usize, length: usize) -> Option<()> {
+    manipulate_range(words, start, length, |word, mask| *word |= mask)
+}
+
+/// Sets the range of bits of the slice to 0. Returns None (and changes nothing) if the range is out of the slice
+/// # Arguments
+/// * `words` - words to work with
+/// * `start` - index of the first bit of the range
+/// * `length` - bits count of the range
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::unset_range_in_slice;
+/// let mut words = [u32::MAX; 2];
+/// unset_range_in_slice(&mut words, 4, 56).unwrap();
+/// assert_eq!(words, [0xF, 0xF000_0000]);
+/// ```
+pub fn unset_range_in_slice(words: &mut [u32], start: usize, length: usize) -> Option<()> {
+    manipulate_range(words, start, length, |word, mask| *word &= ! mask)
+}
+
+/// Inverts the range of bits of the slice. Returns None (and changes nothing) if the range is out of the slice
+/// # Arguments
+/// * `words` - words to work with
+/// * `start` - index of the first bit of the range
+/// * `length` - bits count of the range
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::invert_range_in_slice;
+/// let mut words = [0xFF, 0];
+/// invert_range_in_slice(&mut words, 4, 32).unwrap();
+/// assert_eq!(words, [0xFFFF_FF0F, 0xF]);
+/// assert_eq!(invert_range_in_slice(&mut words, 60, 5), None);
+/// ```
+pub fn invert_range_in_slice(words: &mut [u32], start: usize, length: usize) -> Option<()> {
+    manipulate_range(words, start, length, |word, mask| *word ^= mask)
+}
+
+/// Rotates the bytes left as one bit string: the bit *i* (bit *i % 8* of the byte *i / 8*) moves to the bit
+/// *i + count*, the highest bits of the last byte wrap around to the first byte. The count is taken modulo the
+/// bits count of the slice. One byte rotates like *circular_shl*
+/// # Arguments
+/// * `bytes` - bytes to work with
+/// * `count` - number of positions to be shifted by
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::circular_shl_slice;
+/// let mut bytes = [0b1000_0001, 0b0100_0000];
+/// circular_shl_slice(&mut bytes, 2);
+/// assert_eq!(bytes, [0b0000_0101, 0b0000_0010]);
+/// circular_shl_slice(&mut bytes, 16);
+/// assert_eq!(bytes, [0b0000_0101, 0b0000_0010]);
+/// ```
+pub fn circular_shl_slice(bytes: &mut [u8], count: usize) {
+    let bits = bytes.len() * u8::BITS as usize;
+    if bits == 0 {
+        return;
+    }
+    let count = count % bits;
+    // Whole bytes move first, then the bits move inside the bytes carrying over to the next byte
+    bytes.rotate_right(count / u8::BITS as usize);
+    let shift = (count % u8::BITS as usize) as u32;
+    if shift == 0 {
+        return;
+    }
+    let mut carry = bytes[bytes.len() - 1] >> (u8::BITS - shift);
+    for byte in bytes.iter_mut() {
+        let shifted_out = *byte >> (u8::BITS - shift);
+        *byte = *byte << shift | carry;
+        carry = shifted_out;
+    }
+}
+
+/// Rotates the bytes right as one bit string, see *circular_shl_slice*: the bit *i* moves to the bit *i - count*,
+/// the lowest bits of the first byte wrap around to the last byte
+/// # Arguments
+/// * `bytes` - bytes to work with
+/// * `count` - number of positions to be shifted by
+/// # Examples
+/// ```
+/// # use understanding_bitwise::slice::circular_shr_slice;
+/// let mut bytes = [0b0000_0101, 0b0000_0010];
+/// circular_shr_slice(&mut bytes, 2);
+/// assert_eq!(bytes, [0b1000_0001, 0b0100_0000]);
+/// ```
+pub fn circular_shr_slice(bytes: &mut [u8], count: usize) {
+    let bits = bytes.len() * u8::BITS as usize;
+    if bits == 0 {
+        return;
+    }
+    circular_shl_slice(bytes, bits - count % bits);
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+    use crate::circular_shl;
+
+    fn words(count: usize) -> Vec<u32> {
+        let mut rng = Xorshift32::new(0x2545_F491).unwrap();
+        (0..count).map(|_| rng.next_u32()).collect()
+    }
+
+    #[test]
+    fn test_ones_count_slice() {
+        assert_eq!(ones_count_slice(&[]), 0);
+        assert_eq!(ones_count_slice(&[u32::MAX; 100_003]), 32 * 100_003);
+        for count in [1, 7, 8, 9, 100, 1001] {
+            let words = words(count);
+            let expected: u64 = words.iter().map(|word| word.count_ones() as u64).sum();
+            assert_eq!(ones_count_slice(&words), expected);
+            assert_eq!(ones_count_slice_scalar(&words), expected);
+        }
+    }
+
+    #[test]
+    fn test_ones_count_slice_u8() {
+        assert_eq!(ones_count_slice_u8(&[]), 0);
+        for count in [1usize, 31, 32, 33, 1000] {
+            let words = words(count.div_ceil(4));
+            let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).take(count).collect();
+            let expected: u64 = bytes.iter().map(|byte| byte.count_ones() as u64).sum();
+            assert_eq!(ones_count_slice_u8(&bytes), expected);
+        }
+    }
+
+    #[test]
+    fn test_hamming_distance_slice() {
+        for count in [0, 1, 7, 8, 9, 100] {
+            let left = words(count);
+            let right: Vec<u32> = left.iter().map(|word| word.rotate_left(3)).collect();
+            let expected: u64 = left.iter().zip(&right).map(|(a, b)| (a ^ b).count_ones() as u64).sum();
+            assert_eq!(hamming_distance_slice(&left, &right), Some(expected));
+            assert_eq!(hamming_distance_slice(&left, &left), Some(0));
+        }
+        assert_eq!(hamming_distance_slice(&[1, 2], &[1]), None);
+    }
+
+    #[test]
+    fn test_bit_in_slice() {
+        let mut words = [0u32; 3];
+        for index in (0..96).step_by(7) {
+            set_bit_in_slice(&mut words, index).unwrap();
+        }
+        for index in 0..96 {
+            assert_eq!(get_bit_in_slice(&words, index), Some(index % 7 == 0));
+        }
+        invert_bit_in_slice(&mut words, 95).unwrap();
+        unset_bit_in_slice(&mut words, 0).unwrap();
+        assert_eq!(get_bit_in_slice(&words, 95), Some(true));
+        assert_eq!(get_bit_in_slice(&words, 0), Some(false));
+        assert_eq!(get_bit_in_slice(&words, 96), None);
+        assert_eq!(set_bit_in_slice(&mut words, usize::MAX), None);
+        assert_eq!(unset_bit_in_slice(&mut [], 0), None);
+    }
+
+    #[test]
+    fn test_range_in_slice() {
+        for start in 0..96 {
+            for length in 0..=96 - start {
+                let mut words = [0u32; 3];
+                set_range_in_slice(&mut words, start, length).unwrap();
+                for index in 0..96 {
+                    assert_eq!(get_bit_in_slice(&words, index), Some((start..start + length).contains(&index)));
+                }
+                invert_range_in_slice(&mut words, 0, 96).unwrap();
+                unset_range_in_slice(&mut words, start, length).unwrap();
+                assert_eq!(ones_count_slice(&words), 96 - length as u64);
+            }
+        }
+        let mut words = [0u32; 2];
+        assert_eq!(set_range_in_slice(&mut words, 1, 64), None);
+        assert_eq!(invert_range_in_slice(&mut words, usize::MAX, 2), None);
+        assert_eq!(words, [0, 0]);
+    }
+
+    #[test]
+    fn test_circular_shift_slice() {
+        let bytes: Vec<u8> = words(4).iter().flat_map(|word| word.to_le_bytes()).collect();
+        let number = u128::from_le_bytes(bytes.clone().try_into().unwrap());
+        for count in 0..300 {
+            let mut shifted = bytes.clone();
+            circular_shl_slice(&mut shifted, count);
+            assert_eq!(u128::from_le_bytes(shifted.clone().try_into().unwrap()), number.rotate_left(count as u32 % 128));
+            circular_shr_slice(&mut shifted, count);
+            assert_eq!(shifted, bytes);
+        }
+        // Odd lengths have no integer counterpart, shifting bit by bit does the same
+        let mut three = [0b1010_0110u8, 0b0000_0001, 0b1100_0000];
+        let mut step_by_step = three;
+        for _ in 0..11 {
+            circular_shl_slice(&mut step_by_step, 1);
+        }
+        circular_shl_slice(&mut three, 11);
+        assert_eq!(three, step_by_step);
+        circular_shr_slice(&mut three, 11 + 24 * 5);
+        assert_eq!(three, [0b1010_0110, 0b0000_0001, 0b1100_0000]);
+        for count in 0..20 {
+            let mut byte = [0b1000_0011u8];
+            circular_shl_slice(&mut byte, count);
+            assert_eq!(byte[0], circular_shl(0b1000_0011, count as u32 % 8));
+        }
+        circular_shl_slice(&mut [], 5);
+        circular_shr_slice(&mut [], 5);
+    }
+}
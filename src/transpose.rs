@@ -0,0 +1,124 @@
+//! Transpose of square bit matrices: the row *i* is the element *i* and the column *j* is the bit *j*,
+//! so the bit *j* of the row *i* becomes the bit *i* of the row *j*.
+//! The recursive algorithm swaps the off-diagonal halves, then the off-diagonal quarters of every half and so on:
+//! log₂(n) rounds of n / 2 masked swaps instead of n² single bit moves
+
+/// Returns the transposed matrix, moving the bits one by one
+/// # Arguments
+/// * `matrix` - rows of the matrix
+/// # Examples
+/// ```
+/// # use understanding_bitwise::transpose::transpose32_naive;
+/// let mut matrix = [0u32; 32];
+/// matrix[0] = 0b110;
+/// let transposed = transpose32_naive(&matrix);
+/// assert_eq!((transposed[1], transposed[2]), (1, 1));
+/// ```
+pub fn transpose32_naive(matrix: &[u32; 32]) -> [u32; 32] {
+    std::array::from_fn(|column| {
+        matrix.iter().enumerate().fold(0, |row, (index, &source)| row | (source >> column & 1) << index)
+    })
+}
+
+/// Transposes the matrix in place by masked swaps of blocks of 16, 8, 4, 2 and 1 bits
+/// # Arguments
+/// * `matrix` - rows of the matrix
+/// # Examples
+/// ```
+/// # use understanding_bitwise::transpose::transpose32;
+/// let mut matrix: [u32; 32] = std::array::from_fn(|index| 1 << index);
+/// transpose32(&mut matrix);
+/// assert_eq!(matrix[31], 1 << 31);
+/// let mut matrix = [0u32; 32];
+/// matrix[0] = u32::MAX;
+/// transpose32(&mut matrix);
+/// assert!(matrix.iter().all(|&row| row == 1));
+/// ```
+pub fn transpose32(matrix: &mut [u32; 32]) {
+    let mut width = 16;
+    // Lower halves of the blocks of 2 × width bits
+    let mut mask = 0x0000_FFFFu32;
+    while width != 0 {
+        for row in (0..32).filter(|row| row & width == 0) {
+            // Upper half of the row swaps with the lower half of the row *width* below
+            let difference = (matrix[row] >> width ^ matrix[row + width]) & mask;
+            matrix[row] ^= difference << width;
+            matrix[row + width] ^= difference;
+        }
+        width >>= 1;
+        mask ^= mask << width;
+    }
+}
+
+/// Returns the transposed matrix, moving the bits one by one
+/// # Arguments
+/// * `matrix` - rows of the matrix
+pub fn transpose64_naive(matrix: &[u64; 64]) -> [u64; 64] {
+    std::array::from_fn(|column| {
+        matrix.iter().enumerate().fold(0, |row, (index, &source)| row | (source >> column & 1) << index)
+    })
+}
+
+/// Transposes the matrix in place by masked swaps of blocks of 32, 16, 8, 4, 2 and 1 bits, see *transpose32*
+/// # Arguments
+/// * `matrix` - rows of the matrix
+pub fn transpose64(matrix: &mut [u64; 64]) {
+    let mut width = 32;
+    let mut mask = 0x0000_0000_FFFF_FFFFu64;
+    while width != 0 {
+        for row in (0..64).filter(|row| row & width == 0) {
+            let difference = (matrix[row] >> width ^ matrix[row + width]) & mask;
+            matrix[row] ^= difference << width;
+            matrix[row + width] ^= difference;
+        }
+        width >>= 1;
+        mask ^= mask << width;
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_transpose32() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..50 {
+            let matrix: [u32; 32] = std::array::from_fn(|_| rng.next_u32());
+            let mut transposed = matrix;
+            transpose32(&mut transposed);
+            assert_eq!(transposed, transpose32_naive(&matrix));
+            for (row, &source) in matrix.iter().enumerate() {
+                for (column, &target) in transposed.iter().enumerate() {
+                    assert_eq!(target >> row & 1, source >> column & 1);
+                }
+            }
+            // Transpose is an involution
+            transpose32(&mut transposed);
+            assert_eq!(transposed, matrix);
+        }
+        let identity: [u32; 32] = std::array::from_fn(|index| 1 << index);
+        let mut matrix = identity;
+        transpose32(&mut matrix);
+        assert_eq!(matrix, identity);
+    }
+
+    #[test]
+    fn test_transpose64() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..20 {
+            let matrix: [u64; 64] = std::array::from_fn(|_| (rng.next_u32() as u64) << 32 | rng.next_u32() as u64);
+            let mut transposed = matrix;
+            transpose64(&mut transposed);
+            assert_eq!(transposed, transpose64_naive(&matrix));
+            transpose64(&mut transposed);
+            assert_eq!(transposed, matrix);
+        }
+        let mut matrix = [0u64; 64];
+        matrix[63] = u64::MAX;
+        transpose64(&mut matrix);
+        assert!(matrix.iter().all(|&row| row == 1 << 63));
+    }
+}
@@ -0,0 +1,176 @@
+//! Bits of Unicode characters: the scalar value and its UTF-8 encoding, where the lead byte starts with
+//! *0* (one byte) or as many ones as there are bytes followed by a zero, every continuation byte starts with *10*,
+//! and the remaining bits carry the scalar from the highest bits down
+
+use std::io::{self, Write};
+
+/// Bits of a character and of its UTF-8 encoding
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CharBits {
+    /// Scalar value, 0..=0x10FFFF without the surrogates 0xD800..=0xDFFF
+    pub scalar: u32,
+    bytes: [u8; 4],
+    count: usize,
+}
+
+impl CharBits {
+    /// Returns the UTF-8 bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes[..self.count]
+    }
+
+    /// Returns the count of UTF-8 bytes, 1 to 4
+    pub fn byte_count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the count of scalar bits the encoding carries: 7, 11, 16 or 21
+    pub fn payload_bits(&self) -> u32 {
+        (0..self.count).map(|index| 8 - prefix_bits(self.count, index)).sum()
+    }
+
+    /// Returns the count of fixed prefix bits of the byte, *None* if there is no such byte
+    /// # Arguments
+    /// * `index` - index of the byte
+    pub fn prefix_bits(&self, index: usize) -> Option<u32> {
+        (index < self.count).then(|| prefix_bits(self.count, index))
+    }
+
+    /// Returns the scalar bits the byte carries, *None* if there is no such byte
+    /// # Arguments
+    /// * `index` - index of the byte
+    pub fn payload(&self, index: usize) -> Option<u8> {
+        let prefix = self.prefix_bits(index)?;
+        Some(self.bytes[index] & (0xFF >> prefix))
+    }
+}
+
+/// Helper function. Returns the count of fixed prefix bits of the byte. The prefix is *0* for a single byte,
+/// *n* ones and a zero for the lead byte of *n* bytes, *10* for a continuation byte
+/// # Arguments
+/// * `count` - count of bytes
+/// * `index` - index of the byte
+fn prefix_bits(count: usize, index: usize) -> u32 {
+    match (count, index) {
+        (1, _) => 1,
+        (_, 0) => count as u32 + 1,
+        _ => 2,
+    }
+}
+
+/// Returns the bits of the character, encoding it to UTF-8 with shifts and masks
+/// # Arguments
+/// * `c` - character to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::unicode::char_bits;
+/// // é is U+00E9 = 00011 101001: 110|00011 10|101001
+/// let bits = char_bits('é');
+/// assert_eq!(bits.bytes(), [0b110_00011, 0b10_101001]);
+/// assert_eq!((bits.prefix_bits(0), bits.payload(0)), (Some(3), Some(0b00011)));
+/// assert_eq!((bits.prefix_bits(1), bits.payload(1)), (Some(2), Some(0b101001)));
+/// assert_eq!(bits.payload_bits(), 11);
+/// ```
+pub fn char_bits(c: char) -> CharBits {
+    let scalar = c as u32;
+    let count = match scalar {
+        0..=0x7F => 1,
+        0x80..=0x7FF => 2,
+        0x800..=0xFFFF => 3,
+        _ => 4,
+    };
+    let mut bytes = [0; 4];
+    // Every continuation byte takes 6 bits, the lowest ones go to the last byte
+    for index in (1..count).rev() {
+        bytes[index] = 0b1000_0000 | (scalar >> 6 * (count - 1 - index) & 0x3F) as u8;
+    }
+    // Lead byte: *count* ones unless it's the only byte, then the highest scalar bits
+    let lead_marker = if count == 1 { 0 } else { 0xFF00u32 >> count & 0xFF };
+    bytes[0] = (lead_marker | scalar >> 6 * (count - 1)) as u8;
+    CharBits { scalar, bytes, count }
+}
+
+/// Writes the scalar in binary split into the parts of the bytes, then one line per UTF-8 byte in hexadecimal and
+/// binary with *|* between the prefix and the payload
+/// # Arguments
+/// * `c` - character to work with
+/// * `out` - *Write* object that gets the breakdown
+/// # Examples
+/// ```
+/// # use understanding_bitwise::unicode::write_char_bits;
+/// let mut vec = Vec::new();
+/// write_char_bits('€', &mut vec).unwrap();
+/// assert_eq!(String::from_utf8(vec).unwrap(), "\
+/// U+20AC  0010 000010 101100
+/// 0xE2   1110|0010
+/// 0x82     10|000010
+/// 0xAC     10|101100
+/// ");
+/// ```
+pub fn write_char_bits<W: Write>(c: char, out: &mut W) -> io::Result<()> {
+    let bits = char_bits(c);
+    let payloads: Vec<String> = (0..bits.count).map(|index| {
+        let width = 8 - prefix_bits(bits.count, index) as usize;
+        format!("{:0width$b}", bits.bytes[index] & (0xFF >> (8 - width)))
+    }).collect();
+    writeln!(out, "U+{:04X}  {}", bits.scalar, payloads.join(" "))?;
+    for (index, payload) in payloads.iter().enumerate() {
+        let prefix = prefix_bits(bits.count, index) as usize;
+        let marker = format!("{:08b}", bits.bytes[index]);
+        writeln!(out, "0x{:02X}  {:>5}|{payload}", bits.bytes[index], &marker[..prefix])?;
+    }
+    Ok(())
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_char_bits() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        let mut chars = vec!['\0', '\u{7F}', '\u{80}', '\u{7FF}', '\u{800}', '\u{FFFF}', '\u{10000}', char::MAX];
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            chars.extend(char::from_u32(state % 0x11_0000));
+        }
+        for c in chars {
+            let bits = char_bits(c);
+            let mut buffer = [0; 4];
+            assert_eq!(bits.bytes(), c.encode_utf8(&mut buffer).as_bytes());
+            assert_eq!(bits.byte_count(), c.len_utf8());
+            // Payloads put together give back the scalar
+            let scalar = (0..bits.byte_count()).fold(0u32, |scalar, index| {
+                let width = 8 - bits.prefix_bits(index).unwrap();
+                scalar << width | bits.payload(index).unwrap() as u32
+            });
+            assert_eq!(scalar, c as u32);
+            assert!(c as u32 >> bits.payload_bits() == 0);
+        }
+    }
+
+    #[test]
+    fn test_prefixes() {
+        assert_eq!(char_bits('A').bytes(), [0b0_1000001]);
+        assert_eq!((char_bits('A').prefix_bits(0), char_bits('A').prefix_bits(1)), (Some(1), None));
+        let bits = char_bits('😀');
+        assert_eq!(bits.bytes(), [0xF0, 0x9F, 0x98, 0x80]);
+        assert_eq!((0..4).map(|index| bits.prefix_bits(index).unwrap()).collect::<Vec<_>>(), [5, 2, 2, 2]);
+        assert_eq!(bits.payload_bits(), 21);
+        assert_eq!(bits.payload(4), None);
+    }
+
+    #[test]
+    fn test_write_char_bits() {
+        let mut vec = Vec::new();
+        write_char_bits('A', &mut vec).unwrap();
+        assert_eq!(String::from_utf8(vec).unwrap(), "U+0041  1000001\n0x41      0|1000001\n");
+        let mut vec = Vec::new();
+        write_char_bits('😀', &mut vec).unwrap();
+        let text = String::from_utf8(vec).unwrap();
+        assert_eq!(text.lines().next(), Some("U+1F600  000 011111 011000 000000"));
+        assert_eq!(text.lines().nth(1), Some("0xF0  11110|000"));
+    }
+}
@@ -0,0 +1,224 @@
+//! Bindings for JavaScript through [*wasm-bindgen*](https://rustwasm.github.io/docs/wasm-bindgen/), enabled by
+//! the `wasm` feature. Numbers are JS numbers, missing results are *undefined*, the steps of the explained operations
+//! and of the expressions are *WasmStep* objects, so a browser playground needs no binding layer of its own
+
+use wasm_bindgen::prelude::*;
+use crate::evaluator::Evaluator;
+use crate::explained::{self, BitStep, ShiftDirection, Step};
+
+/// Step of an explained operation or expression as seen from JavaScript, see *explained::Step*
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WasmStep {
+    /// Value after the step
+    pub value: u32,
+    /// Expression that produced the value
+    pub operation: String,
+    /// What the step does and why
+    pub description: String,
+    /// Kind of the operation: input, constant, shl, shr, and, or, xor, not, sub or add
+    pub kind: String,
+    /// Indexes of the steps the operation takes
+    pub operands: Vec<u32>,
+    /// Value of an input or a constant, amount of a shift, 0 otherwise
+    pub argument: u32,
+}
+
+impl From<Step> for WasmStep {
+    fn from(step: Step) -> Self {
+        let index = |index: usize| index as u32;
+        let (kind, operands, argument) = match step.kind {
+            BitStep::Input { value } => ("input", vec![], value),
+            BitStep::Constant { value } => ("constant", vec![], value),
+            BitStep::Shift { source, direction: ShiftDirection::Left, amount } => ("shl", vec![index(source)], amount),
+            BitStep::Shift { source, direction: ShiftDirection::Right, amount } => ("shr", vec![index(source)], amount),
+            BitStep::Mask { source, mask } => ("and", vec![index(source), index(mask)], 0),
+            BitStep::Or { left, right } => ("or", vec![index(left), index(right)], 0),
+            BitStep::Xor { left, right } => ("xor", vec![index(left), index(right)], 0),
+            BitStep::Not { source } => ("not", vec![index(source)], 0),
+            BitStep::Subtract { left, right } => ("sub", vec![index(left), index(right)], 0),
+            BitStep::Add { left, right } => ("add", vec![index(left), index(right)], 0),
+        };
+        WasmStep {
+            value: step.value,
+            operation: step.operation,
+            description: step.description,
+            kind: kind.to_owned(),
+            operands,
+            argument,
+        }
+    }
+}
+
+/// Helper function. Returns the steps converted for JavaScript
+/// # Arguments
+/// * `steps` - steps of an explained operation
+fn to_wasm(steps: Option<Vec<Step>>) -> Option<Vec<WasmStep>> {
+    steps.map(|steps| steps.into_iter().map(WasmStep::from).collect())
+}
+
+/// Returns the number with the bit set to 1, *undefined* if the index is out of range. See *set_bit*
+#[wasm_bindgen(js_name = setBit)]
+pub fn set_bit(number: u32, index: u32) -> Option<u32> {
+    crate::set_bit(number, index)
+}
+
+/// Returns the number with the bit set to 0, *undefined* if the index is out of range. See *unset_bit*
+#[wasm_bindgen(js_name = unsetBit)]
+pub fn unset_bit(number: u32, index: u32) -> Option<u32> {
+    crate::unset_bit(number, index)
+}
+
+/// Returns the number with the bit inverted, *undefined* if the index is out of range. See *invert_bit*
+#[wasm_bindgen(js_name = invertBit)]
+pub fn invert_bit(number: u32, index: u32) -> Option<u32> {
+    crate::invert_bit(number, index)
+}
+
+/// Returns the number with the bits swapped, *undefined* if an index is out of range. See *swap_bits*
+#[wasm_bindgen(js_name = swapBits)]
+pub fn swap_bits(number: u32, index1: u32, index2: u32) -> Option<u32> {
+    crate::swap_bits(number, index1, index2)
+}
+
+/// Returns the number with the bit removed, *undefined* if the index is out of range. See *remove_bit*
+#[wasm_bindgen(js_name = removeBit)]
+pub fn remove_bit(number: u32, index: u32) -> Option<u32> {
+    crate::remove_bit(number, index)
+}
+
+/// Returns the index of the highest one, *undefined* for 0. See *hob*
+#[wasm_bindgen]
+pub fn hob(number: u32) -> Option<u32> {
+    crate::hob(number)
+}
+
+/// Returns the count of ones. See *binary_ones_count*
+#[wasm_bindgen(js_name = countOnes)]
+pub fn count_ones(number: u32) -> u32 {
+    crate::binary_ones_count(number)
+}
+
+/// Returns the number in binary padded to 32 digits
+#[wasm_bindgen(js_name = toBinary)]
+pub fn to_binary(number: u32) -> String {
+    format!("{number:032b}")
+}
+
+/// Returns the number written in binary (0b), hexadecimal (0x) or decimal, *undefined* if it isn't one.
+/// See *parse_number*
+#[wasm_bindgen(js_name = parseNumber)]
+pub fn parse_number(text: &str) -> Option<u32> {
+    crate::parse_number(text)
+}
+
+/// Returns the steps of *set_bit*, *undefined* if the index is out of range
+#[wasm_bindgen(js_name = setBitExplained)]
+pub fn set_bit_explained(number: u32, index: u32) -> Option<Vec<WasmStep>> {
+    to_wasm(explained::set_bit_explained(number, index))
+}
+
+/// Returns the steps of *unset_bit*, *undefined* if the index is out of range
+#[wasm_bindgen(js_name = unsetBitExplained)]
+pub fn unset_bit_explained(number: u32, index: u32) -> Option<Vec<WasmStep>> {
+    to_wasm(explained::unset_bit_explained(number, index))
+}
+
+/// Returns the steps of *invert_bit*, *undefined* if the index is out of range
+#[wasm_bindgen(js_name = invertBitExplained)]
+pub fn invert_bit_explained(number: u32, index: u32) -> Option<Vec<WasmStep>> {
+    to_wasm(explained::invert_bit_explained(number, index))
+}
+
+/// Returns the steps of *swap_bits*, *undefined* if an index is out of range
+#[wasm_bindgen(js_name = swapBitsExplained)]
+pub fn swap_bits_explained(number: u32, index1: u32, index2: u32) -> Option<Vec<WasmStep>> {
+    to_wasm(explained::swap_bits_explained(number, index1, index2))
+}
+
+/// Returns the steps of *remove_bit*, *undefined* if the index is out of range
+#[wasm_bindgen(js_name = removeBitExplained)]
+pub fn remove_bit_explained(number: u32, index: u32) -> Option<Vec<WasmStep>> {
+    to_wasm(explained::remove_bit_explained(number, index))
+}
+
+/// Expression evaluator keeping the variables, see *evaluator::Evaluator*. Errors are thrown as JS errors
+#[wasm_bindgen(js_name = Evaluator)]
+#[derive(Clone, Default, Debug)]
+pub struct WasmEvaluator {
+    evaluator: Evaluator,
+}
+
+#[wasm_bindgen(js_class = Evaluator)]
+impl WasmEvaluator {
+    /// Returns the evaluator without variables
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value of the variable, *undefined* if it isn't defined
+    pub fn variable(&self, name: &str) -> Option<u32> {
+        self.evaluator.variable(name)
+    }
+
+    /// Returns the names of the variables in order
+    #[wasm_bindgen(js_name = variableNames)]
+    pub fn variable_names(&self) -> Vec<String> {
+        self.evaluator.variables().map(|(name, _)| name.to_owned()).collect()
+    }
+
+    /// Evaluates the expression or assignment and returns its steps, the value of the last one is the result
+    pub fn run(&mut self, line: &str) -> Result<Vec<WasmStep>, JsError> {
+        let steps = self.evaluator.run(line).map_err(|error| JsError::new(&error.to_string()))?;
+        Ok(steps.into_iter().map(WasmStep::from).collect())
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // JS values can't be created outside WebAssembly, so only the paths without errors run natively
+
+    #[test]
+    fn test_functions() {
+        assert_eq!(set_bit(0b1000, 0), Some(0b1001));
+        assert_eq!(unset_bit(0b1001, 3), Some(1));
+        assert_eq!(invert_bit(0, 31), Some(1 << 31));
+        assert_eq!(swap_bits(0b01, 0, 1), Some(0b10));
+        assert_eq!(remove_bit(0b101, 1), Some(0b11));
+        assert_eq!(set_bit(0, 32), None);
+        assert_eq!((hob(0), hob(0x80), count_ones(0xFF)), (None, Some(7), 8));
+        assert_eq!(to_binary(5), format!("{}101", "0".repeat(29)));
+        assert_eq!(parse_number("0x10"), Some(16));
+    }
+
+    #[test]
+    fn test_steps() {
+        let steps = swap_bits_explained(0b100011, 1, 4).unwrap();
+        assert_eq!(steps.last().unwrap().value, 0b110001);
+        assert_eq!((steps[0].kind.as_str(), steps[0].argument), ("input", 0b100011));
+        // Operands are earlier steps
+        for (index, step) in steps.iter().enumerate() {
+            assert!(step.operands.iter().all(|&operand| (operand as usize) < index));
+        }
+        assert!(remove_bit_explained(1, 32).is_none());
+        let shift = &set_bit_explained(0, 5).unwrap()[2];
+        assert_eq!((shift.kind.as_str(), shift.operands.as_slice(), shift.argument), ("shl", &[1][..], 5));
+        assert_eq!(unset_bit_explained(7, 0).unwrap().last().unwrap().kind, "sub");
+        assert_eq!(invert_bit_explained(7, 0).unwrap().last().unwrap().kind, "xor");
+    }
+
+    #[test]
+    fn test_evaluator() {
+        let mut evaluator = WasmEvaluator::new();
+        evaluator.run("x = 0xF0").unwrap();
+        let steps = evaluator.run("y = x >> 4 | 1").unwrap();
+        assert_eq!(steps.last().unwrap().value, 0x0F);
+        assert_eq!(steps.iter().map(|step| step.kind.as_str()).collect::<Vec<_>>(), ["input", "constant", "shr", "constant", "or"]);
+        assert_eq!(evaluator.variable("y"), Some(0x0F));
+        assert_eq!(evaluator.variable_names(), ["x", "y"]);
+    }
+}
@@ -0,0 +1,126 @@
+//! Double-width arithmetic built from single words: the full 64-bit product of two words, addition with a carry
+//! in and out, and splitting a *u64* into its 32-bit halves and joining them back. Every operation is done with
+//! 32-bit words only, the way it's done on machines without wider registers
+
+/// Returns the highest and the lowest 32 bits of the number
+/// # Arguments
+/// * `number` - number to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::wide::split_u64;
+/// assert_eq!(split_u64(0x1234_5678_9ABC_DEF0), (0x1234_5678, 0x9ABC_DEF0));
+/// ```
+pub fn split_u64(number: u64) -> (u32, u32) {
+    ((number >> u32::BITS) as u32, number as u32)
+}
+
+/// Returns the number of the highest and the lowest 32 bits
+/// # Arguments
+/// * `high` - highest 32 bits
+/// * `low` - lowest 32 bits
+/// # Examples
+/// ```
+/// # use understanding_bitwise::wide::join_u64;
+/// assert_eq!(join_u64(0x1234_5678, 0x9ABC_DEF0), 0x1234_5678_9ABC_DEF0);
+/// ```
+pub fn join_u64(high: u32, low: u32) -> u64 {
+    (high as u64) << u32::BITS | low as u64
+}
+
+/// Returns the sum of the words and the incoming carry, and the outgoing carry. The carry out of the highest bit
+/// is set where both highest bits are set, or one of them is set and the sum bit is clear (the carry into the
+/// highest bit consumed it): *(a & b | (a | b) & !sum) >> 31*
+/// # Arguments
+/// * `a` - first word
+/// * `b` - second word
+/// * `carry` - incoming carry
+/// # Examples
+/// ```
+/// # use understanding_bitwise::wide::carrying_add;
+/// assert_eq!(carrying_add(u32::MAX, 0, true), (0, true));
+/// assert_eq!(carrying_add(0x8000_0000, 0x8000_0000, false), (0, true));
+/// assert_eq!(carrying_add(1, 2, true), (4, false));
+/// ```
+pub fn carrying_add(a: u32, b: u32, carry: bool) -> (u32, bool) {
+    let sum = a.wrapping_add(b).wrapping_add(carry as u32);
+    let carry_out = (a & b | (a | b) & ! sum) >> (u32::BITS - 1);
+    (sum, carry_out == 1)
+}
+
+/// Returns the highest and the lowest words of the full 64-bit product, in the order of *split_u64* (the standard
+/// *u32::widening_mul* gives the lowest word first). The words are split into 16-bit halves, whose products fit into a word, and the four partial products are
+/// added at their positions as in long multiplication: *a·b = a₁b₁·2³² + (a₁b₀ + a₀b₁)·2¹⁶ + a₀b₀*
+/// # Arguments
+/// * `a` - first factor
+/// * `b` - second factor
+/// # Examples
+/// ```
+/// # use understanding_bitwise::wide::widening_mul;
+/// assert_eq!(widening_mul(u32::MAX, u32::MAX), (u32::MAX - 1, 1));
+/// assert_eq!(widening_mul(0x1_0000, 0x1_0000), (1, 0));
+/// assert_eq!(widening_mul(6, 7), (0, 42));
+/// ```
+pub fn widening_mul(a: u32, b: u32) -> (u32, u32) {
+    const HALF: u32 = u16::BITS;
+    const HALF_MASK: u32 = 0xFFFF;
+    let (a1, a0) = (a >> HALF, a & HALF_MASK);
+    let (b1, b0) = (b >> HALF, b & HALF_MASK);
+    let (low, cross1, cross2, high) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    // Bits 16..32 of the product: below 3·2¹⁶, so the sum can't overflow
+    let middle = (low >> HALF) + (cross1 & HALF_MASK) + (cross2 & HALF_MASK);
+    let low = middle << HALF | low & HALF_MASK;
+    let high = high + (cross1 >> HALF) + (cross2 >> HALF) + (middle >> HALF);
+    (high, low)
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    #[test]
+    fn test_split_join() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let number = join_u64(state, state.rotate_left(13));
+            assert_eq!(split_u64(number), (state, state.rotate_left(13)));
+            assert_eq!(join_u64(split_u64(number).0, split_u64(number).1), number);
+        }
+        assert_eq!(split_u64(u64::MAX), (u32::MAX, u32::MAX));
+    }
+
+    #[test]
+    fn test_carrying_add() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let (a, b) = (state, state.rotate_left(7));
+            for carry in [false, true] {
+                let wide = a as u64 + b as u64 + carry as u64;
+                assert_eq!(carrying_add(a, b, carry), (wide as u32, wide >> 32 == 1));
+            }
+        }
+        // 64-bit addition out of two 32-bit ones
+        let (a, b) = (0x0000_0001_FFFF_FFFFu64, 0x0000_0002_0000_0001u64);
+        let ((a_high, a_low), (b_high, b_low)) = (split_u64(a), split_u64(b));
+        let (low, carry) = carrying_add(a_low, b_low, false);
+        let (high, _) = carrying_add(a_high, b_high, carry);
+        assert_eq!(join_u64(high, low), a + b);
+    }
+
+    #[test]
+    fn test_widening_mul() {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        for _ in 0..1000 {
+            let state = rng.next_u32();
+            let (a, b) = (state, state.rotate_left(17) >> (state % 32));
+            let (high, low) = widening_mul(a, b);
+            assert_eq!(join_u64(high, low), a as u64 * b as u64);
+        }
+        for (a, b) in [(0, u32::MAX), (u32::MAX, 1), (0xFFFF, 0xFFFF), (0x1_0000, u32::MAX)] {
+            assert_eq!(widening_mul(a, b), split_u64(a as u64 * b as u64));
+        }
+    }
+}
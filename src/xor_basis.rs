@@ -0,0 +1,196 @@
+//! Linear basis over XOR (Gaussian elimination in GF(2)): which numbers are XORs of subsets of the inserted ones
+
+use crate::hob;
+
+/// Linear basis of the inserted numbers. Holds at most one basis vector per hob
+/// # Examples
+/// ```
+/// # use understanding_bitwise::xor_basis::XorBasis;
+/// let mut basis = XorBasis::new();
+/// for number in [0b0110, 0b1011, 0b1101] {
+///     basis.insert(number);
+/// }
+/// // 0b1101 = 0b0110 ^ 0b1011, so it's dependent
+/// assert_eq!(basis.rank(), 2);
+/// assert!(basis.can_represent(0b1101));
+/// assert!(! basis.can_represent(0b0001));
+/// assert_eq!(basis.max_xor(), 0b1101);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct XorBasis {
+    /// Element *i* is the basis vector with hob *i* or 0
+    vectors: [u32; u32::BITS as usize],
+}
+
+impl XorBasis {
+    /// Returns the empty basis. Only 0 can be represented
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Helper function. Returns the number reduced by the basis vectors from the highest hob down
+    /// # Arguments
+    /// * `number` - number to work with
+    fn reduce(&self, number: u32) -> u32 {
+        self.vectors.iter().rev().fold(number, |number, &vector| number.min(number ^ vector))
+    }
+
+    /// Adds the number. Returns true if it wasn't representable before (the rank grew)
+    /// # Arguments
+    /// * `number` - number to be added
+    pub fn insert(&mut self, number: u32) -> bool {
+        let reduced = self.reduce(number);
+        match hob(reduced) {
+            Some(index) => {
+                self.vectors[index as usize] = reduced;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the count of basis vectors. 2^rank numbers can be represented
+    pub fn rank(&self) -> u32 {
+        self.vectors.iter().filter(|&&vector| vector != 0).count() as u32
+    }
+
+    /// Returns true if the number is XOR of some subset of the inserted numbers (0 is XOR of the empty subset)
+    /// # Arguments
+    /// * `number` - number to work with
+    pub fn can_represent(&self, number: u32) -> bool {
+        self.reduce(number) == 0
+    }
+
+    /// Returns the maximum XOR of a subset of the inserted numbers
+    pub fn max_xor(&self) -> u32 {
+        // Taking a vector if it increases the value is greedy optimal: its hob isn't affected by the lower vectors
+        self.vectors.iter().rev().fold(0, |max, &vector| max.max(max ^ vector))
+    }
+
+    /// Returns the basis vectors, the highest hob first
+    pub fn vectors(&self) -> impl Iterator<Item = u32> + '_ {
+        self.vectors.iter().rev().copied().filter(|&vector| vector != 0)
+    }
+}
+
+impl Extend<u32> for XorBasis {
+    fn extend<T: IntoIterator<Item = u32>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|number| {
+            self.insert(number);
+        });
+    }
+}
+
+impl FromIterator<u32> for XorBasis {
+    fn from_iter<T: IntoIterator<Item = u32>>(iter: T) -> Self {
+        let mut basis = Self::new();
+        basis.extend(iter);
+        basis
+    }
+}
+
+/// Returns the pair of the numbers with the maximum XOR or None if there are less than two numbers.
+/// Walks a binary trie of the numbers from the highest bit, preferring the opposite bit at each level
+/// # Arguments
+/// * `numbers` - numbers to work with
+/// # Examples
+/// ```
+/// # use understanding_bitwise::xor_basis::find_max_xor_pair;
+/// assert_eq!(find_max_xor_pair(&[3, 10, 5, 25, 2, 8]), Some((5, 25)));
+/// assert_eq!(find_max_xor_pair(&[7]), None);
+/// ```
+pub fn find_max_xor_pair(numbers: &[u32]) -> Option<(u32, u32)> {
+    if numbers.len() < 2 {
+        return None;
+    }
+
+    // Node holds child indexes for bit 0 and 1. Index 0 is the root, so 0 as a child means no child
+    let mut nodes = vec![[0usize; 2]];
+    let mut best: Option<(u32, u32)> = None;
+    for (position, &number) in numbers.iter().enumerate() {
+        if position > 0 {
+            let mut node = 0;
+            let mut partner = 0;
+            for index in (0..u32::BITS).rev() {
+                let wanted = (number >> index & 1 ^ 1) as usize;
+                let bit = if nodes[node][wanted] != 0 { wanted } else { wanted ^ 1 };
+                node = nodes[node][bit];
+                partner |= (bit as u32) << index;
+            }
+            if best.is_none_or(|(left, right)| number ^ partner > left ^ right) {
+                best = Some((partner.min(number), partner.max(number)));
+            }
+        }
+        let mut node = 0;
+        for index in (0..u32::BITS).rev() {
+            let bit = (number >> index & 1) as usize;
+            if nodes[node][bit] == 0 {
+                nodes.push([0; 2]);
+                nodes[node][bit] = nodes.len() - 1;
+            }
+            node = nodes[node][bit];
+        }
+    }
+    best
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    fn numbers(count: usize, mask: u32) -> Vec<u32> {
+        let mut rng = Xorshift32::new(0x1357_9BDF).unwrap();
+        (0..count).map(|_| {
+            let state = rng.next_u32();
+            state & mask
+        }).collect()
+    }
+
+    #[test]
+    fn test_xor_basis() {
+        let numbers = numbers(6, 0xFF);
+        let basis: XorBasis = numbers.iter().copied().collect();
+        let mut representable = vec![false; 256];
+        for subset in 0..1u32 << numbers.len() {
+            let xor = numbers.iter().enumerate()
+                .filter(|(index, _)| subset >> index & 1 == 1)
+                .fold(0, |acc, (_, &number)| acc ^ number);
+            representable[xor as usize] = true;
+        }
+        for number in 0..256 {
+            assert_eq!(basis.can_represent(number), representable[number as usize]);
+        }
+        assert_eq!(representable.iter().filter(|&&flag| flag).count(), 1 << basis.rank());
+        assert_eq!(basis.max_xor(), representable.iter().rposition(|&flag| flag).unwrap() as u32);
+        assert_eq!(basis.vectors().count() as u32, basis.rank());
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut basis = XorBasis::new();
+        assert!(! basis.insert(0));
+        assert!(basis.insert(u32::MAX));
+        assert!(basis.insert(1));
+        assert!(! basis.insert(u32::MAX - 1));
+        assert_eq!(basis.max_xor(), u32::MAX);
+        assert!(XorBasis::new().can_represent(0));
+        assert_eq!(XorBasis::new().max_xor(), 0);
+    }
+
+    #[test]
+    fn test_find_max_xor_pair() {
+        for count in [2, 3, 10, 50] {
+            let numbers = numbers(count, u32::MAX);
+            let expected = numbers.iter().enumerate()
+                .flat_map(|(index, &left)| numbers[index + 1..].iter().map(move |&right| left ^ right))
+                .max();
+            let (left, right) = find_max_xor_pair(&numbers).unwrap();
+            assert_eq!(Some(left ^ right), expected);
+            assert!(numbers.contains(&left) && numbers.contains(&right));
+        }
+        assert_eq!(find_max_xor_pair(&[5, 5]), Some((5, 5)));
+        assert_eq!(find_max_xor_pair(&[]), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Range XOR queries. XOR is its own inverse, so XOR of *l..r* is prefix(r) ^ prefix(l), like sums with subtraction
+
+use std::ops::Range;
+use crate::XorWord;
+
+/// Prefix XORs of a slice. Answers range queries in O(1), doesn't support updates (see *FenwickXor*)
+/// # Examples
+/// ```
+/// # use understanding_bitwise::xor_query::PrefixXor;
+/// let index = PrefixXor::new(&[3u32, 5, 6, 1]);
+/// assert_eq!(index.xor_of_range(1..3), Some(5 ^ 6));
+/// assert_eq!(index.xor_of_range(2..2), Some(0));
+/// assert_eq!(index.xor_of_range(2..5), None);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PrefixXor<B> {
+    /// Element *i* is XOR of the first *i* elements of the slice
+    prefix: Vec<B>,
+}
+
+impl<B: XorWord> PrefixXor<B> {
+    /// Returns the index of the slice
+    /// # Arguments
+    /// * `vals` - elements to work with
+    pub fn new(vals: &[B]) -> Self {
+        let mut prefix = Vec::with_capacity(vals.len() + 1);
+        prefix.push(B::ZERO);
+        for &val in vals {
+            prefix.push(*prefix.last().unwrap() ^ val);
+        }
+        PrefixXor { prefix }
+    }
+
+    /// Returns the count of indexed elements
+    pub fn len(&self) -> usize {
+        self.prefix.len() - 1
+    }
+
+    /// Returns true if there are no indexed elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns XOR of the elements of the range or None if the range is out of the slice. XOR of an empty range is 0
+    /// # Arguments
+    /// * `range` - range of element indexes
+    pub fn xor_of_range(&self, range: Range<usize>) -> Option<B> {
+        if range.start > range.end {
+            return None;
+        }
+        Some(*self.prefix.get(range.end)? ^ *self.prefix.get(range.start)?)
+    }
+}
+
+/// Binary indexed (Fenwick) tree of XORs. Answers range queries and updates elements in O(log n).
+/// Node *i* (1-based) holds XOR of the elements *(i - lowest_set_bit(i))..i*
+/// # Examples
+/// ```
+/// # use understanding_bitwise::xor_query::FenwickXor;
+/// let mut tree = FenwickXor::new(&[3u32, 5, 6, 1]);
+/// assert_eq!(tree.xor_of_range(0..4), Some(3 ^ 5 ^ 6 ^ 1));
+/// tree.update(2, 9).unwrap();
+/// assert_eq!(tree.xor_of_range(1..3), Some(5 ^ 9));
+/// assert_eq!(tree.get(2), Some(9));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FenwickXor<B> {
+    vals: Vec<B>,
+    tree: Vec<B>,
+}
+
+impl<B: XorWord> FenwickXor<B> {
+    /// Returns the tree of the slice. Built in O(n)
+    /// # Arguments
+    /// * `vals` - elements to work with
+    pub fn new(vals: &[B]) -> Self {
+        let mut tree = Vec::with_capacity(vals.len() + 1);
+        tree.push(B::ZERO);
+        tree.extend_from_slice(vals);
+        for node in 1..tree.len() {
+            let parent = node + node.lowest_set_bit();
+            if parent < tree.len() {
+                tree[parent] = tree[parent] ^ tree[node];
+            }
+        }
+        FenwickXor { vals: vals.to_vec(), tree }
+    }
+
+    /// Returns the count of elements
+    pub fn len(&self) -> usize {
+        self.vals.len()
+    }
+
+    /// Returns true if there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.vals.is_empty()
+    }
+
+    /// Returns the element or None if the index is out of range
+    /// # Arguments
+    /// * `index` - index of the element
+    pub fn get(&self, index: usize) -> Option<B> {
+        self.vals.get(index).copied()
+    }
+
+    /// Replaces the element. Returns None if the index is out of range
+    /// # Arguments
+    /// * `index` - index of the element
+    /// * `val` - new value of the element
+    pub fn update(&mut self, index: usize, val: B) -> Option<()> {
+        let old = self.vals.get_mut(index)?;
+        // XOR with the difference replaces the old value in every node covering the element
+        let difference = *old ^ val;
+        *old = val;
+        let mut node = index + 1;
+        while node < self.tree.len() {
+            self.tree[node] = self.tree[node] ^ difference;
+            node += node.lowest_set_bit();
+        }
+        Some(())
+    }
+
+    /// Helper function. Returns XOR of the first *end* elements
+    /// # Arguments
+    /// * `end` - count of elements
+    fn prefix(&self, end: usize) -> B {
+        let mut acc = B::ZERO;
+        let mut node = end;
+        while node > 0 {
+            acc = acc ^ self.tree[node];
+            node -= node.lowest_set_bit();
+        }
+        acc
+    }
+
+    /// Returns XOR of the elements of the range or None if the range is out of the slice. XOR of an empty range is 0
+    /// # Arguments
+    /// * `range` - range of element indexes
+    pub fn xor_of_range(&self, range: Range<usize>) -> Option<B> {
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+        Some(self.prefix(range.end) ^ self.prefix(range.start))
+    }
+}
+
+/// This module contains tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Xorshift32;
+
+    fn vals() -> Vec<u32> {
+        let mut rng = Xorshift32::new(0x00C0_FFEE).unwrap();
+        (0..50).map(|_| rng.next_u32()).collect()
+    }
+
+    fn naive(vals: &[u32], range: Range<usize>) -> u32 {
+        vals[range].iter().fold(0, |acc, val| acc ^ val)
+    }
+
+    #[test]
+    fn test_prefix_xor() {
+        let vals = vals();
+        let index = PrefixXor::new(&vals);
+        assert_eq!(index.len(), 50);
+        for start in 0..=vals.len() {
+            for end in start..=vals.len() {
+                assert_eq!(index.xor_of_range(start..end), Some(naive(&vals, start..end)));
+            }
+        }
+        assert_eq!(index.xor_of_range(0..51), None);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 5..3;
+        assert_eq!(index.xor_of_range(reversed), None);
+        assert!(PrefixXor::<u8>::new(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_fenwick_xor() {
+        let mut vals = vals();
+        let mut tree = FenwickXor::new(&vals);
+        for step in 0..vals.len() {
+            let index = step * 7 % vals.len();
+            vals[index] = vals[index].rotate_left(5) ^ step as u32;
+            tree.update(index, vals[index]).unwrap();
+            for start in (0..=vals.len()).step_by(3) {
+                for end in start..=vals.len() {
+                    assert_eq!(tree.xor_of_range(start..end), Some(naive(&vals, start..end)));
+                }
+            }
+        }
+        assert_eq!(tree.update(50, 0), None);
+        assert_eq!(tree.xor_of_range(10..51), None);
+        assert_eq!(tree.get(50), None);
+    }
+}